@@ -0,0 +1,54 @@
+//! Graph benchmarks over a generated workload (`roadmap gen`'s engine).
+//!
+//! Run with `cargo bench`. The fixture lives in a temporary database; proofs
+//! point at commits of this checkout so staleness checks hit real git.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use roadmap::engine::db::Db;
+use roadmap::engine::gen::{self, GenSpec};
+use roadmap::engine::graph::TaskGraph;
+use std::hint::black_box;
+
+const TASKS: usize = 1_000;
+const EDGES: usize = 3_000;
+const SEED: u64 = 42;
+
+fn fixture() -> (tempfile::TempDir, rusqlite::Connection) {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let conn = Db::open(&dir.path().join("bench.db")).expect("open db");
+    let mut commits = gen::recent_commits(16);
+    if commits.is_empty() {
+        commits.push("0".repeat(40));
+    }
+    gen::populate(
+        &conn,
+        &GenSpec {
+            tasks: TASKS,
+            edges: EDGES,
+            seed: SEED,
+            commits,
+        },
+    )
+    .expect("populate");
+    (dir, conn)
+}
+
+fn graph_benches(c: &mut Criterion) {
+    let (_dir, conn) = fixture();
+    let mut group = c.benchmark_group("graph");
+    group.sample_size(10);
+
+    group.bench_function("build", |b| {
+        b.iter(|| black_box(TaskGraph::build(&conn).expect("build")));
+    });
+
+    let graph = TaskGraph::build(&conn).expect("build");
+    group.bench_function("frontier", |b| {
+        b.iter(|| black_box(graph.get_frontier().len()));
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, graph_benches);
+criterion_main!(benches);
@@ -0,0 +1,665 @@
+//! The top-level commands and their flags.
+
+use super::subcommands::{
+    ConfigCommands, DebugCommands, DepCommands, HooksCommands, MilestoneCommands, MirrorCommands,
+    ProofCommands, ReportCommands, ScopesCommands,
+};
+use clap::Subcommand;
+use roadmap::engine::plan;
+use std::path::PathBuf;
+
+#[derive(Subcommand, Clone)]
+pub enum Commands {
+    /// Initialize the roadmap repository
+    Init,
+    /// Add a new task
+    Add {
+        #[arg(required_unless_present = "from_file")]
+        title: Option<String>,
+        #[arg(long, short = 'b')]
+        blocks: Option<String>,
+        #[arg(long, short = 'a')]
+        after: Option<String>,
+        #[arg(long, short = 't')]
+        test: Option<String>,
+        /// File glob patterns to scope this task (e.g., "src/auth/**")
+        #[arg(long, short = 's')]
+        scope: Option<Vec<String>>,
+        /// Shell for the verification command (sh, bash, zsh, pwsh, nu, cmd, or exec for none)
+        #[arg(long)]
+        shell: Option<String>,
+        /// Verification command used on Unix instead of --test
+        #[arg(long)]
+        test_unix: Option<String>,
+        /// Verification command used on Windows instead of --test
+        #[arg(long)]
+        test_windows: Option<String>,
+        /// Directory to run the verification command in, relative to the roadmap's root
+        #[arg(long, value_name = "DIR")]
+        cwd: Option<String>,
+        /// Coverage report (lcov or Cobertura) produced by the test command
+        #[arg(long, value_name = "PATH")]
+        coverage: Option<String>,
+        /// Minimum line coverage percentage for the task to count as proven
+        #[arg(long, value_name = "PCT")]
+        min_coverage: Option<f64>,
+        /// Treat --test as a benchmark; fail if any result regresses more than PCT percent
+        #[arg(long, value_name = "PCT")]
+        bench: Option<f64>,
+        /// Resource budget checked after the test passes: `time<2s` or `size:PATH<5MB` (repeatable)
+        #[arg(long, value_name = "SPEC")]
+        budget: Vec<String>,
+        /// Scheduling priority; higher comes first in `next` (may be negative)
+        #[arg(long, default_value = "0", allow_negative_numbers = true)]
+        priority: i64,
+        /// Due date: YYYY-MM-DD, today, tomorrow, +3d, or +2w
+        #[arg(long, value_name = "DATE")]
+        due: Option<String>,
+        /// Expected effort: 30m, 4h, 2d (8h days), or 1w (5 days)
+        #[arg(long, value_name = "EFFORT")]
+        estimate: Option<String>,
+        /// Label for slicing the roadmap by area, e.g. `infra` (repeatable or comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        tag: Vec<String>,
+        /// Long-form context for whoever picks the task up
+        #[arg(long)]
+        description: Option<String>,
+        /// Write the description in $EDITOR
+        #[arg(long)]
+        edit: bool,
+        /// Make the new task a subtask of this one
+        #[arg(long, value_name = "TASK")]
+        parent: Option<String>,
+        /// Who will work on it (`me` for yourself)
+        #[arg(long = "assign", value_name = "NAME")]
+        assignee: Option<String>,
+        /// Don't probe the test command for missing binaries or empty test filters
+        #[arg(long)]
+        no_probe: bool,
+        /// Add one task per line of PATH (`-` for stdin): `title | after-ref | test_cmd`
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with_all = ["title", "blocks", "after", "test", "description", "edit"]
+        )]
+        from_file: Option<PathBuf>,
+    },
+    /// Remove a task with its dependencies, scopes, and proofs
+    Rm {
+        task: String,
+        /// Also delete the task's proofs
+        #[arg(long)]
+        force: bool,
+    },
+    /// Revert the last command that changed the roadmap
+    Undo {
+        /// List recent operations instead of undoing
+        #[arg(long)]
+        list: bool,
+    },
+    /// Manage dependencies between existing tasks
+    Dep {
+        #[command(subcommand)]
+        command: DepCommands,
+    },
+    /// Group tasks into milestones and track their completion
+    Milestone {
+        #[command(subcommand)]
+        command: MilestoneCommands,
+    },
+    /// Change the slug of a task
+    Rename {
+        task: String,
+        new_slug: String,
+        /// Keep the old slug as an alias so existing references still resolve
+        #[arg(long)]
+        alias: bool,
+    },
+    /// Change the title or verification command of a task
+    Edit {
+        task: String,
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(long, short = 't')]
+        test: Option<String>,
+        /// Shell for the verification command (sh, bash, zsh, pwsh, nu, cmd, or exec for none)
+        #[arg(long)]
+        shell: Option<String>,
+        /// Verification command used on Unix instead of --test
+        #[arg(long)]
+        test_unix: Option<String>,
+        /// Verification command used on Windows instead of --test
+        #[arg(long)]
+        test_windows: Option<String>,
+        /// Directory to run the verification command in, or `none` for the roadmap's root
+        #[arg(long, value_name = "DIR")]
+        cwd: Option<String>,
+        /// Scheduling priority; higher comes first in `next` (may be negative)
+        #[arg(long, allow_negative_numbers = true)]
+        priority: Option<i64>,
+        /// Due date: YYYY-MM-DD, today, tomorrow, +3d, +2w, or `none` to clear it
+        #[arg(long, value_name = "DATE")]
+        due: Option<String>,
+        /// Expected effort: 30m, 4h, 2d, 1w, or `none` to clear it
+        #[arg(long, value_name = "EFFORT")]
+        estimate: Option<String>,
+        /// Add a tag (repeatable or comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        tag: Vec<String>,
+        /// Remove a tag (repeatable or comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        untag: Vec<String>,
+        /// New long-form description, or `none` to clear it
+        #[arg(long, conflicts_with = "describe")]
+        description: Option<String>,
+        /// Rewrite the description in $EDITOR
+        #[arg(long)]
+        describe: bool,
+        /// Move the task under this parent, or `none` to make it top-level
+        #[arg(long, value_name = "TASK")]
+        parent: Option<String>,
+        /// Who works on it (`me` for yourself), or `none` to unassign
+        #[arg(long = "assign", value_name = "NAME")]
+        assignee: Option<String>,
+        /// Don't probe the test command for missing binaries or empty test filters
+        #[arg(long)]
+        no_probe: bool,
+    },
+    /// Show next actionable tasks
+    Next {
+        #[arg(long)]
+        json: bool,
+        /// Only tasks with this tag (repeatable or comma-separated; all must match)
+        #[arg(long, value_delimiter = ',')]
+        tag: Vec<String>,
+        /// Only tasks in this milestone
+        #[arg(long)]
+        milestone: Option<String>,
+        /// Only this task and those it waits on or that wait on it, transitively
+        #[arg(long, value_name = "TASK")]
+        root: Option<String>,
+        /// Only tasks assigned to you (`user.name` in config, else git's user.name)
+        #[arg(long)]
+        mine: bool,
+        /// Group all unfinished work into waves that can each proceed in parallel
+        #[arg(long)]
+        waves: bool,
+    },
+    /// List all tasks
+    List {
+        #[arg(long)]
+        json: bool,
+        /// Only tasks with this tag (repeatable or comma-separated; all must match)
+        #[arg(long, value_delimiter = ',')]
+        tag: Vec<String>,
+        /// Only tasks in this milestone
+        #[arg(long)]
+        milestone: Option<String>,
+        /// Only this task and those it waits on or that wait on it, transitively
+        #[arg(long, value_name = "TASK")]
+        root: Option<String>,
+        /// Indent subtasks under their parents, with progress counts
+        #[arg(long)]
+        tree: bool,
+    },
+    /// Set active task
+    Do {
+        task: String,
+        /// Strict mode: require exact ID or slug (no fuzzy matching)
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Run verification for active task
+    Check {
+        /// Mark complete without verification (creates ATTESTED, not DONE)
+        #[arg(long)]
+        force: bool,
+        /// Reason for manual attestation (required with --force when `check.lenient_attestation` is off)
+        #[arg(long, requires = "force")]
+        reason: Option<String>,
+        /// Sign the recorded proof with an SSH private key path or `gpg:<key-id>`
+        #[arg(long, value_name = "KEY")]
+        sign: Option<String>,
+        /// Re-verify dependents that went stale only because of this task's files
+        #[arg(long, conflicts_with = "force")]
+        cascade: bool,
+        /// Also write recorded proofs to a portable file (for `proof import`)
+        #[arg(long, value_name = "FILE")]
+        emit_proof: Option<PathBuf>,
+        /// Stream NDJSON progress events (started, heartbeat, finished) on stdout
+        #[arg(long)]
+        json: bool,
+        /// Save scopes inferred from files changed since `do` (or the last proof) without asking
+        #[arg(long, conflicts_with = "force")]
+        adopt_scopes: bool,
+        /// Verify every task with a test command, in dependency order, instead of the active one
+        #[arg(long, conflicts_with_all = ["force", "cascade", "adopt_scopes"])]
+        all: bool,
+        /// With --all, only tasks whose proof is Stale or Broken
+        #[arg(long, requires = "all")]
+        stale: bool,
+        /// With --all, run up to N independent verification commands at once (0: one per CPU)
+        #[arg(long, short = 'j', value_name = "N", requires = "all")]
+        jobs: Option<usize>,
+        /// With --all, skip tasks still waiting on unmet dependencies
+        #[arg(long, requires = "all")]
+        unblocked: bool,
+        /// Re-run a failing verification command up to N more times before recording it as broken
+        #[arg(long, value_name = "N", conflicts_with = "force")]
+        retries: Option<u32>,
+        /// Show the verification command's output on stderr as it runs (or set `runner.stream`)
+        #[arg(long, short = 'v', conflicts_with = "force")]
+        verbose: bool,
+        /// Run the verification command in this docker/podman image, with the repo mounted read-only (or set `runner.container`)
+        #[arg(long, value_name = "IMAGE", conflicts_with = "force")]
+        in_container: Option<String>,
+        /// Confine the verification command: no network, read-only outside the repo, `[sandbox]` resource limits (or set `sandbox.enabled`)
+        #[arg(long, conflicts_with = "force")]
+        sandbox: bool,
+        /// Show what would run (command, shell, directory, timeout, environment) and the status it would lead to, without running it
+        #[arg(long, conflicts_with_all = ["force", "cascade", "emit_proof", "sign"])]
+        dry_run: bool,
+    },
+    /// Move proofs between roadmaps
+    Proof {
+        #[command(subcommand)]
+        command: ProofCommands,
+    },
+    /// Publish tasks, edges, and proofs to `refs/roadmap/state` (and push it)
+    PushState {
+        /// Git remote to push the state ref to
+        remote: Option<String>,
+    },
+    /// Fetch and merge roadmap state from a git remote's state ref
+    PullState {
+        /// Git remote to fetch from (omit to apply the local state ref)
+        remote: Option<String>,
+    },
+    /// Three-way merge another roadmap state into this one
+    MergeState {
+        /// Snapshot file, state ref, or git remote whose fetched state to merge
+        theirs: String,
+        /// Common-ancestor snapshot file (refs find it automatically)
+        #[arg(long, value_name = "FILE")]
+        base: Option<PathBuf>,
+        /// Resolve every conflict in favour of the local roadmap
+        #[arg(long, conflicts_with = "theirs_wins")]
+        ours: bool,
+        /// Resolve every conflict in favour of the incoming state
+        #[arg(long = "theirs", id = "theirs_wins")]
+        theirs_wins: bool,
+    },
+    /// Quarantine a flaky task so its failures don't block dependents
+    Quarantine {
+        task: String,
+        /// Why the task is quarantined
+        #[arg(long, required_unless_present = "release")]
+        reason: Option<String>,
+        /// Rehabilitate the task
+        #[arg(long, conflicts_with = "reason")]
+        release: bool,
+    },
+    /// Show current status
+    Status {
+        #[arg(long)]
+        json: bool,
+        /// Exit with an error if any task is stale or broken (see --fail-on)
+        #[arg(long)]
+        check: bool,
+        /// Statuses that fail --check: broken, stale, attested (repeatable or comma-separated)
+        #[arg(long, value_name = "STATUS", value_delimiter = ',', requires = "check")]
+        fail_on: Vec<String>,
+    },
+    /// Explain the status of a specific task
+    Why {
+        task: String,
+        #[arg(long)]
+        json: bool,
+        /// Strict mode: require exact ID or slug (no fuzzy matching)
+        #[arg(long)]
+        strict: bool,
+        /// Also show the environment (OS, toolchains, variables) each proof was recorded in
+        #[arg(long, conflicts_with = "impact")]
+        full: bool,
+        /// Show every task proving this one would unblock, most-unlocking first
+        #[arg(long)]
+        impact: bool,
+    },
+    /// List the artifacts a task's verification runs kept, or open the latest
+    Artifacts {
+        task: String,
+        /// Open the latest run's artifact directory
+        #[arg(long, conflicts_with = "json")]
+        open: bool,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Delete old proofs, keeping the latest of every task
+    Prune {
+        /// Keep this many proofs per task, newest first
+        #[arg(long, value_name = "N")]
+        keep_last: Option<usize>,
+        /// Only delete proofs older than this, e.g. `90d` or `12w`
+        #[arg(long, value_name = "AGE")]
+        older_than: Option<String>,
+        /// Show what would be deleted without deleting it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print the dependency graph as diagram source
+    Graph {
+        /// Output format: `dot` or `mermaid`
+        #[arg(long, default_value = "dot")]
+        format: String,
+        /// Only tasks with this tag (repeatable or comma-separated; all must match)
+        #[arg(long, value_delimiter = ',')]
+        tag: Vec<String>,
+        /// Only tasks in this milestone
+        #[arg(long)]
+        milestone: Option<String>,
+        /// Only this task and those it waits on or that wait on it, transitively
+        #[arg(long, value_name = "TASK", conflicts_with = "orphans")]
+        root: Option<String>,
+        /// List tasks with no links at all and disconnected components instead of drawing the graph
+        #[arg(long, conflicts_with = "format")]
+        orphans: bool,
+        #[arg(long, requires = "orphans")]
+        json: bool,
+    },
+    /// Interactive terminal UI: frontier, all tasks, and task detail
+    Ui,
+    /// Answer newline-delimited JSON-RPC requests on stdin, for orchestrators
+    Rpc,
+    /// Serve the roadmap to other programs
+    Serve {
+        /// Model Context Protocol over stdio, for agents
+        #[arg(long)]
+        mcp: bool,
+        /// JSON HTTP API on this port, for dashboards and bots
+        #[arg(long, value_name = "PORT")]
+        http: Option<u16>,
+        /// Address the HTTP API binds to
+        #[arg(long, default_value = "127.0.0.1", requires = "http")]
+        host: String,
+        /// Let browser pages from this origin call the HTTP API (reads then need the token too)
+        #[arg(long, value_name = "ORIGIN", requires = "http")]
+        allow_origin: Option<String>,
+    },
+    /// Keep running and report status and frontier changes as commits land
+    Watch {
+        /// Seconds between checks
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+        /// Also raise desktop notifications for stale proofs and frontier changes
+        #[arg(long)]
+        notify: bool,
+    },
+    /// Show the dependency graph as an indented tree
+    Tree,
+    /// Full-text search task slugs and titles
+    Search {
+        query: String,
+        /// Maximum number of results
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print one raw task field (e.g. `status`, `test_cmd`, `proof.git_sha`)
+    Get { task: String, field: String },
+    /// Scan for invalidated (stale) proofs
+    Stale {
+        #[arg(long)]
+        json: bool,
+        /// Re-verify the stale and broken tasks, refreshing their proofs
+        #[arg(long, conflicts_with = "json")]
+        fix: bool,
+        /// With --fix, run up to N independent verification commands at once (0: one per CPU)
+        #[arg(long, short = 'j', value_name = "N", requires = "fix")]
+        jobs: Option<usize>,
+        /// With --fix, skip tasks still waiting on unmet dependencies
+        #[arg(long, requires = "fix")]
+        unblocked: bool,
+        /// Exit with an error if any task is stale or broken (see --fail-on)
+        #[arg(long, conflicts_with = "fix")]
+        check: bool,
+        /// Statuses that fail --check: broken, stale, attested (repeatable or comma-separated)
+        #[arg(long, value_name = "STATUS", value_delimiter = ',', requires = "check")]
+        fail_on: Vec<String>,
+    },
+    /// Show who ran which commands against the roadmap, and how they went
+    Log {
+        /// Number of entries to show
+        #[arg(long, default_value = "20")]
+        limit: usize,
+        #[arg(long)]
+        json: bool,
+        /// Only this person's or agent's commands
+        #[arg(long, value_name = "NAME")]
+        actor: Option<String>,
+        /// Only commands that failed
+        #[arg(long)]
+        failed: bool,
+        /// Only commands since a date (YYYY-MM-DD) or age (7d, 2w)
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+    },
+    /// Show chronological verification history
+    History {
+        /// Number of entries to show
+        #[arg(long, default_value = "20")]
+        limit: usize,
+        #[arg(long)]
+        json: bool,
+        /// Show the commits naming each task (`Roadmap-Task:` trailer or `[slug]` subject) that each proof covers
+        #[arg(long)]
+        commits: bool,
+        /// Only this task's proofs
+        #[arg(long, value_name = "TASK")]
+        task: Option<String>,
+        /// Only failed runs
+        #[arg(long)]
+        failed: bool,
+        /// Only proofs since a date (YYYY-MM-DD) or age (7d, 2w)
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+        /// Only proofs of this commit (a SHA prefix works)
+        #[arg(long, value_name = "SHA")]
+        sha: Option<String>,
+    },
+    /// Show which tasks' derived status changed between two commits
+    Diff {
+        /// The earlier commit (SHA, branch, tag, or e.g. HEAD~5)
+        from: String,
+        /// The later commit
+        #[arg(default_value = "HEAD")]
+        to: String,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show which tasks a commit range touches and how their proofs would change, or what proving a task unblocks
+    Impact {
+        /// Commit range or revision, e.g. HEAD~5..HEAD, or a task (same as `why --impact`)
+        target: String,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Export roadmap data for external tooling
+    Export {
+        /// Write every proof and mutation event as JSON Lines (`-` for stdout)
+        #[arg(long, value_name = "FILE")]
+        audit_log: Option<PathBuf>,
+        /// `md` writes a ROADMAP.md; `json` writes a portable backup bundle
+        #[arg(long)]
+        format: Option<String>,
+        /// Write a signed in-toto statement per proven task as JSON Lines (needs --sign)
+        #[arg(long, requires = "sign")]
+        attestations: bool,
+        /// Key for --attestations: an SSH private key path or `gpg:<key-id>`
+        #[arg(long, value_name = "KEY", requires = "attestations")]
+        sign: Option<String>,
+        /// Destination for `--format` or `--attestations` (`-` for stdout)
+        #[arg(long, short, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Restore a bundle written by `export --format json`
+    Import {
+        file: PathBuf,
+        /// Bundle format
+        #[arg(long, default_value = "json")]
+        format: String,
+    },
+    /// Walk through the core workflow in a temporary sandbox
+    Tutorial {
+        /// Keep the sandbox repository afterwards instead of deleting it
+        #[arg(long)]
+        keep: bool,
+    },
+    /// Analytics computed from the proof history: pass rates, durations, and tasks proven per week
+    Stats {
+        /// Pass rate, mean time between breaks, and time to repair per task
+        #[arg(long)]
+        reliability: bool,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Write a "proven N/M" progress badge for a README
+    Badge {
+        /// File to write (`-` or omitted for stdout)
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+        /// Write the shields.io endpoint JSON instead of SVG
+        #[arg(long)]
+        json: bool,
+    },
+    /// Reports computed from task and proof history
+    Report {
+        #[command(subcommand)]
+        command: ReportCommands,
+    },
+    /// Report work blocked for a long time and the root blockers holding it
+    BlockedReport {
+        /// Only report tasks blocked for at least this many days
+        #[arg(long, default_value = "7")]
+        days: i64,
+        #[arg(long)]
+        json: bool,
+    },
+    /// List unfinished tasks with due dates, most overdue first
+    Due {
+        #[arg(long)]
+        json: bool,
+    },
+    /// Evaluate policy rules for CI and exit non-zero on any violation
+    Gate {
+        /// Tasks that must be Proven: `all`, a slug, or `tag:<name>` (repeatable)
+        #[arg(long, value_name = "SELECTOR")]
+        require_proven: Vec<String>,
+        /// Status no task may have, e.g. `attested` (repeatable)
+        #[arg(long, value_name = "STATUS")]
+        forbid: Vec<String>,
+        /// Maximum number of stale tasks allowed
+        #[arg(long, value_name = "N")]
+        max_stale: Option<usize>,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Upgrade the database schema (every command does this on connect)
+    Migrate {
+        /// List the pending migrations without applying them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Check database integrity
+    Fsck {
+        /// Verify the tamper-evident proof hash chain (currently the only check, run by default)
+        #[arg(long)]
+        chain: bool,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Create, update, and relink tasks to match the declarative plan file
+    Sync {
+        /// Plan file to reconcile against
+        #[arg(default_value = plan::PLAN_FILE)]
+        file: PathBuf,
+        /// Remove tasks (and their proofs) that are not in the plan
+        #[arg(long)]
+        prune: bool,
+        /// Show the changes without applying them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Normalize, sort, and validate the declarative plan file
+    Fmt {
+        /// Plan file to format
+        #[arg(default_value = plan::PLAN_FILE)]
+        file: PathBuf,
+        /// Fail instead of rewriting if the file is not canonical (for CI)
+        #[arg(long)]
+        check: bool,
+    },
+    /// Print the JSON Schema for a `--json` output or input format
+    Schema {
+        /// Schema name (omit to list them)
+        name: Option<String>,
+    },
+    /// Validate a plan, proof file, or snapshot before applying it
+    Validate {
+        file: PathBuf,
+        /// File kind: plan, proof-file, or snapshot (inferred if omitted)
+        #[arg(long)]
+        kind: Option<String>,
+    },
+    /// Check signatures on attestations and signed proofs
+    VerifySignatures {
+        /// Only check proofs for this task
+        #[arg(long)]
+        task: Option<String>,
+        /// SSH allowed-signers file (see ssh-keygen(1) ALLOWED SIGNERS)
+        #[arg(long, value_name = "FILE")]
+        allowed_signers: Option<PathBuf>,
+        /// GPG keyring holding trusted public keys
+        #[arg(long, value_name = "FILE")]
+        keyring: Option<PathBuf>,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Fill an empty (throwaway) roadmap with a seeded random DAG and proof history
+    Gen {
+        #[arg(long, default_value = "1000")]
+        tasks: usize,
+        #[arg(long, default_value = "3000")]
+        edges: usize,
+        #[arg(long, default_value = "42")]
+        seed: u64,
+    },
+    /// Maintain task scope globs
+    Scopes {
+        #[command(subcommand)]
+        command: ScopesCommands,
+    },
+    /// Manage git hooks that block commits and pushes on stale or broken proofs
+    Hooks {
+        #[command(subcommand)]
+        command: HooksCommands,
+    },
+    /// Keep a git-mergeable copy of the state in .roadmap/state.jsonl
+    Mirror {
+        #[command(subcommand)]
+        command: MirrorCommands,
+    },
+    /// Read and change settings in .roadmap/config.toml or ~/.config/roadmap/config.toml
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+    /// Diagnostics for roadmap itself
+    Debug {
+        #[command(subcommand)]
+        command: DebugCommands,
+    },
+    /// Run `roadmap-<name>` from PATH
+    #[command(external_subcommand)]
+    External(Vec<String>),
+}
@@ -0,0 +1,552 @@
+//! Routes each parsed command to its handler, journaling the ones that write.
+
+use super::subcommands::{
+    ConfigCommands, DebugCommands, DepCommands, HooksCommands, MilestoneCommands, MirrorCommands,
+    ProofCommands, ReportCommands, ScopesCommands,
+};
+use super::Commands;
+use crate::handlers;
+use anyhow::Result;
+use roadmap::engine::db::Db;
+use roadmap::engine::merge::Side;
+use roadmap::engine::{journal, lock};
+use std::time::Instant;
+
+/// The invocation as typed, for the operation log.
+pub fn command_line() -> String {
+    std::env::args()
+        .skip(1)
+        .map(|arg| {
+            if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || c == '"') {
+                format!("{arg:?}")
+            } else {
+                arg
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn run(command: Commands) -> Result<()> {
+    match command {
+        Commands::Init
+        | Commands::Add { .. }
+        | Commands::Edit { .. }
+        | Commands::Rm { .. }
+        | Commands::Prune { .. }
+        | Commands::Rename { .. }
+        | Commands::Dep { command: DepCommands::Add { .. } | DepCommands::Rm { .. } }
+        | Commands::Milestone {
+            command: MilestoneCommands::Add { .. } | MilestoneCommands::Assign { .. } | MilestoneCommands::Unassign { .. },
+        }
+        | Commands::Undo { list: false }
+        | Commands::Do { .. }
+        | Commands::Check { dry_run: false, .. }
+        | Commands::Proof { .. }
+        | Commands::PushState { .. }
+        | Commands::PullState { .. }
+        | Commands::MergeState { .. }
+        | Commands::Quarantine { .. }
+        | Commands::Gen { .. }
+        | Commands::Scopes { .. }
+        | Commands::Sync { .. }
+        | Commands::Import { .. }
+        | Commands::Mirror { command: MirrorCommands::Load }
+        | Commands::Stale { fix: true, .. }
+        | Commands::Rpc
+        | Commands::Serve { .. } => {
+            let _lock = match &command {
+                Commands::Add { .. }
+                | Commands::Do { .. }
+                | Commands::Check { .. }
+                | Commands::Stale { .. }
+                | Commands::Mirror { .. } => Some(lock::acquire(&command_line())?),
+                _ => None,
+            };
+            // Servers journal each call instead (see `handlers::serve::ops`).
+            let journaled = !matches!(command, Commands::Rpc | Commands::Serve { .. });
+            let started = Instant::now();
+            let result = dispatch_write_ops(command);
+            if journaled {
+                if let Err(e) = journal(&result, started) {
+                    eprintln!("warning: failed to journal the command: {e:#}");
+                }
+            }
+            if let Err(e) = handlers::mirror::refresh() {
+                eprintln!("warning: failed to refresh the state mirror: {e:#}");
+            }
+            result
+        }
+        Commands::Next { .. }
+        | Commands::List { .. }
+        | Commands::Dep { command: DepCommands::List { .. } }
+        | Commands::Milestone { command: MilestoneCommands::Status { .. } }
+        | Commands::Undo { list: true }
+        | Commands::Status { .. }
+        | Commands::Why { .. }
+        | Commands::Artifacts { .. }
+        | Commands::Search { .. }
+        | Commands::Tree
+        | Commands::Ui
+        | Commands::Watch { .. }
+        | Commands::Graph { .. }
+        | Commands::Get { .. }
+        | Commands::Stale { .. }
+        | Commands::Check { dry_run: true, .. }
+        | Commands::History { .. }
+        | Commands::Log { .. }
+        | Commands::Diff { .. }
+        | Commands::Impact { .. }
+        | Commands::Export { .. }
+        | Commands::Fsck { .. }
+        | Commands::Migrate { .. }
+        | Commands::Badge { .. }
+        | Commands::Report { .. }
+        | Commands::BlockedReport { .. }
+        | Commands::Due { .. }
+        | Commands::Stats { .. }
+        | Commands::Tutorial { .. }
+        | Commands::External(_)
+        | Commands::Gate { .. }
+        | Commands::Fmt { .. }
+        | Commands::Schema { .. }
+        | Commands::Validate { .. }
+        | Commands::VerifySignatures { .. }
+        | Commands::Hooks { .. }
+        | Commands::Mirror { .. }
+        | Commands::Config { .. }
+        | Commands::Debug { .. } => dispatch_read_ops(command),
+    }
+}
+
+/// Appends the invocation to the journal, unless there is no roadmap to journal it in.
+fn journal(result: &Result<()>, started: Instant) -> Result<()> {
+    if !Db::path().exists() {
+        return Ok(());
+    }
+    let conn = Db::connect()?;
+    let outcome = result.as_ref().copied().map_err(|e| format!("{e:#}"));
+    journal::record(&conn, &command_line(), outcome, started.elapsed())
+}
+
+fn dispatch_write_ops(cmd: Commands) -> Result<()> {
+    match cmd {
+        Commands::Init => handlers::init::handle(),
+        Commands::Rm { task, force } => handlers::rm::handle(&task, force),
+        Commands::Prune { keep_last, older_than, dry_run } => {
+            handlers::prune::handle(keep_last, older_than.as_deref(), dry_run)
+        }
+        Commands::Undo { list: false } => handlers::undo::handle(false),
+        Commands::Dep { command } => match command {
+            DepCommands::Add { blocker, blocked } => handlers::dep::add(&blocker, &blocked),
+            DepCommands::Rm { blocker, blocked } => handlers::dep::rm(&blocker, &blocked),
+            DepCommands::List { .. } => unreachable!("Invalid write command dispatch"),
+        },
+        Commands::Milestone { command } => match command {
+            MilestoneCommands::Add { name, title } => handlers::milestone::add(&name, title.as_deref()),
+            MilestoneCommands::Assign { milestone, tasks } => handlers::milestone::assign(&milestone, &tasks),
+            MilestoneCommands::Unassign { tasks } => handlers::milestone::unassign(&tasks),
+            MilestoneCommands::Status { .. } => unreachable!("Invalid write command dispatch"),
+        },
+        command @ (Commands::Add { .. } | Commands::Edit { .. } | Commands::Rename { .. }) => {
+            dispatch_edits(command)
+        }
+        command @ (Commands::Check { .. } | Commands::Stale { .. }) => dispatch_checks(command),
+        Commands::Do { task, strict } => handlers::do_task::handle(&task, strict),
+        Commands::Proof { command } => match command {
+            ProofCommands::Import { file } => handlers::proof::import(&file),
+            ProofCommands::Push => handlers::proof::push(),
+            ProofCommands::Pull => handlers::proof::pull(),
+        },
+        Commands::PushState { remote } => handlers::state_sync::push(remote.as_deref()),
+        Commands::PullState { remote } => handlers::state_sync::pull(remote.as_deref()),
+        Commands::MergeState {
+            theirs,
+            base,
+            ours,
+            theirs_wins,
+        } => {
+            let side = match (ours, theirs_wins) {
+                (true, _) => Some(Side::Ours),
+                (_, true) => Some(Side::Theirs),
+                _ => None,
+            };
+            handlers::merge_state::handle(&theirs, base.as_deref(), side)
+        }
+        Commands::Gen { tasks, edges, seed } => handlers::gen::handle(tasks, edges, seed),
+        Commands::Rpc => handlers::rpc::handle(),
+        Commands::Serve {
+            mcp,
+            http,
+            host,
+            allow_origin,
+        } => handlers::serve::handle(mcp, http, &host, allow_origin.as_deref()),
+        Commands::Import { file, format } => handlers::import::handle(&file, &format),
+        Commands::Mirror { command: MirrorCommands::Load } => handlers::mirror::load(),
+        Commands::Sync {
+            file,
+            prune,
+            dry_run,
+        } => handlers::sync::handle(&file, prune, dry_run),
+        Commands::Scopes { command } => match command {
+            ScopesCommands::Remap { since, apply } => {
+                handlers::scopes::remap(since.as_deref(), apply)
+            }
+        },
+        Commands::Quarantine {
+            task,
+            reason,
+            release,
+        } => handlers::quarantine::handle(&task, reason.as_deref(), release),
+        _ => unreachable!("Invalid write command dispatch"),
+    }
+}
+
+/// Commands that create or change a task's definition.
+fn dispatch_edits(cmd: Commands) -> Result<()> {
+    match cmd {
+        Commands::Add {
+            title,
+            blocks,
+            after,
+            test,
+            scope,
+            shell,
+            test_unix,
+            test_windows,
+            cwd,
+            coverage,
+            min_coverage,
+            bench,
+            budget,
+            priority,
+            due,
+            estimate,
+            tag,
+            description,
+            edit,
+            parent,
+            assignee,
+            no_probe,
+            from_file,
+        } => {
+            let opts = handlers::add::AddOptions {
+                title: title.as_deref().unwrap_or_default(),
+                blocks: blocks.as_deref(),
+                after: after.as_deref(),
+                test_cmd: test.as_deref(),
+                scopes: scope,
+                shell: shell.as_deref(),
+                test_unix: test_unix.as_deref(),
+                test_windows: test_windows.as_deref(),
+                cwd: cwd.as_deref(),
+                coverage: coverage.as_deref(),
+                min_coverage,
+                bench_threshold: bench,
+                budgets: budget,
+                priority,
+                due: due.as_deref(),
+                estimate: estimate.as_deref(),
+                tags: tag,
+                description,
+                edit,
+                parent: parent.as_deref(),
+                assignee: assignee.as_deref(),
+                no_probe,
+            };
+            match from_file {
+                Some(path) => handlers::add::from_file(&path, &opts),
+                None => handlers::add::handle(opts),
+            }
+        }
+        Commands::Rename {
+            task,
+            new_slug,
+            alias,
+        } => handlers::rename::handle(&task, &new_slug, alias),
+        Commands::Edit {
+            task,
+            title,
+            test,
+            shell,
+            test_unix,
+            test_windows,
+            cwd,
+            priority,
+            due,
+            estimate,
+            tag,
+            untag,
+            description,
+            describe,
+            parent,
+            assignee,
+            no_probe,
+        } => handlers::edit::handle(&handlers::edit::EditOptions {
+            task: &task,
+            title: title.as_deref(),
+            test_cmd: test.as_deref(),
+            shell: shell.as_deref(),
+            test_unix: test_unix.as_deref(),
+            test_windows: test_windows.as_deref(),
+            cwd: cwd.as_deref(),
+            priority,
+            due: due.as_deref(),
+            estimate: estimate.as_deref(),
+            tags: &tag,
+            untags: &untag,
+            description: description.as_deref(),
+            describe,
+            parent: parent.as_deref(),
+            assignee: assignee.as_deref(),
+            no_probe,
+        }),
+        _ => unreachable!("Invalid edit command dispatch"),
+    }
+}
+
+/// Verification runs: `check` and `stale --fix`.
+fn dispatch_checks(cmd: Commands) -> Result<()> {
+    match cmd {
+        Commands::Check {
+            force,
+            reason,
+            sign,
+            cascade,
+            emit_proof,
+            json,
+            adopt_scopes,
+            all,
+            stale,
+            jobs,
+            unblocked,
+            retries,
+            verbose,
+            in_container,
+            sandbox,
+            dry_run,
+        } => handlers::check::handle(&handlers::check::CheckOptions {
+            force,
+            reason: reason.as_deref(),
+            sign_key: sign.as_deref(),
+            cascade,
+            emit_proof: emit_proof.as_deref(),
+            json,
+            adopt_scopes,
+            all,
+            stale,
+            jobs: job_count(jobs),
+            unblocked,
+            retries,
+            verbose,
+            in_container: in_container.as_deref(),
+            sandbox,
+            dry_run,
+        }),
+        Commands::Stale {
+            fix: true,
+            jobs,
+            unblocked,
+            ..
+        } => handlers::check::handle(&handlers::check::CheckOptions {
+            force: false,
+            reason: None,
+            sign_key: None,
+            cascade: false,
+            emit_proof: None,
+            json: false,
+            adopt_scopes: false,
+            all: true,
+            stale: true,
+            jobs: job_count(jobs),
+            unblocked,
+            retries: None,
+            verbose: false,
+            in_container: None,
+            sandbox: false,
+            dry_run: false,
+        }),
+        _ => unreachable!("Invalid check command dispatch"),
+    }
+}
+
+/// `--jobs` as given: 0 means one per CPU, absent means one.
+fn job_count(jobs: Option<usize>) -> usize {
+    match jobs {
+        Some(0) => std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get),
+        Some(n) => n,
+        None => 1,
+    }
+}
+
+fn dispatch_read_ops(cmd: Commands) -> Result<()> {
+    match cmd {
+        Commands::Next {
+            json,
+            tag,
+            milestone,
+            root,
+            mine,
+            waves,
+        } => handlers::next::handle(&handlers::next::NextOptions {
+            json,
+            tags: &tag,
+            milestone: milestone.as_deref(),
+            root: root.as_deref(),
+            mine,
+            waves,
+        }),
+        Commands::List {
+            json,
+            tag,
+            milestone,
+            root,
+            tree,
+        } => handlers::list::handle(json, &tag, milestone.as_deref(), root.as_deref(), tree),
+        Commands::Status { json, check, fail_on } => handlers::status::handle(json, check, &fail_on),
+        Commands::Why {
+            task,
+            json,
+            strict,
+            full,
+            impact,
+        } => handlers::why::handle(
+            &task,
+            &handlers::why::WhyOptions {
+                json,
+                strict,
+                full,
+                impact,
+            },
+        ),
+        Commands::Artifacts { task, open, json } => handlers::artifacts::handle(&task, open, json),
+        Commands::Tree => handlers::tree::handle(),
+        Commands::Ui => handlers::ui::handle(),
+        Commands::Watch { interval, notify } => handlers::watch::handle(interval, notify),
+        Commands::Graph {
+            format,
+            tag,
+            milestone,
+            root,
+            orphans,
+            json,
+        } => {
+            if orphans {
+                handlers::graph::orphans(&tag, milestone.as_deref(), json)
+            } else {
+                handlers::graph::handle(&format, &tag, milestone.as_deref(), root.as_deref())
+            }
+        }
+        Commands::Search { query, limit, json } => handlers::search::handle(&query, limit, json),
+        Commands::Get { task, field } => handlers::get::handle(&task, &field),
+        Commands::Stale {
+            json, check, fail_on, ..
+        } => handlers::stale::handle(json, check, &fail_on),
+        Commands::History {
+            limit,
+            json,
+            commits,
+            task,
+            failed,
+            since,
+            sha,
+        } => {
+            let query = handlers::history::HistoryQuery {
+                task: task.as_deref(),
+                failed,
+                since: since.as_deref(),
+                sha: sha.as_deref(),
+            };
+            handlers::history::handle(&query, limit, json, commits)
+        }
+        Commands::Log {
+            limit,
+            json,
+            actor,
+            failed,
+            since,
+        } => {
+            let query = handlers::log::LogQuery {
+                actor: actor.as_deref(),
+                failed,
+                since: since.as_deref(),
+            };
+            handlers::log::handle(&query, limit, json)
+        }
+        Commands::Diff { from, to, json } => handlers::diff::handle(&from, &to, json),
+        Commands::Impact { target, json } => handlers::impact::handle(&target, json),
+        // A dry run reads like any query but takes check's options as they are.
+        command @ Commands::Check { .. } => dispatch_checks(command),
+        command => dispatch_tool_ops(command),
+    }
+}
+
+/// Exports, maintenance, and integrations that only read the roadmap, and
+/// the listings of write commands.
+fn dispatch_tool_ops(cmd: Commands) -> Result<()> {
+    match cmd {
+        Commands::Dep { command: DepCommands::List { task, json } } => handlers::dep::list(&task, json),
+        Commands::Milestone { command: MilestoneCommands::Status { milestone, json } } => {
+            handlers::milestone::status(milestone.as_deref(), json)
+        }
+        Commands::Undo { list: true } => handlers::undo::handle(true),
+        Commands::Export {
+            audit_log,
+            format,
+            attestations: _,
+            sign,
+            output,
+        } => handlers::export::handle(audit_log.as_deref(), format.as_deref(), output.as_deref(), sign.as_deref()),
+        Commands::Migrate { dry_run } => handlers::migrate::handle(dry_run),
+        Commands::Fsck { chain: _, json } => handlers::fsck::handle(json),
+        Commands::Badge { out, json } => handlers::badge::handle(out.as_deref(), json),
+        Commands::Report { command } => match command {
+            ReportCommands::Burndown { format } => handlers::report::burndown(&format),
+        },
+        Commands::BlockedReport { days, json } => handlers::blocked_report::handle(days, json),
+        Commands::Due { json } => handlers::due::handle(json),
+        Commands::Stats { reliability, json } => handlers::stats::handle(reliability, json),
+        Commands::Tutorial { keep } => handlers::tutorial::handle(keep),
+        Commands::External(args) => handlers::plugin::handle(&args),
+        Commands::Gate {
+            require_proven,
+            forbid,
+            max_stale,
+            json,
+        } => handlers::gate::handle(&require_proven, &forbid, max_stale, json),
+        Commands::Fmt { file, check } => handlers::fmt::handle(&file, check),
+        Commands::Schema { name } => handlers::schema::show(name.as_deref()),
+        Commands::Validate { file, kind } => handlers::schema::validate(&file, kind.as_deref()),
+        Commands::VerifySignatures {
+            task,
+            allowed_signers,
+            keyring,
+            json,
+        } => handlers::verify_signatures::handle(&handlers::verify_signatures::VerifyOptions {
+            task: task.as_deref(),
+            allowed_signers: allowed_signers.as_deref(),
+            keyring: keyring.as_deref(),
+            json,
+        }),
+        Commands::Hooks { command } => match command {
+            HooksCommands::Install { hooks, force } => handlers::hooks::install(&hooks, force),
+            HooksCommands::Uninstall { hooks } => handlers::hooks::uninstall(&hooks),
+        },
+        Commands::Mirror { command } => match command {
+            MirrorCommands::Install => handlers::mirror::install(),
+            MirrorCommands::Write => handlers::mirror::write(),
+            MirrorCommands::Merge { base, ours, theirs } => {
+                handlers::mirror::merge_driver(&base, &ours, &theirs)
+            }
+            MirrorCommands::Load => unreachable!("Invalid read command dispatch"),
+        },
+        Commands::Config { command } => match command {
+            ConfigCommands::Get { key } => handlers::config::get(key.as_deref()),
+            ConfigCommands::Set { key, value, global } => handlers::config::set(&key, &value, global),
+        },
+        Commands::Debug { command } => match command {
+            DebugCommands::Timings { limit, json } => handlers::debug::timings(limit, json),
+        },
+        _ => unreachable!("Invalid read command dispatch"),
+    }
+}
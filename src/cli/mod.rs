@@ -0,0 +1,23 @@
+//! Command-line interface: argument definitions and dispatch to the handlers.
+
+mod commands;
+mod dispatch;
+mod subcommands;
+
+use clap::Parser;
+
+pub use commands::Commands;
+pub use dispatch::{command_line, run};
+
+#[derive(Parser)]
+#[command(name = "roadmap", version, about = "Git for your Intent")]
+pub struct Cli {
+    /// Print a per-phase timing breakdown (also `ROADMAP_TRACE=1`)
+    #[arg(long, global = true)]
+    pub trace: bool,
+    /// Work on a named roadmap sharing this .roadmap directory (e.g. `backend`)
+    #[arg(long = "roadmap", global = true, env = "ROADMAP_NS", value_name = "NAME")]
+    pub roadmap: Option<String>,
+    #[command(subcommand)]
+    pub command: Commands,
+}
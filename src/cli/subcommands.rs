@@ -0,0 +1,141 @@
+//! Nested subcommands (`roadmap dep add`, `roadmap mirror write`, ...).
+
+use clap::Subcommand;
+use std::path::PathBuf;
+
+#[derive(Subcommand, Clone)]
+pub enum ProofCommands {
+    /// Ingest proofs written by `check --emit-proof` (e.g. from CI)
+    Import { file: PathBuf },
+    /// Publish every task's latest proof to the configured remote
+    Push,
+    /// Fetch teammates' proofs from the configured remote
+    Pull,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum DepCommands {
+    /// Make BLOCKER a dependency of BLOCKED (cycle-checked)
+    Add { blocker: String, blocked: String },
+    /// Remove the dependency of BLOCKED on BLOCKER
+    Rm { blocker: String, blocked: String },
+    /// Show what a task is blocked by and what it blocks
+    List {
+        task: String,
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+pub enum ReportCommands {
+    /// Remaining tasks and estimated effort per day, with velocity and a projected finish
+    Burndown {
+        /// `spark` (terminal sparkline), `csv`, or `json`
+        #[arg(long, default_value = "spark")]
+        format: String,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+pub enum MilestoneCommands {
+    /// Create a milestone
+    Add {
+        name: String,
+        /// What the milestone delivers, e.g. "Public beta"
+        #[arg(long)]
+        title: Option<String>,
+    },
+    /// Put tasks in a milestone (a task is in at most one)
+    Assign {
+        milestone: String,
+        #[arg(required = true)]
+        tasks: Vec<String>,
+    },
+    /// Take tasks out of their milestone
+    Unassign {
+        #[arg(required = true)]
+        tasks: Vec<String>,
+    },
+    /// Show how complete each milestone is, or the tasks of one
+    Status {
+        milestone: Option<String>,
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+pub enum ScopesCommands {
+    /// Follow git renames for scopes that no longer match any file
+    Remap {
+        /// Look for renames since this revision (default: each task's last proof)
+        #[arg(long, value_name = "REV")]
+        since: Option<String>,
+        /// Write the proposed scopes instead of only listing them
+        #[arg(long)]
+        apply: bool,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+pub enum HooksCommands {
+    /// Install hooks that run `roadmap stale` and refuse on stale or broken proofs
+    Install {
+        /// Hook to install: pre-push (default) or pre-commit; repeatable
+        #[arg(long = "hook", value_name = "HOOK")]
+        hooks: Vec<String>,
+        /// Move an existing non-roadmap hook aside as `<hook>.local` and chain it
+        #[arg(long)]
+        force: bool,
+    },
+    /// Remove installed hooks, restoring any that were moved aside
+    Uninstall {
+        /// Hook to remove: pre-push (default) or pre-commit; repeatable
+        #[arg(long = "hook", value_name = "HOOK")]
+        hooks: Vec<String>,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+pub enum MirrorCommands {
+    /// Register the merge driver and write the mirror; it then refreshes after every change
+    Install,
+    /// Write the database to the mirror now
+    Write,
+    /// Apply the mirror to the database, e.g. after pulling teammates' changes
+    Load,
+    /// Git merge driver: three-way merge THEIRS into OURS given BASE (`%O %A %B`)
+    Merge {
+        base: PathBuf,
+        ours: PathBuf,
+        theirs: PathBuf,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+pub enum ConfigCommands {
+    /// Print the effective value of a key (`runner.timeout`), or of every key
+    Get { key: Option<String> },
+    /// Set a key in the repository config (or the user config with --global)
+    Set {
+        key: String,
+        /// A TOML value (`600`, `true`, `["a", "b"]`) or plain string
+        value: String,
+        /// Write to ~/.config/roadmap/config.toml, the default for every repository
+        #[arg(long)]
+        global: bool,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+pub enum DebugCommands {
+    /// Summarize phase timings of recent traced invocations
+    Timings {
+        /// Number of recent invocations to summarize
+        #[arg(long, default_value = "50")]
+        limit: usize,
+        #[arg(long)]
+        json: bool,
+    },
+}
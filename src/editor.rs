@@ -0,0 +1,47 @@
+//! Editor: Composes long-form text in the user's `$VISUAL` / `$EDITOR`.
+//!
+//! The text is written to a scratch file under a comment header, the editor
+//! runs in the foreground, and the saved file is read back the way git reads
+//! a commit message: lines starting with `#` are dropped and surrounding
+//! blank lines trimmed.
+
+use anyhow::{bail, Context, Result};
+use roadmap::engine::shell::Shell;
+use std::fs;
+
+/// Opens the editor on `initial` and returns what was saved, or `None` if it was left empty.
+///
+/// `subject` names what is being written, for the comment header.
+///
+/// # Errors
+/// Returns error if the scratch file cannot be written or the editor fails to run or exits non-zero.
+pub fn compose(subject: &str, initial: Option<&str>) -> Result<Option<String>> {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.trim().is_empty()))
+        .unwrap_or_else(|| if cfg!(target_os = "windows") { "notepad" } else { "vi" }.to_string());
+
+    let path = std::env::temp_dir().join(format!("roadmap-{}.md", std::process::id()));
+    let template = format!(
+        "{}\n\n# Describe {subject}. Lines starting with '#' are ignored;\n# an empty description clears it.\n",
+        initial.unwrap_or_default()
+    );
+    fs::write(&path, template).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    // The editor setting may carry arguments (`code --wait`), so a shell runs it.
+    let status = Shell::platform_default()
+        .command(&format!("{editor} \"{}\"", path.display()))?
+        .status()
+        .with_context(|| format!("Failed to run editor '{editor}'"));
+    let text = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+    if !status?.success() {
+        bail!("Editor '{editor}' exited with an error; nothing was saved");
+    }
+
+    let text = text.with_context(|| format!("Failed to read {}", path.display()))?;
+    let body: Vec<&str> = text.lines().filter(|l| !l.starts_with('#')).collect();
+    let body = body.join("\n");
+    let body = body.trim_matches(|c: char| c == '\n' || c == '\r').trim_end();
+    Ok((!body.trim().is_empty()).then(|| body.to_string()))
+}
@@ -0,0 +1,104 @@
+//! Artifacts: Files a verification run leaves behind as evidence.
+//!
+//! Every run gets a fresh directory, `.roadmap/artifacts/<task>/<timestamp>/`,
+//! passed to the verification command and its hooks as `$ROADMAP_ARTIFACTS`.
+//! Whatever they write there (coverage reports, logs, screenshots) is listed
+//! in the proof; a task's coverage report is copied in as well. A run that
+//! leaves nothing behind leaves no directory either.
+
+use super::db::DB_DIR;
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming the run's artifact directory.
+pub const ENV_VAR: &str = "ROADMAP_ARTIFACTS";
+
+const ARTIFACTS_DIR: &str = "artifacts";
+
+/// Where a task's artifacts are kept, one subdirectory per run.
+#[must_use]
+pub fn task_dir(slug: &str) -> PathBuf {
+    Path::new(DB_DIR).join(ARTIFACTS_DIR).join(slug)
+}
+
+/// Creates an empty directory for a new run of the task.
+///
+/// # Errors
+/// Returns error if the directory cannot be created.
+pub fn prepare(slug: &str) -> Result<PathBuf> {
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string();
+    let parent = task_dir(slug);
+    let mut dir = parent.join(&stamp);
+    for attempt in 1..=8 {
+        fs::create_dir_all(&parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        match fs::create_dir(&dir) {
+            // Commands may change directory, so they get an absolute path.
+            Ok(()) => return Ok(std::env::current_dir().map_or_else(|_| dir.clone(), |cwd| cwd.join(&dir))),
+            // Another run of the task started in the same millisecond.
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => dir = parent.join(format!("{stamp}-{attempt}")),
+            // A concurrent run that left nothing behind removed the task's directory.
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e).with_context(|| format!("Failed to create {}", dir.display())),
+        }
+    }
+    bail!("Failed to create a run directory under {}", parent.display())
+}
+
+/// Copies a file the run produced elsewhere (e.g. a coverage report) into `dir`.
+///
+/// A missing file is skipped; it is the policy checks' job to complain.
+///
+/// # Errors
+/// Returns error if an existing file cannot be copied.
+pub fn keep(dir: &Path, file: &Path) -> Result<()> {
+    let (true, Some(name)) = (file.is_file(), file.file_name()) else {
+        return Ok(());
+    };
+    fs::copy(file, dir.join(name)).with_context(|| format!("Failed to copy {} into the artifacts", file.display()))?;
+    Ok(())
+}
+
+/// Lists the files in a run's directory, relative to the repository root and
+/// sorted, removing the directory when the run left nothing in it.
+///
+/// # Errors
+/// Returns error if the directory cannot be read.
+pub fn collect(dir: &Path) -> Result<Vec<String>> {
+    let mut files = Vec::new();
+    walk(dir, &mut files).with_context(|| format!("Failed to read {}", dir.display()))?;
+    if files.is_empty() {
+        // Only removes what is empty: the run's directory, then the task's if it was the first.
+        let _ = fs::remove_dir(dir);
+        if let Some(parent) = dir.parent() {
+            let _ = fs::remove_dir(parent);
+        }
+        return Ok(Vec::new());
+    }
+    let root = std::env::current_dir().unwrap_or_default();
+    let mut paths: Vec<String> = files
+        .iter()
+        .map(|f| f.strip_prefix(&root).unwrap_or(f).to_string_lossy().replace('\\', "/"))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// The run directory an artifact path belongs to: `.roadmap/artifacts/<task>/<timestamp>`.
+#[must_use]
+pub fn run_dir(artifact: &str) -> PathBuf {
+    Path::new(artifact).components().take(4).collect()
+}
+
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            walk(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
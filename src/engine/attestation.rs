@@ -0,0 +1,235 @@
+//! Attestations: Proofs as in-toto statements for supply-chain tooling.
+//!
+//! Each proven task becomes one in-toto v1 statement. Its subjects are the
+//! files in the task's scopes as they were at the proven commit, by SHA-256
+//! of their contents; an unscoped task vouches for the whole commit, so its
+//! subject is the commit itself. The predicate carries what was run and how
+//! it ended. Statements are wrapped in DSSE envelopes, the format `cosign`
+//! and the in-toto verifiers read, and signed with the same SSH or GPG keys
+//! as proofs.
+
+use super::canonical;
+use super::signing;
+use super::types::{Proof, Task};
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+
+/// `_type` of every statement.
+pub const STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v1";
+
+/// `predicateType` of a roadmap proof.
+pub const PREDICATE_TYPE: &str = "https://github.com/junovhs/roadmap/proof/v1";
+
+/// DSSE `payloadType` of an in-toto statement.
+pub const PAYLOAD_TYPE: &str = "application/vnd.in-toto+json";
+
+/// Git's empty tree, the base for listing a commit's files by pathspec.
+const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+/// An in-toto v1 statement about one proven task.
+#[derive(Debug, Serialize)]
+pub struct Statement {
+    #[serde(rename = "_type")]
+    pub kind: &'static str,
+    pub subject: Vec<Subject>,
+    #[serde(rename = "predicateType")]
+    pub predicate_type: &'static str,
+    pub predicate: Predicate,
+}
+
+/// An artifact the statement is about, identified by digest.
+#[derive(Debug, Serialize)]
+pub struct Subject {
+    pub name: String,
+    pub digest: BTreeMap<&'static str, String>,
+}
+
+/// What roadmap observed when it proved the task.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Predicate {
+    pub task: String,
+    pub title: String,
+    pub command: String,
+    pub exit_code: i32,
+    pub git_sha: String,
+    pub timestamp: String,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+}
+
+/// A signed statement, as a DSSE envelope.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Envelope {
+    pub payload_type: &'static str,
+    /// The statement's JSON, base64-encoded.
+    pub payload: String,
+    pub signatures: Vec<EnvelopeSignature>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnvelopeSignature {
+    /// The signing key as given to `--sign`.
+    pub keyid: String,
+    /// The armored SSH or GPG signature over the DSSE pre-authentication encoding, base64-encoded.
+    pub sig: String,
+}
+
+/// Builds the statement for a task from the proof that proved it.
+///
+/// # Errors
+/// Returns error if git cannot list or read the scoped files at the proof's commit.
+pub fn statement(task: &Task, proof: &Proof) -> Result<Statement> {
+    let subject = if task.scopes.is_empty() {
+        vec![Subject {
+            name: format!("git+commit:{}", proof.git_sha),
+            digest: BTreeMap::from([("gitCommit", proof.git_sha.clone())]),
+        }]
+    } else {
+        scoped_files(&proof.git_sha, &task.scopes)?
+    };
+    Ok(Statement {
+        kind: STATEMENT_TYPE,
+        subject,
+        predicate_type: PREDICATE_TYPE,
+        predicate: Predicate {
+            task: task.slug.clone(),
+            title: task.title.clone(),
+            command: proof.cmd.clone(),
+            exit_code: proof.exit_code,
+            git_sha: proof.git_sha.clone(),
+            timestamp: proof.timestamp.clone(),
+            duration_ms: proof.duration_ms,
+            scopes: task.scopes.clone(),
+        },
+    })
+}
+
+/// Signs a statement with an SSH key path or `gpg:<key-id>`.
+///
+/// # Errors
+/// Returns error if the statement cannot be serialized or signing fails.
+pub fn sign(statement: &Statement, key_spec: &str) -> Result<Envelope> {
+    let body = canonical::to_line(statement)?;
+    let signature = signing::sign(key_spec, &pae(PAYLOAD_TYPE, &body))?;
+    Ok(Envelope {
+        payload_type: PAYLOAD_TYPE,
+        payload: base64(body.as_bytes()),
+        signatures: vec![EnvelopeSignature {
+            keyid: key_spec.to_string(),
+            sig: base64(signature.as_bytes()),
+        }],
+    })
+}
+
+/// DSSE pre-authentication encoding: what is actually signed.
+fn pae(payload_type: &str, body: &str) -> String {
+    format!("DSSEv1 {} {payload_type} {} {body}", payload_type.len(), body.len())
+}
+
+/// Subjects for the files matching `scopes` at `sha`, sorted by path.
+fn scoped_files(sha: &str, scopes: &[String]) -> Result<Vec<Subject>> {
+    let _span = tracing::info_span!("git", op = "diff-tree").entered();
+    let listing = Command::new("git")
+        .args(["diff-tree", "-r", "-z", "--no-renames", EMPTY_TREE, sha, "--"])
+        .args(scopes)
+        .output()
+        .context("Failed to run git diff-tree")?;
+    if !listing.status.success() {
+        bail!(
+            "Cannot list the scoped files at {sha}: {}",
+            String::from_utf8_lossy(&listing.stderr).trim()
+        );
+    }
+
+    // -z output: ":mode mode oid oid status" NUL path NUL, one pair per file.
+    let raw = String::from_utf8_lossy(&listing.stdout);
+    let mut fields = raw.split('\0').filter(|f| !f.is_empty());
+    let mut files = Vec::new();
+    while let (Some(meta), Some(path)) = (fields.next(), fields.next()) {
+        if let Some(oid) = meta.split(' ').nth(3) {
+            files.push((path.to_string(), oid.to_string()));
+        }
+    }
+    let contents = read_blobs(files.iter().map(|(_, oid)| oid.as_str()))?;
+    Ok(files
+        .into_iter()
+        .zip(contents)
+        .map(|((name, _), content)| Subject {
+            name,
+            digest: BTreeMap::from([("sha256", format!("{:x}", Sha256::digest(content)))]),
+        })
+        .collect())
+}
+
+/// Reads blobs in one `git cat-file --batch`, in order.
+fn read_blobs<'a>(oids: impl Iterator<Item = &'a str>) -> Result<Vec<Vec<u8>>> {
+    let request = oids.fold(String::new(), |mut request, oid| {
+        request.push_str(oid);
+        request.push('\n');
+        request
+    });
+    if request.is_empty() {
+        return Ok(Vec::new());
+    }
+    let _span = tracing::info_span!("git", op = "cat-file").entered();
+    let mut child = Command::new("git")
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to run git cat-file")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(request.as_bytes())?;
+    }
+    let mut out = Vec::new();
+    if let Some(mut stdout) = child.stdout.take() {
+        stdout.read_to_end(&mut out)?;
+    }
+    child.wait()?;
+
+    // Each blob: "<oid> blob <size>\n" then <size> bytes and a newline.
+    let mut blobs = Vec::new();
+    let mut rest = out.as_slice();
+    while let Some(eol) = rest.iter().position(|&b| b == b'\n') {
+        let header = String::from_utf8_lossy(&rest[..eol]);
+        let size: usize = match header.split(' ').collect::<Vec<_>>()[..] {
+            [_, "blob", size] => size.parse().with_context(|| format!("Bad git cat-file header '{header}'"))?,
+            _ => bail!("Cannot read blob: git cat-file answered '{header}'"),
+        };
+        let start = eol + 1;
+        let Some(content) = rest.get(start..start + size) else {
+            bail!("git cat-file output ended early");
+        };
+        blobs.push(content.to_vec());
+        rest = rest.get(start + size + 1..).unwrap_or_default();
+    }
+    Ok(blobs)
+}
+
+/// Standard base64 with padding, as DSSE expects.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, &b)| acc | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(ALPHABET[(n >> (18 - 6 * i) & 63) as usize]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
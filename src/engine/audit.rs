@@ -0,0 +1,156 @@
+//! Audit Trail: Flattens roadmap history into a chronological event stream.
+//!
+//! Task creations and proofs come from their tables; every command that
+//! changed the roadmap (status changes, `do`, links, edits, removals) comes
+//! from the journal, and every deleted proof from its tombstone.
+//!
+//! Field names are part of the export contract. Add fields, never rename them.
+
+use super::journal::{self, EventFilter};
+use super::repo::{ProofRepo, TaskRepo};
+use anyhow::Result;
+use rusqlite::Connection;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// A single audit event, serialized as one JSON object per line.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AuditEvent {
+    pub event: &'static str,
+    pub timestamp: String,
+    /// The task the event is about; absent for commands, which may touch many.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_slug: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cmd: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_sha: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attested_reason: Option<String>,
+    /// The journal entry of a `command` event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub journal_id: Option<i64>,
+    /// Who ran the command.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    /// The invocation, without the leading `roadmap`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ok: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Rows the command changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changes: Option<usize>,
+    /// Whether `undo` reverted the command's changes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub undone: Option<bool>,
+    /// Why a proof was removed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl AuditEvent {
+    fn bare(event: &'static str, timestamp: String) -> Self {
+        Self {
+            event,
+            timestamp,
+            task_id: None,
+            task_slug: None,
+            proof_id: None,
+            cmd: None,
+            exit_code: None,
+            git_sha: None,
+            duration_ms: None,
+            attested_reason: None,
+            journal_id: None,
+            actor: None,
+            command: None,
+            ok: None,
+            error: None,
+            changes: None,
+            undone: None,
+            reason: None,
+        }
+    }
+
+    fn for_task(event: &'static str, timestamp: String, task_id: i64, task_slug: String) -> Self {
+        Self {
+            task_id: Some(task_id),
+            task_slug: Some(task_slug),
+            ..Self::bare(event, timestamp)
+        }
+    }
+}
+
+/// Collects every task creation, proof, proof removal, and journaled command, oldest first.
+///
+/// # Errors
+/// Returns an error if the database queries fail.
+pub fn collect_events(conn: &Connection) -> Result<Vec<AuditEvent>> {
+    let mut events: Vec<AuditEvent> = TaskRepo::new(conn)
+        .get_all()?
+        .into_iter()
+        .map(|t| AuditEvent::for_task("task_created", t.created_at, t.id, t.slug))
+        .collect();
+
+    let proofs = ProofRepo::new(conn);
+    for entry in proofs.get_audit_trail()? {
+        let event = if entry.proof.attested_reason.is_some() {
+            "proof_attested"
+        } else {
+            "proof_recorded"
+        };
+        let p = entry.proof;
+        let mut e = AuditEvent::for_task(event, p.timestamp, entry.task_id, entry.slug);
+        e.proof_id = Some(entry.proof_id);
+        e.cmd = Some(p.cmd);
+        e.exit_code = Some(p.exit_code);
+        e.git_sha = Some(p.git_sha);
+        e.duration_ms = Some(p.duration_ms);
+        e.attested_reason = p.attested_reason;
+        events.push(e);
+    }
+
+    for tombstone in proofs.get_tombstones()? {
+        let mut e = AuditEvent::bare("proof_removed", tombstone.removed_at);
+        e.task_id = Some(tombstone.task_id);
+        e.proof_id = Some(tombstone.proof_id);
+        e.reason = Some(tombstone.reason);
+        events.push(e);
+    }
+
+    for entry in journal::recent(conn, &EventFilter::default(), usize::MAX)? {
+        let mut e = AuditEvent::bare("command", entry.timestamp);
+        e.journal_id = Some(entry.id);
+        e.actor = Some(entry.actor);
+        e.command = Some(entry.command);
+        e.ok = Some(entry.ok);
+        e.error = entry.error;
+        e.duration_ms = Some(entry.duration_ms);
+        e.changes = Some(entry.changes);
+        e.undone = Some(entry.undone);
+        events.push(e);
+    }
+
+    // Ties on the (second-resolution) timestamp fall back to identity, never to query order.
+    events.sort_by(|a, b| {
+        (&a.timestamp, &a.task_slug, a.proof_id, a.journal_id, a.event).cmp(&(
+            &b.timestamp,
+            &b.task_slug,
+            b.proof_id,
+            b.journal_id,
+            b.event,
+        ))
+    });
+    Ok(events)
+}
@@ -0,0 +1,124 @@
+//! Badge: Roadmap progress as an embeddable image.
+//!
+//! Renders the flat two-part badge familiar from READMEs, "proven | 34/50",
+//! as a self-contained SVG, or describes it in the shields.io endpoint format
+//! so shields.io can render (and cache) it from a published JSON file. The
+//! color moves from red to bright green as the share of proven tasks grows.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Horizontal padding on each side of a badge's text, in pixels.
+const PADDING: usize = 6;
+
+/// A two-part badge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Badge {
+    pub label: String,
+    pub message: String,
+    /// A shields.io color name.
+    pub color: &'static str,
+}
+
+/// The shields.io endpoint format.
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Endpoint {
+    /// Always 1.
+    pub schema_version: u8,
+    pub label: String,
+    pub message: String,
+    pub color: String,
+}
+
+impl Badge {
+    /// The badge for `proven` of `total` tasks.
+    #[must_use]
+    pub fn progress(proven: usize, total: usize) -> Self {
+        let color = match (proven * 100).checked_div(total) {
+            None => "lightgrey",
+            Some(100) => "brightgreen",
+            Some(75..) => "green",
+            Some(50..) => "yellow",
+            Some(25..) => "orange",
+            Some(_) => "red",
+        };
+        Self {
+            label: "proven".to_string(),
+            message: format!("{proven}/{total}"),
+            color,
+        }
+    }
+
+    /// The badge as a shields.io endpoint.
+    #[must_use]
+    pub fn endpoint(&self) -> Endpoint {
+        Endpoint {
+            schema_version: 1,
+            label: self.label.clone(),
+            message: self.message.clone(),
+            color: self.color.to_string(),
+        }
+    }
+
+    /// The badge as a standalone SVG document.
+    #[must_use]
+    pub fn to_svg(&self) -> String {
+        let label_width = text_width(&self.label) + 2 * PADDING;
+        let message_width = text_width(&self.message) + 2 * PADDING;
+        let width = label_width + message_width;
+        let label_x = label_width / 2;
+        let message_x = label_width + message_width / 2;
+        let (label, message) = (escape(&self.label), escape(&self.message));
+        let fill = hex(self.color);
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {message}">
+  <title>{label}: {message}</title>
+  <linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>
+  <clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath>
+  <g clip-path="url(#r)">
+    <rect width="{label_width}" height="20" fill="#555"/>
+    <rect x="{label_width}" width="{message_width}" height="20" fill="{fill}"/>
+    <rect width="{width}" height="20" fill="url(#s)"/>
+  </g>
+  <g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">
+    <text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{label}</text>
+    <text x="{label_x}" y="14">{label}</text>
+    <text x="{message_x}" y="15" fill="#010101" fill-opacity=".3">{message}</text>
+    <text x="{message_x}" y="14">{message}</text>
+  </g>
+</svg>
+"##
+        )
+    }
+}
+
+/// Approximate width of `text` in 11px Verdana: digits and most lowercase
+/// letters are about 7px, narrow glyphs less.
+fn text_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c {
+            'i' | 'l' | 'j' | '.' | ',' | ':' | '|' | '\'' => 3,
+            'f' | 'r' | 't' | '/' | ' ' | '(' | ')' => 5,
+            'm' | 'w' | 'M' | 'W' | '%' => 10,
+            c if c.is_ascii_uppercase() => 8,
+            _ => 7,
+        })
+        .sum()
+}
+
+/// The hex shade shields.io uses for a color name.
+fn hex(color: &str) -> &'static str {
+    match color {
+        "brightgreen" => "#4c1",
+        "green" => "#97ca00",
+        "yellow" => "#dfb317",
+        "orange" => "#fe7d37",
+        "red" => "#e05d44",
+        _ => "#9f9f9f",
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
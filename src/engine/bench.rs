@@ -0,0 +1,69 @@
+//! Benchmark Guard: Parses benchmark output and detects regressions.
+//!
+//! Two output formats are understood (lower values are better):
+//! - libtest: `test parse_large ... bench:   1,234 ns/iter (+/- 56)`
+//! - generic: `BENCH <name> <value>`
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Named benchmark measurements.
+pub type Metrics = BTreeMap<String, f64>;
+
+/// A metric that got slower than the allowed threshold.
+#[derive(Debug, Clone, Serialize)]
+pub struct Regression {
+    pub name: String,
+    pub baseline: f64,
+    pub current: f64,
+    pub percent: f64,
+}
+
+/// Extracts benchmark metrics from command output.
+#[must_use]
+pub fn parse_metrics(output: &str) -> Metrics {
+    output.lines().filter_map(parse_line).collect()
+}
+
+fn parse_line(line: &str) -> Option<(String, f64)> {
+    let line = line.trim();
+    if let Some(rest) = line.strip_prefix("BENCH ") {
+        let mut parts = rest.split_whitespace();
+        let name = parts.next()?;
+        let value = parts.next()?.parse().ok()?;
+        return Some((name.to_string(), value));
+    }
+
+    let rest = line.strip_prefix("test ")?;
+    let (name, tail) = rest.split_once(" ... bench:")?;
+    let digits: String = tail
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == ',' || *c == '.')
+        .filter(|c| *c != ',')
+        .collect();
+    Some((name.trim().to_string(), digits.parse().ok()?))
+}
+
+/// Compares current metrics to a baseline, returning those slower than `threshold` percent.
+///
+/// Metrics missing from either side are ignored.
+#[must_use]
+pub fn find_regressions(baseline: &Metrics, current: &Metrics, threshold: f64) -> Vec<Regression> {
+    current
+        .iter()
+        .filter_map(|(name, &now)| {
+            let &before = baseline.get(name)?;
+            if before <= 0.0 {
+                return None;
+            }
+            let percent = (now - before) / before * 100.0;
+            (percent > threshold).then(|| Regression {
+                name: name.clone(),
+                baseline: before,
+                current: now,
+                percent,
+            })
+        })
+        .collect()
+}
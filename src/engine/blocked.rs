@@ -0,0 +1,155 @@
+//! Blocked Work: Finds tasks stuck behind unmet dependencies and what they wait on.
+//!
+//! Every blocked chain bottoms out on a root blocker: an unmet dependency
+//! that is not itself blocked, so it is the thing someone has to act on.
+//! Roots nobody can act on without help (broken, or with no test command)
+//! are flagged, because work behind them will never unblock by itself.
+
+use super::graph::TaskGraph;
+use super::types::{parse_timestamp, DerivedStatus, Task};
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Why a root blocker needs attention before anything behind it can move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RootFlag {
+    /// Its latest verification failed.
+    Broken,
+    /// It has no verification command, so it can never be proven by `check`.
+    NoTest,
+}
+
+/// A task that has been blocked for at least the threshold.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StuckTask {
+    pub slug: String,
+    pub title: String,
+    pub blocked_since: String,
+    pub blocked_days: i64,
+    /// Slugs of the root blockers its chains bottom out on.
+    pub roots: Vec<String>,
+}
+
+/// An unmet dependency that is not itself blocked.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RootBlocker {
+    pub slug: String,
+    pub title: String,
+    pub status: String,
+    /// Number of stuck tasks waiting on it.
+    pub stuck: usize,
+    pub flags: Vec<RootFlag>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BlockedReport {
+    pub threshold_days: i64,
+    pub tasks: Vec<StuckTask>,
+    pub roots: Vec<RootBlocker>,
+}
+
+/// Collects tasks blocked for `threshold_days` or more, and their root blockers.
+///
+/// A task counts as blocked since the latest change we can see to the
+/// blockers holding it: its own creation, a blocker's creation, or a
+/// blocker's last proof. The first blocker to go unmet sets the clock.
+/// Stale blockers count from their last proof, since the commit that
+/// invalidated them isn't recorded.
+#[must_use]
+pub fn analyze(graph: &TaskGraph, threshold_days: i64, now: DateTime<Utc>) -> BlockedReport {
+    let mut tasks = Vec::new();
+    let mut stuck_behind: HashMap<i64, usize> = HashMap::new();
+
+    for task in graph.tasks() {
+        let unmet = graph.get_unmet_blockers(task.id);
+        let Some(since) = unmet.iter().filter_map(|b| blocked_since(task, b)).min() else {
+            continue;
+        };
+        let days = (now - since).num_days();
+        if days < threshold_days {
+            continue;
+        }
+
+        let roots = root_blockers(graph, task.id);
+        for root in &roots {
+            *stuck_behind.entry(root.id).or_default() += 1;
+        }
+        tasks.push(StuckTask {
+            slug: task.slug.clone(),
+            title: task.title.clone(),
+            blocked_since: since.to_rfc3339(),
+            blocked_days: days,
+            roots: roots.iter().map(|r| r.slug.clone()).collect(),
+        });
+    }
+    tasks.sort_by(|a, b| b.blocked_days.cmp(&a.blocked_days).then_with(|| a.slug.cmp(&b.slug)));
+
+    let mut roots: Vec<RootBlocker> = graph
+        .tasks()
+        .filter_map(|t| stuck_behind.get(&t.id).map(|n| (t, *n)))
+        .map(|(t, stuck)| {
+            let status = t.derive_status(graph.context());
+            RootBlocker {
+                slug: t.slug.clone(),
+                title: t.title.clone(),
+                status: status.to_string(),
+                stuck,
+                flags: flags(t, status),
+            }
+        })
+        .collect();
+    roots.sort_by(|a, b| b.stuck.cmp(&a.stuck).then_with(|| a.slug.cmp(&b.slug)));
+
+    BlockedReport {
+        threshold_days,
+        tasks,
+        roots,
+    }
+}
+
+fn blocked_since(task: &Task, blocker: &Task) -> Option<DateTime<Utc>> {
+    [
+        Some(task.created_at.as_str()),
+        Some(blocker.created_at.as_str()),
+        blocker.proof.as_ref().map(|p| p.timestamp.as_str()),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(parse_timestamp)
+    .max()
+}
+
+/// Walks unmet blockers upstream to the ones that are not blocked themselves.
+fn root_blockers(graph: &TaskGraph, id: i64) -> Vec<&Task> {
+    let mut seen = HashSet::new();
+    let mut roots = Vec::new();
+    let mut stack = vec![id];
+    while let Some(current) = stack.pop() {
+        for blocker in graph.get_unmet_blockers(current) {
+            if !seen.insert(blocker.id) {
+                continue;
+            }
+            if graph.get_unmet_blockers(blocker.id).is_empty() {
+                roots.push(blocker);
+            } else {
+                stack.push(blocker.id);
+            }
+        }
+    }
+    roots.sort_by(|a, b| a.slug.cmp(&b.slug));
+    roots
+}
+
+fn flags(task: &Task, status: DerivedStatus) -> Vec<RootFlag> {
+    let mut flags = Vec::new();
+    if status == DerivedStatus::Broken {
+        flags.push(RootFlag::Broken);
+    }
+    if task.platform_test_cmd().is_none() {
+        flags.push(RootFlag::NoTest);
+    }
+    flags
+}
@@ -0,0 +1,114 @@
+//! Resource Budgets: Declarative limits evaluated as verification predicates.
+//!
+//! Budget specs:
+//! - `time<2s` — the verification command finishes within the limit (`ms`, `s`, `m`)
+//! - `size:PATH<5MB` — the file at PATH is no larger than the limit (`B`, `KB`, `MB`, `GB`)
+
+use anyhow::{bail, Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::time::Duration;
+
+/// A parsed resource budget.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Budget {
+    MaxDuration(Duration),
+    MaxFileSize { path: String, bytes: u64 },
+}
+
+/// The evaluated outcome of one budget, as recorded in a proof.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BudgetResult {
+    pub budget: String,
+    pub actual: String,
+    pub passed: bool,
+}
+
+impl Budget {
+    /// Parses a budget spec.
+    ///
+    /// # Errors
+    /// Returns an error if the spec is malformed or uses an unknown unit.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let Some((lhs, limit)) = spec.split_once('<') else {
+            bail!("Invalid budget '{spec}': expected `time<LIMIT` or `size:PATH<LIMIT`");
+        };
+        let lhs = lhs.trim();
+        let limit = limit.trim();
+
+        if lhs == "time" {
+            return Ok(Self::MaxDuration(parse_duration(limit)?));
+        }
+        if let Some(path) = lhs.strip_prefix("size:") {
+            return Ok(Self::MaxFileSize {
+                path: path.trim().to_string(),
+                bytes: parse_size(limit)?,
+            });
+        }
+        bail!("Invalid budget '{spec}': unknown measure '{lhs}'");
+    }
+
+    /// Evaluates the budget. `elapsed` is the verification command's runtime, if one ran.
+    #[must_use]
+    pub fn evaluate(&self, spec: &str, elapsed: Option<Duration>) -> BudgetResult {
+        let (actual, passed) = match self {
+            Self::MaxDuration(limit) => match elapsed {
+                Some(d) => (format!("{}ms", d.as_millis()), d <= *limit),
+                None => ("no command ran".to_string(), false),
+            },
+            Self::MaxFileSize { path, bytes } => match fs::metadata(path) {
+                Ok(meta) => (format_size(meta.len()), meta.len() <= *bytes),
+                Err(_) => (format!("{path} not found"), false),
+            },
+        };
+        BudgetResult {
+            budget: spec.to_string(),
+            actual,
+            passed,
+        }
+    }
+}
+
+fn split_unit(value: &str) -> (&str, &str) {
+    let idx = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    (&value[..idx], value[idx..].trim())
+}
+
+fn parse_duration(value: &str) -> Result<Duration> {
+    let (num, unit) = split_unit(value);
+    let n: f64 = num.parse().with_context(|| format!("Invalid duration '{value}'"))?;
+    let secs = match unit.to_lowercase().as_str() {
+        "ms" => n / 1000.0,
+        "" | "s" => n,
+        "m" => n * 60.0,
+        other => bail!("Unknown duration unit '{other}' (use ms, s, m)"),
+    };
+    Ok(Duration::from_secs_f64(secs))
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn parse_size(value: &str) -> Result<u64> {
+    let (num, unit) = split_unit(value);
+    let n: f64 = num.parse().with_context(|| format!("Invalid size '{value}'"))?;
+    let factor: f64 = match unit.to_uppercase().as_str() {
+        "" | "B" => 1.0,
+        "KB" | "K" => 1024.0,
+        "MB" | "M" => 1024.0 * 1024.0,
+        "GB" | "G" => 1024.0 * 1024.0 * 1024.0,
+        other => bail!("Unknown size unit '{other}' (use B, KB, MB, GB)"),
+    };
+    Ok((n * factor) as u64)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn format_size(bytes: u64) -> String {
+    let mb = bytes as f64 / (1024.0 * 1024.0);
+    if mb >= 1.0 {
+        format!("{mb:.2}MB")
+    } else {
+        format!("{:.1}KB", bytes as f64 / 1024.0)
+    }
+}
@@ -0,0 +1,126 @@
+//! Burndown: How much work was left on each day, and how fast it shrinks.
+//!
+//! Replayed from history: a task counts from the day it was created, and is
+//! done on a day when its latest proof by the end of that day passed or was
+//! attested. A task that breaks again goes back onto the pile. Days are UTC.
+//! Velocity is the tasks finished per week over the last four weeks; the
+//! projection assumes the remaining work burns down at that rate.
+
+use super::repo::ProofRecord;
+use super::types::{parse_timestamp, Task};
+use chrono::{Days, NaiveDate};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Days the velocity is averaged over.
+const VELOCITY_DAYS: u64 = 28;
+
+/// One day of the burndown.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct BurndownPoint {
+    /// `YYYY-MM-DD`.
+    pub date: String,
+    /// Tasks that existed by the end of the day.
+    pub total: usize,
+    /// Tasks not yet done at the end of the day.
+    pub remaining: usize,
+    /// Sum of the estimates (hours) of the remaining tasks; unestimated ones add nothing.
+    pub remaining_hours: f64,
+}
+
+/// The burndown series with its velocity.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Burndown {
+    /// Oldest day first, through today.
+    pub points: Vec<BurndownPoint>,
+    /// Tasks finished per week over the last four weeks.
+    pub velocity_per_week: f64,
+    /// When the remaining tasks run out at that velocity; absent when nothing burns down.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projected_finish: Option<String>,
+}
+
+/// Replays task creation and proofs into a daily series ending `today`.
+#[must_use]
+pub fn compute(tasks: &[Task], trail: &[ProofRecord], today: NaiveDate) -> Burndown {
+    let created: HashMap<i64, NaiveDate> = tasks
+        .iter()
+        .map(|t| (t.id, day(&t.created_at).unwrap_or(today)))
+        .collect();
+    let estimates: HashMap<i64, f64> = tasks.iter().filter_map(|t| Some((t.id, t.estimate?))).collect();
+
+    // Per day, each task's final verdict: done or not.
+    let mut verdicts: Vec<(NaiveDate, i64, bool)> = trail
+        .iter()
+        .filter(|r| created.contains_key(&r.task_id))
+        .filter_map(|r| {
+            let proof = &r.proof;
+            let done = proof.attested_reason.is_some() || (proof.exit_code == 0 && proof.failure.is_none());
+            Some((day(&proof.timestamp)?, r.task_id, done))
+        })
+        .collect();
+    verdicts.sort_by_key(|(date, _, _)| *date);
+
+    let Some(start) = created.values().min().copied() else {
+        return Burndown {
+            points: Vec::new(),
+            velocity_per_week: 0.0,
+            projected_finish: None,
+        };
+    };
+    let velocity_from = today.checked_sub_days(Days::new(VELOCITY_DAYS)).unwrap_or(start);
+    let mut done: HashMap<i64, bool> = HashMap::new();
+    let mut finished_recently = 0usize;
+    let mut next = verdicts.iter().peekable();
+    let mut points = Vec::new();
+    let mut date = start;
+    while date <= today {
+        while let Some((_, task_id, verdict)) = next.next_if(|(at, _, _)| *at <= date) {
+            let was_done = done.insert(*task_id, *verdict).unwrap_or(false);
+            if *verdict && !was_done && date > velocity_from {
+                finished_recently += 1;
+            }
+        }
+        let existing: Vec<i64> = created.iter().filter(|(_, c)| **c <= date).map(|(id, _)| *id).collect();
+        let remaining: Vec<i64> = existing
+            .iter()
+            .copied()
+            .filter(|id| !done.get(id).copied().unwrap_or(false))
+            .collect();
+        points.push(BurndownPoint {
+            date: date.format("%Y-%m-%d").to_string(),
+            total: existing.len(),
+            remaining: remaining.len(),
+            remaining_hours: remaining.iter().filter_map(|id| estimates.get(id)).sum(),
+        });
+        let Some(following) = date.checked_add_days(Days::new(1)) else {
+            break;
+        };
+        date = following;
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let weeks = (today - velocity_from.max(start)).num_days().max(7) as f64 / 7.0;
+    #[allow(clippy::cast_precision_loss)]
+    let velocity_per_week = finished_recently as f64 / weeks;
+    let remaining = points.last().map_or(0, |p| p.remaining);
+    let projected_finish = (velocity_per_week > 0.0 && remaining > 0)
+        .then(|| {
+            #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let days = (remaining as f64 / velocity_per_week * 7.0).ceil() as u64;
+            today.checked_add_days(Days::new(days))
+        })
+        .flatten()
+        .map(|d| d.format("%Y-%m-%d").to_string());
+
+    Burndown {
+        points,
+        velocity_per_week,
+        projected_finish,
+    }
+}
+
+fn day(timestamp: &str) -> Option<NaiveDate> {
+    parse_timestamp(timestamp).map(|t| t.date_naive())
+}
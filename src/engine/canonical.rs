@@ -0,0 +1,27 @@
+//! Canonical JSON: Byte-stable serialization for exports that live in git.
+//!
+//! Object keys are sorted alphabetically regardless of struct field order, so
+//! re-exporting unchanged data yields identical bytes and a changed record
+//! produces a minimal diff. (The proof chain payload predates this and keeps
+//! its own field order; changing it would break every stored hash.)
+
+use anyhow::Result;
+use serde::Serialize;
+
+/// Pretty-printed JSON with sorted keys and a trailing newline.
+///
+/// # Errors
+/// Returns error if the value cannot be represented as JSON.
+pub fn to_pretty<T: Serialize>(value: &T) -> Result<String> {
+    let mut out = serde_json::to_string_pretty(&serde_json::to_value(value)?)?;
+    out.push('\n');
+    Ok(out)
+}
+
+/// Compact single-line JSON with sorted keys, for JSON Lines output.
+///
+/// # Errors
+/// Returns error if the value cannot be represented as JSON.
+pub fn to_line<T: Serialize>(value: &T) -> Result<String> {
+    Ok(serde_json::to_string(&serde_json::to_value(value)?)?)
+}
@@ -0,0 +1,204 @@
+//! Proof Chain: Tamper-evident hash linking of the proof log.
+//!
+//! Every proof stores `hash = sha256(prev_hash || payload)`, where `prev_hash`
+//! is the hash of the proof recorded immediately before it. Editing, deleting,
+//! or reordering any historical row breaks every link after it.
+
+use super::bench::Metrics;
+use super::budget::BudgetResult;
+use super::fingerprint::Environment;
+use super::hooks::HookRun;
+use super::types::Proof;
+use super::usage::Usage;
+use schemars::JsonSchema;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// The `prev_hash` of the first proof in a chain.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// The evidence fields covered by the chain, in canonical order.
+///
+/// Fields added after chaining shipped are omitted when empty, so proofs
+/// recorded before they existed keep hashing to the same value.
+#[derive(Serialize)]
+struct Payload<'a> {
+    task_id: i64,
+    cmd: &'a str,
+    exit_code: i32,
+    git_sha: &'a str,
+    duration_ms: u64,
+    timestamp: &'a str,
+    attested_reason: Option<&'a str>,
+    stdout: &'a str,
+    stderr: &'a str,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    hooks: &'a [HookRun],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coverage: Option<f64>,
+    #[serde(skip_serializing_if = "Metrics::is_empty")]
+    metrics: &'a Metrics,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    budgets: &'a [BudgetResult],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failure: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    origin: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attempts: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    environment: Option<&'a Environment>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    artifacts: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    truncated: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<&'a Usage>,
+}
+
+/// Serializes the canonical payload for a proof.
+#[must_use]
+pub fn payload(task_id: i64, proof: &Proof) -> String {
+    let payload = Payload {
+        task_id,
+        cmd: &proof.cmd,
+        exit_code: proof.exit_code,
+        git_sha: &proof.git_sha,
+        duration_ms: proof.duration_ms,
+        timestamp: &proof.timestamp,
+        attested_reason: proof.attested_reason.as_deref(),
+        stdout: &proof.stdout,
+        stderr: &proof.stderr,
+        hooks: &proof.hooks,
+        coverage: proof.coverage,
+        metrics: &proof.metrics,
+        budgets: &proof.budgets,
+        failure: proof.failure.as_deref(),
+        origin: proof.origin.as_deref(),
+        attempts: proof.attempts,
+        environment: proof.environment.as_ref(),
+        artifacts: &proof.artifacts,
+        truncated: proof.truncated,
+        usage: proof.usage.as_ref(),
+    };
+    serde_json::to_string(&payload).unwrap_or_default()
+}
+
+/// Computes the chain hash of a proof given its predecessor's hash.
+#[must_use]
+pub fn compute_hash(prev_hash: &str, task_id: i64, proof: &Proof) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(prev_hash.as_bytes());
+    hasher.update(payload(task_id, proof).as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// A proof row as stored, including its chain columns.
+pub struct ChainLink {
+    pub proof_id: i64,
+    pub task_id: i64,
+    pub proof: Proof,
+    pub prev_hash: Option<String>,
+    pub hash: Option<String>,
+}
+
+/// The chain link left in place of a deleted proof.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Tombstone {
+    pub proof_id: i64,
+    pub task_id: i64,
+    #[serde(skip)]
+    pub prev_hash: Option<String>,
+    #[serde(skip)]
+    pub hash: Option<String>,
+    /// Why the proof was deleted, e.g. its task was removed.
+    pub reason: String,
+    pub removed_at: String,
+}
+
+/// A single integrity violation found while walking the chain.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ChainIssue {
+    pub proof_id: i64,
+    pub problem: String,
+}
+
+/// Outcome of verifying the proof chain.
+#[derive(Debug, Default, Serialize, JsonSchema)]
+pub struct ChainReport {
+    pub checked: usize,
+    pub legacy: usize,
+    /// Proofs deleted on purpose; their tombstones bridge the gap they left.
+    pub removed: Vec<Tombstone>,
+    pub issues: Vec<ChainIssue>,
+}
+
+impl ChainReport {
+    #[must_use]
+    pub fn is_intact(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn issue(&mut self, proof_id: i64, problem: &str) {
+        self.issues.push(ChainIssue {
+            proof_id,
+            problem: problem.to_string(),
+        });
+    }
+}
+
+/// Walks proofs in insertion order and reports broken or forged links.
+///
+/// Unhashed rows that precede the first hashed row pre-date chaining and are
+/// counted as legacy; an unhashed row after the chain starts is an issue.
+/// A tombstone stands in for its deleted proof: its link must fit, though
+/// the contents it covered can no longer be checked.
+#[must_use]
+pub fn verify(links: &[ChainLink], tombstones: &[Tombstone]) -> ChainReport {
+    let mut report = ChainReport::default();
+    let mut expected_prev: Option<String> = None;
+    let mut tombstones = tombstones.iter().peekable();
+
+    for link in links {
+        while let Some(tombstone) = tombstones.next_if(|t| t.proof_id < link.proof_id) {
+            bridge(&mut report, &mut expected_prev, tombstone);
+        }
+        let Some(hash) = &link.hash else {
+            if expected_prev.is_some() {
+                report.issue(link.proof_id, "missing hash after chain start");
+            } else {
+                report.legacy += 1;
+            }
+            continue;
+        };
+
+        report.checked += 1;
+        let prev = link.prev_hash.as_deref().unwrap_or_default();
+        let anchor = expected_prev.as_deref().unwrap_or(GENESIS_HASH);
+        if prev != anchor {
+            report.issue(link.proof_id, "prev_hash does not match preceding proof");
+        }
+        if *hash != compute_hash(prev, link.task_id, &link.proof) {
+            report.issue(link.proof_id, "hash does not match recorded contents");
+        }
+        expected_prev = Some(hash.clone());
+    }
+    for tombstone in tombstones {
+        bridge(&mut report, &mut expected_prev, tombstone);
+    }
+
+    report
+}
+
+/// Steps over a deleted proof, checking only that its link fits.
+fn bridge(report: &mut ChainReport, expected_prev: &mut Option<String>, tombstone: &Tombstone) {
+    report.removed.push(tombstone.clone());
+    let Some(hash) = &tombstone.hash else {
+        return;
+    };
+    let anchor = expected_prev.as_deref().unwrap_or(GENESIS_HASH);
+    if tombstone.prev_hash.as_deref() != Some(anchor) {
+        report.issue(tombstone.proof_id, "removed proof's prev_hash does not match preceding proof");
+    }
+    *expected_prev = Some(hash.clone());
+}
@@ -0,0 +1,360 @@
+//! Configuration: Settings from the user and repository config files.
+//!
+//! `~/.config/roadmap/config.toml` holds personal defaults and
+//! `.roadmap/config.toml` the repository's; a key set in both takes the
+//! repository's value. Missing keys use built-in defaults.
+
+use super::db::DB_DIR;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const CONFIG_FILE: &str = "config.toml";
+
+/// Every settable key, as `section.name`.
+pub const KEYS: &[&str] = &[
+    "runner.shell",
+    "runner.timeout",
+    "runner.retries",
+    "runner.stream",
+    "runner.output_head",
+    "runner.output_tail",
+    "runner.spill_output",
+    "runner.container",
+    "runner.container_engine",
+    "sandbox.enabled",
+    "sandbox.network",
+    "sandbox.writable",
+    "sandbox.memory_mb",
+    "sandbox.cpu_secs",
+    "sandbox.processes",
+    "resolve.strict",
+    "check.lenient_attestation",
+    "hooks.pre",
+    "hooks.post",
+    "remote.url",
+    "remote.push_on_check",
+    "remote.pull_on_status",
+    "retention.keep_last",
+    "retention.older_than",
+    "theme.palette",
+    "theme.color",
+    "user.name",
+];
+
+/// Which config file a setting is written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// `.roadmap/config.toml`, shared with the repository.
+    Repo,
+    /// `~/.config/roadmap/config.toml`, for every repository of this user.
+    User,
+}
+
+/// The merged settings. Missing keys use defaults.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub runner: RunnerSettings,
+    pub sandbox: SandboxSettings,
+    pub resolve: ResolveSettings,
+    pub check: CheckSettings,
+    pub hooks: HookSettings,
+    pub remote: RemoteSettings,
+    pub retention: RetentionSettings,
+    pub theme: ThemeSettings,
+    pub user: UserSettings,
+}
+
+/// The `[runner]` section.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunnerSettings {
+    /// Default shell for verification commands (overridden per task).
+    pub shell: Option<String>,
+    /// Seconds a verification command may run before it is killed.
+    pub timeout: u64,
+    /// Extra runs a failing verification command gets before it counts as broken.
+    pub retries: u32,
+    /// Show verification output on stderr while it runs, not only in the proof.
+    pub stream: bool,
+    /// Bytes of each output stream a proof keeps from the start.
+    pub output_head: usize,
+    /// Bytes of each output stream a proof keeps from the end.
+    pub output_tail: usize,
+    /// Keep the full output of a truncated stream as an artifact of the run.
+    pub spill_output: bool,
+    /// Image to run verification commands in, instead of on the host.
+    pub container: Option<String>,
+    /// `docker` or `podman`; the first one on PATH when unset.
+    pub container_engine: Option<String>,
+}
+
+impl Default for RunnerSettings {
+    fn default() -> Self {
+        Self {
+            shell: None,
+            timeout: 300,
+            retries: 0,
+            stream: false,
+            output_head: 64 * 1024,
+            output_tail: 64 * 1024,
+            spill_output: false,
+            container: None,
+            container_engine: None,
+        }
+    }
+}
+
+/// The `[resolve]` section: how task references are matched.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResolveSettings {
+    /// Require an exact ID or slug in `do` and `why`, as if `--strict` were always passed.
+    pub strict: bool,
+}
+
+/// The `[sandbox]` section: confinement for verification commands (see [`super::sandbox`]).
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SandboxSettings {
+    /// Confine every verification command, as if `check --sandbox` were always passed.
+    pub enabled: bool,
+    /// Let confined commands use the network.
+    pub network: bool,
+    /// Directories confined commands may write besides their artifacts and temp, e.g. `target`.
+    pub writable: Vec<String>,
+    /// Memory a confined command may use, in megabytes.
+    pub memory_mb: Option<u64>,
+    /// CPU time a confined command may use, in seconds.
+    pub cpu_secs: Option<u64>,
+    /// Processes a confined command may run at once.
+    pub processes: Option<u64>,
+}
+
+/// The `[check]` section.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CheckSettings {
+    /// Allow `check --force` without `--reason`.
+    pub lenient_attestation: bool,
+}
+
+impl Default for CheckSettings {
+    fn default() -> Self {
+        Self {
+            lenient_attestation: true,
+        }
+    }
+}
+
+/// The `[hooks]` section: commands run around every verification.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HookSettings {
+    /// Setup commands; a failure aborts the check without recording a proof.
+    pub pre: Vec<String>,
+    /// Teardown commands; always run, failures are recorded but don't change the verdict.
+    pub post: Vec<String>,
+}
+
+/// The `[remote]` section: a shared proof store for the team.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteSettings {
+    /// `http(s)://` endpoint, `file://` URL, or directory path. Unset disables sharing.
+    pub url: Option<String>,
+    /// Publish proofs as soon as `check` records them.
+    pub push_on_check: bool,
+    /// Fetch teammates' proofs before `status` renders.
+    pub pull_on_status: bool,
+}
+
+impl Default for RemoteSettings {
+    fn default() -> Self {
+        Self {
+            url: None,
+            push_on_check: true,
+            pull_on_status: true,
+        }
+    }
+}
+
+/// The `[retention]` section: old proofs pruned after every check. Unset keeps everything.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionSettings {
+    /// Proofs kept per task, newest first.
+    pub keep_last: Option<usize>,
+    /// Age past which proofs are dropped, e.g. `90d` or `12w`.
+    pub older_than: Option<String>,
+}
+
+/// The `[theme]` section: how human-readable output is colored.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeSettings {
+    pub palette: Palette,
+    pub color: ColorMode,
+}
+
+/// The `[user]` section: who "me" is on a shared roadmap.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserSettings {
+    /// Name matched against task assignees; defaults to git's `user.name`.
+    pub name: Option<String>,
+}
+
+/// Whether to color output at all. `NO_COLOR` and `CLICOLOR_FORCE` still win.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    /// Color when writing to a terminal.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// A named color scheme for terminal output.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Palette {
+    /// Classic red/green/yellow terminal colors.
+    #[default]
+    Default,
+    /// Okabe-Ito hues that stay distinguishable under red-green color blindness.
+    Colorblind,
+    /// No hues at all; emphasis is carried by bold and dim only.
+    Mono,
+}
+
+impl Config {
+    /// Loads the user config overlaid with the repository config, or defaults if neither exists.
+    ///
+    /// # Errors
+    /// Returns an error if a file exists but cannot be read or parsed.
+    pub fn load() -> Result<Self> {
+        let mut merged = toml::Table::new();
+        for path in [Self::user_path(), Some(Self::repo_path())].into_iter().flatten() {
+            if let Some(table) = read_table(&path)? {
+                overlay(&mut merged, table);
+            }
+        }
+        toml::Value::Table(merged)
+            .try_into()
+            .context("Invalid config")
+    }
+
+    /// The current user's name: `user.name` from config, else git's `user.name`.
+    #[must_use]
+    pub fn identity(&self) -> Option<String> {
+        let configured = self.user.name.as_deref().map(str::trim).filter(|n| !n.is_empty());
+        if let Some(name) = configured {
+            return Some(name.to_string());
+        }
+        let output = Command::new("git").args(["config", "user.name"]).output().ok()?;
+        let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !name.is_empty()).then_some(name)
+    }
+
+    /// `.roadmap/config.toml`, relative to the working directory.
+    #[must_use]
+    pub fn repo_path() -> PathBuf {
+        Path::new(DB_DIR).join(CONFIG_FILE)
+    }
+
+    /// The per-user config file, under `$XDG_CONFIG_HOME` or `~/.config`.
+    #[must_use]
+    pub fn user_path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME")
+                    .or_else(|| std::env::var_os("USERPROFILE"))
+                    .map(|home| PathBuf::from(home).join(".config"))
+            })?;
+        Some(base.join("roadmap").join(CONFIG_FILE))
+    }
+
+    /// The effective value of `key`, or `None` if it is unset and has no default.
+    ///
+    /// # Errors
+    /// Returns an error if the key is unknown or a config file is invalid.
+    pub fn get(key: &str) -> Result<Option<toml::Value>> {
+        let (section, name) = split_key(key)?;
+        let value = toml::Value::try_from(Self::load()?).context("Failed to render config")?;
+        Ok(value.get(section).and_then(|s| s.get(name)).cloned())
+    }
+
+    /// Sets `key` in the file for `scope`, keeping the rest of the file as written.
+    ///
+    /// `value` is read as TOML (`true`, `600`, `["a", "b"]`), falling back to
+    /// a plain string. Returns the file that was written.
+    ///
+    /// # Errors
+    /// Returns an error if the key is unknown, the value is invalid for it, or the file cannot be written.
+    pub fn set(scope: Scope, key: &str, value: &str) -> Result<PathBuf> {
+        let (section, name) = split_key(key)?;
+        let path = match scope {
+            Scope::Repo => {
+                if !Path::new(DB_DIR).exists() {
+                    bail!("Roadmap not initialized. Run `roadmap init` first, or use --global.");
+                }
+                Self::repo_path()
+            }
+            Scope::User => Self::user_path().context("Cannot locate the user config directory (HOME is not set)")?,
+        };
+        let raw = fs::read_to_string(&path).unwrap_or_default();
+        let mut doc: toml_edit::DocumentMut = raw
+            .parse()
+            .with_context(|| format!("Invalid config in {}", path.display()))?;
+        let value = value
+            .parse::<toml_edit::Value>()
+            .unwrap_or_else(|_| toml_edit::Value::from(value));
+        doc.entry(section).or_insert(toml_edit::table())[name] = toml_edit::value(value);
+
+        let rendered = doc.to_string();
+        toml::from_str::<Self>(&rendered)
+            .with_context(|| format!("Invalid value for {key}"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        fs::write(&path, rendered).with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+fn split_key(key: &str) -> Result<(&str, &str)> {
+    match key.split_once('.') {
+        Some(parts) if KEYS.contains(&key) => Ok(parts),
+        _ => bail!("Unknown config key '{key}' (expected: {})", KEYS.join(", ")),
+    }
+}
+
+/// Reads a config file as a table, checking it on its own so errors name the file.
+fn read_table(path: &Path) -> Result<Option<toml::Table>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str::<Config>(&raw).with_context(|| format!("Invalid config in {}", path.display()))?;
+    let table = toml::from_str(&raw).with_context(|| format!("Invalid config in {}", path.display()))?;
+    Ok(Some(table))
+}
+
+/// Copies `top` over `base`, merging tables key by key.
+fn overlay(base: &mut toml::Table, top: toml::Table) {
+    for (key, value) in top {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(inner)), toml::Value::Table(top_inner)) => overlay(inner, top_inner),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
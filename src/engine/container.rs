@@ -0,0 +1,140 @@
+//! Container: Running verification commands inside docker or podman.
+//!
+//! With an image configured, the command runs in a throwaway container
+//! instead of on the host. The repository is mounted read-only at its own
+//! path, so the command sees the same files at the same place; only the
+//! run's artifact directory is writable. The image's digest is recorded in
+//! the proof's environment, which pins exactly what the evidence ran on.
+
+use super::fingerprint::{self, Environment};
+use super::shell;
+use anyhow::{bail, Context, Result};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Container engines tried, in order, when none is configured.
+const ENGINES: &[&str] = &["docker", "podman"];
+
+/// The image verification commands run in.
+#[derive(Debug, Clone)]
+pub struct Container {
+    pub image: String,
+    /// `docker` or `podman`; found on PATH when unset.
+    pub engine: Option<String>,
+}
+
+/// A command prepared to run in a container, and the name to stop it by.
+pub struct Prepared {
+    pub command: Command,
+    pub name: String,
+}
+
+impl Container {
+    #[must_use]
+    pub fn new(image: &str, engine: Option<&str>) -> Self {
+        Self {
+            image: image.to_string(),
+            engine: engine.map(str::to_string),
+        }
+    }
+
+    /// The engine binary to run.
+    ///
+    /// # Errors
+    /// Returns an error if none is configured and neither docker nor podman is installed.
+    pub fn engine(&self) -> Result<String> {
+        if let Some(engine) = &self.engine {
+            return Ok(engine.clone());
+        }
+        ENGINES
+            .iter()
+            .find(|e| shell::which(e).is_some())
+            .map(|e| (*e).to_string())
+            .context("Container verification needs docker or podman on PATH (or set runner.container_engine)")
+    }
+
+    /// Wraps `inner` (a shell invocation built for the host) in `<engine> run`.
+    ///
+    /// `root` is mounted read-only and `writable` read-write, each at its own
+    /// path; the command starts in `workdir` with `envs` set.
+    ///
+    /// # Errors
+    /// Returns an error if no engine is available.
+    pub fn wrap(
+        &self,
+        inner: &Command,
+        root: &Path,
+        workdir: &Path,
+        writable: &[PathBuf],
+        envs: &[(String, String)],
+    ) -> Result<Prepared> {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let name = format!(
+            "roadmap-{}-{}",
+            std::process::id(),
+            SEQUENCE.fetch_add(1, Ordering::Relaxed)
+        );
+        let mut command = Command::new(self.engine()?);
+        command
+            .args(["run", "--rm", "--name", &name])
+            .arg("-v")
+            .arg(format!("{}:{}:ro", root.display(), root.display()));
+        for dir in writable {
+            command.arg("-v").arg(format!("{}:{}", dir.display(), dir.display()));
+        }
+        command.arg("-w").arg(workdir);
+        for (key, value) in envs {
+            command.arg("-e").arg(format!("{key}={value}"));
+        }
+        command
+            .arg(&self.image)
+            .arg(inner.get_program())
+            .args(inner.get_args().map(OsStr::to_os_string));
+        Ok(Prepared { command, name })
+    }
+
+    /// Stops a container started by [`Self::wrap`]; killing the engine client alone leaves it running.
+    pub fn kill(&self, name: &str) {
+        if let Ok(engine) = self.engine() {
+            let _ = Command::new(engine)
+                .args(["kill", name])
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status();
+        }
+    }
+
+    /// The image pinned by digest (`image@sha256:...`), or its ID for a local build.
+    ///
+    /// # Errors
+    /// Returns an error if the engine cannot inspect the image.
+    pub fn pinned(&self) -> Result<String> {
+        let output = Command::new(self.engine()?)
+            .args(["image", "inspect", "--format", "{{join .RepoDigests \",\"}}|{{.Id}}", &self.image])
+            .output()
+            .context("Failed to inspect the container image")?;
+        if !output.status.success() {
+            bail!(
+                "Failed to inspect image '{}': {}",
+                self.image,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let text = String::from_utf8_lossy(&output.stdout);
+        let (digests, id) = text.trim().split_once('|').unwrap_or(("", text.trim()));
+        Ok(digests
+            .split(',')
+            .find(|d| !d.is_empty())
+            .map_or_else(|| format!("{}@{id}", self.image), str::to_string))
+    }
+
+    /// The environment a proof records for a run in this container.
+    ///
+    /// Falls back to the image as named when it cannot be pinned.
+    #[must_use]
+    pub fn environment(&self) -> Environment {
+        fingerprint::in_container(self.pinned().unwrap_or_else(|_| self.image.clone()))
+    }
+}
@@ -1,5 +1,6 @@
 //! Repository Context: The oracle for repo state and file changes.
 
+use super::db::DB_DIR;
 use anyhow::Result;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -49,7 +50,7 @@ impl RepoContext {
         &self.head_sha
     }
 
-    /// Checks if files matching the given scopes have changed between `since_sha` and HEAD.
+    /// Checks if files matching the given scopes have changed between `since_sha` and the context's HEAD.
     ///
     /// # Returns
     /// - `true` if changes are detected or if git fails (safe default).
@@ -75,19 +76,46 @@ impl RepoContext {
         }
 
         // Cache Miss: Run Git
-        let has_change = Self::run_git_diff(since_sha, scopes);
+        let has_change = Self::run_git_diff(since_sha, &self.head_sha, scopes);
         
         // Store Result
         self.cache.borrow_mut().insert(key, has_change);
         has_change
     }
 
-    fn run_git_diff(since_sha: &str, scopes: &[String]) -> bool {
+    /// Checks if files matching `scopes` changed since `since_sha`, ignoring `excluded` paths.
+    ///
+    /// Empty `scopes` means the whole tree. Returns `true` if git fails (safe default).
+    #[must_use]
+    pub fn has_changes_outside(&self, since_sha: &str, scopes: &[String], excluded: &[String]) -> bool {
+        if since_sha == self.head_sha {
+            return false;
+        }
+
+        let _span = tracing::info_span!("git", op = "diff").entered();
+        let mut cmd = Command::new("git");
+        cmd.args(["diff", "--quiet", since_sha, &self.head_sha, "--"]);
+        if scopes.is_empty() {
+            cmd.arg(".");
+        }
+        cmd.args(scopes);
+        for path in excluded {
+            cmd.arg(format!(":(exclude){path}"));
+        }
+
+        match cmd.status() {
+            Ok(status) => !status.success(),
+            Err(_) => true,
+        }
+    }
+
+    fn run_git_diff(since_sha: &str, head_sha: &str, scopes: &[String]) -> bool {
+        let _span = tracing::info_span!("git", op = "diff").entered();
         let mut cmd = Command::new("git");
         cmd.arg("diff")
            .arg("--quiet")
            .arg(since_sha)
-           .arg("HEAD")
+           .arg(head_sha)
            .arg("--");
         
         for scope in scopes {
@@ -102,6 +130,7 @@ impl RepoContext {
 }
 
 fn get_git_sha() -> String {
+    let _span = tracing::info_span!("git", op = "rev-parse").entered();
     Command::new("git")
         .args(["rev-parse", "HEAD"])
         .output()
@@ -110,10 +139,15 @@ fn get_git_sha() -> String {
         .map_or_else(|| "unknown".to_string(), |s| s.trim().to_string())
 }
 
+/// Whether the working tree has uncommitted changes outside `.roadmap/`.
+///
+/// The roadmap's own files (e.g. the committed state mirror, rewritten by
+/// every `check`) are bookkeeping about the code, not part of it.
 fn check_if_dirty() -> bool {
+    let _span = tracing::info_span!("git", op = "status").entered();
     match Command::new("git")
-        .arg("status")
-        .arg("--porcelain")
+        .args(["status", "--porcelain", "--", "."])
+        .arg(format!(":(exclude){DB_DIR}"))
         .output()
     {
         Ok(o) => !o.stdout.is_empty(),
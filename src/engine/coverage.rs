@@ -0,0 +1,51 @@
+//! Coverage Parsing: Extracts line coverage from lcov and Cobertura reports.
+
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// Reads a coverage report and returns line coverage as a percentage.
+///
+/// The format is detected from content: Cobertura XML or lcov tracefile.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or contains no coverage data.
+pub fn read_percentage(path: &Path) -> Result<f64> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read coverage report {}", path.display()))?;
+
+    let parsed = if raw.contains("<coverage") {
+        parse_cobertura(&raw)
+    } else {
+        parse_lcov(&raw)
+    };
+
+    match parsed {
+        Some(pct) => Ok(pct),
+        None => bail!("No line coverage data found in {}", path.display()),
+    }
+}
+
+/// Sums `LF` (lines found) and `LH` (lines hit) across all lcov records.
+#[allow(clippy::cast_precision_loss)]
+fn parse_lcov(raw: &str) -> Option<f64> {
+    let (mut found, mut hit) = (0u64, 0u64);
+    for line in raw.lines() {
+        if let Some(n) = line.strip_prefix("LF:") {
+            found += n.trim().parse::<u64>().ok()?;
+        } else if let Some(n) = line.strip_prefix("LH:") {
+            hit += n.trim().parse::<u64>().ok()?;
+        }
+    }
+    (found > 0).then(|| hit as f64 / found as f64 * 100.0)
+}
+
+/// Reads the `line-rate` attribute of the root `<coverage>` element.
+fn parse_cobertura(raw: &str) -> Option<f64> {
+    let root = &raw[raw.find("<coverage")?..];
+    let root = &root[..root.find('>')?];
+    let attr = &root[root.find("line-rate=")? + "line-rate=".len()..];
+    let quote = attr.chars().next()?;
+    let value = attr[1..].split(quote).next()?;
+    value.parse::<f64>().ok().map(|rate| rate * 100.0)
+}
@@ -1,13 +1,29 @@
 //! Database initialization and connection management.
 
+use super::oplog;
+use crate::Error;
 use anyhow::{Context, Result};
-use rusqlite::Connection;
+use rusqlite::{Connection, ErrorCode, Transaction, TransactionBehavior};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
 
-const DB_DIR: &str = ".roadmap";
+pub mod migrations;
+
+/// Directory holding all roadmap state for a repository.
+pub const DB_DIR: &str = ".roadmap";
 const DB_FILE: &str = "state.db";
 
+/// How long a statement waits for another process's write lock before failing.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Tries at taking the write lock, each waiting up to [`BUSY_TIMEOUT`].
+const WRITE_ATTEMPTS: u32 = 3;
+
+/// The named roadmap this process works on; unset means the default one.
+static NAMESPACE: OnceLock<String> = OnceLock::new();
+
 pub struct Db;
 
 impl Db {
@@ -21,11 +37,11 @@ impl Db {
             fs::create_dir(DB_DIR).context("Failed to create .roadmap directory")?;
         }
 
-        let db_path = Path::new(DB_DIR).join(DB_FILE);
-        let conn = Connection::open(db_path).context("Failed to open database")?;
+        let conn = Connection::open(Self::path()).context("Failed to open database")?;
 
         Self::configure(&conn)?;
         Self::migrate(&conn)?;
+        oplog::install(&conn)?;
 
         Ok(())
     }
@@ -33,99 +49,155 @@ impl Db {
     /// Connects to an existing database and ensures schema is up-to-date.
     ///
     /// # Errors
-    /// Returns an error if the database file does not exist or cannot be opened.
+    /// Returns an error if the database file does not exist or cannot be opened or migrated.
     pub fn connect() -> Result<Connection> {
-        let db_path = Path::new(DB_DIR).join(DB_FILE);
+        let _span = tracing::info_span!("db.open").entered();
+        Self::open(&Self::existing_path()?)
+    }
+
+    /// Connects to an existing database as it is, without migrating it.
+    ///
+    /// For inspecting the schema itself (`roadmap migrate --dry-run`); the
+    /// repositories assume a current schema.
+    ///
+    /// # Errors
+    /// Returns an error if the database file does not exist or cannot be opened.
+    pub fn connect_unmigrated() -> Result<Connection> {
+        let conn = Connection::open(Self::existing_path()?).context("Failed to open database")?;
+        Self::configure(&conn)?;
+        Ok(conn)
+    }
+
+    fn existing_path() -> Result<PathBuf> {
+        let db_path = Self::path();
         if !db_path.exists() {
-            anyhow::bail!("Roadmap not initialized. Run `roadmap init` first.");
+            anyhow::bail!(Error::NotInitialized {
+                roadmap: Self::namespace().map(str::to_string),
+            });
+        }
+        Ok(db_path)
+    }
+
+    /// Location of the roadmap database, relative to the working directory.
+    #[must_use]
+    pub fn path() -> PathBuf {
+        Path::new(DB_DIR).join(Self::file_name(DB_FILE))
+    }
+
+    /// Selects a named roadmap (e.g. `backend`) for the rest of the process.
+    ///
+    /// Named roadmaps share `.roadmap/` and its config but keep their own
+    /// database, tasks, and active task. `default` is the unnamed roadmap.
+    ///
+    /// # Errors
+    /// Returns an error if the name is not a lowercase slug or a different roadmap was already selected.
+    pub fn use_namespace(name: &str) -> Result<()> {
+        let valid = name.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if !valid {
+            anyhow::bail!("Invalid roadmap name '{name}' (use lowercase letters, digits, '-', '_')");
+        }
+        if name == "default" {
+            return Ok(());
+        }
+        if NAMESPACE.get_or_init(|| name.to_string()) != name {
+            anyhow::bail!("Another roadmap is already selected");
+        }
+        Ok(())
+    }
+
+    /// The selected named roadmap, or `None` for the default one.
+    #[must_use]
+    pub fn namespace() -> Option<&'static str> {
+        NAMESPACE.get().map(String::as_str)
+    }
+
+    /// `file` with the selected roadmap's name inserted before its extension
+    /// (`state.db` becomes `state.backend.db`).
+    #[must_use]
+    pub fn file_name(file: &str) -> String {
+        match (Self::namespace(), file.rsplit_once('.')) {
+            (Some(ns), Some((stem, ext))) => format!("{stem}.{ns}.{ext}"),
+            (Some(ns), None) => format!("{file}.{ns}"),
+            (None, _) => file.to_string(),
         }
-        let conn = Connection::open(db_path).context("Failed to open database")?;
-        
+    }
+
+    /// Opens (creating if needed) a database at an arbitrary path with the current schema.
+    ///
+    /// Used for scratch databases such as benchmark fixtures.
+    ///
+    /// # Errors
+    /// Returns an error if the database cannot be opened or migrated.
+    pub fn open(path: &Path) -> Result<Connection> {
+        let conn = Connection::open(path).context("Failed to open database")?;
+
         Self::configure(&conn)?;
         Self::migrate(&conn)?;
-        
+        oplog::install(&conn)?;
+
         Ok(conn)
     }
 
+    /// Begins a transaction that takes the write lock up front.
+    ///
+    /// A deferred transaction that reads first and writes later fails outright
+    /// when another process wrote in between (the busy timeout does not apply
+    /// to that upgrade), so every write path starts here instead. While another
+    /// process holds the lock, this waits for it, retrying with backoff.
+    ///
+    /// # Errors
+    /// Returns an error if the lock stays taken through every attempt.
+    pub fn begin_write(conn: &Connection) -> Result<Transaction<'_>> {
+        Self::retry_busy(|| Transaction::new_unchecked(conn, TransactionBehavior::Immediate))
+    }
+
+    /// Runs a database operation, retrying with backoff while another process
+    /// holds the write lock past the busy timeout.
+    ///
+    /// # Errors
+    /// Returns the operation's error, explained if the database stayed busy.
+    pub fn retry_busy<T>(mut op: impl FnMut() -> rusqlite::Result<T>) -> Result<T> {
+        let mut attempt = 1;
+        loop {
+            match op() {
+                Err(e) if is_busy(&e) && attempt < WRITE_ATTEMPTS => {
+                    std::thread::sleep(Duration::from_millis(100 << attempt));
+                    attempt += 1;
+                }
+                Err(e) if is_busy(&e) => {
+                    return Err(anyhow::Error::new(e).context(Error::Busy));
+                }
+                result => return Ok(result?),
+            }
+        }
+    }
+
     /// Configures `SQLite` connection for integrity and concurrency.
+    ///
+    /// WAL lets readers (an editor plugin, `status`) run while a check writes.
     fn configure(conn: &Connection) -> Result<()> {
+        conn.busy_timeout(BUSY_TIMEOUT)?;
         conn.execute_batch(
             "PRAGMA foreign_keys = ON;
              PRAGMA journal_mode = WAL;
-             PRAGMA busy_timeout = 5000;",
+             PRAGMA synchronous = NORMAL;",
         )?;
         Ok(())
     }
 
-    /// Applies schema migrations.
+    /// Brings the schema up to date, applying pending migrations.
     fn migrate(conn: &Connection) -> Result<()> {
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS tasks (
-                id INTEGER PRIMARY KEY,
-                slug TEXT UNIQUE NOT NULL,
-                title TEXT NOT NULL,
-                status TEXT NOT NULL,
-                test_cmd TEXT,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS task_scopes (
-                id INTEGER PRIMARY KEY,
-                task_id INTEGER NOT NULL,
-                glob TEXT NOT NULL,
-                FOREIGN KEY(task_id) REFERENCES tasks(id)
-            )",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS proofs (
-                id INTEGER PRIMARY KEY,
-                task_id INTEGER NOT NULL,
-                cmd TEXT NOT NULL,
-                exit_code INTEGER NOT NULL,
-                git_sha TEXT NOT NULL,
-                duration_ms INTEGER NOT NULL,
-                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
-                attested_reason TEXT,
-                FOREIGN KEY(task_id) REFERENCES tasks(id)
-            )",
-            [],
-        )?;
-
-        // Migration: Add stdout/stderr to proofs if missing (v0.3.1)
-        let has_logs: bool = conn
-            .prepare("SELECT stdout FROM proofs LIMIT 1")
-            .is_ok();
-        
-        if !has_logs {
-            // We use default empty string for existing records
-            let _ = conn.execute("ALTER TABLE proofs ADD COLUMN stdout TEXT DEFAULT ''", []);
-            let _ = conn.execute("ALTER TABLE proofs ADD COLUMN stderr TEXT DEFAULT ''", []);
-        }
-
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS dependencies (
-                blocker_id INTEGER,
-                blocked_id INTEGER,
-                PRIMARY KEY (blocker_id, blocked_id),
-                FOREIGN KEY(blocker_id) REFERENCES tasks(id),
-                FOREIGN KEY(blocked_id) REFERENCES tasks(id)
-            )",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS state (
-                key TEXT PRIMARY KEY,
-                value TEXT
-            )",
-            [],
-        )?;
-
+        migrations::apply(conn)?;
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+fn is_busy(error: &rusqlite::Error) -> bool {
+    matches!(
+        error.sqlite_error_code(),
+        Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
@@ -0,0 +1,439 @@
+//! Schema Migrations: Ordered, versioned steps from an empty file to the current schema.
+//!
+//! Each step runs once, in its own transaction, and is recorded in
+//! `schema_version`. Databases from before versioning have no such table;
+//! every step is written to tolerate what they already contain (tables are
+//! created if missing, columns added if missing), so they are simply brought
+//! forward like any other. New steps go at the end with the next version
+//! number; released steps are never edited or reordered.
+
+use anyhow::{bail, Result};
+use rusqlite::Connection;
+
+/// A schema change.
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    apply: fn(&Connection) -> Result<()>,
+}
+
+/// Every migration, in order. Versions are consecutive from 1.
+const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "tasks, scopes, proofs, and dependencies", apply: initial },
+    Migration { version: 2, name: "proof stdout and stderr", apply: proof_output },
+    Migration { version: 3, name: "per-task shell and platform commands", apply: task_shells },
+    Migration { version: 4, name: "coverage report and threshold", apply: task_coverage },
+    Migration { version: 5, name: "benchmark regression guard", apply: task_bench },
+    Migration { version: 6, name: "quarantine for flaky tasks", apply: task_quarantine },
+    Migration { version: 7, name: "task priority", apply: task_priority },
+    Migration { version: 8, name: "due dates", apply: task_due },
+    Migration { version: 9, name: "effort estimates", apply: task_estimate },
+    Migration { version: 10, name: "task descriptions", apply: task_description },
+    Migration { version: 11, name: "subtasks", apply: task_parent },
+    Migration { version: 12, name: "milestones", apply: milestones },
+    Migration { version: 13, name: "assignees", apply: task_assignee },
+    Migration { version: 14, name: "tags and resource budgets", apply: tags_and_budgets },
+    Migration { version: 15, name: "hash-chained proof log", apply: proof_chain },
+    Migration { version: 16, name: "proof signatures", apply: proof_signature },
+    Migration { version: 17, name: "hook runs on proofs", apply: proof_hooks },
+    Migration { version: 18, name: "measured coverage on proofs", apply: proof_coverage },
+    Migration { version: 19, name: "benchmark metrics and policy failures on proofs", apply: proof_metrics },
+    Migration { version: 20, name: "budget results on proofs", apply: proof_budgets },
+    Migration { version: 21, name: "provenance of imported proofs", apply: proof_origin },
+    Migration { version: 22, name: "attempts of retried verifications", apply: proof_attempts },
+    Migration { version: 23, name: "environment fingerprints", apply: proof_environment },
+    Migration { version: 24, name: "kept artifacts", apply: proof_artifacts },
+    Migration { version: 25, name: "task aliases and state", apply: aliases_and_state },
+    Migration { version: 26, name: "full-text search index", apply: search_index },
+    Migration { version: 27, name: "operation log for undo", apply: operation_log },
+    Migration { version: 28, name: "state mirror bookkeeping", apply: mirror_sync },
+    Migration { version: 29, name: "indexes for loading tasks", apply: task_indexes },
+    Migration { version: 30, name: "event journal", apply: event_journal },
+    Migration { version: 31, name: "per-task working directory", apply: task_cwd },
+    Migration { version: 32, name: "truncated proof output", apply: proof_truncation },
+    Migration { version: 33, name: "proof resource usage", apply: proof_usage },
+];
+
+/// The schema version this build writes.
+#[must_use]
+pub fn latest() -> u32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// The highest version applied to `conn`, 0 for a database from before versioning.
+///
+/// # Errors
+/// Returns an error if the version table exists but cannot be read.
+pub fn current(conn: &Connection) -> Result<u32> {
+    let versioned = conn
+        .prepare("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'schema_version'")?
+        .exists([])?;
+    if !versioned {
+        return Ok(0);
+    }
+    let version: Option<u32> = conn.query_row("SELECT MAX(version) FROM schema_version", [], |row| row.get(0))?;
+    Ok(version.unwrap_or(0))
+}
+
+/// The migrations `conn` still needs, in order.
+///
+/// # Errors
+/// Returns an error if the database was written by a newer roadmap or the version cannot be read.
+pub fn pending(conn: &Connection) -> Result<Vec<&'static Migration>> {
+    let current = current(conn)?;
+    if current > latest() {
+        bail!(crate::Error::SchemaTooNew {
+            found: current,
+            supported: latest(),
+        });
+    }
+    Ok(MIGRATIONS.iter().filter(|m| m.version > current).collect())
+}
+
+/// Applies every pending migration, each in its own transaction. Returns what was applied.
+///
+/// # Errors
+/// Returns an error naming the migration that failed; earlier ones stay applied.
+pub fn apply(conn: &Connection) -> Result<Vec<&'static Migration>> {
+    let pending = pending(conn)?;
+    if pending.is_empty() {
+        return Ok(pending);
+    }
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    let mut applied = Vec::new();
+    for migration in pending {
+        let tx = super::Db::begin_write(conn)?;
+        // Another process may have migrated while this one waited for the lock.
+        if current(&tx)? >= migration.version {
+            continue;
+        }
+        (migration.apply)(&tx).map_err(|e| {
+            e.context(format!("Schema migration {} ({}) failed", migration.version, migration.name))
+        })?;
+        tx.execute(
+            "INSERT INTO schema_version (version, name) VALUES (?1, ?2)",
+            rusqlite::params![migration.version, migration.name],
+        )?;
+        tx.commit()?;
+        applied.push(migration);
+    }
+    Ok(applied)
+}
+
+fn initial(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS tasks (
+            id INTEGER PRIMARY KEY,
+            slug TEXT UNIQUE NOT NULL,
+            title TEXT NOT NULL,
+            status TEXT NOT NULL,
+            test_cmd TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE TABLE IF NOT EXISTS task_scopes (
+            id INTEGER PRIMARY KEY,
+            task_id INTEGER NOT NULL,
+            glob TEXT NOT NULL,
+            FOREIGN KEY(task_id) REFERENCES tasks(id)
+        );
+        CREATE TABLE IF NOT EXISTS proofs (
+            id INTEGER PRIMARY KEY,
+            task_id INTEGER NOT NULL,
+            cmd TEXT NOT NULL,
+            exit_code INTEGER NOT NULL,
+            git_sha TEXT NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+            attested_reason TEXT,
+            FOREIGN KEY(task_id) REFERENCES tasks(id)
+        );
+        CREATE TABLE IF NOT EXISTS dependencies (
+            blocker_id INTEGER,
+            blocked_id INTEGER,
+            PRIMARY KEY (blocker_id, blocked_id),
+            FOREIGN KEY(blocker_id) REFERENCES tasks(id),
+            FOREIGN KEY(blocked_id) REFERENCES tasks(id)
+        );",
+    )?;
+    Ok(())
+}
+
+/// Existing records get empty output.
+fn proof_output(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "proofs", "stdout", "TEXT DEFAULT ''")?;
+    ensure_column(conn, "proofs", "stderr", "TEXT DEFAULT ''")
+}
+
+fn task_shells(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "tasks", "shell", "TEXT")?;
+    ensure_column(conn, "tasks", "test_cmd_unix", "TEXT")?;
+    ensure_column(conn, "tasks", "test_cmd_windows", "TEXT")
+}
+
+fn task_coverage(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "tasks", "coverage_path", "TEXT")?;
+    ensure_column(conn, "tasks", "coverage_min", "REAL")
+}
+
+fn task_bench(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "tasks", "bench_threshold", "REAL")
+}
+
+fn task_quarantine(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "tasks", "quarantine_reason", "TEXT")
+}
+
+fn task_priority(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "tasks", "priority", "INTEGER NOT NULL DEFAULT 0")
+}
+
+fn task_due(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "tasks", "due", "TEXT")
+}
+
+fn task_estimate(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "tasks", "estimate", "REAL")
+}
+
+fn task_description(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "tasks", "description", "TEXT")
+}
+
+fn task_parent(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "tasks", "parent_id", "INTEGER REFERENCES tasks(id)")
+}
+
+fn milestones(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS milestones (
+            id INTEGER PRIMARY KEY,
+            name TEXT UNIQUE NOT NULL,
+            title TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    ensure_column(conn, "tasks", "milestone_id", "INTEGER REFERENCES milestones(id)")
+}
+
+fn task_assignee(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "tasks", "assignee", "TEXT")
+}
+
+fn tags_and_budgets(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS task_tags (
+            id INTEGER PRIMARY KEY,
+            task_id INTEGER NOT NULL,
+            tag TEXT NOT NULL,
+            FOREIGN KEY(task_id) REFERENCES tasks(id)
+        );
+        CREATE TABLE IF NOT EXISTS task_budgets (
+            id INTEGER PRIMARY KEY,
+            task_id INTEGER NOT NULL,
+            spec TEXT NOT NULL,
+            FOREIGN KEY(task_id) REFERENCES tasks(id)
+        );",
+    )?;
+    Ok(())
+}
+
+/// Legacy rows stay NULL. A deleted proof leaves a tombstone with its link,
+/// so the rest of the chain still verifies.
+fn proof_chain(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "proofs", "prev_hash", "TEXT")?;
+    ensure_column(conn, "proofs", "hash", "TEXT")?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS proof_tombstones (
+            proof_id INTEGER PRIMARY KEY,
+            task_id INTEGER NOT NULL,
+            prev_hash TEXT,
+            hash TEXT,
+            reason TEXT NOT NULL,
+            removed_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );",
+    )?;
+    Ok(())
+}
+
+fn proof_signature(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "proofs", "signature", "TEXT")
+}
+
+/// Pre/post hook runs, as JSON.
+fn proof_hooks(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "proofs", "hooks", "TEXT")
+}
+
+fn proof_coverage(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "proofs", "coverage", "REAL")
+}
+
+fn proof_metrics(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "proofs", "metrics", "TEXT")?;
+    ensure_column(conn, "proofs", "failure", "TEXT")
+}
+
+fn proof_budgets(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "proofs", "budgets", "TEXT")
+}
+
+fn proof_origin(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "proofs", "origin", "TEXT")
+}
+
+fn proof_attempts(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "proofs", "attempts", "INTEGER")
+}
+
+fn proof_environment(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "proofs", "environment", "TEXT")
+}
+
+fn proof_artifacts(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "proofs", "artifacts", "TEXT")
+}
+
+fn aliases_and_state(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS task_aliases (
+            alias TEXT PRIMARY KEY,
+            task_id INTEGER NOT NULL,
+            FOREIGN KEY(task_id) REFERENCES tasks(id)
+        );
+        CREATE TABLE IF NOT EXISTS state (
+            key TEXT PRIMARY KEY,
+            value TEXT
+        );",
+    )?;
+    Ok(())
+}
+
+/// External content: the index reads rows from `tasks` and triggers keep it in step.
+/// An index from before descriptions were searchable is dropped and rebuilt.
+fn search_index(conn: &Connection) -> Result<()> {
+    let indexed = conn.prepare("SELECT description FROM task_search LIMIT 1").is_ok();
+    if !indexed {
+        conn.execute_batch(
+            "DROP TRIGGER IF EXISTS task_search_insert;
+             DROP TRIGGER IF EXISTS task_search_delete;
+             DROP TRIGGER IF EXISTS task_search_update;
+             DROP TABLE IF EXISTS task_search;",
+        )?;
+    }
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS task_search
+             USING fts5(slug, title, description, content = 'tasks', content_rowid = 'id');
+         CREATE TRIGGER IF NOT EXISTS task_search_insert AFTER INSERT ON tasks BEGIN
+             INSERT INTO task_search (rowid, slug, title, description)
+                 VALUES (new.id, new.slug, new.title, new.description);
+         END;
+         CREATE TRIGGER IF NOT EXISTS task_search_delete AFTER DELETE ON tasks BEGIN
+             INSERT INTO task_search (task_search, rowid, slug, title, description)
+                 VALUES ('delete', old.id, old.slug, old.title, old.description);
+         END;
+         CREATE TRIGGER IF NOT EXISTS task_search_update AFTER UPDATE OF slug, title, description ON tasks BEGIN
+             INSERT INTO task_search (task_search, rowid, slug, title, description)
+                 VALUES ('delete', old.id, old.slug, old.title, old.description);
+             INSERT INTO task_search (rowid, slug, title, description)
+                 VALUES (new.id, new.slug, new.title, new.description);
+         END;",
+    )?;
+    if !indexed {
+        conn.execute("INSERT INTO task_search (task_search) VALUES ('rebuild')", [])?;
+    }
+    Ok(())
+}
+
+/// Append-only operation log for `undo` (see `oplog`).
+fn operation_log(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS operations (
+            id INTEGER PRIMARY KEY,
+            batch TEXT UNIQUE NOT NULL,
+            command TEXT NOT NULL,
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+            reverts INTEGER
+        );
+        CREATE TABLE IF NOT EXISTS operation_steps (
+            id INTEGER PRIMARY KEY,
+            operation_id INTEGER NOT NULL,
+            undo_sql TEXT NOT NULL,
+            FOREIGN KEY(operation_id) REFERENCES operations(id)
+        );",
+    )?;
+    Ok(())
+}
+
+/// Bookkeeping for the git-mergeable mirror (see `mirror`). Not undoable
+/// on purpose: it records what is on disk, not roadmap state.
+fn mirror_sync(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS mirror_sync (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            digest TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Finding each task's latest proof and its scopes, tags, and budgets
+/// without scanning whole tables (see `TaskRepo::get_all`).
+fn task_indexes(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS proofs_by_task ON proofs (task_id, timestamp, id);
+        CREATE INDEX IF NOT EXISTS task_scopes_by_task ON task_scopes (task_id);
+        CREATE INDEX IF NOT EXISTS task_tags_by_task ON task_tags (task_id);
+        CREATE INDEX IF NOT EXISTS task_budgets_by_task ON task_budgets (task_id);
+        CREATE INDEX IF NOT EXISTS tasks_by_parent ON tasks (parent_id);",
+    )?;
+    Ok(())
+}
+
+/// Append-only record of commands run (see `journal`); not undoable, like the operation log.
+fn event_journal(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS events (
+            id INTEGER PRIMARY KEY,
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+            actor TEXT NOT NULL,
+            command TEXT NOT NULL,
+            ok INTEGER NOT NULL,
+            error TEXT,
+            duration_ms INTEGER NOT NULL,
+            batch TEXT
+        );",
+    )?;
+    Ok(())
+}
+
+fn task_cwd(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "tasks", "cwd", "TEXT")
+}
+
+fn proof_truncation(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "proofs", "output_truncated", "INTEGER")
+}
+
+fn proof_usage(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "proofs", "usage", "TEXT")
+}
+
+/// Adds a column to a table unless it already exists.
+fn ensure_column(conn: &Connection, table: &str, column: &str, decl: &str) -> Result<()> {
+    let exists = conn
+        .prepare(&format!("SELECT {column} FROM {table} LIMIT 1"))
+        .is_ok();
+    if !exists {
+        conn.execute(&format!("ALTER TABLE {table} ADD COLUMN {column} {decl}"), [])?;
+    }
+    Ok(())
+}
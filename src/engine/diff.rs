@@ -0,0 +1,121 @@
+//! Diff: How derived statuses changed between two commits.
+//!
+//! A task's status at a commit is derived from the newest proof recorded at
+//! that commit or one of its ancestors, judged as if that commit were HEAD.
+//! Proofs recorded later, or on branches the commit doesn't contain, are
+//! invisible to it. Tasks, scopes, and quarantines are taken as they are now,
+//! since the roadmap keeps no history of them.
+
+use super::context::RepoContext;
+use super::repo::ProofRepo;
+use super::types::{DerivedStatus, Proof, Task};
+use anyhow::{bail, Context, Result};
+use rusqlite::Connection;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+
+/// A task whose status differs between the two commits.
+pub struct StatusChange<'a> {
+    pub task: &'a Task,
+    pub before: DerivedStatus,
+    pub after: DerivedStatus,
+}
+
+/// Resolves a revision (SHA, branch, tag, `HEAD~3`) to a full commit SHA.
+///
+/// # Errors
+/// Returns error if git cannot resolve it to a commit.
+pub fn resolve(rev: &str) -> Result<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet", &format!("{rev}^{{commit}}")])
+        .output()
+        .context("Failed to run git rev-parse")?;
+    if !output.status.success() {
+        bail!("Unknown commit '{rev}'");
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Compares every task's status at commit `from` with its status at `to`,
+/// both full SHAs, returning only the tasks that changed, in the order given.
+///
+/// # Errors
+/// Returns error if git cannot list a commit's history or the database query fails.
+pub fn between<'a>(conn: &Connection, tasks: &'a [Task], from: &str, to: &str) -> Result<Vec<StatusChange<'a>>> {
+    let repo = ProofRepo::new(conn);
+    let mut histories = HashMap::new();
+    for task in tasks {
+        collect_histories(&repo, task, &mut histories)?;
+    }
+    let before = Snapshot::at(from, &histories)?;
+    let after = Snapshot::at(to, &histories)?;
+
+    Ok(tasks
+        .iter()
+        .filter_map(|task| {
+            let change = StatusChange {
+                task,
+                before: before.status(task),
+                after: after.status(task),
+            };
+            (change.before != change.after).then_some(change)
+        })
+        .collect())
+}
+
+/// Loads the proof history of `task` and its subtasks, at any depth.
+fn collect_histories(repo: &ProofRepo<'_>, task: &Task, histories: &mut HashMap<i64, Vec<Proof>>) -> Result<()> {
+    if let Entry::Vacant(entry) = histories.entry(task.id) {
+        entry.insert(repo.get_history(task.id)?);
+    }
+    for subtask in &task.subtasks {
+        collect_histories(repo, subtask, histories)?;
+    }
+    Ok(())
+}
+
+/// The roadmap as of one commit.
+struct Snapshot<'h> {
+    context: RepoContext,
+    /// The commit and every one of its ancestors.
+    reachable: HashSet<String>,
+    /// Newest first, as `ProofRepo::get_history` returns them.
+    histories: &'h HashMap<i64, Vec<Proof>>,
+}
+
+impl<'h> Snapshot<'h> {
+    fn at(sha: &str, histories: &'h HashMap<i64, Vec<Proof>>) -> Result<Self> {
+        let output = Command::new("git")
+            .args(["rev-list", sha])
+            .output()
+            .context("Failed to run git rev-list")?;
+        if !output.status.success() {
+            bail!(
+                "Failed to list the history of {sha}: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(Self {
+            context: RepoContext::from_sha(sha.to_string()),
+            reachable: String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect(),
+            histories,
+        })
+    }
+
+    fn status(&self, task: &Task) -> DerivedStatus {
+        self.task(task).derive_status(&self.context)
+    }
+
+    /// `task` with the proof it had at this commit, and its subtasks likewise.
+    fn task(&self, task: &Task) -> Task {
+        let mut then = task.clone();
+        then.proof = self
+            .histories
+            .get(&task.id)
+            .and_then(|history| history.iter().find(|p| self.reachable.contains(&p.git_sha)))
+            .cloned();
+        then.subtasks = task.subtasks.iter().map(|s| self.task(s)).collect();
+        then
+    }
+}
@@ -0,0 +1,111 @@
+//! Due Dates: Deadlines on tasks, and which of them are slipping.
+//!
+//! A due date is a calendar day (`YYYY-MM-DD`) with no time or zone. Only
+//! unfinished work can be overdue: once a task is proven or attested its
+//! deadline no longer matters.
+
+use super::graph::TaskGraph;
+use super::types::Task;
+use anyhow::{bail, Result};
+use chrono::{Days, NaiveDate};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Storage format of a due date.
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// An unfinished task with a due date.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DueTask {
+    pub slug: String,
+    pub title: String,
+    pub status: String,
+    /// `YYYY-MM-DD`.
+    pub due: String,
+    /// Days until the due date; negative once it has passed.
+    pub days_left: i64,
+    pub overdue: bool,
+    /// Waiting on unmet dependencies, so it cannot be worked on yet.
+    pub blocked: bool,
+}
+
+/// Parses a due date as typed: `2026-11-01`, `today`, `tomorrow`, `+3d`, or `+2w`.
+///
+/// # Errors
+/// Returns error if the input is none of those forms.
+pub fn parse(input: &str, today: NaiveDate) -> Result<NaiveDate> {
+    let input = input.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(input, DATE_FORMAT) {
+        return Ok(date);
+    }
+    let offset = match input {
+        "today" => Some(0),
+        "tomorrow" => Some(1),
+        _ => input.strip_prefix('+').and_then(|rest| {
+            let (count, unit) = rest.split_at(rest.len().saturating_sub(1));
+            let count: u64 = count.parse().ok()?;
+            match unit {
+                "d" => Some(count),
+                "w" => count.checked_mul(7),
+                _ => None,
+            }
+        }),
+    };
+    match offset.and_then(|days| today.checked_add_days(Days::new(days))) {
+        Some(date) => Ok(date),
+        None => bail!("Invalid due date '{input}' (expected YYYY-MM-DD, today, tomorrow, +3d, or +2w)"),
+    }
+}
+
+/// The stored due date of a task, if it has a valid one.
+#[must_use]
+pub fn date(task: &Task) -> Option<NaiveDate> {
+    task.due
+        .as_deref()
+        .and_then(|d| NaiveDate::parse_from_str(d, DATE_FORMAT).ok())
+}
+
+/// Describes a due date relative to today: "due in 3 days", "overdue by 1 day".
+#[must_use]
+pub fn relative(days_left: i64) -> String {
+    let plural = |n: i64| if n == 1 { "" } else { "s" };
+    match days_left {
+        0 => "due today".to_string(),
+        1 => "due tomorrow".to_string(),
+        n if n > 1 => format!("due in {n} days"),
+        n => format!("overdue by {} day{}", -n, plural(-n)),
+    }
+}
+
+/// Unfinished tasks with a due date, soonest (most overdue) first.
+#[must_use]
+pub fn analyze(graph: &TaskGraph, today: NaiveDate) -> Vec<DueTask> {
+    let mut tasks: Vec<DueTask> = graph
+        .tasks()
+        .filter_map(|task| {
+            let due = date(task)?;
+            let status = task.derive_status(graph.context());
+            if status.satisfies_dependency() {
+                return None;
+            }
+            let days_left = (due - today).num_days();
+            Some(DueTask {
+                slug: task.slug.clone(),
+                title: task.title.clone(),
+                status: status.to_string(),
+                due: due.format(DATE_FORMAT).to_string(),
+                days_left,
+                overdue: days_left < 0,
+                blocked: !graph.get_unmet_blockers(task.id).is_empty(),
+            })
+        })
+        .collect();
+    tasks.sort_by(|a, b| a.days_left.cmp(&b.days_left).then_with(|| a.slug.cmp(&b.slug)));
+    tasks
+}
+
+/// Today's date on this machine.
+#[must_use]
+pub fn today() -> NaiveDate {
+    chrono::Local::now().date_naive()
+}
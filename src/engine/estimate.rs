@@ -0,0 +1,50 @@
+//! Estimates: How much effort a task is expected to take.
+//!
+//! Estimates are stored in hours. They are typed with a unit: `30m`, `4h`,
+//! `2d` (a working day of 8 hours), or `1w` (5 working days). A bare number
+//! is hours.
+
+use anyhow::{bail, Result};
+
+const HOURS_PER_DAY: f64 = 8.0;
+const DAYS_PER_WEEK: f64 = 5.0;
+
+/// Parses an estimate into hours.
+///
+/// # Errors
+/// Returns error if the input is not a non-negative number with an optional `m`, `h`, `d`, or `w` unit.
+pub fn parse(input: &str) -> Result<f64> {
+    let input = input.trim();
+    let (number, scale) = match input.char_indices().last() {
+        Some((i, 'm')) => (&input[..i], 1.0 / 60.0),
+        Some((i, 'h')) => (&input[..i], 1.0),
+        Some((i, 'd')) => (&input[..i], HOURS_PER_DAY),
+        Some((i, 'w')) => (&input[..i], HOURS_PER_DAY * DAYS_PER_WEEK),
+        _ => (input, 1.0),
+    };
+    match number.trim().parse::<f64>() {
+        Ok(n) if n.is_finite() && n >= 0.0 => Ok(n * scale),
+        _ => bail!("Invalid estimate '{input}' (expected e.g. 30m, 4h, 2d, or 1w)"),
+    }
+}
+
+/// Formats hours in the largest unit that reads naturally: `45m`, `6h`, `1.5d`, `2w`.
+#[must_use]
+pub fn format(hours: f64) -> String {
+    let week = HOURS_PER_DAY * DAYS_PER_WEEK;
+    let (value, unit) = if hours >= week {
+        (hours / week, "w")
+    } else if hours >= HOURS_PER_DAY {
+        (hours / HOURS_PER_DAY, "d")
+    } else if hours >= 1.0 || hours == 0.0 {
+        (hours, "h")
+    } else {
+        (hours * 60.0, "m")
+    };
+    let rounded = (value * 10.0).round() / 10.0;
+    if rounded.fract() == 0.0 {
+        format!("{rounded:.0}{unit}")
+    } else {
+        format!("{rounded:.1}{unit}")
+    }
+}
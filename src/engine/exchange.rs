@@ -0,0 +1,167 @@
+//! Proof Exchange: Portable proof files for moving verification between machines.
+//!
+//! CI runs `check --emit-proof` on the exact merge commit; developers ingest
+//! the file with `proof import`. Proofs are matched to tasks by slug, since
+//! row IDs differ between databases.
+
+use super::canonical;
+use super::repo::{ProofRepo, TaskRepo};
+use super::types::{Proof, Task};
+use anyhow::{bail, Context, Result};
+use rusqlite::Connection;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Version of the proof file layout. Bump on incompatible changes.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// A proof detached from the database it was recorded in.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PortableProof {
+    pub task: String,
+    pub proof: Proof,
+}
+
+/// The on-disk proof file.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ProofFile {
+    pub version: u32,
+    pub proofs: Vec<PortableProof>,
+}
+
+impl ProofFile {
+    /// Builds a proof file, ordering proofs by task then time for stable output.
+    #[must_use]
+    pub fn new(mut proofs: Vec<PortableProof>) -> Self {
+        sort_proofs(&mut proofs);
+        Self {
+            version: FORMAT_VERSION,
+            proofs,
+        }
+    }
+
+    /// Reads and validates a proof file.
+    ///
+    /// # Errors
+    /// Returns error if the file is unreadable, malformed, or from a newer format.
+    pub fn read(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read proof file {}", path.display()))?;
+        let file: Self = serde_json::from_str(&text)
+            .with_context(|| format!("Malformed proof file {}", path.display()))?;
+        if file.version > FORMAT_VERSION {
+            bail!(
+                "Proof file version {} is newer than supported ({FORMAT_VERSION})",
+                file.version
+            );
+        }
+        Ok(file)
+    }
+
+    /// Writes the proof file as pretty JSON.
+    ///
+    /// # Errors
+    /// Returns error if the file cannot be written.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, canonical::to_pretty(self)?)
+            .with_context(|| format!("Failed to write proof file {}", path.display()))
+    }
+}
+
+/// Orders proofs by task, then time, then commit: stable across databases.
+pub fn sort_proofs(proofs: &mut [PortableProof]) {
+    proofs.sort_by(|a, b| {
+        (&a.task, &a.proof.timestamp, &a.proof.git_sha, &a.proof.cmd).cmp(&(
+            &b.task,
+            &b.proof.timestamp,
+            &b.proof.git_sha,
+            &b.proof.cmd,
+        ))
+    });
+}
+
+/// What happened to one proof during import.
+pub enum ImportOutcome {
+    Imported,
+    Skipped(String),
+}
+
+/// Ingests portable proofs into the local proof chain.
+///
+/// A proof is accepted only if its task exists, its commit is known locally,
+/// its command is one the task actually defines, and it is newer than the
+/// task's current proof. It keeps its original timestamp. Signatures are
+/// dropped: they cover the emitting database's task IDs and would not verify here.
+///
+/// # Errors
+/// Returns error if a database operation fails.
+pub fn import(
+    conn: &Connection,
+    proofs: &[PortableProof],
+    origin: &str,
+) -> Result<Vec<(String, ImportOutcome)>> {
+    let tasks = TaskRepo::new(conn);
+    let proof_repo = ProofRepo::new(conn);
+    let mut results = Vec::new();
+
+    for entry in proofs {
+        let outcome = match tasks.find_by_slug(&entry.task)? {
+            None => ImportOutcome::Skipped("no such task".to_string()),
+            Some(task) => {
+                if let Some(reason) = rejection(&task, &entry.proof) {
+                    ImportOutcome::Skipped(reason)
+                } else {
+                    let mut proof = entry.proof.clone();
+                    proof.signature = None;
+                    proof.origin = Some(origin.to_string());
+                    proof_repo.save_imported(task.id, &proof)?;
+                    ImportOutcome::Imported
+                }
+            }
+        };
+        results.push((entry.task.clone(), outcome));
+    }
+    Ok(results)
+}
+
+fn rejection(task: &Task, proof: &Proof) -> Option<String> {
+    if !commit_exists(&proof.git_sha) {
+        return Some(format!("commit {} not in local history", short(&proof.git_sha)));
+    }
+
+    let commands = [
+        task.test_cmd.as_deref(),
+        task.test_cmd_unix.as_deref(),
+        task.test_cmd_windows.as_deref(),
+    ];
+    let known = proof.attested_reason.is_some()
+        || (proof.cmd == "(budgets)" && !task.budgets.is_empty())
+        || commands.contains(&Some(proof.cmd.as_str()));
+    if !known {
+        return Some(format!("command `{}` is not this task's", proof.cmd));
+    }
+
+    let current = task.proof.as_ref()?;
+    if current.timestamp == proof.timestamp && current.git_sha == proof.git_sha {
+        return Some("already imported".to_string());
+    }
+    if current.timestamp >= proof.timestamp {
+        return Some("local proof is newer".to_string());
+    }
+    None
+}
+
+fn commit_exists(sha: &str) -> bool {
+    let _span = tracing::info_span!("git", op = "cat-file").entered();
+    Command::new("git")
+        .args(["cat-file", "-e", &format!("{sha}^{{commit}}")])
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+fn short(sha: &str) -> &str {
+    &sha[..7.min(sha.len())]
+}
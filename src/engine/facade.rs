@@ -0,0 +1,418 @@
+//! Facade: roadmap as a library, for tools that embed it.
+//!
+//! Editors, bots, and other Rust programs can drive a roadmap through
+//! [`RoadmapEngine`] instead of spawning the CLI and parsing its output. It
+//! wraps the same database, task graph, and git context the commands use,
+//! and keeps their rules: a blocked task cannot be started, a dependency
+//! cannot close a cycle, and a dirty worktree cannot be verified. Each
+//! change is journaled and recorded for `roadmap undo` like a CLI command,
+//! and runs the same lifecycle scripts. Nothing is printed; failures the
+//! caller may want to handle are [`crate::Error`]s.
+//!
+//! Like the CLI, the engine works on the roadmap and git repository of the
+//! current directory, selected with [`Db::use_namespace`] if not the default.
+
+use super::artifacts;
+use super::bench;
+use super::budget::Budget;
+use super::config::Config;
+use super::context::RepoContext;
+use super::coverage;
+use super::db::Db;
+use super::fingerprint;
+use super::graph::TaskGraph;
+use super::hooks::{self, HookPhase};
+use super::journal;
+use super::lifecycle::{self, Event, Payload};
+use super::oplog;
+use super::output::{self, Limits};
+use super::repo::{ProofRepo, TaskRepo};
+use super::resolver::{slugify, TaskResolver};
+use super::runner::{RunnerConfig, VerifyRunner};
+use super::types::{DerivedStatus, Proof, ProofOutcome, Task, TaskStatus};
+use super::usage::Usage;
+use crate::Error;
+use anyhow::{bail, Result};
+use rusqlite::Connection;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// An open roadmap.
+pub struct RoadmapEngine {
+    conn: Connection,
+}
+
+/// The outcome of [`RoadmapEngine::verify`].
+#[derive(Debug, Clone)]
+pub struct Verification {
+    /// The task as it stands after the proof was recorded.
+    pub task: Task,
+    pub proof: Proof,
+    pub proven: bool,
+}
+
+/// Why a task has its status: the answer of [`RoadmapEngine::why`].
+#[derive(Debug, Clone)]
+pub struct Explanation {
+    pub task: Task,
+    pub status: DerivedStatus,
+    /// The commit the status was derived at.
+    pub head_sha: String,
+    /// Dependencies that are not done yet.
+    pub unmet_blockers: Vec<Task>,
+    /// Every recorded proof, newest first.
+    pub history: Vec<Proof>,
+}
+
+impl RoadmapEngine {
+    /// Opens the roadmap of the current directory, migrating it if needed.
+    ///
+    /// # Errors
+    /// Returns [`Error::NotInitialized`] if there is no roadmap, or an error if the database cannot be opened.
+    pub fn open() -> Result<Self> {
+        Ok(Self { conn: Db::connect()? })
+    }
+
+    /// The underlying connection, for the lower-level engine modules.
+    #[must_use]
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Every task, with its latest proof.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn tasks(&self) -> Result<Vec<Task>> {
+        TaskRepo::new(&self.conn).get_all()
+    }
+
+    /// The task a reference (slug, alias, `#id`, or fuzzy title) names.
+    ///
+    /// # Errors
+    /// Returns [`Error::TaskNotFound`] if nothing matches, or an error if the query fails.
+    pub fn task(&self, task_ref: &str) -> Result<Task> {
+        Ok(TaskResolver::new(&self.conn).resolve(task_ref)?.task)
+    }
+
+    /// The active task, if one is set.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn active(&self) -> Result<Option<Task>> {
+        let repo = TaskRepo::new(&self.conn);
+        match repo.get_active_task_id()? {
+            Some(id) => repo.find_by_id(id),
+            None => Ok(None),
+        }
+    }
+
+    /// Adds a task, verified by `test_cmd` if given.
+    ///
+    /// # Errors
+    /// Returns an error if the title's slug is taken or the database fails.
+    pub fn add_task(&self, title: &str, test_cmd: Option<&str>) -> Result<Task> {
+        let task = self.change(&format!("add {title}"), |conn| {
+            let slug = slugify(title);
+            if slug.is_empty() {
+                bail!("Title must contain a letter or digit");
+            }
+            let tx = Db::begin_write(conn)?;
+            let repo = TaskRepo::new(&tx);
+            if repo.find_by_slug(&slug)?.is_some() {
+                bail!("Task with slug '{slug}' already exists");
+            }
+            if let Some(owner) = repo.find_by_alias(&slug)? {
+                bail!("Slug '{slug}' is an alias of [{}]", owner.slug);
+            }
+            let id = repo.add(&slug, title, test_cmd)?;
+            tx.commit()?;
+            TaskRepo::new(conn)
+                .find_by_id(id)?
+                .ok_or_else(|| anyhow::anyhow!("Task [{slug}] vanished after insert"))
+        })?;
+        let _ = lifecycle::fire(&Payload::task(Event::PostAdd, &task));
+        Ok(task)
+    }
+
+    /// Makes `upstream` a dependency of `downstream`. Linking an existing edge changes nothing.
+    ///
+    /// # Errors
+    /// Returns [`Error::Cycle`] if the edge would close a cycle, or an error if a task is not found.
+    pub fn add_dependency(&self, upstream_ref: &str, downstream_ref: &str) -> Result<()> {
+        self.change(&format!("link {upstream_ref} {downstream_ref}"), |conn| {
+            let tx = Db::begin_write(conn)?;
+            let resolver = TaskResolver::new(&tx);
+            let upstream = resolver.resolve(upstream_ref)?.task;
+            let downstream = resolver.resolve(downstream_ref)?.task;
+            if upstream.id == downstream.id {
+                bail!("A task cannot block itself");
+            }
+            let graph = TaskGraph::build(&tx)?;
+            if graph.get_blockers(downstream.id).iter().any(|t| t.id == upstream.id) {
+                return Ok(());
+            }
+            if graph.would_create_cycle(upstream.id, downstream.id) {
+                bail!(Error::Cycle {
+                    blocker: upstream.slug,
+                    blocked: downstream.slug,
+                });
+            }
+            TaskRepo::new(&tx).link(upstream.id, downstream.id)?;
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Makes a task the active one, as `roadmap do` does.
+    ///
+    /// # Errors
+    /// Returns [`Error::Blocked`] if a dependency is not done, or an error if the task is not found.
+    pub fn set_active(&self, task_ref: &str) -> Result<Task> {
+        let task = self.change(&format!("do {task_ref}"), |conn| {
+            let context = RepoContext::new()?;
+            let task = TaskResolver::new(conn).resolve(task_ref)?.task;
+            let graph = TaskGraph::build(conn)?;
+            let incomplete: Vec<_> = graph
+                .get_blockers(task.id)
+                .into_iter()
+                .filter(|t| !t.derive_status(&context).satisfies_dependency())
+                .map(|t| t.slug.clone())
+                .collect();
+            if !incomplete.is_empty() {
+                bail!(Error::Blocked {
+                    task: task.slug,
+                    blockers: incomplete,
+                });
+            }
+
+            let tx = Db::begin_write(conn)?;
+            let repo = TaskRepo::new(&tx);
+            if repo.get_active_task_id()? != Some(task.id) {
+                repo.set_active_since(context.head_sha())?;
+            }
+            repo.update_status(task.id, TaskStatus::Active)?;
+            repo.set_active_task(task.id)?;
+            tx.commit()?;
+            Ok(Task {
+                status: TaskStatus::Active,
+                ..task
+            })
+        })?;
+        let _ = lifecycle::fire(&Payload::task(Event::PostDo, &task));
+        Ok(task)
+    }
+
+    /// Runs a task's verification at HEAD and records the proof, as `roadmap check` does.
+    ///
+    /// Returns `None` if the task has neither a command nor budgets to check.
+    /// The proof is not signed or pushed to a remote store, and old proofs are
+    /// not pruned; use the CLI for those.
+    ///
+    /// # Errors
+    /// Returns an error if the worktree is dirty, a pre-verification hook
+    /// fails, the command cannot be started, or the database fails.
+    pub fn verify(&self, task_ref: &str) -> Result<Option<Verification>> {
+        let context = RepoContext::new()?;
+        if context.is_dirty {
+            bail!("Repository is dirty. Commit your changes before verifying.");
+        }
+        let task = self.task(task_ref)?;
+        if task.platform_test_cmd().is_none() && task.budgets.is_empty() {
+            return Ok(None);
+        }
+
+        let verification = self.change(&format!("check {}", task.slug), |conn| {
+            let (cmd, outcome, passed) = if let Some(cmd) = task.platform_test_cmd() {
+                execute(conn, &task, cmd, context.head_sha())?
+            } else {
+                let mut outcome = ProofOutcome {
+                    environment: Some(fingerprint::capture("")),
+                    ..empty_outcome()
+                };
+                enforce_budgets(&task, &mut outcome, None)?;
+                ("(budgets)".to_string(), outcome, true)
+            };
+            let proven = passed && outcome.failure.is_none() && task.coverage_ok(outcome.coverage);
+            let proof = Proof::new(&cmd, context.head_sha(), outcome);
+
+            let tx = Db::begin_write(conn)?;
+            ProofRepo::new(&tx).save(task.id, &proof)?;
+            if proven {
+                TaskRepo::new(&tx).update_status(task.id, TaskStatus::Done)?;
+            }
+            tx.commit()?;
+
+            let stored = TaskRepo::new(conn)
+                .find_by_id(task.id)?
+                .ok_or_else(|| anyhow::anyhow!("Task [{}] vanished while verifying", task.slug))?;
+            let proof = stored.proof.clone().unwrap_or(proof);
+            Ok(Verification {
+                task: stored,
+                proof,
+                proven,
+            })
+        })?;
+        let _ = lifecycle::fire(&Payload::check(&verification.task, &verification.proof));
+        Ok(Some(verification))
+    }
+
+    /// Tasks ready to start: not done, with every dependency done.
+    ///
+    /// # Errors
+    /// Returns an error if the graph cannot be built.
+    pub fn frontier(&self) -> Result<Vec<Task>> {
+        let graph = TaskGraph::build(&self.conn)?;
+        Ok(graph.get_frontier().into_iter().cloned().collect())
+    }
+
+    /// Why a task has its status, as `roadmap why` explains it.
+    ///
+    /// # Errors
+    /// Returns [`Error::TaskNotFound`] if nothing matches, or an error if the query fails.
+    pub fn why(&self, task_ref: &str) -> Result<Explanation> {
+        let task = self.task(task_ref)?;
+        let graph = TaskGraph::build(&self.conn)?;
+        let context = graph.context();
+        let unmet_blockers = graph.get_unmet_blockers(task.id).into_iter().cloned().collect();
+        Ok(Explanation {
+            status: task.derive_status(context),
+            head_sha: context.head_sha().to_string(),
+            unmet_blockers,
+            history: ProofRepo::new(&self.conn).get_history(task.id)?,
+            task,
+        })
+    }
+
+    /// Runs a change as one journaled operation that `roadmap undo` can revert.
+    fn change<T>(&self, command: &str, op: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+        oplog::start(&self.conn, command)?;
+        let started = Instant::now();
+        let result = op(&self.conn);
+        let outcome = result.as_ref().map(|_| ()).map_err(|e| format!("{e:#}"));
+        journal::record(&self.conn, command, outcome, started.elapsed())?;
+        result
+    }
+}
+
+/// Runs the hooks and the command, then judges coverage and benchmarks.
+/// Returns the command, the outcome so far, and whether the command passed.
+fn execute(conn: &Connection, task: &Task, cmd: &str, head_sha: &str) -> Result<(String, ProofOutcome, bool)> {
+    let config = Config::load()?;
+    let mut runner_config = RunnerConfig::for_task(task, &config)?;
+    runner_config.add_task_env(task, head_sha);
+    let container = runner_config.container.clone();
+    let artifact_dir = artifacts::prepare(&task.slug)?;
+    runner_config
+        .envs
+        .push((artifacts::ENV_VAR.to_string(), artifact_dir.display().to_string()));
+    let hook_runner = VerifyRunner::new(RunnerConfig {
+        working_dir: None,
+        container: None,
+        sandbox: None,
+        ..runner_config.clone()
+    });
+    let runner = VerifyRunner::new(runner_config);
+
+    let mut hook_runs = hooks::run_phase(HookPhase::Pre, &config.hooks.pre, &hook_runner)?;
+    if let Some(failed) = hook_runs.iter().find(|h| !h.passed()) {
+        artifacts::collect(&artifact_dir)?;
+        bail!("Pre-verification hook failed: `{}`", failed.cmd);
+    }
+    let result = runner.verify_quietly(cmd);
+    hook_runs.extend(hooks::run_phase(HookPhase::Post, &config.hooks.post, &hook_runner)?);
+    if let Some(report) = &task.coverage_path {
+        artifacts::keep(&artifact_dir, Path::new(report))?;
+    }
+    let limits = Limits::from_settings(&config.runner);
+    if let (Ok(result), true) = (&result, config.runner.spill_output) {
+        output::spill(&artifact_dir, limits, &result.stdout, &result.stderr)?;
+    }
+    let kept = artifacts::collect(&artifact_dir)?;
+    let result = result?;
+
+    let mut outcome = ProofOutcome {
+        exit_code: result.exit_code.unwrap_or(1),
+        duration_ms: u64::try_from(result.duration.as_millis()).unwrap_or(u64::MAX),
+        stdout: result.stdout.clone(),
+        stderr: result.stderr.clone(),
+        hooks: hook_runs,
+        attempts: (result.attempts > 1).then_some(result.attempts),
+        environment: Some(container.map_or_else(|| fingerprint::capture(cmd), |c| c.environment())),
+        artifacts: kept,
+        usage: (result.usage != Usage::default()).then(|| result.usage.clone()),
+        ..empty_outcome()
+    };
+    outcome.coverage = task
+        .coverage_path
+        .as_deref()
+        .and_then(|path| coverage::read_percentage(Path::new(path)).ok());
+    if result.passed() {
+        guard_benchmarks(conn, task, &mut outcome)?;
+        enforce_budgets(task, &mut outcome, Some(result.duration))?;
+    }
+    limits.apply(&mut outcome);
+    Ok((cmd.to_string(), outcome, result.passed()))
+}
+
+fn empty_outcome() -> ProofOutcome {
+    ProofOutcome {
+        exit_code: 0,
+        duration_ms: 0,
+        stdout: String::new(),
+        stderr: String::new(),
+        hooks: Vec::new(),
+        coverage: None,
+        metrics: bench::Metrics::new(),
+        budgets: Vec::new(),
+        failure: None,
+        attempts: None,
+        environment: None,
+        artifacts: Vec::new(),
+        truncated: None,
+        usage: None,
+    }
+}
+
+/// In benchmark mode, records metrics and rejects regressions against the last proven run.
+fn guard_benchmarks(conn: &Connection, task: &Task, outcome: &mut ProofOutcome) -> Result<()> {
+    let Some(threshold) = task.bench_threshold else {
+        return Ok(());
+    };
+    outcome.metrics = bench::parse_metrics(&outcome.stdout);
+    if outcome.metrics.is_empty() {
+        outcome.failure = Some("no benchmark results found in output".to_string());
+        return Ok(());
+    }
+    let Some(baseline) = ProofRepo::new(conn).get_baseline_metrics(task.id)? else {
+        return Ok(());
+    };
+    let regressions = bench::find_regressions(&baseline, &outcome.metrics, threshold);
+    if !regressions.is_empty() {
+        let names: Vec<_> = regressions.iter().map(|r| r.name.as_str()).collect();
+        outcome.failure = Some(format!(
+            "benchmark regression over {threshold}%: {}",
+            names.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Evaluates the task's budgets, rejecting the outcome if any is exceeded.
+///
+/// `elapsed` is the command's runtime, or `None` for budget-only tasks.
+fn enforce_budgets(task: &Task, outcome: &mut ProofOutcome, elapsed: Option<Duration>) -> Result<()> {
+    for spec in &task.budgets {
+        outcome.budgets.push(Budget::parse(spec)?.evaluate(spec, elapsed));
+    }
+    let exceeded: Vec<_> = outcome
+        .budgets
+        .iter()
+        .filter(|b| !b.passed)
+        .map(|b| b.budget.as_str())
+        .collect();
+    if !exceeded.is_empty() && outcome.failure.is_none() {
+        outcome.failure = Some(format!("budget exceeded: {}", exceeded.join(", ")));
+    }
+    Ok(())
+}
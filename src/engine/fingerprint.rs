@@ -0,0 +1,114 @@
+//! Environment Fingerprint: The machine a proof was produced on.
+//!
+//! Each proof records the OS, the versions of the toolchains its command
+//! uses, and a fixed allowlist of environment variables that change how
+//! builds and tests behave. Only toolchains named in the command (or implied
+//! by it, like `rustc` behind `cargo`) are probed, so a check pays for a
+//! handful of `--version` calls at most. Variables outside the allowlist are
+//! never recorded, so secrets in the environment stay out of the proof log.
+
+use super::shell;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::process::Command;
+
+/// Toolchains worth a version probe: the program, its version flag, and the
+/// command words that imply it.
+const TOOLCHAINS: &[(&str, &str, &[&str])] = &[
+    ("rustc", "--version", &["cargo", "rustc", "cargo-nextest"]),
+    ("cargo", "--version", &["cargo", "cargo-nextest"]),
+    ("node", "--version", &["node", "npm", "npx", "yarn", "pnpm", "jest", "vitest"]),
+    ("npm", "--version", &["npm", "npx"]),
+    ("python3", "--version", &["python", "python3", "pytest", "pip", "tox", "uv"]),
+    ("go", "version", &["go"]),
+    ("java", "-version", &["java", "mvn", "gradle", "gradlew"]),
+    ("dotnet", "--version", &["dotnet"]),
+    ("ruby", "--version", &["ruby", "bundle", "rake", "rspec"]),
+    ("deno", "--version", &["deno"]),
+    ("bun", "--version", &["bun"]),
+];
+
+/// Variables that commonly change a build or test outcome.
+const VARS: &[&str] = &[
+    "CI",
+    "RUSTFLAGS",
+    "RUSTUP_TOOLCHAIN",
+    "CARGO_BUILD_TARGET",
+    "CARGO_PROFILE",
+    "NODE_ENV",
+    "NODE_OPTIONS",
+    "PYTHONPATH",
+    "VIRTUAL_ENV",
+    "GOFLAGS",
+    "GOOS",
+    "GOARCH",
+    "JAVA_HOME",
+    "LANG",
+    "TZ",
+];
+
+/// Where a verification command ran.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Environment {
+    /// Operating system and CPU architecture, e.g. `linux x86_64`.
+    pub os: String,
+    /// First line of each relevant toolchain's version output, by program.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tools: BTreeMap<String, String>,
+    /// Allowlisted environment variables that were set.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub vars: BTreeMap<String, String>,
+    /// The container image the command ran in, pinned by digest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<String>,
+}
+
+/// Fingerprints this machine for a proof of `cmd`.
+#[must_use]
+pub fn capture(cmd: &str) -> Environment {
+    let _span = tracing::info_span!("fingerprint").entered();
+    let words: Vec<&str> = cmd
+        .split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    let tools = TOOLCHAINS
+        .iter()
+        .filter(|(_, _, triggers)| triggers.iter().any(|t| words.contains(t)))
+        .filter_map(|(program, flag, _)| Some(((*program).to_string(), version(program, flag)?)))
+        .collect();
+    let vars = VARS
+        .iter()
+        .filter_map(|name| Some(((*name).to_string(), std::env::var(name).ok()?)))
+        .collect();
+    Environment {
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        tools,
+        vars,
+        container: None,
+    }
+}
+
+/// Fingerprints a run inside a container: the pinned image stands in for
+/// the host's toolchains and variables, which the command never saw.
+#[must_use]
+pub fn in_container(pinned: String) -> Environment {
+    Environment {
+        os: format!("linux {}", std::env::consts::ARCH),
+        container: Some(pinned),
+        ..Environment::default()
+    }
+}
+
+/// The first non-empty line a program prints for its version flag.
+fn version(program: &str, flag: &str) -> Option<String> {
+    shell::which(program)?;
+    let output = Command::new(program).arg(flag).output().ok()?;
+    // Some toolchains (java) print their version on stderr.
+    let text = format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    text.lines().map(str::trim).find(|line| !line.is_empty()).map(str::to_string)
+}
@@ -0,0 +1,127 @@
+//! Gate Policy: Evaluates the graph against pass/fail rules for CI pipelines.
+
+use super::context::RepoContext;
+use super::types::{DerivedStatus, Task};
+use anyhow::{bail, Result};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Picks the tasks a rule applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Selector {
+    All,
+    Slug(String),
+}
+
+impl Selector {
+    /// Parses `all`, `tag:<name>`, or a task slug.
+    ///
+    /// # Errors
+    /// Returns error for selector kinds this roadmap cannot evaluate.
+    pub fn parse(spec: &str) -> Result<Self> {
+        if spec == "all" {
+            return Ok(Self::All);
+        }
+        if let Some(tag) = spec.strip_prefix("tag:") {
+            bail!("Cannot select 'tag:{tag}': tasks have no tags yet. Use a slug or 'all'.");
+        }
+        Ok(Self::Slug(spec.to_string()))
+    }
+
+    #[must_use]
+    pub fn matches(&self, task: &Task) -> bool {
+        match self {
+            Self::All => true,
+            Self::Slug(slug) => task.slug == *slug,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::All => "all".to_string(),
+            Self::Slug(slug) => slug.clone(),
+        }
+    }
+}
+
+/// The rules a roadmap must satisfy to pass the gate.
+#[derive(Debug, Default)]
+pub struct Policy {
+    pub require_proven: Vec<Selector>,
+    pub forbid: Vec<DerivedStatus>,
+    pub max_stale: Option<usize>,
+}
+
+/// A single rule failure.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Violation {
+    pub rule: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    pub detail: String,
+}
+
+/// Outcome of evaluating a policy.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GateReport {
+    pub passed: bool,
+    pub checked: usize,
+    pub violations: Vec<Violation>,
+}
+
+/// Evaluates every rule and collects all violations rather than stopping at the first.
+///
+/// # Errors
+/// Returns error if a slug selector names no task, so typos can't pass silently.
+pub fn evaluate(tasks: &[Task], context: &RepoContext, policy: &Policy) -> Result<GateReport> {
+    let statuses: Vec<(&Task, DerivedStatus)> =
+        tasks.iter().map(|t| (t, t.derive_status(context))).collect();
+    let mut violations = Vec::new();
+
+    for selector in &policy.require_proven {
+        let selected: Vec<_> = statuses.iter().filter(|(t, _)| selector.matches(t)).collect();
+        if let Selector::Slug(slug) = selector {
+            if selected.is_empty() {
+                bail!("--require-proven: no task with slug '{slug}'");
+            }
+        }
+        let rule = format!("require-proven {}", selector.describe());
+        for (task, status) in selected {
+            if *status != DerivedStatus::Proven {
+                violations.push(Violation {
+                    rule: rule.clone(),
+                    task: Some(task.slug.clone()),
+                    detail: format!("is {status}, not Proven"),
+                });
+            }
+        }
+    }
+
+    for forbidden in &policy.forbid {
+        let rule = format!("forbid {}", forbidden.to_string().to_lowercase());
+        for (task, _) in statuses.iter().filter(|(_, s)| s == forbidden) {
+            violations.push(Violation {
+                rule: rule.clone(),
+                task: Some(task.slug.clone()),
+                detail: format!("is {forbidden}"),
+            });
+        }
+    }
+
+    if let Some(max) = policy.max_stale {
+        let stale = statuses.iter().filter(|(_, s)| *s == DerivedStatus::Stale).count();
+        if stale > max {
+            violations.push(Violation {
+                rule: format!("max-stale {max}"),
+                task: None,
+                detail: format!("{stale} stale task(s)"),
+            });
+        }
+    }
+
+    Ok(GateReport {
+        passed: violations.is_empty(),
+        checked: tasks.len(),
+        violations,
+    })
+}
@@ -0,0 +1,196 @@
+//! Synthetic Workloads: Random but realistic roadmaps for performance work.
+//!
+//! The graph, scopes, and proof outcomes are determined by the seed alone, so
+//! a workload can be rebuilt anywhere from its parameters. Only timestamps
+//! are anchored to the current time.
+
+use super::bench::Metrics;
+use super::db::Db;
+use super::repo::{ProofRepo, TaskRepo};
+use super::types::{Proof, ProofOutcome, TaskStatus};
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::Connection;
+use std::collections::HashSet;
+use std::process::Command;
+
+const AREAS: &[&str] = &[
+    "auth", "billing", "cli", "core", "db", "api", "ui", "search", "sync", "infra",
+    "metrics", "export", "import", "cache", "queue", "mail", "admin", "docs",
+];
+const VERBS: &[&str] = &["add", "fix", "refactor", "harden", "speed-up", "migrate", "document"];
+const NOUNS: &[&str] = &[
+    "parser", "handler", "schema", "retry", "index", "session", "config", "worker",
+    "endpoint", "report", "layout", "token",
+];
+
+/// How far back (in generation order) a task usually looks for its blockers.
+const LOCALITY: usize = 64;
+/// Proof history is spread over this many weeks, ending now.
+const HISTORY_WEEKS: i64 = 8;
+
+/// Shape of the roadmap to generate.
+pub struct GenSpec {
+    pub tasks: usize,
+    pub edges: usize,
+    pub seed: u64,
+    /// Commits proofs are recorded against, newest (HEAD) first.
+    pub commits: Vec<String>,
+}
+
+/// What was actually written.
+#[derive(Debug, Default)]
+pub struct GenSummary {
+    pub tasks: usize,
+    pub edges: usize,
+    pub proofs: usize,
+}
+
+/// Fills an empty roadmap with a seeded random DAG and proof history.
+///
+/// Edges only point from earlier to later tasks, so the graph is acyclic by
+/// construction, and they favour nearby tasks the way real plans cluster.
+/// Proofs are chained normally, with timestamps spread over past weeks.
+///
+/// # Errors
+/// Returns error if the roadmap already has tasks or the database fails.
+pub fn populate(conn: &Connection, spec: &GenSpec) -> Result<GenSummary> {
+    let existing: i64 = conn.query_row("SELECT COUNT(*) FROM tasks", [], |r| r.get(0))?;
+    if existing > 0 {
+        bail!("Refusing to generate into a roadmap that already has {existing} task(s)");
+    }
+    if spec.commits.is_empty() {
+        bail!("At least one commit is needed to record proofs against");
+    }
+
+    let mut rng = SplitMix64(spec.seed);
+    let tx = Db::begin_write(conn)?;
+    let repo = TaskRepo::new(&tx);
+    let mut summary = GenSummary::default();
+
+    let mut ids = Vec::with_capacity(spec.tasks);
+    for i in 0..spec.tasks {
+        let area = rng.pick(AREAS);
+        let (verb, noun) = (rng.pick(VERBS), rng.pick(NOUNS));
+        let slug = format!("{area}-{verb}-{noun}-{i}");
+        let test = format!("cargo test {area}::{noun}");
+        let id = repo.add(&slug, &format!("{verb} {area} {noun}"), Some(&test))?;
+        if rng.chance(60) {
+            repo.add_scope(id, &format!("src/{area}/**"))?;
+        }
+        ids.push(id);
+    }
+    summary.tasks = ids.len();
+
+    let max_edges = spec.tasks * spec.tasks.saturating_sub(1) / 2;
+    let wanted = spec.edges.min(max_edges);
+    let mut seen = HashSet::with_capacity(wanted);
+    let mut attempts = 0;
+    while seen.len() < wanted && attempts < wanted * 8 {
+        attempts += 1;
+        // Edges point from an earlier task to a later one, so the graph stays acyclic.
+        let later = 1 + rng.below(spec.tasks - 1);
+        let earlier = later - 1 - rng.below(later.min(LOCALITY));
+        if seen.insert((earlier, later)) {
+            repo.link(ids[earlier], ids[later])?;
+        }
+    }
+    summary.edges = seen.len();
+
+    let proofs = ProofRepo::new(&tx);
+    let start = Utc::now() - Duration::weeks(HISTORY_WEEKS);
+    let slots = i64::try_from(spec.tasks * 3).unwrap_or(i64::MAX).max(1);
+    let step = (Duration::weeks(HISTORY_WEEKS).num_minutes() / slots).max(1);
+    for (i, &id) in ids.iter().enumerate() {
+        if !rng.chance(75) {
+            continue;
+        }
+        let runs = 1 + rng.below(3);
+        let mut passed = false;
+        for run in 0..runs {
+            let latest = run + 1 == runs;
+            passed = rng.chance(if latest { 85 } else { 70 });
+            let sha = if latest && rng.chance(50) {
+                &spec.commits[0]
+            } else {
+                rng.pick(&spec.commits)
+            };
+            let slot = i64::try_from(i * 3 + run).unwrap_or(0);
+            let at = start + Duration::minutes(slot * step);
+            proofs.save_imported(id, &synthetic_proof(&mut rng, sha, passed, at))?;
+            summary.proofs += 1;
+        }
+        if passed {
+            repo.update_status(id, TaskStatus::Done)?;
+        }
+    }
+
+    tx.commit()?;
+    Ok(summary)
+}
+
+/// Up to `limit` commits reachable from HEAD, newest first.
+#[must_use]
+pub fn recent_commits(limit: usize) -> Vec<String> {
+    let _span = tracing::info_span!("git", op = "rev-list").entered();
+    Command::new("git")
+        .args(["rev-list", &format!("--max-count={limit}"), "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn synthetic_proof(rng: &mut SplitMix64, sha: &str, passed: bool, at: DateTime<Utc>) -> Proof {
+    let duration_ms = 50 + rng.below(5000) as u64;
+    let mut proof = Proof::new(
+        "cargo test",
+        sha,
+        ProofOutcome {
+            exit_code: if passed { 0 } else { 101 },
+            duration_ms,
+            stdout: String::new(),
+            stderr: if passed { String::new() } else { "test failed".to_string() },
+            hooks: Vec::new(),
+            coverage: None,
+            metrics: Metrics::new(),
+            budgets: Vec::new(),
+            failure: None,
+            attempts: None,
+            environment: None,
+            artifacts: Vec::new(),
+            truncated: None,
+            usage: None,
+        },
+    );
+    proof.timestamp = at.to_rfc3339();
+    proof
+}
+
+/// Small, dependency-free PRNG with a stable sequence for a given seed.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n` (n > 0).
+    #[allow(clippy::cast_possible_truncation)]
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
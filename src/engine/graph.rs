@@ -4,11 +4,14 @@ use super::context::RepoContext;
 use super::repo::TaskRepo;
 use super::types::{DerivedStatus, Task};
 use anyhow::Result;
-use petgraph::algo::is_cyclic_directed;
+use petgraph::algo::{is_cyclic_directed, toposort};
 use petgraph::graphmap::DiGraphMap;
+use petgraph::visit::Dfs;
 use rusqlite::Connection;
+use schemars::JsonSchema;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 
 pub struct TaskGraph {
     graph: DiGraphMap<i64, ()>,
@@ -22,6 +25,7 @@ impl TaskGraph {
     /// # Errors
     /// Returns an error if the database query fails or git context cannot be loaded.
     pub fn build(conn: &Connection) -> Result<Self> {
+        let _span = tracing::info_span!("graph.build").entered();
         let mut graph = DiGraphMap::new();
         let repo = TaskRepo::new(conn);
         let tasks = repo.get_all()?;
@@ -46,7 +50,8 @@ impl TaskGraph {
         })
     }
 
-    /// Returns tasks that are unblocked and require work (Unproven, Stale, or Broken).
+    /// Returns tasks that are unblocked and require work (Unproven, Stale, or Broken),
+    /// highest priority first and then in creation order.
     #[must_use]
     pub fn get_frontier(&self) -> Vec<&Task> {
         let mut frontier: Vec<_> = self
@@ -59,21 +64,30 @@ impl TaskGraph {
             .filter(|t| !self.is_blocked(t.id))
             .collect();
 
-        frontier.sort_by_key(|t| t.id);
+        frontier.sort_by_key(|t| (std::cmp::Reverse(t.priority), t.id));
         frontier
     }
 
-    /// Checks if a task is blocked by any dependency that isn't Proven or Attested.
+    /// Checks if a task is blocked by any dependency that doesn't satisfy it.
     fn is_blocked(&self, id: i64) -> bool {
-        self.graph
-            .neighbors_directed(id, petgraph::Direction::Incoming)
-            .any(|sid| {
-                let Some(task) = self.tasks.get(&sid) else {
-                    return false;
-                };
-                let status = task.derive_status(&self.context);
-                !matches!(status, DerivedStatus::Proven | DerivedStatus::Attested)
-            })
+        !self.get_unmet_blockers(id).is_empty()
+    }
+
+    /// Gets the direct blockers of the given ID that don't satisfy it yet.
+    ///
+    /// Unfinished subtasks block their parent, so work starts at the leaves.
+    #[must_use]
+    pub fn get_unmet_blockers(&self, id: i64) -> Vec<&Task> {
+        self.get_blockers(id)
+            .into_iter()
+            .chain(self.get_subtasks(id))
+            .filter(|t| !t.derive_status(&self.context).satisfies_dependency())
+            .collect()
+    }
+
+    /// Iterates over every task in the graph, in no particular order.
+    pub fn tasks(&self) -> impl Iterator<Item = &Task> {
+        self.tasks.values()
     }
 
     /// Detects if adding an edge would create a cycle.
@@ -90,6 +104,57 @@ impl TaskGraph {
         self.context.head_sha()
     }
 
+    /// Returns the repository context statuses are derived against.
+    #[must_use]
+    pub fn context(&self) -> &RepoContext {
+        &self.context
+    }
+
+    /// Gets every task transitively blocked by the given ID, in dependency order.
+    #[must_use]
+    pub fn get_downstream(&self, id: i64) -> Vec<&Task> {
+        let mut reachable = HashSet::new();
+        let mut dfs = Dfs::new(&self.graph, id);
+        while let Some(node) = dfs.next(&self.graph) {
+            if node != id {
+                reachable.insert(node);
+            }
+        }
+
+        toposort(&self.graph, None)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|n| reachable.contains(n))
+            .filter_map(|n| self.tasks.get(&n))
+            .collect()
+    }
+
+    /// Gets every task in dependency order: blockers before the tasks they
+    /// block, otherwise oldest first.
+    #[must_use]
+    pub fn in_dependency_order(&self) -> Vec<&Task> {
+        let mut waiting: HashMap<i64, usize> = self
+            .graph
+            .nodes()
+            .map(|n| (n, self.graph.neighbors_directed(n, petgraph::Direction::Incoming).count()))
+            .collect();
+        let mut ready: BinaryHeap<Reverse<i64>> =
+            waiting.iter().filter(|(_, count)| **count == 0).map(|(id, _)| Reverse(*id)).collect();
+        let mut order = Vec::new();
+        while let Some(Reverse(id)) = ready.pop() {
+            order.extend(self.tasks.get(&id));
+            for next in self.graph.neighbors_directed(id, petgraph::Direction::Outgoing) {
+                if let Some(count) = waiting.get_mut(&next) {
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.push(Reverse(next));
+                    }
+                }
+            }
+        }
+        order
+    }
+
     /// Gets tasks blocked by the given ID.
     #[must_use]
     pub fn get_blocked_by(&self, id: i64) -> Vec<&Task> {
@@ -99,6 +164,32 @@ impl TaskGraph {
             .collect()
     }
 
+    /// Gets tasks nothing blocks, in creation order.
+    #[must_use]
+    pub fn roots(&self) -> Vec<&Task> {
+        let mut roots: Vec<_> = self
+            .tasks
+            .values()
+            .filter(|t| {
+                self.graph
+                    .neighbors_directed(t.id, petgraph::Direction::Incoming)
+                    .next()
+                    .is_none()
+            })
+            .collect();
+        roots.sort_by_key(|t| t.id);
+        roots
+    }
+
+    /// Gets the direct subtasks of the given ID, by slug.
+    #[must_use]
+    pub fn get_subtasks(&self, id: i64) -> Vec<&Task> {
+        self.tasks
+            .get(&id)
+            .map(|t| t.subtasks.iter().filter_map(|s| self.tasks.get(&s.id)).collect())
+            .unwrap_or_default()
+    }
+
     /// Gets tasks that block the given ID.
     #[must_use]
     pub fn get_blockers(&self, id: i64) -> Vec<&Task> {
@@ -108,36 +199,379 @@ impl TaskGraph {
             .collect()
     }
 
+    /// Gets the tasks waiting on the given ID: those it blocks, and its parent.
+    fn get_dependents(&self, id: i64) -> Vec<&Task> {
+        let parent = self
+            .tasks
+            .get(&id)
+            .and_then(|t| t.parent.as_ref())
+            .and_then(|slug| self.tasks.values().find(|t| &t.slug == slug));
+        self.get_blocked_by(id).into_iter().chain(parent).collect()
+    }
+
+    /// The given ID with everything it waits on and everything waiting on it,
+    /// transitively and whatever their status: the area of the roadmap it belongs to.
+    ///
+    /// It waits on its blockers and subtasks; its dependents and parent wait on it.
+    #[must_use]
+    pub fn get_lineage(&self, id: i64) -> HashSet<i64> {
+        let mut lineage = HashSet::from([id]);
+        let mut ancestors = vec![id];
+        while let Some(next) = ancestors.pop() {
+            for task in self.get_blockers(next).into_iter().chain(self.get_subtasks(next)) {
+                if lineage.insert(task.id) {
+                    ancestors.push(task.id);
+                }
+            }
+        }
+        let mut descendants = vec![id];
+        let mut seen = HashSet::from([id]);
+        while let Some(next) = descendants.pop() {
+            for task in self.get_dependents(next) {
+                if seen.insert(task.id) {
+                    lineage.insert(task.id);
+                    descendants.push(task.id);
+                }
+            }
+        }
+        lineage
+    }
+
+    /// Groups of tasks linked to each other by dependencies or subtasks,
+    /// ignoring direction: largest first, then by oldest task, each oldest first.
+    ///
+    /// A task alone in its group is an orphan: nothing blocks it, it blocks
+    /// nothing, and it has no parent or subtasks.
+    #[must_use]
+    pub fn components(&self) -> Vec<Vec<&Task>> {
+        let mut seen = HashSet::new();
+        let mut ids: Vec<i64> = self.tasks.keys().copied().collect();
+        ids.sort_unstable();
+        let mut components = Vec::new();
+        for start in ids {
+            if !seen.insert(start) {
+                continue;
+            }
+            let mut component = Vec::new();
+            let mut queue = vec![start];
+            while let Some(id) = queue.pop() {
+                let Some(task) = self.tasks.get(&id) else {
+                    continue;
+                };
+                component.push(task);
+                let linked = self
+                    .get_blockers(id)
+                    .into_iter()
+                    .chain(self.get_dependents(id))
+                    .chain(self.get_subtasks(id));
+                for next in linked {
+                    if seen.insert(next.id) {
+                        queue.push(next.id);
+                    }
+                }
+            }
+            component.sort_by_key(|t| t.id);
+            components.push(component);
+        }
+        components.sort_by_key(|c| (Reverse(c.len()), c.first().map(|t| t.id)));
+        components
+    }
+
+    /// Unfinished tasks waiting on the given ID, directly or through other
+    /// unfinished tasks; a finished task in between already lets its own
+    /// dependents through.
+    fn get_waiting_on(&self, id: i64) -> HashSet<i64> {
+        let mut waiting = HashSet::new();
+        let mut queue = vec![id];
+        while let Some(next) = queue.pop() {
+            for task in self.get_dependents(next) {
+                if !task.derive_status(&self.context).satisfies_dependency() && waiting.insert(task.id) {
+                    queue.push(task.id);
+                }
+            }
+        }
+        waiting
+    }
+
+    /// What proving the given ID moves toward unblocked: every unfinished task
+    /// waiting on it, most-unlocking first, then oldest.
+    ///
+    /// Empty once the task is finished, since nothing waits on it any more.
+    #[must_use]
+    pub fn get_impact(&self, id: i64) -> Vec<Unlock<'_>> {
+        let finished = self
+            .tasks
+            .get(&id)
+            .is_none_or(|t| t.derive_status(&self.context).satisfies_dependency());
+        if finished {
+            return Vec::new();
+        }
+        let mut unlocks: Vec<Unlock<'_>> = self
+            .get_waiting_on(id)
+            .into_iter()
+            .filter_map(|waiting| self.tasks.get(&waiting))
+            .map(|task| Unlock {
+                task,
+                descendants: self.get_waiting_on(task.id).len(),
+                waits_on: self
+                    .get_unmet_blockers(task.id)
+                    .into_iter()
+                    .filter(|b| b.id != id)
+                    .collect(),
+            })
+            .collect();
+        unlocks.sort_by_key(|u| (Reverse(u.descendants), u.task.id));
+        unlocks
+    }
+
+    /// Unfinished tasks grouped into waves that can each proceed in parallel.
+    ///
+    /// The first wave is the frontier; each later wave holds the tasks whose
+    /// unmet blockers (dependencies and subtasks) all sit in earlier waves,
+    /// so it unlocks once those are done. Within a wave tasks are ordered as
+    /// in the frontier: highest priority first, then oldest.
+    #[must_use]
+    pub fn waves(&self) -> Vec<Vec<&Task>> {
+        let mut remaining: Vec<&Task> = self
+            .tasks
+            .values()
+            .filter(|t| !t.derive_status(&self.context).satisfies_dependency())
+            .collect();
+        remaining.sort_by_key(|t| (Reverse(t.priority), t.id));
+        let mut placed = HashSet::new();
+        let mut waves = Vec::new();
+        while !remaining.is_empty() {
+            let (wave, rest): (Vec<&Task>, Vec<&Task>) = remaining
+                .into_iter()
+                .partition(|t| self.get_unmet_blockers(t.id).iter().all(|b| placed.contains(&b.id)));
+            // Only a cycle leaves tasks that can never start.
+            if wave.is_empty() {
+                break;
+            }
+            placed.extend(wave.iter().map(|t| t.id));
+            waves.push(wave);
+            remaining = rest;
+        }
+        waves
+    }
+
+    /// The chain of unfinished tasks with the most effort left.
+    ///
+    /// Finished tasks (proven, attested, quarantined) drop out, so the chain
+    /// starts at work that can begin now. Effort is the estimate, or for an
+    /// unestimated task the time its last verification took; between chains
+    /// of equal effort the one with more tasks wins. Returns the chain in
+    /// dependency order and its total hours; remaining ties go to the chain
+    /// ending at the oldest task.
+    #[must_use]
+    pub fn critical_path(&self) -> (Vec<&Task>, f64) {
+        let chains = self.chains();
+        let Some((mut end, total)) = chains.longest() else {
+            return (Vec::new(), 0.0);
+        };
+        let mut chain = Vec::new();
+        loop {
+            if let Some(task) = self.tasks.get(&end) {
+                chain.push(task);
+            }
+            match chains.before.get(&end).and_then(|(_, previous)| *previous) {
+                Some(previous) => end = previous,
+                None => break,
+            }
+        }
+        chain.reverse();
+        (chain, total.hours)
+    }
+
+    /// Unfinished tasks with no slack: delaying any of them delays the end of
+    /// the longest chain. Everything else can proceed alongside them.
+    ///
+    /// Empty when there is no chain to speak of: no dependencies between
+    /// unfinished tasks and no effort recorded.
+    #[must_use]
+    pub fn critical_tasks(&self) -> HashSet<i64> {
+        let chains = self.chains();
+        let Some((_, total)) = chains.longest() else {
+            return HashSet::new();
+        };
+        if total.tasks <= 1 && total.hours <= 0.0 {
+            return HashSet::new();
+        }
+        chains
+            .before
+            .iter()
+            .filter(|(id, (before, _))| {
+                let (Some(after), Some(task)) = (chains.after.get(id), self.tasks.get(id)) else {
+                    return false;
+                };
+                let through = Effort {
+                    hours: before.hours + after.hours - Effort::of(task).hours,
+                    tasks: before.tasks + after.tasks - 1,
+                };
+                through.tasks == total.tasks && (through.hours - total.hours).abs() < 1e-9
+            })
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// The heaviest chain of unfinished tasks ending at, and starting from, each one.
+    fn chains(&self) -> Chains {
+        let order = toposort(&self.graph, None).unwrap_or_default();
+        let remaining: HashMap<i64, &Task> = order
+            .iter()
+            .filter_map(|id| self.tasks.get(id).map(|t| (*id, t)))
+            .filter(|(_, t)| !t.derive_status(&self.context).satisfies_dependency())
+            .collect();
+
+        let mut before: HashMap<i64, (Effort, Option<i64>)> = HashMap::new();
+        for id in &order {
+            let Some(task) = remaining.get(id) else {
+                continue;
+            };
+            let previous = self
+                .graph
+                .neighbors_directed(*id, petgraph::Direction::Incoming)
+                .filter_map(|b| before.get(&b).map(|(effort, _)| (b, *effort)))
+                .max_by(|a, b| by_effort(*a, *b));
+            let effort = previous.map_or_else(Effort::default, |(_, e)| e).plus(Effort::of(task));
+            before.insert(*id, (effort, previous.map(|(b, _)| b)));
+        }
+
+        let mut after: HashMap<i64, Effort> = HashMap::new();
+        for id in order.iter().rev() {
+            let Some(task) = remaining.get(id) else {
+                continue;
+            };
+            let next = self
+                .graph
+                .neighbors_directed(*id, petgraph::Direction::Outgoing)
+                .filter_map(|d| after.get(&d).copied())
+                .max_by(Effort::compare)
+                .unwrap_or_default();
+            after.insert(*id, next.plus(Effort::of(task)));
+        }
+        Chains { before, after }
+    }
+
     /// Calculates status counts for the entire graph.
     #[must_use]
     pub fn status_counts(&self) -> StatusCounts {
-        let mut counts = StatusCounts::default();
-        for task in self.tasks.values() {
-            match task.derive_status(&self.context) {
-                DerivedStatus::Unproven => counts.unproven += 1,
-                DerivedStatus::Proven => counts.proven += 1,
-                DerivedStatus::Stale => counts.stale += 1,
-                DerivedStatus::Broken => counts.broken += 1,
-                DerivedStatus::Attested => counts.attested += 1,
-            }
+        self.tasks.values().map(|t| t.derive_status(&self.context)).collect()
+    }
+}
+
+/// A task that proving another moves toward unblocked.
+pub struct Unlock<'a> {
+    pub task: &'a Task,
+    /// How many unfinished tasks wait on this one in turn.
+    pub descendants: usize,
+    /// Its unmet blockers besides the task being proven; empty when proving
+    /// that task alone makes it actionable.
+    pub waits_on: Vec<&'a Task>,
+}
+
+/// Effort along a chain of tasks.
+#[derive(Debug, Clone, Copy, Default)]
+struct Effort {
+    hours: f64,
+    tasks: usize,
+}
+
+impl Effort {
+    /// One task's share: its estimate, or failing that how long its last
+    /// verification took, so a chain of slow checks still weighs something.
+    #[allow(clippy::cast_precision_loss)]
+    fn of(task: &Task) -> Self {
+        let hours = task
+            .estimate
+            .unwrap_or_else(|| task.proof.as_ref().map_or(0.0, |p| p.duration_ms as f64 / 3_600_000.0));
+        Self { hours, tasks: 1 }
+    }
+
+    fn plus(self, other: Self) -> Self {
+        Self {
+            hours: self.hours + other.hours,
+            tasks: self.tasks + other.tasks,
         }
-        counts
     }
+
+    /// More hours first, then more tasks.
+    fn compare(&self, other: &Self) -> std::cmp::Ordering {
+        self.hours.total_cmp(&other.hours).then(self.tasks.cmp(&other.tasks))
+    }
+}
+
+/// The heaviest chains through unfinished tasks, from [`TaskGraph::chains`].
+struct Chains {
+    /// Ending at each task, with the task before it on that chain.
+    before: HashMap<i64, (Effort, Option<i64>)>,
+    /// Starting from each task.
+    after: HashMap<i64, Effort>,
+}
+
+impl Chains {
+    /// Where the heaviest chain ends, and its effort.
+    fn longest(&self) -> Option<(i64, Effort)> {
+        self.before
+            .iter()
+            .map(|(id, (effort, _))| (*id, *effort))
+            .max_by(|a, b| by_effort(*a, *b))
+    }
+}
+
+/// Orders `(id, effort)` pairs by effort, preferring the older task on a tie.
+fn by_effort(a: (i64, Effort), b: (i64, Effort)) -> std::cmp::Ordering {
+    a.1.compare(&b.1).then_with(|| b.0.cmp(&a.0))
 }
 
 /// Aggregate counts of tasks by status.
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Default, Serialize, JsonSchema)]
 pub struct StatusCounts {
     pub unproven: usize,
     pub proven: usize,
     pub stale: usize,
     pub broken: usize,
     pub attested: usize,
+    pub quarantined: usize,
 }
 
 impl StatusCounts {
     #[must_use]
     pub fn total(&self) -> usize {
-        self.unproven + self.proven + self.stale + self.broken + self.attested
+        self.unproven + self.proven + self.stale + self.broken + self.attested + self.quarantined
+    }
+
+    /// How many tasks have `status`.
+    #[must_use]
+    pub fn of(&self, status: DerivedStatus) -> usize {
+        match status {
+            DerivedStatus::Unproven => self.unproven,
+            DerivedStatus::Proven => self.proven,
+            DerivedStatus::Stale => self.stale,
+            DerivedStatus::Broken => self.broken,
+            DerivedStatus::Attested => self.attested,
+            DerivedStatus::Quarantined => self.quarantined,
+        }
+    }
+
+    fn of_mut(&mut self, status: DerivedStatus) -> &mut usize {
+        match status {
+            DerivedStatus::Unproven => &mut self.unproven,
+            DerivedStatus::Proven => &mut self.proven,
+            DerivedStatus::Stale => &mut self.stale,
+            DerivedStatus::Broken => &mut self.broken,
+            DerivedStatus::Attested => &mut self.attested,
+            DerivedStatus::Quarantined => &mut self.quarantined,
+        }
+    }
+}
+
+impl FromIterator<DerivedStatus> for StatusCounts {
+    fn from_iter<I: IntoIterator<Item = DerivedStatus>>(statuses: I) -> Self {
+        let mut counts = Self::default();
+        for status in statuses {
+            *counts.of_mut(status) += 1;
+        }
+        counts
     }
 }
\ No newline at end of file
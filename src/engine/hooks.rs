@@ -0,0 +1,67 @@
+//! Verification Hooks: Commands run around a verification (setup/teardown).
+
+use super::runner::VerifyRunner;
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// When a hook runs relative to the verification command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HookPhase {
+    Pre,
+    Post,
+}
+
+impl fmt::Display for HookPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pre => write!(f, "pre"),
+            Self::Post => write!(f, "post"),
+        }
+    }
+}
+
+/// The recorded outcome of one hook command.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HookRun {
+    pub phase: HookPhase,
+    pub cmd: String,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+}
+
+impl HookRun {
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Runs hooks in order, stopping at the first pre-hook failure.
+///
+/// Post-hooks are cleanup, so every one runs regardless of earlier failures.
+///
+/// # Errors
+/// Returns error if a hook cannot be spawned.
+#[allow(clippy::cast_possible_truncation)]
+pub fn run_phase(phase: HookPhase, cmds: &[String], runner: &VerifyRunner) -> Result<Vec<HookRun>> {
+    let _span = tracing::info_span!("hooks", op = %phase).entered();
+    let mut runs = Vec::new();
+    for cmd in cmds {
+        let result = runner.run(cmd)?;
+        let run = HookRun {
+            phase,
+            cmd: cmd.clone(),
+            exit_code: result.exit_code,
+            duration_ms: result.duration.as_millis() as u64,
+        };
+        let failed = !run.passed();
+        runs.push(run);
+        if failed && phase == HookPhase::Pre {
+            break;
+        }
+    }
+    Ok(runs)
+}
@@ -0,0 +1,91 @@
+//! Impact Analysis: Predicts how a commit range would move proof status.
+
+use super::context::RepoContext;
+use super::types::{DerivedStatus, Task};
+use anyhow::{bail, Context, Result};
+use std::process::{Command, Stdio};
+
+/// How a single task is affected by a range of commits.
+pub struct TaskImpact<'a> {
+    pub task: &'a Task,
+    /// Changed files that fall inside the task's scopes.
+    pub files: Vec<String>,
+    pub current: DerivedStatus,
+    pub projected: DerivedStatus,
+}
+
+/// Lists the files changed in a `A..B` (or `A...B`) range, filtered by `scopes`.
+///
+/// Empty `scopes` means the whole tree.
+///
+/// # Errors
+/// Returns error if git cannot resolve the range.
+pub fn changed_files(range: &str, scopes: &[String]) -> Result<Vec<String>> {
+    let _span = tracing::info_span!("git", op = "diff").entered();
+    let output = Command::new("git")
+        .args(["diff", "--name-only", range, "--"])
+        .args(scopes)
+        .output()
+        .context("Failed to run git diff")?;
+    if !output.status.success() {
+        bail!(
+            "Invalid commit range '{range}': {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+/// Returns true if git parses `target` as a revision or range.
+#[must_use]
+pub fn is_revision(target: &str) -> bool {
+    if target.starts_with('-') {
+        return false;
+    }
+    Command::new("git")
+        .args(["rev-parse", "--revs-only", target, "--"])
+        .stderr(Stdio::null())
+        .output()
+        .is_ok_and(|out| out.status.success() && !out.stdout.is_empty())
+}
+
+/// Assesses every task against a commit range, returning only touched tasks.
+///
+/// A single revision is compared with the working tree, as `git diff` does.
+///
+/// A touched task that is currently proven would go stale once the range
+/// lands; every other status is unaffected by new commits.
+///
+/// # Errors
+/// Returns error if git cannot resolve the range.
+pub fn assess<'a>(
+    tasks: &'a [Task],
+    context: &RepoContext,
+    range: &str,
+) -> Result<Vec<TaskImpact<'a>>> {
+    // Resolve once up front so a bad range fails loudly even with no scoped tasks.
+    changed_files(range, &[])?;
+
+    let mut impacts = Vec::new();
+    for task in tasks {
+        let files = changed_files(range, &task.scopes)?;
+        if files.is_empty() {
+            continue;
+        }
+        let current = task.derive_status(context);
+        let projected = match current {
+            DerivedStatus::Proven => DerivedStatus::Stale,
+            other => other,
+        };
+        impacts.push(TaskImpact {
+            task,
+            files,
+            current,
+            projected,
+        });
+    }
+    Ok(impacts)
+}
@@ -0,0 +1,130 @@
+//! Journal: Who ran what against the roadmap, and how it went.
+//!
+//! Every command that can change the roadmap appends an event: when, who,
+//! the invocation as typed, whether it succeeded (with the error if not),
+//! and how long it took. Its changes are the operation the undo log
+//! recorded for it, linked by batch, so an event knows what it changed and
+//! whether that was undone. Proofs say what was verified; the journal says
+//! what people and agents did, failed attempts included. Events are never
+//! updated or deleted, and `undo` does not touch them.
+
+use super::config::Config;
+use super::oplog;
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Names the actor in place of the user name, e.g. for an agent.
+pub const ACTOR_VAR: &str = "ROADMAP_ACTOR";
+
+/// One journaled command.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Event {
+    pub id: i64,
+    pub timestamp: String,
+    pub actor: String,
+    /// The invocation, without the leading `roadmap`.
+    pub command: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    /// Rows the command changed.
+    pub changes: usize,
+    /// The undo log's operation holding those changes, if there were any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation: Option<i64>,
+    /// Whether `undo` reverted the changes.
+    pub undone: bool,
+}
+
+/// Which events [`recent`] returns. The default matches all.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    /// Only this actor's events (case-insensitive).
+    pub actor: Option<String>,
+    /// Only failed commands.
+    pub failed: bool,
+    /// Only events on or after this timestamp (`YYYY-MM-DD` works).
+    pub since: Option<String>,
+}
+
+/// Who is running roadmap: [`ACTOR_VAR`], else the configured or git user name, else the login name.
+#[must_use]
+pub fn actor() -> String {
+    std::env::var(ACTOR_VAR)
+        .ok()
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty())
+        .or_else(|| Config::load().ok().and_then(|c| c.identity()))
+        .or_else(|| std::env::var("USER").ok())
+        .or_else(|| std::env::var("USERNAME").ok())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Appends the event for the command the undo log is currently recording (see [`oplog::set_command`]).
+///
+/// # Errors
+/// Returns an error if the event cannot be written.
+pub fn record(conn: &Connection, command: &str, outcome: Result<(), String>, elapsed: Duration) -> Result<()> {
+    let (ok, error) = match outcome {
+        Ok(()) => (true, None),
+        Err(e) => (false, Some(e)),
+    };
+    conn.execute(
+        "INSERT INTO events (actor, command, ok, error, duration_ms, batch) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            actor(),
+            command,
+            ok,
+            error,
+            u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+            oplog::current_batch()
+        ],
+    )?;
+    Ok(())
+}
+
+/// The most recent events matching `filter`, newest first.
+///
+/// # Errors
+/// Returns an error if the query fails.
+pub fn recent(conn: &Connection, filter: &EventFilter, limit: usize) -> Result<Vec<Event>> {
+    let mut stmt = conn.prepare(
+        "SELECT e.id, e.timestamp, e.actor, e.command, e.ok, e.error, e.duration_ms, o.id,
+                (SELECT COUNT(*) FROM operation_steps s WHERE s.operation_id = o.id),
+                EXISTS (SELECT 1 FROM operations u WHERE u.reverts = o.id)
+         FROM events e LEFT JOIN operations o ON o.batch = e.batch
+         WHERE (?1 IS NULL OR LOWER(e.actor) = LOWER(?1))
+           AND (?2 = 0 OR e.ok = 0)
+           AND (?3 IS NULL OR e.timestamp >= ?3)
+         ORDER BY e.id DESC LIMIT ?4",
+    )?;
+    let events = stmt
+        .query_map(
+            params![
+                filter.actor,
+                filter.failed,
+                filter.since,
+                i64::try_from(limit).unwrap_or(i64::MAX)
+            ],
+            |r| {
+                Ok(Event {
+                    id: r.get(0)?,
+                    timestamp: r.get(1)?,
+                    actor: r.get(2)?,
+                    command: r.get(3)?,
+                    ok: r.get(4)?,
+                    error: r.get(5)?,
+                    duration_ms: r.get(6)?,
+                    operation: r.get(7)?,
+                    changes: r.get::<_, Option<i64>>(8)?.and_then(|n| n.try_into().ok()).unwrap_or(0),
+                    undone: r.get(9)?,
+                })
+            },
+        )?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(events)
+}
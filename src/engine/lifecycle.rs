@@ -0,0 +1,152 @@
+//! Lifecycle Scripts: Team automations run after roadmap changes a task.
+//!
+//! An executable at `.roadmap/hooks/post-add`, `post-do`, or `post-check`
+//! runs after that command succeeds for a task, once per task, with a JSON
+//! event on stdin: which event, the task as `roadmap list --json` sees it,
+//! and for `post-check` the recorded proof and its result. It can notify a
+//! channel, update an external tracker, or anything else; its output goes
+//! to stderr, so it never mixes with JSON on stdout. The command has already
+//! happened, so a failing or hanging script is reported but changes nothing.
+//! These are unrelated to verification hooks, which run inside a check, and
+//! to the git hooks `roadmap hooks install` writes.
+
+use super::db::DB_DIR;
+use super::journal;
+use super::lock;
+use super::types::{Proof, Task};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use wait_timeout::ChildExt;
+
+/// How long a script may run before it is killed.
+pub const TIMEOUT: Duration = Duration::from_mins(1);
+
+/// Environment variable naming the event for the script.
+pub const ENV_VAR: &str = "ROADMAP_EVENT";
+
+const HOOKS_DIR: &str = "hooks";
+
+/// A point in a task's life that runs a script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Event {
+    PostAdd,
+    PostDo,
+    PostCheck,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PostAdd => write!(f, "post-add"),
+            Self::PostDo => write!(f, "post-do"),
+            Self::PostCheck => write!(f, "post-check"),
+        }
+    }
+}
+
+/// What a script reads on stdin.
+#[derive(Debug, Serialize)]
+pub struct Payload<'a> {
+    pub event: Event,
+    /// Who ran the command (see [`journal::actor`]).
+    pub actor: String,
+    pub task: &'a Task,
+    /// `post-check` only: the proof just recorded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof: Option<&'a Proof>,
+    /// `post-check` only: `proven`, `broken`, or `attested`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<&'static str>,
+}
+
+impl<'a> Payload<'a> {
+    /// The payload for `post-add` or `post-do`.
+    #[must_use]
+    pub fn task(event: Event, task: &'a Task) -> Self {
+        Self {
+            event,
+            actor: journal::actor(),
+            task,
+            proof: None,
+            result: None,
+        }
+    }
+
+    /// The payload for `post-check`.
+    #[must_use]
+    pub fn check(task: &'a Task, proof: &'a Proof) -> Self {
+        let result = if proof.attested_reason.is_some() {
+            "attested"
+        } else if proof.exit_code == 0 && proof.failure.is_none() {
+            "proven"
+        } else {
+            "broken"
+        };
+        Self {
+            event: Event::PostCheck,
+            actor: journal::actor(),
+            task,
+            proof: Some(proof),
+            result: Some(result),
+        }
+    }
+}
+
+/// Where the script for `event` lives.
+#[must_use]
+pub fn script(event: Event) -> PathBuf {
+    Path::new(DB_DIR).join(HOOKS_DIR).join(event.to_string())
+}
+
+/// Runs the script for the payload's event, if there is one.
+///
+/// Returns `Ok(None)` when no executable script exists, otherwise whether it exited 0.
+///
+/// # Errors
+/// Returns error if the script cannot be started or the payload cannot be written to it.
+pub fn fire(payload: &Payload<'_>) -> Result<Option<bool>> {
+    let path = script(payload.event);
+    if !is_executable(&path) {
+        return Ok(None);
+    }
+    let _span = tracing::info_span!("lifecycle", event = %payload.event).entered();
+    let body = serde_json::to_string(payload)?;
+    let mut child = Command::new(&path)
+        .env(ENV_VAR, payload.event.to_string())
+        .envs(lock::shared())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::from(std::io::stderr()))
+        .spawn()
+        .with_context(|| format!("Failed to run {}", path.display()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A script that ignores its input may exit before reading it.
+        if let Err(e) = stdin.write_all(body.as_bytes()) {
+            if e.kind() != std::io::ErrorKind::BrokenPipe {
+                return Err(e).with_context(|| format!("Failed to write the event to {}", path.display()));
+            }
+        }
+    }
+    if let Some(status) = child.wait_timeout(TIMEOUT)? {
+        return Ok(Some(status.success()));
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+    Ok(Some(false))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata().is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
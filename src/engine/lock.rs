@@ -0,0 +1,125 @@
+//! Lock: One writing roadmap process per roadmap at a time.
+//!
+//! `do`, `check`, and `add` hold an advisory lock on `.roadmap/state.lock`
+//! while they run, so two agents working in one checkout take turns instead
+//! of interleaving their changes. It is the operating system's file lock,
+//! released when the process exits however it exits; the file only records
+//! who holds it, for the other process's message. A process that finds the
+//! lock taken waits up to [`WAIT`] before giving up. Roadmap processes started
+//! by the holder (from verification commands and hooks) share its lock: the
+//! holder passes them [`shared`] in their environment.
+
+use super::db::{Db, DB_DIR};
+use crate::Error;
+use anyhow::{bail, Context, Result};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// How long to wait for another process to release the lock.
+pub const WAIT: Duration = Duration::from_mins(1);
+
+/// Set for the holder's child processes, which must not wait on their parent.
+const HELD_VAR: &str = "ROADMAP_LOCK_HELD";
+
+/// Locks this process holds right now.
+static HELD: AtomicUsize = AtomicUsize::new(0);
+
+const LOCK_FILE: &str = "state.lock";
+
+const POLL: Duration = Duration::from_millis(200);
+
+/// The held lock; released on drop.
+pub struct WriteLock {
+    file: Option<File>,
+}
+
+impl Drop for WriteLock {
+    fn drop(&mut self) {
+        if self.file.take().is_some() {
+            HELD.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// The lock file of the selected roadmap.
+#[must_use]
+pub fn path() -> PathBuf {
+    Path::new(DB_DIR).join(Db::file_name(LOCK_FILE))
+}
+
+/// Takes the lock for `command`, waiting up to [`WAIT`] for another holder.
+///
+/// Outside an initialized roadmap there is nothing to guard, and the
+/// command reports that itself; the returned lock then holds nothing.
+///
+/// # Errors
+/// Returns an error if another process keeps the lock past [`WAIT`] or the lock file cannot be opened.
+pub fn acquire(command: &str) -> Result<WriteLock> {
+    if std::env::var_os(HELD_VAR).is_some() || !Path::new(DB_DIR).is_dir() {
+        return Ok(WriteLock { file: None });
+    }
+    let path = path();
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+
+    let started = Instant::now();
+    let mut announced = false;
+    loop {
+        match file.try_lock() {
+            Ok(()) => break,
+            Err(TryLockError::WouldBlock) => {
+                let holder = holder(&mut file);
+                if started.elapsed() >= WAIT {
+                    bail!(Error::Locked {
+                        holder,
+                        waited_secs: WAIT.as_secs(),
+                    });
+                }
+                if !announced {
+                    eprintln!("⏳ Waiting for another roadmap process ({holder})...");
+                    announced = true;
+                }
+                std::thread::sleep(POLL);
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(e).with_context(|| format!("Failed to lock {}", path.display()));
+            }
+        }
+    }
+
+    // Best effort: the record is only for the message other processes print.
+    file.set_len(0)
+        .and_then(|()| file.seek(SeekFrom::Start(0)))
+        .and_then(|_| writeln!(file, "pid {}: roadmap {command}", std::process::id()))
+        .ok();
+    HELD.fetch_add(1, Ordering::Relaxed);
+    Ok(WriteLock { file: Some(file) })
+}
+
+/// The variable to set for a child process so it shares the lock this
+/// process holds or shares; `None` when there is no lock to share.
+#[must_use]
+pub fn shared() -> Option<(&'static str, String)> {
+    if let Some(holder) = std::env::var_os(HELD_VAR) {
+        return Some((HELD_VAR, holder.to_string_lossy().into_owned()));
+    }
+    (HELD.load(Ordering::Relaxed) > 0).then(|| (HELD_VAR, std::process::id().to_string()))
+}
+
+/// Who holds the lock, as its holder recorded it.
+fn holder(file: &mut File) -> String {
+    let mut record = String::new();
+    let read = file.seek(SeekFrom::Start(0)).and_then(|_| file.read_to_string(&mut record));
+    match read {
+        Ok(_) if !record.trim().is_empty() => record.trim().to_string(),
+        _ => "holder unknown".to_string(),
+    }
+}
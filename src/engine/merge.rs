@@ -0,0 +1,246 @@
+//! State Merge: Structural three-way merge of roadmap snapshots.
+//!
+//! Tasks are merged field by field against the common ancestor, edges are
+//! added or removed only where one side changed them, milestones are unioned
+//! by name, and proof histories are unioned so the newest proof wins. Fields
+//! changed differently on both sides are reported as conflicts for the
+//! caller to resolve.
+
+use super::exchange::PortableProof;
+use super::repo::{MilestoneRepo, TaskRepo};
+use super::snapshot::{self, Edge, Snapshot, TaskRecord};
+use super::types::Milestone;
+use anyhow::{bail, Result};
+use rusqlite::Connection;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Fields that record local history rather than intent; ours always wins.
+const LOCAL_FIELDS: &[&str] = &["slug", "status", "created_at"];
+
+/// A field both sides changed to different values.
+#[derive(Debug, Clone)]
+pub struct FieldConflict {
+    pub slug: String,
+    pub field: String,
+    pub ours: Value,
+    pub theirs: Value,
+    pub resolution: Option<Side>,
+}
+
+/// Which side wins a conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Ours,
+    Theirs,
+}
+
+/// The changes a merge will make, before conflicts are resolved.
+#[derive(Debug, Default)]
+pub struct MergePlan {
+    /// Milestones only `theirs` has.
+    pub new_milestones: Vec<Milestone>,
+    pub new_tasks: Vec<TaskRecord>,
+    /// Merged definitions of existing tasks that differ from ours.
+    updated: BTreeMap<String, Map<String, Value>>,
+    pub conflicts: Vec<FieldConflict>,
+    pub edges_added: Vec<Edge>,
+    pub edges_removed: Vec<Edge>,
+    proofs: Vec<PortableProof>,
+}
+
+/// What a merge changed.
+#[derive(Debug, Default)]
+pub struct MergeSummary {
+    pub tasks_added: usize,
+    pub tasks_updated: usize,
+    pub edges_added: usize,
+    pub edges_removed: usize,
+    /// Edges dropped because they would close a dependency cycle.
+    pub edges_rejected: Vec<Edge>,
+    pub proofs_added: usize,
+}
+
+/// Plans a merge of `theirs` into `ours`. Without a `base`, every differing
+/// field is a conflict and no edge is ever removed.
+///
+/// # Errors
+/// Returns error if a task record cannot be represented as JSON.
+pub fn plan(base: Option<&Snapshot>, ours: &Snapshot, theirs: &Snapshot) -> Result<MergePlan> {
+    let mut plan = MergePlan::default();
+    let base_tasks = index(base.map_or(&[][..], |b| &b.tasks));
+    let our_tasks = index(&ours.tasks);
+
+    plan.new_milestones = theirs
+        .milestones
+        .iter()
+        .filter(|m| !ours.milestones.iter().any(|o| o.name == m.name))
+        .cloned()
+        .collect();
+
+    for theirs_task in &theirs.tasks {
+        let Some(ours_task) = our_tasks.get(theirs_task.slug.as_str()) else {
+            plan.new_tasks.push(theirs_task.clone());
+            continue;
+        };
+        let base_task = base_tasks.get(theirs_task.slug.as_str()).copied();
+        plan.merge_task(base_task, ours_task, theirs_task)?;
+    }
+
+    let base_edges: BTreeSet<&Edge> = base.map(|b| b.edges.iter().collect()).unwrap_or_default();
+    let our_edges: BTreeSet<&Edge> = ours.edges.iter().collect();
+    let their_edges: BTreeSet<&Edge> = theirs.edges.iter().collect();
+    for edge in their_edges.difference(&our_edges) {
+        if !base_edges.contains(edge) {
+            plan.edges_added.push((*edge).clone());
+        }
+    }
+    for edge in our_edges.difference(&their_edges) {
+        if base_edges.contains(edge) {
+            plan.edges_removed.push((*edge).clone());
+        }
+    }
+
+    plan.proofs.clone_from(&theirs.proofs);
+    Ok(plan)
+}
+
+impl MergePlan {
+    fn merge_task(
+        &mut self,
+        base: Option<&TaskRecord>,
+        ours: &TaskRecord,
+        theirs: &TaskRecord,
+    ) -> Result<()> {
+        let base = base.map(to_map).transpose()?.unwrap_or_default();
+        let ours_map = to_map(ours)?;
+        let theirs_map = to_map(theirs)?;
+
+        let keys: BTreeSet<&String> = ours_map.keys().chain(theirs_map.keys()).collect();
+        let mut merged = ours_map.clone();
+        for key in keys {
+            if LOCAL_FIELDS.contains(&key.as_str()) {
+                continue;
+            }
+            let (o, t, b) = (ours_map.get(key), theirs_map.get(key), base.get(key));
+            if o == t || t == b {
+                continue;
+            }
+            if o == b {
+                set(&mut merged, key, t);
+                continue;
+            }
+            self.conflicts.push(FieldConflict {
+                slug: ours.slug.clone(),
+                field: key.clone(),
+                ours: o.cloned().unwrap_or(Value::Null),
+                theirs: t.cloned().unwrap_or(Value::Null),
+                resolution: None,
+            });
+        }
+
+        if merged != ours_map || self.conflicts.iter().any(|c| c.slug == ours.slug) {
+            self.updated.insert(ours.slug.clone(), merged);
+        }
+        Ok(())
+    }
+
+    /// Settles conflict `index` in favour of `side`.
+    pub fn resolve(&mut self, index: usize, side: Side) {
+        let conflict = &mut self.conflicts[index];
+        conflict.resolution = Some(side);
+        if side == Side::Theirs {
+            if let Some(merged) = self.updated.get_mut(&conflict.slug) {
+                let value = Some(&conflict.theirs).filter(|v| !v.is_null());
+                set(merged, &conflict.field, value);
+            }
+        }
+    }
+
+    /// Applies the plan. Call [`Self::ensure_resolved`] first: an unresolved
+    /// conflict keeps our value.
+    ///
+    /// # Errors
+    /// Returns error if a merged record is invalid or a database operation fails.
+    pub fn apply(self, conn: &Connection) -> Result<MergeSummary> {
+        let repo = TaskRepo::new(conn);
+        let mut summary = MergeSummary::default();
+
+        for milestone in &self.new_milestones {
+            MilestoneRepo::new(conn).restore(milestone)?;
+        }
+        for record in &self.new_tasks {
+            snapshot::restore_task(&repo, record)?;
+            summary.tasks_added += 1;
+        }
+
+        let ids = snapshot::slug_ids(&repo)?;
+        let mut updated = Vec::new();
+        for (slug, merged) in self.updated {
+            let Some(&id) = ids.get(&slug) else { continue };
+            let record: TaskRecord = serde_json::from_value(Value::Object(merged))?;
+            snapshot::overwrite_task(&repo, id, &record)?;
+            updated.push(record);
+            summary.tasks_updated += 1;
+        }
+        snapshot::set_parents(&repo, &ids, self.new_tasks.iter().chain(&updated))?;
+
+        for edge in &self.edges_removed {
+            if let (Some(&from), Some(&to)) = (ids.get(&edge.blocker), ids.get(&edge.blocked)) {
+                repo.unlink(from, to)?;
+                summary.edges_removed += 1;
+            }
+        }
+        let (added, rejected) = snapshot::link_acyclic(conn, &ids, &self.edges_added)?;
+        summary.edges_added = added;
+        summary.edges_rejected = rejected;
+        summary.proofs_added = snapshot::append_proofs(conn, &ids, &self.proofs)?;
+        Ok(summary)
+    }
+
+    /// Fails if any conflict is still unresolved.
+    ///
+    /// # Errors
+    /// Returns error listing every unresolved conflict.
+    pub fn ensure_resolved(&self) -> Result<()> {
+        let open: Vec<&FieldConflict> = self
+            .conflicts
+            .iter()
+            .filter(|c| c.resolution.is_none())
+            .collect();
+        if open.is_empty() {
+            return Ok(());
+        }
+        let list: Vec<String> = open
+            .iter()
+            .map(|c| format!("  [{}] {}: ours={} theirs={}", c.slug, c.field, c.ours, c.theirs))
+            .collect();
+        bail!(
+            "{} unresolved conflict(s):\n{}\nRe-run interactively, or pass --ours/--theirs",
+            open.len(),
+            list.join("\n")
+        )
+    }
+}
+
+fn index(tasks: &[TaskRecord]) -> BTreeMap<&str, &TaskRecord> {
+    tasks.iter().map(|t| (t.slug.as_str(), t)).collect()
+}
+
+fn to_map(record: &TaskRecord) -> Result<Map<String, Value>> {
+    match serde_json::to_value(record)? {
+        Value::Object(map) => Ok(map),
+        _ => bail!("Task record did not serialize to an object"),
+    }
+}
+
+fn set(map: &mut Map<String, Value>, key: &str, value: Option<&Value>) {
+    match value {
+        Some(v) => {
+            map.insert(key.to_string(), v.clone());
+        }
+        None => {
+            map.remove(key);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Milestones: Groups of tasks that ship together, and how far along each is.
+//!
+//! A task belongs to at most one milestone. A milestone is complete when
+//! every task in it is done (proven, attested, or quarantined); one without
+//! tasks is never complete.
+
+use super::graph::TaskGraph;
+use super::resolver::slugify;
+use super::types::{Milestone, Task};
+use anyhow::{bail, Result};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Completion of one milestone.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct MilestoneProgress {
+    pub name: String,
+    pub title: Option<String>,
+    pub done: usize,
+    pub total: usize,
+    /// Whole percent of tasks done; 0 for an empty milestone.
+    pub percent: usize,
+}
+
+/// Normalizes a milestone name the way slugs are: `Q4 Launch` becomes `q4-launch`.
+///
+/// # Errors
+/// Returns error if nothing is left of the name.
+pub fn normalize(name: &str) -> Result<String> {
+    let normalized = slugify(name);
+    if normalized.is_empty() {
+        bail!("Invalid milestone name '{name}'");
+    }
+    Ok(normalized)
+}
+
+/// The tasks assigned to `name`, in creation order.
+#[must_use]
+pub fn tasks<'a>(graph: &'a TaskGraph, name: &str) -> Vec<&'a Task> {
+    let mut tasks: Vec<&Task> = graph
+        .tasks()
+        .filter(|t| t.milestone.as_deref() == Some(name))
+        .collect();
+    tasks.sort_by_key(|t| t.id);
+    tasks
+}
+
+/// Completion of each milestone, in the order given.
+#[must_use]
+pub fn progress(graph: &TaskGraph, milestones: &[Milestone]) -> Vec<MilestoneProgress> {
+    milestones
+        .iter()
+        .map(|milestone| {
+            let tasks = tasks(graph, &milestone.name);
+            let done = tasks
+                .iter()
+                .filter(|t| t.derive_status(graph.context()).satisfies_dependency())
+                .count();
+            MilestoneProgress {
+                name: milestone.name.clone(),
+                title: milestone.title.clone(),
+                done,
+                total: tasks.len(),
+                percent: (done * 100).checked_div(tasks.len()).unwrap_or(0),
+            }
+        })
+        .collect()
+}
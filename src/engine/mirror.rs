@@ -0,0 +1,159 @@
+//! State Mirror: The database as a line-per-record file that git can merge.
+//!
+//! `state.db` is binary, so two branches that both add tasks collide on it.
+//! The mirror holds the same snapshot as JSON Lines: a header, then one
+//! line per milestone, task, edge, and proof, each sorted by name or slug. Independent additions
+//! touch different lines, and when git's text merge still conflicts, the
+//! merge driver redoes it structurally with the snapshot three-way merge.
+//!
+//! The database remembers the digest of the mirror it last wrote or loaded.
+//! A file that no longer matches came from elsewhere (a clone, a pull, a
+//! merge) and is never overwritten automatically.
+
+use super::canonical;
+use super::db::{Db, DB_DIR};
+use super::exchange::PortableProof;
+use super::merge::{self, FieldConflict};
+use super::snapshot::{Edge, Snapshot, TaskRecord, SNAPSHOT_VERSION};
+use super::types::Milestone;
+use anyhow::{bail, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// File name of the mirror inside `.roadmap/`.
+pub const MIRROR_FILE: &str = "state.jsonl";
+
+/// Location of the selected roadmap's mirror, relative to the working directory.
+#[must_use]
+pub fn path() -> PathBuf {
+    Path::new(DB_DIR).join(Db::file_name(MIRROR_FILE))
+}
+
+/// One line of the mirror.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Line {
+    Roadmap { version: u32 },
+    Milestone(Milestone),
+    Task(Box<TaskRecord>),
+    Edge(Edge),
+    Proof(Box<PortableProof>),
+}
+
+/// Renders a snapshot as mirror text.
+///
+/// # Errors
+/// Returns error if a record cannot be represented as JSON.
+pub fn render(snapshot: &Snapshot) -> Result<String> {
+    let mut out = canonical::to_line(&Line::Roadmap {
+        version: snapshot.version,
+    })?;
+    out.push('\n');
+    let lines = snapshot
+        .milestones
+        .iter()
+        .map(|m| canonical::to_line(&Line::Milestone(m.clone())))
+        .chain(snapshot.tasks.iter().map(|t| canonical::to_line(&Line::Task(Box::new(t.clone())))))
+        .chain(snapshot.edges.iter().map(|e| canonical::to_line(&Line::Edge(e.clone()))))
+        .chain(
+            snapshot
+                .proofs
+                .iter()
+                .map(|p| canonical::to_line(&Line::Proof(Box::new(p.clone())))),
+        );
+    for line in lines {
+        out.push_str(&line?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Parses mirror text back into a snapshot.
+///
+/// # Errors
+/// Returns error if a line is malformed, the header is missing, or it is from a newer version.
+pub fn parse(text: &str) -> Result<Snapshot> {
+    let mut snapshot = Snapshot {
+        version: 0,
+        milestones: Vec::new(),
+        tasks: Vec::new(),
+        edges: Vec::new(),
+        proofs: Vec::new(),
+    };
+    for (number, line) in text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+        if line.starts_with("<<<<<<<") || line.starts_with(">>>>>>>") {
+            bail!("Line {}: unresolved git conflict marker", number + 1);
+        }
+        let parsed: Line = serde_json::from_str(line)
+            .with_context(|| format!("Line {}: not a mirror record", number + 1))?;
+        match parsed {
+            Line::Roadmap { version } => snapshot.version = version,
+            Line::Milestone(milestone) => snapshot.milestones.push(milestone),
+            Line::Task(task) => snapshot.tasks.push(*task),
+            Line::Edge(edge) => snapshot.edges.push(edge),
+            Line::Proof(proof) => snapshot.proofs.push(*proof),
+        }
+    }
+    if snapshot.version == 0 {
+        bail!("Missing roadmap header line");
+    }
+    if snapshot.version > SNAPSHOT_VERSION {
+        bail!(
+            "Mirror version {} is newer than supported ({SNAPSHOT_VERSION})",
+            snapshot.version
+        );
+    }
+    Ok(snapshot)
+}
+
+/// Three-way merges mirror snapshots without touching the roadmap database.
+///
+/// Tasks and edges merge as in `merge-state`; proofs are unioned. Tasks
+/// deleted on one side are kept. Returns the merged snapshot, or the fields
+/// both sides changed differently.
+///
+/// # Errors
+/// Returns error if a snapshot cannot be loaded into the scratch database.
+pub fn merge(
+    base: Option<&Snapshot>,
+    ours: &Snapshot,
+    theirs: &Snapshot,
+) -> Result<std::result::Result<Snapshot, Vec<FieldConflict>>> {
+    let plan = merge::plan(base, ours, theirs)?;
+    if !plan.conflicts.is_empty() {
+        return Ok(Err(plan.conflicts));
+    }
+    let conn = Db::open(Path::new(":memory:"))?;
+    ours.apply(&conn)?;
+    plan.apply(&conn)?;
+    Ok(Ok(Snapshot::capture(&conn)?))
+}
+
+/// Whether `text` is the mirror this database last wrote or loaded.
+///
+/// # Errors
+/// Returns error if the query fails.
+pub fn is_synced(conn: &Connection, text: &str) -> Result<bool> {
+    let stored: Option<String> = conn
+        .query_row("SELECT digest FROM mirror_sync WHERE id = 1", [], |r| r.get(0))
+        .optional()?;
+    Ok(stored.is_some_and(|d| d == digest(text)))
+}
+
+/// Records `text` as the mirror this database is in step with.
+///
+/// # Errors
+/// Returns error if the update fails.
+pub fn mark_synced(conn: &Connection, text: &str) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO mirror_sync (id, digest) VALUES (1, ?1)",
+        params![digest(text)],
+    )?;
+    Ok(())
+}
+
+fn digest(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
@@ -1,10 +1,55 @@
 //! Core engine modules for roadmap.
 
+pub mod artifacts;
+pub mod attestation;
+pub mod audit;
+pub mod badge;
+pub mod bench;
+pub mod blocked;
+pub mod budget;
+pub mod burndown;
+pub mod canonical;
+pub mod chain;
+pub mod config;
+pub mod container;
 pub mod context;
+pub mod coverage;
 pub mod db;
+pub mod diff;
+pub mod due;
+pub mod estimate;
+pub mod exchange;
+pub mod facade;
+pub mod fingerprint;
+pub mod gen;
+pub mod gate;
 pub mod graph;
+pub mod hooks;
+pub mod impact;
+pub mod journal;
+pub mod lifecycle;
+pub mod lock;
+pub mod merge;
+pub mod milestone;
+pub mod mirror;
+pub mod oplog;
+pub mod output;
+pub mod plan;
+pub mod reliability;
+pub mod remote;
 pub mod repo;
 pub mod resolver;
+pub mod retention;
 pub mod runner;
+pub mod sandbox;
+pub mod scopes;
+pub mod shell;
+pub mod signing;
+pub mod snapshot;
 pub mod state;
-pub mod types;
\ No newline at end of file
+pub mod state_ref;
+pub mod stats;
+pub mod sync;
+pub mod trailers;
+pub mod types;
+pub mod usage;
\ No newline at end of file
@@ -0,0 +1,264 @@
+//! Operation Log: Every mutation, recorded with the SQL that reverts it.
+//!
+//! Each connection installs TEMP triggers on the roadmap tables. Whenever a
+//! row is inserted, updated, or deleted, the trigger appends the statement
+//! that restores the previous row to `operation_steps`. Steps are grouped
+//! into one `operations` row per CLI invocation, so `undo` reverts a whole
+//! command (an `add` with its scopes and edges, a bulk import) at once.
+//!
+//! The log is append-only. Undoing replays the inverse statements, which
+//! are themselves logged as a new operation that records what it reverted.
+
+use super::db::Db;
+use anyhow::{bail, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Tables whose rows are tracked. Bookkeeping tables are deliberately absent.
+const TRACKED: &[&str] = &[
+    "tasks",
+    "task_scopes",
+    "task_tags",
+    "task_budgets",
+    "task_aliases",
+    "milestones",
+    "dependencies",
+    "proofs",
+    "proof_tombstones",
+    "state",
+];
+
+/// The operation mutations are currently recorded into.
+struct Batch {
+    id: String,
+    command: String,
+}
+
+static CURRENT: Mutex<Option<Batch>> = Mutex::new(None);
+
+/// Names the command whose mutations this process records from now on.
+///
+/// Each call starts a new operation, so long-running servers call it once
+/// per request and every request can be undone on its own. Connections
+/// opened before the first call, or in processes that never call it
+/// (benchmarks, tests), record nothing.
+pub fn set_command(command: &str) {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let id = format!(
+        "{}-{nanos}-{}",
+        std::process::id(),
+        SEQUENCE.fetch_add(1, Ordering::Relaxed)
+    );
+    *lock() = Some(Batch {
+        id,
+        command: command.to_string(),
+    });
+}
+
+/// Starts a new operation and records into it on an already open connection.
+///
+/// For servers that keep one connection across requests; connections opened
+/// later pick the operation up by themselves.
+///
+/// # Errors
+/// Returns an error if the recording triggers cannot be reinstalled.
+pub fn start(conn: &Connection, command: &str) -> Result<()> {
+    set_command(command);
+    install(conn)
+}
+
+/// The batch of the operation being recorded, which links it to its journal event.
+#[must_use]
+pub fn current_batch() -> Option<String> {
+    lock().as_ref().map(|b| b.id.clone())
+}
+
+fn lock() -> std::sync::MutexGuard<'static, Option<Batch>> {
+    CURRENT.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// A logged command.
+#[derive(Debug)]
+pub struct Operation {
+    pub id: i64,
+    pub timestamp: String,
+    pub command: String,
+    pub changes: usize,
+    /// The operation this one undid, if it was an `undo`.
+    pub reverts: Option<i64>,
+    /// Whether a later `undo` reverted this operation.
+    pub undone: bool,
+}
+
+/// Creates the per-connection recording context and triggers.
+///
+/// # Errors
+/// Returns an error if a table cannot be inspected or a trigger cannot be created.
+pub(crate) fn install(conn: &Connection) -> Result<()> {
+    let Some((batch, command)) = lock().as_ref().map(|b| (b.id.clone(), b.command.clone())) else {
+        return Ok(());
+    };
+    // REPLACE only fires delete triggers with recursive triggers on; ours never recurse.
+    conn.execute_batch(
+        "PRAGMA recursive_triggers = ON;
+         CREATE TEMP TABLE IF NOT EXISTS roadmap_op (batch TEXT, command TEXT, reverts INTEGER);
+         DELETE FROM temp.roadmap_op;",
+    )?;
+    conn.execute(
+        "INSERT INTO temp.roadmap_op (batch, command) VALUES (?1, ?2)",
+        params![batch, command],
+    )?;
+    for table in TRACKED {
+        let columns = columns(conn, table)?;
+        conn.execute_batch(&triggers(table, &columns))?;
+    }
+    Ok(())
+}
+
+/// Stops recording on this connection until the next [`start`].
+///
+/// For maintenance whose inverse is not worth keeping, like pruning old
+/// proofs: the log would otherwise hold a copy of everything it deleted.
+///
+/// # Errors
+/// Returns an error if a trigger cannot be dropped.
+pub fn suspend(conn: &Connection) -> Result<()> {
+    for table in TRACKED {
+        for event in ["insert", "update", "delete"] {
+            conn.execute_batch(&format!("DROP TRIGGER IF EXISTS temp.roadmap_oplog_{table}_{event};"))?;
+        }
+    }
+    Ok(())
+}
+
+fn columns(conn: &Connection, table: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA main.table_info({table})"))?;
+    let names = stmt
+        .query_map([], |r| r.get::<_, String>(1))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(names)
+}
+
+/// Builds the three triggers for a table; each records its inverse statement.
+///
+/// Statements inside a trigger inherit the conflict policy of the statement
+/// that fired it (an `INSERT OR REPLACE` would turn `OR IGNORE` into a
+/// replace), so the operation row is guarded with `NOT EXISTS` instead.
+fn triggers(table: &str, columns: &[String]) -> String {
+    let restore_values = columns
+        .iter()
+        .map(|c| format!("quote(old.{c})"))
+        .collect::<Vec<_>>()
+        .join(" || ',' || ");
+    let restore = format!(
+        "'INSERT INTO {table} (rowid, {}) VALUES (' || old.rowid || ',' || {restore_values} || ')'",
+        columns.join(", ")
+    );
+    let revert_update = format!(
+        "'UPDATE {table} SET ' || {} || ' WHERE rowid = ' || old.rowid",
+        columns
+            .iter()
+            .map(|c| format!("'{c} = ' || quote(old.{c})"))
+            .collect::<Vec<_>>()
+            .join(" || ', ' || ")
+    );
+    let remove = format!("'DELETE FROM {table} WHERE rowid = ' || new.rowid");
+
+    let mut sql = String::new();
+    for (event, inverse) in [("INSERT", remove), ("UPDATE", revert_update), ("DELETE", restore)] {
+        let name = format!("roadmap_oplog_{table}_{}", event.to_lowercase());
+        let _ = write!(
+            sql,
+            "DROP TRIGGER IF EXISTS temp.{name};
+                 CREATE TEMP TRIGGER {name} AFTER {event} ON main.{table} BEGIN
+                     INSERT INTO operations (batch, command, reverts)
+                         SELECT batch, command, reverts FROM temp.roadmap_op c
+                         WHERE NOT EXISTS (SELECT 1 FROM main.operations o WHERE o.batch = c.batch);
+                     INSERT INTO operation_steps (operation_id, undo_sql)
+                         SELECT o.id, {inverse}
+                         FROM temp.roadmap_op c JOIN main.operations o ON o.batch = c.batch;
+                 END;"
+        );
+    }
+    sql
+}
+
+/// Lists the most recent operations, newest first.
+///
+/// # Errors
+/// Returns an error if the query fails.
+pub fn recent(conn: &Connection, limit: usize) -> Result<Vec<Operation>> {
+    let sql = format!("{OPERATION_SELECT} ORDER BY o.id DESC LIMIT ?1");
+    let mut stmt = conn.prepare(&sql)?;
+    let ops = stmt
+        .query_map(params![i64::try_from(limit).unwrap_or(i64::MAX)], row_to_operation)?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(ops)
+}
+
+const OPERATION_SELECT: &str = "SELECT o.id, o.timestamp, o.command, o.reverts,
+        (SELECT COUNT(*) FROM operation_steps s WHERE s.operation_id = o.id),
+        EXISTS (SELECT 1 FROM operations u WHERE u.reverts = o.id)
+    FROM operations o";
+
+fn row_to_operation(r: &rusqlite::Row) -> rusqlite::Result<Operation> {
+    Ok(Operation {
+        id: r.get(0)?,
+        timestamp: r.get(1)?,
+        command: r.get(2)?,
+        reverts: r.get(3)?,
+        changes: r.get::<_, i64>(4)?.try_into().unwrap_or(0),
+        undone: r.get(5)?,
+    })
+}
+
+/// Reverts the most recent operation that is neither an undo nor already undone.
+///
+/// Returns the reverted operation, or `None` if there is nothing left to undo.
+///
+/// # Errors
+/// Returns an error if recording is not active or a revert statement fails;
+/// the transaction is rolled back and nothing changes.
+pub fn undo_last(conn: &mut Connection) -> Result<Option<Operation>> {
+    let target: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM operations
+             WHERE reverts IS NULL AND id NOT IN (SELECT reverts FROM operations WHERE reverts IS NOT NULL)
+             ORDER BY id DESC LIMIT 1",
+            [],
+            |r| r.get(0),
+        )
+        .optional()?;
+    let Some(target) = target else {
+        return Ok(None);
+    };
+    if lock().is_none() {
+        bail!("Operation recording is not active in this process");
+    }
+
+    let tx = Db::begin_write(conn)?;
+    tx.execute("UPDATE temp.roadmap_op SET reverts = ?1", params![target])?;
+    let steps: Vec<String> = tx
+        .prepare("SELECT undo_sql FROM operation_steps WHERE operation_id = ?1 ORDER BY id DESC")?
+        .query_map(params![target], |r| r.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    for sql in &steps {
+        tx.execute_batch(sql)?;
+    }
+    // An operation with no effective steps still has to be marked as undone.
+    tx.execute(
+        "INSERT OR IGNORE INTO operations (batch, command, reverts)
+         SELECT batch, command, reverts FROM temp.roadmap_op",
+        [],
+    )?;
+    tx.commit()?;
+
+    let sql = format!("{OPERATION_SELECT} WHERE o.id = ?1");
+    Ok(Some(conn.query_row(&sql, params![target], row_to_operation)?))
+}
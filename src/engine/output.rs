@@ -0,0 +1,94 @@
+//! Output: Bounding the command output a proof stores.
+//!
+//! A noisy test suite can print megabytes, and every proof keeps its output.
+//! Output over the limit keeps its first and last bytes, where the command
+//! line and the failure summary usually are, with a marker saying how much
+//! was left out in between. The proof records the count, and with
+//! `runner.spill_output` the full output is kept as artifacts of the run.
+
+use super::config::RunnerSettings;
+use super::types::ProofOutcome;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// File names of spilled output in a run's artifact directory.
+pub const STDOUT_FILE: &str = "stdout.log";
+pub const STDERR_FILE: &str = "stderr.log";
+
+/// How much of each stream a proof keeps.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    /// Bytes kept from the start.
+    pub head: usize,
+    /// Bytes kept from the end.
+    pub tail: usize,
+}
+
+impl Limits {
+    #[must_use]
+    pub fn from_settings(settings: &RunnerSettings) -> Self {
+        Self {
+            head: settings.output_head,
+            tail: settings.output_tail,
+        }
+    }
+
+    /// Whether `text` is longer than the proof keeps.
+    #[must_use]
+    pub fn exceeded(&self, text: &str) -> bool {
+        text.len() > self.head.saturating_add(self.tail)
+    }
+
+    /// Cuts both streams of `outcome` to the limits, recording how many bytes were left out.
+    pub fn apply(&self, outcome: &mut ProofOutcome) {
+        let omitted = self.cut(&mut outcome.stdout) + self.cut(&mut outcome.stderr);
+        if omitted > 0 {
+            outcome.truncated = Some(omitted);
+        }
+    }
+
+    /// Keeps the head and tail of `text`; returns the bytes removed.
+    fn cut(&self, text: &mut String) -> u64 {
+        if !self.exceeded(text) {
+            return 0;
+        }
+        let head = floor_boundary(text, self.head);
+        let tail = ceil_boundary(text, text.len() - self.tail);
+        let omitted = tail - head;
+        *text = format!(
+            "{}\n… [{omitted} bytes omitted] …\n{}",
+            &text[..head],
+            &text[tail..]
+        );
+        u64::try_from(omitted).unwrap_or(u64::MAX)
+    }
+}
+
+/// Writes the full output of a run into its artifact directory, for streams over the limit.
+///
+/// # Errors
+/// Returns error if a file cannot be written.
+pub fn spill(dir: &Path, limits: Limits, stdout: &str, stderr: &str) -> Result<()> {
+    for (name, text) in [(STDOUT_FILE, stdout), (STDERR_FILE, stderr)] {
+        if limits.exceeded(text) {
+            let path = dir.join(name);
+            fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+fn floor_boundary(text: &str, mut at: usize) -> usize {
+    while !text.is_char_boundary(at) {
+        at -= 1;
+    }
+    at
+}
+
+fn ceil_boundary(text: &str, mut at: usize) -> usize {
+    while !text.is_char_boundary(at) {
+        at += 1;
+    }
+    at
+}
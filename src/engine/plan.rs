@@ -0,0 +1,232 @@
+//! Plan File: The declarative, hand-editable roadmap (`roadmap.toml`).
+//!
+//! The plan describes intent only: tasks, their commands, scopes, and
+//! dependencies. Proofs never live here. Its canonical form sorts tasks by
+//! slug and every list alphabetically, so concurrent edits merge cleanly.
+
+use super::budget::Budget;
+use super::due::DATE_FORMAT;
+use super::resolver::slugify;
+use super::runner;
+use super::shell::Shell;
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use petgraph::algo::is_cyclic_directed;
+use petgraph::graphmap::DiGraphMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Default plan file name, at the repository root.
+pub const PLAN_FILE: &str = "roadmap.toml";
+
+/// The whole plan file.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Plan {
+    #[serde(default, rename = "task")]
+    pub tasks: Vec<PlanTask>,
+}
+
+/// One `[[task]]` entry. Field names mirror the `add` flags.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PlanTask {
+    pub slug: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub after: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_unix: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_windows: Option<String>,
+    /// Directory the test command runs in, relative to the roadmap's root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_coverage: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bench: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub budgets: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub priority: i64,
+    /// `YYYY-MM-DD`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<String>,
+    /// Hours.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Slug of the planned task this one is a subtask of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// Name of the milestone; created on sync if it doesn't exist.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub milestone: Option<String>,
+    /// Sets the assignee on sync; leaving it out keeps whoever is assigned locally.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // serde passes fields by reference
+fn is_zero(n: &i64) -> bool {
+    *n == 0
+}
+
+impl Plan {
+    /// Reads and parses a plan file.
+    ///
+    /// # Errors
+    /// Returns error if the file is unreadable or not valid plan TOML.
+    pub fn read(path: &Path) -> Result<Self> {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&raw).with_context(|| format!("Invalid plan in {}", path.display()))
+    }
+
+    /// Sorts tasks by slug and every list alphabetically, dropping duplicates.
+    ///
+    /// Budgets keep their written order; it is how they are reported.
+    pub fn normalize(&mut self) {
+        for task in &mut self.tasks {
+            task.title = task.title.trim().to_string();
+            for list in [&mut task.after, &mut task.scopes, &mut task.tags] {
+                list.sort();
+                list.dedup();
+            }
+        }
+        self.tasks.sort_by(|a, b| a.slug.cmp(&b.slug));
+    }
+
+    /// Checks slugs, references, option values, and acyclicity.
+    ///
+    /// Returns every problem found, so one run reports them all.
+    #[must_use]
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut seen = HashSet::new();
+        for task in &self.tasks {
+            if !seen.insert(task.slug.as_str()) {
+                problems.push(format!("duplicate slug '{}'", task.slug));
+            }
+            if task.slug.is_empty() || slugify(&task.slug) != task.slug {
+                problems.push(format!("'{}' is not a valid slug", task.slug));
+            }
+            task.validate_options(&mut problems);
+        }
+
+        let mut graph: DiGraphMap<&str, ()> = DiGraphMap::new();
+        for task in &self.tasks {
+            graph.add_node(task.slug.as_str());
+            for dep in &task.after {
+                if dep == &task.slug {
+                    problems.push(format!("[{}] depends on itself", task.slug));
+                } else if !seen.contains(dep.as_str()) {
+                    problems.push(format!("[{}] is after unknown task '{dep}'", task.slug));
+                } else {
+                    graph.add_edge(dep.as_str(), task.slug.as_str(), ());
+                }
+            }
+        }
+        if is_cyclic_directed(&graph) {
+            problems.push("dependencies form a cycle".to_string());
+        }
+
+        let mut parents: DiGraphMap<&str, ()> = DiGraphMap::new();
+        for task in &self.tasks {
+            let Some(parent) = &task.parent else { continue };
+            if seen.contains(parent.as_str()) {
+                parents.add_edge(parent.as_str(), task.slug.as_str(), ());
+            } else {
+                problems.push(format!("[{}] has unknown parent '{parent}'", task.slug));
+            }
+        }
+        if is_cyclic_directed(&parents) {
+            problems.push("subtasks form a cycle".to_string());
+        }
+        problems
+    }
+
+    /// Renders the canonical TOML text of the plan.
+    ///
+    /// # Errors
+    /// Returns error if serialization fails.
+    pub fn render(&self) -> Result<String> {
+        let mut out = String::new();
+        for (i, task) in self.tasks.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            out.push_str("[[task]]\n");
+            out.push_str(&toml::to_string(task)?);
+        }
+        Ok(out)
+    }
+}
+
+impl PlanTask {
+    fn validate_options(&self, problems: &mut Vec<String>) {
+        if let Some(shell) = &self.shell {
+            if let Err(e) = Shell::parse(shell) {
+                problems.push(format!("[{}] {e}", self.slug));
+            }
+        }
+        if let Some(Err(e)) = self.cwd.as_deref().map(runner::working_dir) {
+            problems.push(format!("[{}] {e}", self.slug));
+        }
+        for spec in &self.budgets {
+            if let Err(e) = Budget::parse(spec) {
+                problems.push(format!("[{}] {e}", self.slug));
+            }
+        }
+        if let Some(due) = &self.due {
+            if NaiveDate::parse_from_str(due, DATE_FORMAT).is_err() {
+                problems.push(format!("[{}] due '{due}' is not a YYYY-MM-DD date", self.slug));
+            }
+        }
+        for tag in &self.tags {
+            if slugify(tag) != *tag {
+                problems.push(format!("[{}] '{tag}' is not a valid tag", self.slug));
+            }
+        }
+        if let Some(milestone) = self.milestone.as_ref().filter(|m| slugify(m) != **m) {
+            problems.push(format!("[{}] '{milestone}' is not a valid milestone name", self.slug));
+        }
+        if self.estimate.is_some_and(|h| !h.is_finite() || h < 0.0) {
+            problems.push(format!("[{}] estimate must be a non-negative number of hours", self.slug));
+        }
+        if self.min_coverage.is_some() && self.coverage.is_none() {
+            problems.push(format!("[{}] min_coverage requires coverage", self.slug));
+        }
+    }
+}
+
+/// Fails with a readable list if `problems` is non-empty.
+///
+/// # Errors
+/// Returns error describing every problem.
+pub fn ensure_valid(path: &Path, problems: &[String]) -> Result<()> {
+    if problems.is_empty() {
+        return Ok(());
+    }
+    bail!(
+        "{} has {} problem(s):\n  - {}",
+        path.display(),
+        problems.len(),
+        problems.join("\n  - ")
+    )
+}
@@ -0,0 +1,112 @@
+//! Reliability: How often each claim breaks and how long it stays broken.
+//!
+//! Computed from the full proof history. A break is a failing run that
+//! follows a passing one (or opens the history); a repair is the next pass
+//! after a break. Attestations are not verifications and are ignored.
+
+use super::repo::ProofRecord;
+use super::types::{parse_timestamp, Proof};
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Reliability figures for one task.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TaskReliability {
+    pub slug: String,
+    pub runs: usize,
+    pub passes: usize,
+    /// Passing runs as a percentage of all runs.
+    pub pass_rate: f64,
+    pub breaks: usize,
+    /// Mean time between consecutive breaks, once there are at least two.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_hours_between_breaks: Option<f64>,
+    /// Mean time from a break to the next passing run, over repaired breaks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_hours_to_repair: Option<f64>,
+    /// True while the latest run is a failure.
+    pub broken_now: bool,
+}
+
+/// Computes reliability per task, most frequently broken first.
+///
+/// Tasks that were never verified are left out.
+#[must_use]
+pub fn analyze(trail: &[ProofRecord]) -> Vec<TaskReliability> {
+    let mut runs: BTreeMap<&str, Vec<(DateTime<Utc>, bool)>> = BTreeMap::new();
+    for record in trail {
+        if record.proof.attested_reason.is_some() {
+            continue;
+        }
+        let Some(at) = parse_timestamp(&record.proof.timestamp) else {
+            continue;
+        };
+        runs.entry(&record.slug).or_default().push((at, passed(&record.proof)));
+    }
+
+    let mut report: Vec<TaskReliability> = runs
+        .into_iter()
+        .map(|(slug, mut runs)| {
+            // Imported proofs can arrive out of order; stable sort keeps ties as recorded.
+            runs.sort_by_key(|(at, _)| *at);
+            summarize(slug, &runs)
+        })
+        .collect();
+    report.sort_by(|a, b| {
+        b.breaks
+            .cmp(&a.breaks)
+            .then_with(|| a.pass_rate.total_cmp(&b.pass_rate))
+            .then_with(|| a.slug.cmp(&b.slug))
+    });
+    report
+}
+
+fn passed(proof: &Proof) -> bool {
+    proof.exit_code == 0 && proof.failure.is_none()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn summarize(slug: &str, runs: &[(DateTime<Utc>, bool)]) -> TaskReliability {
+    let passes = runs.iter().filter(|(_, ok)| *ok).count();
+    let mut break_times = Vec::new();
+    let mut repairs = Vec::new();
+    let mut broken_since: Option<DateTime<Utc>> = None;
+
+    for &(at, ok) in runs {
+        match (ok, broken_since) {
+            (false, None) => {
+                break_times.push(at);
+                broken_since = Some(at);
+            }
+            (true, Some(since)) => {
+                repairs.push(hours(at - since));
+                broken_since = None;
+            }
+            _ => {}
+        }
+    }
+
+    let gaps: Vec<f64> = break_times.windows(2).map(|w| hours(w[1] - w[0])).collect();
+    TaskReliability {
+        slug: slug.to_string(),
+        runs: runs.len(),
+        passes,
+        pass_rate: passes as f64 * 100.0 / runs.len() as f64,
+        breaks: break_times.len(),
+        mean_hours_between_breaks: mean(&gaps),
+        mean_hours_to_repair: mean(&repairs),
+        broken_now: broken_since.is_some(),
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn hours(d: chrono::Duration) -> f64 {
+    d.num_seconds() as f64 / 3600.0
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
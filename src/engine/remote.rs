@@ -0,0 +1,165 @@
+//! Remote Proof Store: One shared source of verified truth for a team.
+//!
+//! Each task's newest proof lives at `<url>/tasks/<slug>.json` as a
+//! [`ProofFile`]. The same layout works over HTTP (`GET`/`PUT`, e.g. a small
+//! service or an S3-compatible bucket) and on a shared directory.
+
+use super::canonical;
+use super::exchange::{self, ImportOutcome, PortableProof, ProofFile};
+use super::types::{Proof, Task};
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Environment variable holding a bearer token for HTTP remotes.
+pub const TOKEN_ENV: &str = "ROADMAP_REMOTE_TOKEN";
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A backend that can hold one proof file per task.
+pub trait ProofStore {
+    /// Fetches a task's proof file, or `None` if the remote has none.
+    ///
+    /// # Errors
+    /// Returns error if the remote cannot be reached or returns garbage.
+    fn fetch(&self, slug: &str) -> Result<Option<ProofFile>>;
+
+    /// Replaces a task's proof file.
+    ///
+    /// # Errors
+    /// Returns error if the remote rejects the write.
+    fn store(&self, slug: &str, file: &ProofFile) -> Result<()>;
+}
+
+/// Opens the store for a remote URL (`http(s)://…`, `file://…`, or a plain path).
+#[must_use]
+pub fn open(url: &str) -> Box<dyn ProofStore> {
+    if url.starts_with("http://") || url.starts_with("https://") {
+        Box::new(HttpStore {
+            base: url.trim_end_matches('/').to_string(),
+            token: std::env::var(TOKEN_ENV).ok(),
+        })
+    } else {
+        let path = url.strip_prefix("file://").unwrap_or(url);
+        Box::new(DirStore {
+            root: PathBuf::from(path),
+        })
+    }
+}
+
+/// Publishes a proof unless the remote already holds a newer one for the task.
+///
+/// Returns `true` if the remote was updated.
+///
+/// # Errors
+/// Returns error if the remote cannot be read or written.
+pub fn push(store: &dyn ProofStore, slug: &str, proof: &Proof) -> Result<bool> {
+    let newer_exists = store
+        .fetch(slug)?
+        .and_then(|f| f.proofs.into_iter().next())
+        .is_some_and(|p| p.proof.timestamp >= proof.timestamp);
+    if newer_exists {
+        return Ok(false);
+    }
+
+    let mut shared = proof.clone();
+    shared.signature = None;
+    shared.origin = None;
+    store.store(
+        slug,
+        &ProofFile::new(vec![PortableProof {
+            task: slug.to_string(),
+            proof: shared,
+        }]),
+    )?;
+    Ok(true)
+}
+
+/// Imports the remote's proofs for `tasks`, subject to the usual import checks.
+///
+/// # Errors
+/// Returns error if the remote cannot be read or the database fails.
+pub fn pull(
+    conn: &Connection,
+    store: &dyn ProofStore,
+    tasks: &[Task],
+    origin: &str,
+) -> Result<Vec<(String, ImportOutcome)>> {
+    let mut incoming = Vec::new();
+    for task in tasks {
+        if let Some(file) = store.fetch(&task.slug)? {
+            incoming.extend(file.proofs);
+        }
+    }
+    exchange::import(conn, &incoming, origin)
+}
+
+struct HttpStore {
+    base: String,
+    token: Option<String>,
+}
+
+impl HttpStore {
+    fn request(&self, method: &str, slug: &str) -> ureq::Request {
+        let req = ureq::request(method, &format!("{}/tasks/{slug}.json", self.base))
+            .timeout(HTTP_TIMEOUT);
+        match &self.token {
+            Some(token) => req.set("Authorization", &format!("Bearer {token}")),
+            None => req,
+        }
+    }
+}
+
+impl ProofStore for HttpStore {
+    fn fetch(&self, slug: &str) -> Result<Option<ProofFile>> {
+        match self.request("GET", slug).call() {
+            Ok(resp) => {
+                let body = resp.into_string()?;
+                let file = serde_json::from_str(&body)
+                    .with_context(|| format!("Malformed remote proof file for '{slug}'"))?;
+                Ok(Some(file))
+            }
+            Err(ureq::Error::Status(404, _)) => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to fetch remote proof for '{slug}'")),
+        }
+    }
+
+    fn store(&self, slug: &str, file: &ProofFile) -> Result<()> {
+        self.request("PUT", slug)
+            .set("Content-Type", "application/json")
+            .send_string(&canonical::to_pretty(file)?)
+            .with_context(|| format!("Failed to push proof for '{slug}'"))?;
+        Ok(())
+    }
+}
+
+struct DirStore {
+    root: PathBuf,
+}
+
+impl DirStore {
+    fn path(&self, slug: &str) -> PathBuf {
+        self.root.join("tasks").join(format!("{slug}.json"))
+    }
+}
+
+impl ProofStore for DirStore {
+    fn fetch(&self, slug: &str) -> Result<Option<ProofFile>> {
+        let path = self.path(slug);
+        if !path.exists() {
+            return Ok(None);
+        }
+        ProofFile::read(&path).map(Some)
+    }
+
+    fn store(&self, slug: &str, file: &ProofFile) -> Result<()> {
+        let path = self.path(slug);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        file.write(&path)
+    }
+}
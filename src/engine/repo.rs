@@ -1,9 +1,12 @@
 //! Repository module.
 //!
-//! Splits responsibilities into Tasks (structure) and Proofs (verification).
+//! Splits responsibilities into Tasks (structure), Proofs (verification),
+//! and Milestones (grouping).
 
+pub mod milestones;
 pub mod proofs;
 pub mod tasks;
 
-pub use proofs::ProofRepo;
-pub use tasks::{TaskRepo, TASK_SELECT};
\ No newline at end of file
+pub use milestones::MilestoneRepo;
+pub use proofs::{HistoryFilter, ProofAge, ProofRecord, ProofRepo};
+pub use tasks::TaskRepo;
\ No newline at end of file
@@ -0,0 +1,81 @@
+//! Milestone Repository: Named groups of tasks.
+
+use crate::engine::types::Milestone;
+use anyhow::{bail, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+
+pub struct MilestoneRepo<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> MilestoneRepo<'a> {
+    /// Creates a new milestone repository instance.
+    #[must_use]
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Creates a milestone.
+    ///
+    /// # Errors
+    /// Returns an error if the name is taken or the insert fails.
+    pub fn add(&self, name: &str, title: Option<&str>) -> Result<i64> {
+        if self.find(name)?.is_some() {
+            bail!("Milestone '{name}' already exists");
+        }
+        self.conn.execute(
+            "INSERT INTO milestones (name, title) VALUES (?1, ?2)",
+            params![name, title],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Returns the ID of a milestone, creating it without a title if it doesn't exist.
+    ///
+    /// # Errors
+    /// Returns an error if the query or insert fails.
+    pub fn ensure(&self, name: &str) -> Result<i64> {
+        match self.find(name)? {
+            Some(id) => Ok(id),
+            None => self.add(name, None),
+        }
+    }
+
+    /// Creates a milestone from shared state unless one by that name exists.
+    ///
+    /// # Errors
+    /// Returns an error if the insert fails.
+    pub fn restore(&self, milestone: &Milestone) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO milestones (name, title) VALUES (?1, ?2)",
+            params![milestone.name, milestone.title],
+        )?;
+        Ok(())
+    }
+
+    /// Finds the ID of a milestone by name.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn find(&self, name: &str) -> Result<Option<i64>> {
+        Ok(self
+            .conn
+            .query_row("SELECT id FROM milestones WHERE name = ?1", params![name], |r| r.get(0))
+            .optional()?)
+    }
+
+    /// Retrieves every milestone, in creation order.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn get_all(&self) -> Result<Vec<Milestone>> {
+        let mut stmt = self.conn.prepare("SELECT name, title FROM milestones ORDER BY id")?;
+        let rows = stmt.query_map([], |r| {
+            Ok(Milestone {
+                name: r.get(0)?,
+                title: r.get(1)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
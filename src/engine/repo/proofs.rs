@@ -1,9 +1,38 @@
 //! Proof Repository: Handles verification evidence and audit logs.
 
+use crate::engine::bench::Metrics;
+use crate::engine::chain::{self, ChainLink, Tombstone};
+use crate::engine::db::Db;
 use crate::engine::types::Proof;
 use anyhow::Result;
 use rusqlite::{params, Connection, OptionalExtension};
 
+/// Proof columns in the order expected by [`row_to_proof`].
+pub const PROOF_COLUMNS: &str =
+    "p.cmd, p.exit_code, p.git_sha, p.duration_ms, p.timestamp, p.attested_reason, p.stdout, p.stderr, p.signature, p.hooks, p.coverage, p.metrics, p.failure, p.budgets, p.origin, p.attempts, p.environment, p.artifacts, p.output_truncated, p.usage";
+
+/// A proof joined with the identity of its row and owning task.
+#[derive(Debug, Clone)]
+pub struct ProofRecord {
+    pub proof_id: i64,
+    pub task_id: i64,
+    pub slug: String,
+    pub proof: Proof,
+}
+
+/// Which proofs [`ProofRepo::get_global_history`] returns. The default matches all.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    /// Only this task's proofs.
+    pub task_id: Option<i64>,
+    /// Only failed runs: a non-zero exit or a policy failure.
+    pub failed: bool,
+    /// Only proofs recorded on or after this timestamp (`YYYY-MM-DD` works).
+    pub since: Option<String>,
+    /// Only proofs of commits whose SHA starts with this.
+    pub sha: Option<String>,
+}
+
 pub struct ProofRepo<'a> {
     conn: &'a Connection,
 }
@@ -17,12 +46,55 @@ impl<'a> ProofRepo<'a> {
 
     /// Records a verification proof for a task.
     ///
+    /// The proof is linked into the tamper-evident chain: the stored row
+    /// (including its database-assigned timestamp) is hashed together with the
+    /// hash of the previously recorded proof.
+    ///
+    /// Returns the ID of the stored proof row.
+    ///
     /// # Errors
     /// Returns an error if the proof cannot be saved.
-    pub fn save(&self, task_id: i64, proof: &Proof) -> Result<()> {
+    pub fn save(&self, task_id: i64, proof: &Proof) -> Result<i64> {
+        self.insert(task_id, proof, None)
+    }
+
+    /// Records a proof produced elsewhere, keeping its original timestamp.
+    ///
+    /// # Errors
+    /// Returns an error if the proof cannot be saved.
+    pub fn save_imported(&self, task_id: i64, proof: &Proof) -> Result<i64> {
+        self.insert(task_id, proof, Some(&proof.timestamp))
+    }
+
+    /// Returns true if an identical proof (same run, same commit) is already stored.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn contains(&self, task_id: i64, proof: &Proof) -> Result<bool> {
+        let found = self
+            .conn
+            .query_row(
+                "SELECT 1 FROM proofs 
+                 WHERE task_id = ?1 AND timestamp = ?2 AND git_sha = ?3 AND cmd = ?4 AND exit_code = ?5",
+                params![task_id, proof.timestamp, proof.git_sha, proof.cmd, proof.exit_code],
+                |_| Ok(()),
+            )
+            .optional()?;
+        Ok(found.is_some())
+    }
+
+    fn insert(&self, task_id: i64, proof: &Proof, timestamp: Option<&str>) -> Result<i64> {
+        // Reading the chain's tip and appending to it must not interleave with another writer.
+        let tx = if self.conn.is_autocommit() {
+            Some(Db::begin_write(self.conn)?)
+        } else {
+            None
+        };
+        let prev_hash = self.last_hash()?;
         self.conn.execute(
-            "INSERT INTO proofs (task_id, cmd, exit_code, git_sha, duration_ms, attested_reason, stdout, stderr) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            // IDs of removed proofs stay taken by their tombstones, so the chain order stays unambiguous.
+            "INSERT INTO proofs (id, task_id, cmd, exit_code, git_sha, duration_ms, attested_reason, stdout, stderr, prev_hash, hooks, coverage, metrics, failure, budgets, origin, attempts, environment, artifacts, output_truncated, usage, timestamp) 
+             VALUES ((SELECT COALESCE(MAX(id), 0) + 1 FROM (SELECT id FROM proofs UNION ALL SELECT proof_id AS id FROM proof_tombstones)), ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, COALESCE(?21, CURRENT_TIMESTAMP))",
             params![
                 task_id,
                 proof.cmd,
@@ -31,59 +103,220 @@ impl<'a> ProofRepo<'a> {
                 proof.duration_ms,
                 proof.attested_reason,
                 proof.stdout,
-                proof.stderr
+                proof.stderr,
+                prev_hash,
+                to_json_column(&proof.hooks)?,
+                proof.coverage,
+                to_json_map(&proof.metrics)?,
+                proof.failure,
+                to_json_column(&proof.budgets)?,
+                proof.origin,
+                proof.attempts,
+                proof.environment.as_ref().map(serde_json::to_string).transpose()?,
+                to_json_column(&proof.artifacts)?,
+                proof.truncated,
+                proof.usage.as_ref().map(serde_json::to_string).transpose()?,
+                timestamp
             ],
         )?;
+
+        let id = self.conn.last_insert_rowid();
+        let stored = self.get_by_id(id)?;
+        let hash = chain::compute_hash(&prev_hash, task_id, &stored);
+        self.conn.execute(
+            "UPDATE proofs SET hash = ?1 WHERE id = ?2",
+            params![hash, id],
+        )?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+        Ok(id)
+    }
+
+    /// Gets a proof exactly as stored, by row ID.
+    ///
+    /// # Errors
+    /// Returns an error if the proof does not exist or the query fails.
+    pub fn get_by_id(&self, id: i64) -> Result<Proof> {
+        let sql = format!("SELECT {PROOF_COLUMNS} FROM proofs p WHERE p.id = ?1");
+        Ok(self.conn.query_row(&sql, params![id], |row| row_to_proof(row, 0))?)
+    }
+
+    /// Attaches a detached signature to a stored proof.
+    ///
+    /// # Errors
+    /// Returns an error if the update fails.
+    pub fn set_signature(&self, id: i64, signature: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE proofs SET signature = ?1 WHERE id = ?2",
+            params![signature, id],
+        )?;
         Ok(())
     }
 
+    /// Deletes every proof of a task and returns how many were removed.
+    ///
+    /// Like [`Self::delete`], each leaves a tombstone on the chain.
+    ///
+    /// # Errors
+    /// Returns an error if a query fails.
+    pub fn delete_for_task(&self, task_id: i64, reason: &str) -> Result<usize> {
+        let ids: Vec<i64> = self
+            .conn
+            .prepare("SELECT id FROM proofs WHERE task_id = ?1")?
+            .query_map(params![task_id], |r| r.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        self.delete(&ids, reason)
+    }
+
+    /// Deletes proofs by ID and returns how many were removed.
+    ///
+    /// Later proofs are not re-sealed: each removed proof leaves a tombstone
+    /// with its chain hashes and `reason`, so the chain still verifies across
+    /// the gap and `fsck` reports what was removed and why.
+    ///
+    /// # Errors
+    /// Returns an error if a query fails.
+    pub fn delete(&self, ids: &[i64], reason: &str) -> Result<usize> {
+        let mut removed = 0;
+        for id in ids {
+            self.conn.execute(
+                "INSERT INTO proof_tombstones (proof_id, task_id, prev_hash, hash, reason)
+                 SELECT id, task_id, prev_hash, hash, ?2 FROM proofs WHERE id = ?1",
+                params![id, reason],
+            )?;
+            removed += self.conn.execute("DELETE FROM proofs WHERE id = ?1", params![id])?;
+        }
+        Ok(removed)
+    }
+
+    /// Lists every proof's age and size without loading its output, newest first within each task.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn get_ages(&self) -> Result<Vec<ProofAge>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT p.id, p.task_id, julianday('now') - julianday(p.timestamp),
+                    p.exit_code = 0 AND p.failure IS NULL,
+                    length(p.stdout) + length(p.stderr), p.artifacts
+             FROM proofs p ORDER BY p.task_id, p.timestamp DESC, p.id DESC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ProofAge {
+                id: row.get(0)?,
+                task_id: row.get(1)?,
+                age_days: row.get::<_, Option<f64>>(2)?.unwrap_or_default(),
+                passed: row.get(3)?,
+                output_bytes: row.get::<_, Option<u64>>(4)?.unwrap_or_default(),
+                artifacts: json_column(row, 5)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Returns the hash of the most recently chained proof, removed or not, or the genesis hash.
+    fn last_hash(&self) -> Result<String> {
+        let last: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT hash FROM (SELECT id, hash FROM proofs UNION ALL SELECT proof_id, hash FROM proof_tombstones)
+                 WHERE hash IS NOT NULL ORDER BY id DESC LIMIT 1",
+                [],
+                |r| r.get(0),
+            )
+            .optional()?;
+        Ok(last.unwrap_or_else(|| chain::GENESIS_HASH.to_string()))
+    }
+
+    /// Retrieves every proof with its chain columns, in insertion order.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn get_chain(&self) -> Result<Vec<ChainLink>> {
+        let sql = format!(
+            "SELECT p.id, p.task_id, p.prev_hash, p.hash, {PROOF_COLUMNS} 
+             FROM proofs p ORDER BY p.id ASC"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ChainLink {
+                proof_id: row.get(0)?,
+                task_id: row.get(1)?,
+                prev_hash: row.get(2)?,
+                hash: row.get(3)?,
+                proof: row_to_proof(row, 4)?,
+            })
+        })?;
+
+        let mut links = Vec::new();
+        for link in rows {
+            links.push(link?);
+        }
+        Ok(links)
+    }
+
+    /// Retrieves the tombstones of removed proofs, in chain order.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn get_tombstones(&self) -> Result<Vec<Tombstone>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT proof_id, task_id, prev_hash, hash, reason, removed_at FROM proof_tombstones ORDER BY proof_id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(Tombstone {
+                proof_id: row.get(0)?,
+                task_id: row.get(1)?,
+                prev_hash: row.get(2)?,
+                hash: row.get(3)?,
+                reason: row.get(4)?,
+                removed_at: row.get(5)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
     /// Gets the most recent proof recorded for a task.
     ///
     /// # Errors
     /// Returns a `rusqlite` error if query logic fails.
     pub fn get_latest(&self, task_id: i64) -> rusqlite::Result<Option<Proof>> {
+        let sql = format!(
+            "SELECT {PROOF_COLUMNS} FROM proofs p WHERE p.task_id = ?1 ORDER BY p.timestamp DESC, p.id DESC LIMIT 1"
+        );
         self.conn
-            .query_row(
-                "SELECT cmd, exit_code, git_sha, duration_ms, timestamp, attested_reason, stdout, stderr 
-                 FROM proofs WHERE task_id = ?1 ORDER BY timestamp DESC LIMIT 1",
-                params![task_id],
-                |row| {
-                    Ok(Proof {
-                        cmd: row.get(0)?,
-                        exit_code: row.get(1)?,
-                        git_sha: row.get(2)?,
-                        duration_ms: row.get(3)?,
-                        timestamp: row.get(4)?,
-                        attested_reason: row.get(5)?,
-                        stdout: row.get(6)?,
-                        stderr: row.get(7)?,
-                    })
-                },
-            )
+            .query_row(&sql, params![task_id], |row| row_to_proof(row, 0))
             .optional()
     }
 
+    /// Gets benchmark metrics from the most recent Proven (passing, unrejected) proof.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn get_baseline_metrics(&self, task_id: i64) -> Result<Option<Metrics>> {
+        let sql = format!(
+            "SELECT {PROOF_COLUMNS} FROM proofs p 
+             WHERE p.task_id = ?1 AND p.exit_code = 0 AND p.attested_reason IS NULL 
+               AND p.failure IS NULL AND p.metrics IS NOT NULL 
+             ORDER BY p.timestamp DESC, p.id DESC LIMIT 1"
+        );
+        let proof = self
+            .conn
+            .query_row(&sql, params![task_id], |row| row_to_proof(row, 0))
+            .optional()?;
+        Ok(proof.map(|p| p.metrics))
+    }
+
     /// Retrieves the full history of proofs for a task.
     ///
     /// # Errors
     /// Returns an error if the query fails.
     pub fn get_history(&self, task_id: i64) -> Result<Vec<Proof>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT cmd, exit_code, git_sha, duration_ms, timestamp, attested_reason, stdout, stderr 
-             FROM proofs WHERE task_id = ?1 ORDER BY timestamp DESC",
-        )?;
-        let rows = stmt.query_map(params![task_id], |row| {
-            Ok(Proof {
-                cmd: row.get(0)?,
-                exit_code: row.get(1)?,
-                git_sha: row.get(2)?,
-                duration_ms: row.get(3)?,
-                timestamp: row.get(4)?,
-                attested_reason: row.get(5)?,
-                stdout: row.get(6)?,
-                stderr: row.get(7)?,
-            })
-        })?;
+        let sql = format!(
+            "SELECT {PROOF_COLUMNS} FROM proofs p WHERE p.task_id = ?1 ORDER BY p.timestamp DESC, p.id DESC"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![task_id], |row| row_to_proof(row, 0))?;
 
         let mut proofs = Vec::new();
         for p in rows {
@@ -92,32 +325,27 @@ impl<'a> ProofRepo<'a> {
         Ok(proofs)
     }
 
-    /// Retrieves global proof history joined with task slugs.
+    /// Retrieves the newest proofs matching `filter`, joined with task slugs.
     ///
     /// # Errors
     /// Returns an error if the query fails.
-    pub fn get_global_history(&self, limit: usize) -> Result<Vec<(String, Proof)>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT t.slug, p.cmd, p.exit_code, p.git_sha, p.duration_ms, p.timestamp, p.attested_reason, p.stdout, p.stderr 
+    pub fn get_global_history(&self, filter: &HistoryFilter, limit: usize) -> Result<Vec<(String, Proof)>> {
+        let sql = format!(
+            "SELECT t.slug, {PROOF_COLUMNS} 
              FROM proofs p 
              JOIN tasks t ON p.task_id = t.id 
-             ORDER BY p.timestamp DESC 
+             WHERE (?2 IS NULL OR p.task_id = ?2)
+               AND (?3 = 0 OR (p.attested_reason IS NULL AND (p.exit_code != 0 OR p.failure IS NOT NULL)))
+               AND (?4 IS NULL OR p.timestamp >= ?4)
+               AND (?5 IS NULL OR substr(p.git_sha, 1, length(?5)) = ?5)
+             ORDER BY p.timestamp DESC, p.id DESC 
              LIMIT ?1"
-        )?;
-
-        let rows = stmt.query_map(params![limit], |row| {
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let params = params![limit, filter.task_id, filter.failed, filter.since, filter.sha];
+        let rows = stmt.query_map(params, |row| {
             let slug: String = row.get(0)?;
-            let proof = Proof {
-                cmd: row.get(1)?,
-                exit_code: row.get(2)?,
-                git_sha: row.get(3)?,
-                duration_ms: row.get(4)?,
-                timestamp: row.get(5)?,
-                attested_reason: row.get(6)?,
-                stdout: row.get(7)?,
-                stderr: row.get(8)?,
-            };
-            Ok((slug, proof))
+            Ok((slug, row_to_proof(row, 1)?))
         })?;
 
         let mut history = Vec::new();
@@ -126,4 +354,103 @@ impl<'a> ProofRepo<'a> {
         }
         Ok(history)
     }
-}
\ No newline at end of file
+
+    /// Retrieves every proof ever recorded, oldest first, with row identities.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn get_audit_trail(&self) -> Result<Vec<ProofRecord>> {
+        let sql = format!(
+            "SELECT p.id, p.task_id, t.slug, {PROOF_COLUMNS} 
+             FROM proofs p 
+             JOIN tasks t ON p.task_id = t.id 
+             ORDER BY p.id ASC"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ProofRecord {
+                proof_id: row.get(0)?,
+                task_id: row.get(1)?,
+                slug: row.get(2)?,
+                proof: row_to_proof(row, 3)?,
+            })
+        })?;
+
+        let mut records = Vec::new();
+        for r in rows {
+            records.push(r?);
+        }
+        Ok(records)
+    }
+}
+
+/// What retention needs to know about a stored proof.
+pub struct ProofAge {
+    pub id: i64,
+    pub task_id: i64,
+    pub age_days: f64,
+    /// Exit code 0 and no policy rejection.
+    pub passed: bool,
+    /// Bytes of captured stdout and stderr.
+    pub output_bytes: u64,
+    pub artifacts: Vec<String>,
+}
+
+/// Converts a row to a Proof, reading [`PROOF_COLUMNS`] starting at `offset`.
+///
+/// # Errors
+/// Returns a `rusqlite` error if data conversion fails.
+pub fn row_to_proof(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<Proof> {
+    Ok(Proof {
+        cmd: row.get(offset)?,
+        exit_code: row.get(offset + 1)?,
+        git_sha: row.get(offset + 2)?,
+        duration_ms: row.get(offset + 3)?,
+        timestamp: row.get(offset + 4)?,
+        attested_reason: row.get(offset + 5)?,
+        stdout: row.get(offset + 6)?,
+        stderr: row.get(offset + 7)?,
+        signature: row.get(offset + 8)?,
+        hooks: json_column(row, offset + 9)?,
+        coverage: row.get(offset + 10)?,
+        metrics: json_column(row, offset + 11)?,
+        failure: row.get(offset + 12)?,
+        budgets: json_column(row, offset + 13)?,
+        origin: row.get(offset + 14)?,
+        attempts: row.get(offset + 15)?,
+        environment: json_column(row, offset + 16)?,
+        artifacts: json_column(row, offset + 17)?,
+        truncated: row.get(offset + 18)?,
+        usage: json_column(row, offset + 19)?,
+    })
+}
+
+/// Serializes a collection for a JSON text column, storing NULL when empty.
+fn to_json_column<T: serde::Serialize>(items: &[T]) -> Result<Option<String>> {
+    if items.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::to_string(items)?))
+}
+
+/// Serializes a map for a JSON text column, storing NULL when empty.
+fn to_json_map<V: serde::Serialize>(map: &std::collections::BTreeMap<String, V>) -> Result<Option<String>> {
+    if map.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::to_string(map)?))
+}
+
+/// Reads a nullable JSON text column, treating NULL as the default value.
+fn json_column<T: serde::de::DeserializeOwned + Default>(
+    row: &rusqlite::Row,
+    idx: usize,
+) -> rusqlite::Result<T> {
+    let raw: Option<String> = row.get(idx)?;
+    match raw {
+        Some(text) => serde_json::from_str(&text).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+        }),
+        None => Ok(T::default()),
+    }
+}
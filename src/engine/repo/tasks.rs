@@ -1,11 +1,37 @@
-//! Task Repository: Core Task operations, Scopes, and State.
+//! Task Repository: Core task operations; tags, aliases, scopes, the
+//! hierarchy, and the active task live in submodules.
 
-use super::proofs::ProofRepo;
+mod aliases;
+mod hierarchy;
+mod scopes;
+mod state;
+mod tags;
+
+use super::proofs::{row_to_proof, PROOF_COLUMNS};
 use crate::engine::types::{Task, TaskStatus};
 use anyhow::{Context, Result};
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, Connection, Params};
+use std::collections::HashMap;
 
-pub const TASK_SELECT: &str = "SELECT id, slug, title, status, test_cmd, created_at FROM tasks";
+/// Task columns in the order expected by `row_to_task`, whether any proof
+/// passed, then the id and [`PROOF_COLUMNS`] of the task's latest proof
+/// (NULL without one). Joined
+/// rather than fetched per task, so listing the roadmap is one query.
+fn task_select() -> String {
+    format!(
+        "SELECT tasks.id, tasks.slug, tasks.title, tasks.status, tasks.test_cmd, tasks.created_at, \
+         tasks.shell, tasks.test_cmd_unix, tasks.test_cmd_windows, tasks.coverage_path, tasks.coverage_min, \
+         tasks.bench_threshold, tasks.quarantine_reason, tasks.priority, tasks.due, tasks.estimate, \
+         tasks.description, (SELECT parent.slug FROM tasks parent WHERE parent.id = tasks.parent_id), \
+         (SELECT name FROM milestones WHERE milestones.id = tasks.milestone_id), tasks.assignee, \
+         tasks.cwd, EXISTS(SELECT 1 FROM proofs ok WHERE ok.task_id = tasks.id AND ok.exit_code = 0 \
+             AND ok.failure IS NULL AND ok.attested_reason IS NULL), p.id, {PROOF_COLUMNS} \
+         FROM tasks LEFT JOIN proofs p ON p.id = (\
+             SELECT latest.id FROM proofs latest WHERE latest.task_id = tasks.id \
+             ORDER BY latest.timestamp DESC, latest.id DESC LIMIT 1\
+         )"
+    )
+}
 
 pub struct TaskRepo<'a> {
     conn: &'a Connection,
@@ -36,26 +62,188 @@ impl<'a> TaskRepo<'a> {
         Ok(self.conn.last_insert_rowid())
     }
 
-    /// Associates a file glob scope with a task.
+    /// Renames a task's title.
+    ///
+    /// # Errors
+    /// Returns an error if the update fails.
+    pub fn set_title(&self, task_id: i64, title: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET title = ?1 WHERE id = ?2",
+            params![title, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Changes the slug of a task. Edges, proofs, and state refer to the ID, so they follow.
     ///
     /// # Errors
-    /// Returns an error if insertion fails.
-    pub fn add_scope(&self, task_id: i64, glob: &str) -> Result<()> {
+    /// Returns an error if the update fails (e.g. the slug is taken).
+    pub fn set_slug(&self, task_id: i64, slug: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET slug = ?1 WHERE id = ?2",
+            params![slug, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Sets the verification command of a task.
+    ///
+    /// # Errors
+    /// Returns an error if the update fails.
+    pub fn set_test_cmd(&self, task_id: i64, test_cmd: Option<&str>) -> Result<()> {
         self.conn.execute(
-            "INSERT INTO task_scopes (task_id, glob) VALUES (?1, ?2)",
-            params![task_id, glob],
+            "UPDATE tasks SET test_cmd = ?1 WHERE id = ?2",
+            params![test_cmd, task_id],
         )?;
         Ok(())
     }
 
-    /// Creates a dependency link between two tasks.
+    /// Sets the shell and platform-specific command variants of a task.
     ///
     /// # Errors
-    /// Returns an error if the link cannot be created.
-    pub fn link(&self, from_id: i64, to_id: i64) -> Result<()> {
+    /// Returns an error if the update fails.
+    pub fn set_exec(
+        &self,
+        task_id: i64,
+        shell: Option<&str>,
+        unix_cmd: Option<&str>,
+        windows_cmd: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET shell = ?1, test_cmd_unix = ?2, test_cmd_windows = ?3 WHERE id = ?4",
+            params![shell, unix_cmd, windows_cmd, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Sets the directory a task's verification command runs in.
+    ///
+    /// # Errors
+    /// Returns an error if the update fails.
+    pub fn set_cwd(&self, task_id: i64, cwd: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET cwd = ?1 WHERE id = ?2",
+            params![cwd, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Sets the coverage report path and optional threshold of a task.
+    ///
+    /// # Errors
+    /// Returns an error if the update fails.
+    pub fn set_coverage(&self, task_id: i64, path: Option<&str>, min: Option<f64>) -> Result<()> {
         self.conn.execute(
-            "INSERT OR IGNORE INTO dependencies (blocker_id, blocked_id) VALUES (?1, ?2)",
-            params![from_id, to_id],
+            "UPDATE tasks SET coverage_path = ?1, coverage_min = ?2 WHERE id = ?3",
+            params![path, min, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Enables benchmark mode with the given regression threshold (percent).
+    ///
+    /// # Errors
+    /// Returns an error if the update fails.
+    pub fn set_bench_threshold(&self, task_id: i64, threshold: Option<f64>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET bench_threshold = ?1 WHERE id = ?2",
+            params![threshold, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Quarantines a task with a reason, or rehabilitates it when `reason` is `None`.
+    ///
+    /// # Errors
+    /// Returns an error if the update fails.
+    pub fn set_quarantine(&self, task_id: i64, reason: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET quarantine_reason = ?1 WHERE id = ?2",
+            params![reason, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Sets the scheduling priority of a task.
+    ///
+    /// # Errors
+    /// Returns an error if the update fails.
+    pub fn set_priority(&self, task_id: i64, priority: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET priority = ?1 WHERE id = ?2",
+            params![priority, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Sets or clears the due date (`YYYY-MM-DD`) of a task.
+    ///
+    /// # Errors
+    /// Returns an error if the update fails.
+    pub fn set_due(&self, task_id: i64, due: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET due = ?1 WHERE id = ?2",
+            params![due, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Sets or clears the effort estimate (hours) of a task.
+    ///
+    /// # Errors
+    /// Returns an error if the update fails.
+    pub fn set_estimate(&self, task_id: i64, hours: Option<f64>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET estimate = ?1 WHERE id = ?2",
+            params![hours, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Sets or clears the long-form description of a task.
+    ///
+    /// # Errors
+    /// Returns an error if the update fails.
+    pub fn set_description(&self, task_id: i64, description: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET description = ?1 WHERE id = ?2",
+            params![description, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Sets or clears who a task is assigned to.
+    ///
+    /// # Errors
+    /// Returns an error if the update fails.
+    pub fn set_assignee(&self, task_id: i64, assignee: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET assignee = ?1 WHERE id = ?2",
+            params![assignee, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Assigns a task to a milestone, or removes it from its milestone with `None`.
+    ///
+    /// # Errors
+    /// Returns an error if the update fails.
+    pub fn set_milestone(&self, task_id: i64, milestone_id: Option<i64>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET milestone_id = ?1 WHERE id = ?2",
+            params![milestone_id, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Overrides the creation time of a task (used when restoring shared state).
+    ///
+    /// # Errors
+    /// Returns an error if the update fails.
+    pub fn set_created_at(&self, task_id: i64, created_at: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET created_at = ?1 WHERE id = ?2",
+            params![created_at, task_id],
         )?;
         Ok(())
     }
@@ -65,13 +253,38 @@ impl<'a> TaskRepo<'a> {
     /// # Errors
     /// Returns an error if the query fails.
     pub fn get_all(&self) -> Result<Vec<Task>> {
-        let mut stmt = self.conn.prepare(TASK_SELECT)?;
-        let rows = stmt.query_map([], |r| self.row_to_task(r))?;
-        let mut tasks = Vec::new();
-        for task in rows {
-            tasks.push(task?);
+        self.load(&task_select(), [])
+    }
+
+    /// Full-text searches slugs and titles, best matches first.
+    ///
+    /// Every word of `query` must match, each as a prefix; FTS operators in
+    /// the input are treated as plain text.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<Task>> {
+        let terms: Vec<String> = query
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(|w| format!("\"{w}\"*"))
+            .collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
         }
-        Ok(tasks)
+        // Slug hits weigh double: they are what people type. Descriptions weigh half.
+        let sql = format!(
+            "{} JOIN (
+                 SELECT rowid AS hit, bm25(task_search, 2.0, 1.0, 0.5) AS score
+                 FROM task_search WHERE task_search MATCH ?1
+             ) ON hit = tasks.id
+             ORDER BY score, tasks.slug LIMIT ?2",
+            task_select()
+        );
+        self.load(
+            &sql,
+            params![terms.join(" "), i64::try_from(limit).unwrap_or(i64::MAX)],
+        )
     }
 
     /// Finds a task by its slug (case-insensitive).
@@ -79,11 +292,9 @@ impl<'a> TaskRepo<'a> {
     /// # Errors
     /// Returns an error if the query fails.
     pub fn find_by_slug(&self, slug: &str) -> Result<Option<Task>> {
-        let sql = format!("{TASK_SELECT} WHERE LOWER(slug) = LOWER(?1)");
-        self.conn
-            .query_row(&sql, params![slug], |r| self.row_to_task(r))
-            .optional()
-            .context("Search by slug failed")
+        let sql = format!("{} WHERE LOWER(tasks.slug) = LOWER(?1)", task_select());
+        let found = self.load(&sql, params![slug]).context("Search by slug failed")?;
+        Ok(found.into_iter().next())
     }
 
     /// Finds a task by its internal ID.
@@ -91,58 +302,32 @@ impl<'a> TaskRepo<'a> {
     /// # Errors
     /// Returns an error if the query fails.
     pub fn find_by_id(&self, id: i64) -> Result<Option<Task>> {
-        let sql = format!("{TASK_SELECT} WHERE id = ?1");
-        self.conn
-            .query_row(&sql, params![id], |r| self.row_to_task(r))
-            .optional()
-            .context("Search by ID failed")
+        let sql = format!("{} WHERE tasks.id = ?1", task_select());
+        let found = self.load(&sql, params![id]).context("Search by ID failed")?;
+        Ok(found.into_iter().next())
     }
 
-    /// Retrieves scopes associated with a task.
+    /// Deletes a task with its scopes, tags, budgets, aliases, and dependency edges.
     ///
-    /// # Errors
-    /// Returns a `rusqlite` error if query logic fails.
-    pub fn get_scopes(&self, task_id: i64) -> rusqlite::Result<Vec<String>> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT glob FROM task_scopes WHERE task_id = ?1")?;
-        let rows = stmt.query_map(params![task_id], |row| row.get(0))?;
-
-        let mut scopes = Vec::new();
-        for r in rows {
-            scopes.push(r?);
-        }
-        Ok(scopes)
-    }
-
-    /// Sets the active task in global state.
+    /// Proofs must be removed first (see `ProofRepo::delete_for_task`), since
+    /// deleting them also leaves tombstones on the proof chain.
     ///
     /// # Errors
-    /// Returns an error if the state cannot be updated.
-    pub fn set_active_task(&self, task_id: i64) -> Result<()> {
+    /// Returns an error if a delete fails, e.g. because proofs still reference the task.
+    pub fn remove(&self, task_id: i64) -> Result<()> {
         self.conn.execute(
-            "INSERT OR REPLACE INTO state (key, value) VALUES ('active_task', ?1)",
-            params![task_id.to_string()],
+            "DELETE FROM dependencies WHERE blocker_id = ?1 OR blocked_id = ?1",
+            params![task_id],
         )?;
+        self.conn.execute("DELETE FROM task_scopes WHERE task_id = ?1", params![task_id])?;
+        self.conn.execute("DELETE FROM task_tags WHERE task_id = ?1", params![task_id])?;
+        self.conn.execute("DELETE FROM task_budgets WHERE task_id = ?1", params![task_id])?;
+        self.conn.execute("DELETE FROM task_aliases WHERE task_id = ?1", params![task_id])?;
+        self.conn.execute("UPDATE tasks SET parent_id = NULL WHERE parent_id = ?1", params![task_id])?;
+        self.conn.execute("DELETE FROM tasks WHERE id = ?1", params![task_id])?;
         Ok(())
     }
 
-    /// Retrieves the ID of the currently active task.
-    ///
-    /// # Errors
-    /// Returns an error if the state query fails.
-    pub fn get_active_task_id(&self) -> Result<Option<i64>> {
-        let res: Option<String> = self
-            .conn
-            .query_row(
-                "SELECT value FROM state WHERE key = 'active_task'",
-                [],
-                |r| r.get(0),
-            )
-            .optional()?;
-        Ok(res.and_then(|s| s.parse().ok()))
-    }
-
     /// Updates the cached status column of a task.
     ///
     /// # Errors
@@ -155,25 +340,87 @@ impl<'a> TaskRepo<'a> {
         Ok(())
     }
 
-    /// Converts a database row to a Task object.
-    ///
-    /// # Errors
-    /// Returns a `rusqlite` error if data conversion fails.
-    pub fn row_to_task(&self, row: &rusqlite::Row) -> rusqlite::Result<Task> {
-        let id: i64 = row.get(0)?;
-        let proof_repo = ProofRepo::new(self.conn);
-        let proof = proof_repo.get_latest(id)?;
-        let scopes = self.get_scopes(id)?;
+    /// Runs a task query built on `task_select` and fills in the scopes,
+    /// budgets, tags, and subtasks of every task it returns, with one query
+    /// per kind instead of one per task.
+    fn load<P: Params>(&self, sql: &str, params: P) -> Result<Vec<Task>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let mut tasks = stmt.query_map(params, row_to_task)?.collect::<rusqlite::Result<Vec<_>>>()?;
+        if tasks.is_empty() {
+            return Ok(tasks);
+        }
+        let ids = serde_json::to_string(&tasks.iter().map(|t| t.id).collect::<Vec<_>>())?;
+        let mut scopes = self.grouped("SELECT task_id, glob FROM task_scopes WHERE task_id IN (SELECT value FROM json_each(?1)) ORDER BY id", &ids)?;
+        let mut budgets = self.grouped("SELECT task_id, spec FROM task_budgets WHERE task_id IN (SELECT value FROM json_each(?1)) ORDER BY id", &ids)?;
+        let mut tags = self.grouped("SELECT task_id, tag FROM task_tags WHERE task_id IN (SELECT value FROM json_each(?1)) ORDER BY tag", &ids)?;
+        // One level of the hierarchy per call: parents cannot form cycles (`set_parent`).
+        let mut subtasks: HashMap<String, Vec<Task>> = HashMap::new();
+        let children = self.load(
+            &format!(
+                "{} WHERE tasks.parent_id IN (SELECT value FROM json_each(?1)) ORDER BY tasks.slug",
+                task_select()
+            ),
+            params![ids],
+        )?;
+        for child in children {
+            if let Some(parent) = child.parent.clone() {
+                subtasks.entry(parent).or_default().push(child);
+            }
+        }
+        for task in &mut tasks {
+            task.scopes = scopes.remove(&task.id).unwrap_or_default();
+            task.budgets = budgets.remove(&task.id).unwrap_or_default();
+            task.tags = tags.remove(&task.id).unwrap_or_default();
+            task.subtasks = subtasks.remove(&task.slug).unwrap_or_default();
+        }
+        Ok(tasks)
+    }
 
-        Ok(Task {
-            id,
-            slug: row.get(1)?,
-            title: row.get(2)?,
-            status: TaskStatus::from(row.get::<_, String>(3)?),
-            test_cmd: row.get(4)?,
-            created_at: row.get(5)?,
-            proof,
-            scopes,
-        })
+    /// Runs a `(task_id, value)` query over the JSON array of task ids `ids`, grouping values by task.
+    fn grouped(&self, sql: &str, ids: &str) -> Result<HashMap<i64, Vec<String>>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let mut groups: HashMap<i64, Vec<String>> = HashMap::new();
+        for row in stmt.query_map(params![ids], |r| Ok((r.get(0)?, r.get(1)?)))? {
+            let (task_id, value) = row?;
+            groups.entry(task_id).or_default().push(value);
+        }
+        Ok(groups)
     }
-}
\ No newline at end of file
+}
+
+/// Converts a row of `task_select` to a Task, leaving the collections `TaskRepo::load` fills empty.
+fn row_to_task(row: &rusqlite::Row) -> rusqlite::Result<Task> {
+    let proof = match row.get::<_, Option<i64>>(22)? {
+        Some(_) => Some(row_to_proof(row, 23)?),
+        None => None,
+    };
+    Ok(Task {
+        id: row.get(0)?,
+        slug: row.get(1)?,
+        title: row.get(2)?,
+        status: TaskStatus::from(row.get::<_, String>(3)?),
+        test_cmd: row.get(4)?,
+        created_at: row.get(5)?,
+        proof,
+        scopes: Vec::new(),
+        shell: row.get(6)?,
+        test_cmd_unix: row.get(7)?,
+        test_cmd_windows: row.get(8)?,
+        coverage_path: row.get(9)?,
+        coverage_min: row.get(10)?,
+        bench_threshold: row.get(11)?,
+        budgets: Vec::new(),
+        quarantine_reason: row.get(12)?,
+        has_passed: row.get(21)?,
+        priority: row.get(13)?,
+        due: row.get(14)?,
+        estimate: row.get(15)?,
+        description: row.get(16)?,
+        parent: row.get(17)?,
+        milestone: row.get(18)?,
+        assignee: row.get(19)?,
+        cwd: row.get(20)?,
+        subtasks: Vec::new(),
+        tags: Vec::new(),
+    })
+}
@@ -0,0 +1,62 @@
+//! Aliases: Old slugs kept by `rename --alias`, still resolving to their task.
+
+use super::TaskRepo;
+use crate::engine::types::Task;
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension};
+
+impl TaskRepo<'_> {
+    /// Records an old slug that should keep resolving to the task.
+    ///
+    /// # Errors
+    /// Returns an error if the insert fails.
+    pub fn add_alias(&self, task_id: i64, alias: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO task_aliases (alias, task_id) VALUES (?1, ?2)",
+            params![alias, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Forgets an alias, e.g. when it becomes a task's slug again.
+    ///
+    /// # Errors
+    /// Returns an error if the delete fails.
+    pub fn remove_alias(&self, alias: &str) -> Result<()> {
+        self.conn.execute("DELETE FROM task_aliases WHERE alias = ?1", params![alias])?;
+        Ok(())
+    }
+
+    /// Finds the task an old slug was renamed to.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn find_by_alias(&self, alias: &str) -> Result<Option<Task>> {
+        let id: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT task_id FROM task_aliases WHERE LOWER(alias) = LOWER(?1)",
+                params![alias],
+                |r| r.get(0),
+            )
+            .optional()?;
+        match id {
+            Some(id) => self.find_by_id(id),
+            None => Ok(None),
+        }
+    }
+
+    /// Lists the old slugs that resolve to a task.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn get_aliases(&self, task_id: i64) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT alias FROM task_aliases WHERE task_id = ?1 ORDER BY alias")?;
+        let aliases = stmt
+            .query_map(params![task_id], |r| r.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(aliases)
+    }
+}
@@ -0,0 +1,66 @@
+//! Hierarchy: Subtasks and the dependency edges between tasks.
+
+use super::{task_select, TaskRepo};
+use crate::engine::types::Task;
+use anyhow::{bail, Result};
+use rusqlite::{params, OptionalExtension};
+
+impl TaskRepo<'_> {
+    /// Makes a task a subtask of `parent_id`, or top-level again with `None`.
+    ///
+    /// # Errors
+    /// Returns an error if the parent is the task itself or one of its subtasks, or the update fails.
+    pub fn set_parent(&self, task_id: i64, parent_id: Option<i64>) -> Result<()> {
+        let mut ancestor = parent_id;
+        while let Some(id) = ancestor {
+            if id == task_id {
+                bail!("A task cannot be a subtask of itself or of its own subtasks");
+            }
+            ancestor = self
+                .conn
+                .query_row("SELECT parent_id FROM tasks WHERE id = ?1", params![id], |r| r.get(0))
+                .optional()?
+                .flatten();
+        }
+        self.conn.execute(
+            "UPDATE tasks SET parent_id = ?1 WHERE id = ?2",
+            params![parent_id, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Gets the direct subtasks of a task, by slug.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn get_subtasks(&self, task_id: i64) -> Result<Vec<Task>> {
+        self.load(
+            &format!("{} WHERE tasks.parent_id = ?1 ORDER BY tasks.slug", task_select()),
+            params![task_id],
+        )
+    }
+
+    /// Creates a dependency link between two tasks.
+    ///
+    /// # Errors
+    /// Returns an error if the link cannot be created.
+    pub fn link(&self, from_id: i64, to_id: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO dependencies (blocker_id, blocked_id) VALUES (?1, ?2)",
+            params![from_id, to_id],
+        )?;
+        Ok(())
+    }
+
+    /// Removes a dependency link between two tasks.
+    ///
+    /// # Errors
+    /// Returns an error if the deletion fails.
+    pub fn unlink(&self, from_id: i64, to_id: i64) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM dependencies WHERE blocker_id = ?1 AND blocked_id = ?2",
+            params![from_id, to_id],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+//! Scopes and budgets: The files a task covers and the limits its verification must meet.
+
+use super::TaskRepo;
+use anyhow::Result;
+use rusqlite::params;
+
+impl TaskRepo<'_> {
+    /// Associates a file glob scope with a task.
+    ///
+    /// # Errors
+    /// Returns an error if insertion fails.
+    pub fn add_scope(&self, task_id: i64, glob: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO task_scopes (task_id, glob) VALUES (?1, ?2)",
+            params![task_id, glob],
+        )?;
+        Ok(())
+    }
+
+    /// Replaces every scope of a task.
+    ///
+    /// # Errors
+    /// Returns an error if the update fails.
+    pub fn replace_scopes(&self, task_id: i64, globs: &[String]) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM task_scopes WHERE task_id = ?1", params![task_id])?;
+        for glob in globs {
+            self.add_scope(task_id, glob)?;
+        }
+        Ok(())
+    }
+
+    /// Retrieves scopes associated with a task.
+    ///
+    /// # Errors
+    /// Returns a `rusqlite` error if query logic fails.
+    pub fn get_scopes(&self, task_id: i64) -> rusqlite::Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT glob FROM task_scopes WHERE task_id = ?1")?;
+        let rows = stmt.query_map(params![task_id], |row| row.get(0))?;
+
+        let mut scopes = Vec::new();
+        for r in rows {
+            scopes.push(r?);
+        }
+        Ok(scopes)
+    }
+
+    /// Adds a resource budget spec to a task.
+    ///
+    /// # Errors
+    /// Returns an error if insertion fails.
+    pub fn add_budget(&self, task_id: i64, spec: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO task_budgets (task_id, spec) VALUES (?1, ?2)",
+            params![task_id, spec],
+        )?;
+        Ok(())
+    }
+
+    /// Replaces every resource budget of a task.
+    ///
+    /// # Errors
+    /// Returns an error if the update fails.
+    pub fn replace_budgets(&self, task_id: i64, specs: &[String]) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM task_budgets WHERE task_id = ?1", params![task_id])?;
+        for spec in specs {
+            self.add_budget(task_id, spec)?;
+        }
+        Ok(())
+    }
+
+    /// Retrieves resource budget specs associated with a task.
+    ///
+    /// # Errors
+    /// Returns a `rusqlite` error if query logic fails.
+    pub fn get_budgets(&self, task_id: i64) -> rusqlite::Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT spec FROM task_budgets WHERE task_id = ?1 ORDER BY id")?;
+        let rows = stmt.query_map(params![task_id], |row| row.get(0))?;
+
+        let mut budgets = Vec::new();
+        for r in rows {
+            budgets.push(r?);
+        }
+        Ok(budgets)
+    }
+}
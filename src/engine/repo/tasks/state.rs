@@ -0,0 +1,74 @@
+//! Active task: Which task `do` picked up, and at which commit.
+
+use super::TaskRepo;
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension};
+
+impl TaskRepo<'_> {
+    /// Sets the active task in global state.
+    ///
+    /// # Errors
+    /// Returns an error if the state cannot be updated.
+    pub fn set_active_task(&self, task_id: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO state (key, value) VALUES ('active_task', ?1)",
+            params![task_id.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// Clears the active task and the commit it was picked up at.
+    ///
+    /// # Errors
+    /// Returns an error if the state cannot be updated.
+    pub fn clear_active_task(&self) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM state WHERE key IN ('active_task', 'active_since')",
+            [],
+        )?;
+        Ok(())
+    }
+
+    /// Retrieves the ID of the currently active task.
+    ///
+    /// # Errors
+    /// Returns an error if the state query fails.
+    pub fn get_active_task_id(&self) -> Result<Option<i64>> {
+        let res: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT value FROM state WHERE key = 'active_task'",
+                [],
+                |r| r.get(0),
+            )
+            .optional()?;
+        Ok(res.and_then(|s| s.parse().ok()))
+    }
+
+    /// Records the commit HEAD was at when the active task was picked up.
+    ///
+    /// # Errors
+    /// Returns an error if the state cannot be updated.
+    pub fn set_active_since(&self, git_sha: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO state (key, value) VALUES ('active_since', ?1)",
+            params![git_sha],
+        )?;
+        Ok(())
+    }
+
+    /// Retrieves the commit HEAD was at when the active task was picked up.
+    ///
+    /// # Errors
+    /// Returns an error if the state query fails.
+    pub fn get_active_since(&self) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT value FROM state WHERE key = 'active_since'",
+                [],
+                |r| r.get(0),
+            )
+            .optional()?)
+    }
+}
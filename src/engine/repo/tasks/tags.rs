@@ -0,0 +1,62 @@
+//! Tags: Free-form labels for filtering tasks.
+
+use super::TaskRepo;
+use anyhow::Result;
+use rusqlite::params;
+
+impl TaskRepo<'_> {
+    /// Adds a tag to a task; adding one it already has is a no-op.
+    ///
+    /// # Errors
+    /// Returns an error if insertion fails.
+    pub fn add_tag(&self, task_id: i64, tag: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO task_tags (task_id, tag)
+             SELECT ?1, ?2 WHERE NOT EXISTS (SELECT 1 FROM task_tags WHERE task_id = ?1 AND tag = ?2)",
+            params![task_id, tag],
+        )?;
+        Ok(())
+    }
+
+    /// Removes a tag from a task.
+    ///
+    /// # Errors
+    /// Returns an error if the delete fails.
+    pub fn remove_tag(&self, task_id: i64, tag: &str) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM task_tags WHERE task_id = ?1 AND tag = ?2",
+            params![task_id, tag],
+        )?;
+        Ok(())
+    }
+
+    /// Replaces all tags of a task.
+    ///
+    /// # Errors
+    /// Returns an error if a delete or insert fails.
+    pub fn replace_tags(&self, task_id: i64, tags: &[String]) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM task_tags WHERE task_id = ?1", params![task_id])?;
+        for tag in tags {
+            self.add_tag(task_id, tag)?;
+        }
+        Ok(())
+    }
+
+    /// Retrieves the tags of a task, sorted.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn get_tags(&self, task_id: i64) -> rusqlite::Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT tag FROM task_tags WHERE task_id = ?1 ORDER BY tag")?;
+        let rows = stmt.query_map(params![task_id], |row| row.get(0))?;
+
+        let mut tags = Vec::new();
+        for r in rows {
+            tags.push(r?);
+        }
+        Ok(tags)
+    }
+}
@@ -1,9 +1,10 @@
 //! Fuzzy Task Resolver: Matches human queries to Task IDs.
 
-use super::repo::{TaskRepo, TASK_SELECT};
+use super::repo::TaskRepo;
 use super::types::Task;
+use crate::Error;
 use anyhow::{bail, Result};
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::Connection;
 use std::collections::HashSet;
 
 pub struct ResolveResult {
@@ -13,7 +14,6 @@ pub struct ResolveResult {
 
 pub struct TaskResolver<'a> {
     repo: TaskRepo<'a>,
-    conn: &'a Connection,
     strict: bool,
 }
 
@@ -23,7 +23,6 @@ impl<'a> TaskResolver<'a> {
     pub fn new(conn: &'a Connection) -> Self {
         Self {
             repo: TaskRepo::new(conn),
-            conn,
             strict: false,
         }
     }
@@ -33,7 +32,6 @@ impl<'a> TaskResolver<'a> {
     pub fn strict(conn: &'a Connection) -> Self {
         Self {
             repo: TaskRepo::new(conn),
-            conn,
             strict: true,
         }
     }
@@ -52,13 +50,15 @@ impl<'a> TaskResolver<'a> {
             }
         }
 
-        let sql = format!("{TASK_SELECT} WHERE LOWER(slug) = LOWER(?1)");
-        let exact: Option<Task> = self
-            .conn
-            .query_row(&sql, params![query], |r| self.repo.row_to_task(r))
-            .optional()?;
+        if let Some(task) = self.repo.find_by_slug(query)? {
+            return Ok(ResolveResult {
+                task,
+                confidence: 1.0,
+            });
+        }
 
-        if let Some(task) = exact {
+        // Old slugs kept by `rename --alias` are exact references too.
+        if let Some(task) = self.repo.find_by_alias(query)? {
             return Ok(ResolveResult {
                 task,
                 confidence: 1.0,
@@ -66,7 +66,7 @@ impl<'a> TaskResolver<'a> {
         }
 
         if self.strict {
-            bail!("No exact match for '{query}' in strict mode.");
+            bail!(Error::TaskNotFound(query.to_string()));
         }
         self.fuzzy_resolve(query)
     }
@@ -89,7 +89,7 @@ impl<'a> TaskResolver<'a> {
         let (_, task) = matches
             .into_iter()
             .next()
-            .ok_or_else(|| anyhow::anyhow!("No task matches '{query}'"))?;
+            .ok_or_else(|| Error::TaskNotFound(query.to_string()))?;
 
         Ok(ResolveResult {
             task,
@@ -112,6 +112,26 @@ pub fn slugify(title: &str) -> String {
         .join("-")
 }
 
+/// Normalizes a tag the way titles become slugs (`Front End` becomes `front-end`).
+///
+/// # Errors
+/// Returns error if nothing is left of the tag.
+pub fn normalize_tag(tag: &str) -> Result<String> {
+    let normalized = slugify(tag);
+    if normalized.is_empty() {
+        bail!("Invalid tag '{tag}'");
+    }
+    Ok(normalized)
+}
+
+/// Normalizes every tag in a list.
+///
+/// # Errors
+/// Returns error if any tag is invalid.
+pub fn normalize_tags(tags: &[String]) -> Result<Vec<String>> {
+    tags.iter().map(|t| normalize_tag(t)).collect()
+}
+
 /// Calculates a match score between a task and a query.
 fn calculate_score(task: &Task, query: &str, query_words: &[&str]) -> f64 {
     let slug_lower = task.slug.to_lowercase();
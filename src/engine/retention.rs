@@ -0,0 +1,143 @@
+//! Retention: Pruning old proofs so the log doesn't grow forever.
+//!
+//! A proof is pruned when it falls outside every limit given: it is not among
+//! its task's `keep_last` newest, and it is older than `older_than`. The latest
+//! proof of each task is always kept, and so is its latest passing one, which
+//! benchmark checks compare against. Artifacts of pruned proofs go with them.
+//!
+//! Tombstones bridge the gap in the chain, as after `rm --force`. Pruning is
+//! maintenance, not an edit: it is not recorded for `undo`, which would
+//! otherwise keep a copy of every deleted proof.
+
+use super::artifacts;
+use super::config::RetentionSettings;
+use super::db::Db;
+use super::oplog;
+use super::repo::ProofRepo;
+use anyhow::{bail, Result};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+
+/// Which proofs may go.
+#[derive(Debug, Clone, Copy)]
+pub struct Policy {
+    /// Proofs kept per task, newest first.
+    pub keep_last: Option<usize>,
+    /// Age in days past which proofs may go.
+    pub older_than_days: Option<f64>,
+}
+
+impl Policy {
+    /// Builds a policy from its limits as typed; `None` when neither is given.
+    ///
+    /// # Errors
+    /// Returns error if `keep_last` is zero or the age cannot be parsed.
+    pub fn new(keep_last: Option<usize>, older_than: Option<&str>) -> Result<Option<Self>> {
+        if keep_last == Some(0) {
+            bail!("--keep-last must be at least 1 (the latest proof of each task is always kept)");
+        }
+        let older_than_days = older_than.map(parse_age).transpose()?;
+        if keep_last.is_none() && older_than_days.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            keep_last,
+            older_than_days,
+        }))
+    }
+
+    /// The policy configured in `[retention]`, if any.
+    ///
+    /// # Errors
+    /// Returns error if a configured limit is invalid.
+    pub fn from_settings(settings: &RetentionSettings) -> Result<Option<Self>> {
+        Self::new(settings.keep_last, settings.older_than.as_deref())
+    }
+}
+
+/// Parses an age into days: `90d`, `12w`, or a bare number of days.
+///
+/// # Errors
+/// Returns error if the input is not a positive whole number with an optional `d` or `w` unit.
+pub fn parse_age(input: &str) -> Result<f64> {
+    let input = input.trim();
+    let (number, scale) = match input.char_indices().last() {
+        Some((i, 'd')) => (&input[..i], 1),
+        Some((i, 'w')) => (&input[..i], 7),
+        _ => (input, 1),
+    };
+    match number.parse::<u32>() {
+        Ok(n) if n > 0 => Ok(f64::from(n * scale)),
+        _ => bail!("Invalid age '{input}' (expected e.g. 30d or 12w)"),
+    }
+}
+
+/// What a prune removed, or would remove.
+#[derive(Debug, Default, Serialize)]
+pub struct PruneSummary {
+    pub proofs: usize,
+    /// Tasks that lost at least one proof.
+    pub tasks: usize,
+    /// Bytes of captured stdout and stderr in the pruned proofs.
+    pub output_bytes: u64,
+    /// Artifact files of the pruned proofs.
+    pub artifacts: usize,
+}
+
+/// Deletes the proofs the policy lets go, or with `dry_run` only counts them.
+///
+/// Runs in its own transaction, so the connection must not be inside one.
+///
+/// # Errors
+/// Returns error if the database fails.
+pub fn prune(conn: &Connection, policy: &Policy, dry_run: bool) -> Result<PruneSummary> {
+    let ages = ProofRepo::new(conn).get_ages()?;
+    let mut doomed = Vec::new();
+    let mut summary = PruneSummary::default();
+    let mut tasks = HashSet::new();
+    let (mut task, mut rank, mut kept_pass) = (None, 0, false);
+    for proof in &ages {
+        if task != Some(proof.task_id) {
+            (task, rank, kept_pass) = (Some(proof.task_id), 0, false);
+        }
+        rank += 1;
+        let protected = rank == 1 || (proof.passed && !kept_pass);
+        kept_pass |= proof.passed;
+        let beyond_count = policy.keep_last.is_none_or(|n| rank > n);
+        let beyond_age = policy.older_than_days.is_none_or(|days| proof.age_days > days);
+        if protected || !beyond_count || !beyond_age {
+            continue;
+        }
+        doomed.push(proof.id);
+        tasks.insert(proof.task_id);
+        summary.output_bytes += proof.output_bytes;
+        summary.artifacts += proof.artifacts.len();
+    }
+    summary.proofs = doomed.len();
+    summary.tasks = tasks.len();
+    if dry_run || doomed.is_empty() {
+        return Ok(summary);
+    }
+
+    oplog::suspend(conn)?;
+    let tx = Db::begin_write(conn)?;
+    ProofRepo::new(&tx).delete(&doomed, "pruned by the retention policy")?;
+    tx.commit()?;
+
+    // Files can't roll back with the transaction, so they go once it is committed.
+    let doomed: HashSet<i64> = doomed.into_iter().collect();
+    let runs: HashSet<_> = ages
+        .iter()
+        .filter(|p| doomed.contains(&p.id))
+        .flat_map(|p| p.artifacts.iter().map(|a| artifacts::run_dir(a)))
+        .collect();
+    for run in runs {
+        let _ = fs::remove_dir_all(&run);
+        if let Some(task_dir) = run.parent() {
+            let _ = fs::remove_dir(task_dir);
+        }
+    }
+    Ok(summary)
+}
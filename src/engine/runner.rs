@@ -1,10 +1,34 @@
 //! Verification Runner: Executes shell commands to verify task completion.
+//!
+//! Each command runs on background threads, so callers can start several
+//! and wait on them together; the blocking methods wrap [`VerifyRunner::spawn`].
 
+use super::artifacts;
+use super::config::Config;
+use super::container::Container;
+use super::lock;
+use super::sandbox::{Guard, Sandbox};
+use super::usage::{self, Usage};
+use super::shell::Shell;
+use super::types::Task;
 use anyhow::{bail, Context, Result};
-use std::io::Read;
-use std::process::{Command, Stdio};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
 use std::time::{Duration, Instant};
-use wait_timeout::ChildExt;
+
+mod probe;
+
+/// The verified task's slug, set for verification commands.
+pub const TASK_SLUG_VAR: &str = "ROADMAP_TASK_SLUG";
+/// The verified task's numeric ID.
+pub const TASK_ID_VAR: &str = "ROADMAP_TASK_ID";
+/// The task's scope globs, one per line; empty when it has none.
+pub const SCOPES_VAR: &str = "ROADMAP_SCOPES";
+/// The commit the proof will be recorded against.
+pub const PROOF_SHA_VAR: &str = "ROADMAP_PROOF_SHA";
 
 /// Result of running a verification command.
 #[derive(Debug)]
@@ -14,6 +38,10 @@ pub struct VerifyResult {
     pub stdout: String,
     pub stderr: String,
     pub duration: Duration,
+    /// Runs it took, counting retries; 1 when the first run decided it.
+    pub attempts: u32,
+    /// What the last run used, and whether it timed out.
+    pub usage: Usage,
 }
 
 impl VerifyResult {
@@ -30,6 +58,17 @@ pub struct RunnerConfig {
     pub timeout_secs: u64,
     pub capture_output: bool,
     pub working_dir: Option<String>,
+    pub shell: Shell,
+    /// Extra runs a failing command gets in `verify`; a pass on any run counts.
+    pub retries: u32,
+    /// Variables set for the command on top of the inherited environment.
+    pub envs: Vec<(String, String)>,
+    /// Copy the command's output to stderr as it arrives, besides capturing it.
+    pub stream: bool,
+    /// Run the command in this container image instead of on the host.
+    pub container: Option<Container>,
+    /// Confine the command; see [`super::sandbox`].
+    pub sandbox: Option<Sandbox>,
 }
 
 impl Default for RunnerConfig {
@@ -38,8 +77,131 @@ impl Default for RunnerConfig {
             timeout_secs: 300,
             capture_output: true,
             working_dir: None,
+            shell: Shell::platform_default(),
+            retries: 0,
+            envs: Vec::new(),
+            stream: false,
+            container: None,
+            sandbox: None,
+        }
+    }
+}
+
+impl RunnerConfig {
+    /// Resolves the runner settings for a task.
+    ///
+    /// The shell is chosen from the task, then the config, then the platform
+    /// default; the timeout, retries, streaming, container, and sandbox come
+    /// from the config, and the working directory from the task.
+    ///
+    /// # Errors
+    /// Returns an error if the configured shell is not supported.
+    pub fn for_task(task: &Task, config: &Config) -> Result<Self> {
+        let shell = match task.shell.as_deref().or(config.runner.shell.as_deref()) {
+            Some(name) => Shell::parse(name)?,
+            None => Shell::platform_default(),
+        };
+        Ok(Self {
+            shell,
+            timeout_secs: config.runner.timeout,
+            retries: config.runner.retries,
+            stream: config.runner.stream,
+            working_dir: task.cwd.clone(),
+            container: config
+                .runner
+                .container
+                .as_deref()
+                .map(|image| Container::new(image, config.runner.container_engine.as_deref())),
+            sandbox: config.sandbox.enabled.then(|| Sandbox::from_settings(&config.sandbox)),
+            ..Self::default()
+        })
+    }
+
+    /// Tells commands which task they verify, and at which commit, through environment variables.
+    pub fn add_task_env(&mut self, task: &Task, head_sha: &str) {
+        self.envs.extend([
+            (TASK_SLUG_VAR.to_string(), task.slug.clone()),
+            (TASK_ID_VAR.to_string(), task.id.to_string()),
+            (SCOPES_VAR.to_string(), task.scopes.join("\n")),
+            (PROOF_SHA_VAR.to_string(), head_sha.to_string()),
+        ]);
+    }
+}
+
+/// Checks a task's working directory as typed: a path inside the roadmap's root.
+///
+/// # Errors
+/// Returns an error if the path is empty, absolute, or climbs out with `..`.
+pub fn working_dir(input: &str) -> Result<String> {
+    let dir = input.trim().trim_end_matches(['/', '\\']);
+    if dir.is_empty() || dir == "." {
+        bail!("Working directory must name a subdirectory");
+    }
+    let path = Path::new(dir);
+    if path.has_root() || path.is_absolute() {
+        bail!("Working directory '{dir}' must be relative to the roadmap's root");
+    }
+    if path.components().any(|c| c == std::path::Component::ParentDir) {
+        bail!("Working directory '{dir}' must not leave the roadmap's root");
+    }
+    Ok(dir.to_string())
+}
+
+/// How often a running command is checked for cancellation.
+const POLL: Duration = Duration::from_millis(50);
+
+/// How long output is still collected after a timed-out command is killed.
+/// A descendant it started may hold the pipes open and never exit.
+const KILL_GRACE: Duration = Duration::from_secs(1);
+
+/// A verification command running in the background, from [`VerifyRunner::spawn`].
+///
+/// Dropping it without waiting leaves the command running until it finishes or times out.
+pub struct RunningVerification {
+    receiver: mpsc::Receiver<Result<VerifyResult>>,
+    cancelled: Arc<AtomicBool>,
+    started: Instant,
+}
+
+impl RunningVerification {
+    /// Time since the command started.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// The result, if the command has finished.
+    #[must_use]
+    pub fn try_result(&self) -> Option<Result<VerifyResult>> {
+        self.wait_for(Duration::ZERO)
+    }
+
+    /// Waits up to `limit` for the command to finish.
+    #[must_use]
+    pub fn wait_for(&self, limit: Duration) -> Option<Result<VerifyResult>> {
+        match self.receiver.recv_timeout(limit) {
+            Ok(result) => Some(result),
+            Err(mpsc::RecvTimeoutError::Timeout) => None,
+            Err(mpsc::RecvTimeoutError::Disconnected) => Some(Err(anyhow::anyhow!(
+                "Verification command was lost before it finished"
+            ))),
         }
     }
+
+    /// Waits for the command to finish.
+    ///
+    /// # Errors
+    /// Returns error if the command was cancelled or could not be waited on.
+    pub fn wait(self) -> Result<VerifyResult> {
+        self.receiver
+            .recv()
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("Verification command was lost before it finished")))
+    }
+
+    /// Kills the command; its result becomes an error.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
 }
 
 /// Executes verification commands.
@@ -61,69 +223,201 @@ impl VerifyRunner {
     /// Executes a shell command and returns the result.
     ///
     /// # Errors
-    /// Returns error if command fails to spawn or times out.
+    /// Returns error if command fails to spawn.
     pub fn run(&self, cmd: &str) -> Result<VerifyResult> {
-        if cmd.trim().is_empty() {
-            bail!("Empty verification command");
-        }
+        self.run_observed(cmd, None)
+    }
 
-        let start = Instant::now();
-        let timeout = Duration::from_secs(self.config.timeout_secs);
-        
-        let shell = if cfg!(target_os = "windows") {
-            ("cmd", "/C")
-        } else {
-            ("sh", "-c")
-        };
+    /// Like [`Self::run`], but calls `on_tick` with the elapsed time every
+    /// `every` while the command is still running.
+    ///
+    /// # Errors
+    /// Returns error if command fails to spawn.
+    pub fn run_with_heartbeat(
+        &self,
+        cmd: &str,
+        every: Duration,
+        on_tick: &mut dyn FnMut(Duration),
+    ) -> Result<VerifyResult> {
+        self.run_observed(cmd, Some((every, on_tick)))
+    }
 
-        let mut child = Command::new(shell.0)
-            .arg(shell.1)
-            .arg(cmd)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .context("Failed to spawn verification command")?;
-
-        // Enforce Timeout logic (Fixes Double Wait & Clippy)
-        let status_code = if let Some(status) = child.wait_timeout(timeout).context("Failed to wait")? {
-            status.code()
-        } else {
-            // Timeout occurred, kill the child
-            let _ = child.kill();
-            // Wait to clean up the zombie process
-            let _ = child.wait(); 
-            bail!("Verification timed out after {}s", self.config.timeout_secs);
+    fn run_observed(
+        &self,
+        cmd: &str,
+        mut heartbeat: Option<(Duration, &mut dyn FnMut(Duration))>,
+    ) -> Result<VerifyResult> {
+        let running = self.spawn(cmd)?;
+        let Some((every, on_tick)) = heartbeat.as_mut() else {
+            return running.wait();
         };
+        loop {
+            if let Some(result) = running.wait_for(*every) {
+                return result;
+            }
+            on_tick(running.elapsed());
+        }
+    }
 
-        let duration = start.elapsed();
-        
-        // Manual Output Capture (Fixes Double Wait Bug)
-        let mut stdout_str = String::new();
-        let mut stderr_str = String::new();
-
-        if let Some(mut out) = child.stdout {
-            let _ = out.read_to_string(&mut stdout_str);
+    /// Starts a command and returns at once; the command runs on its own threads.
+    ///
+    /// This lets one process run several verifications, or keep serving
+    /// requests, while commands run. The timeout is enforced in the background.
+    ///
+    /// # Errors
+    /// Returns error if the command is empty or fails to spawn.
+    pub fn spawn(&self, cmd: &str) -> Result<RunningVerification> {
+        if cmd.trim().is_empty() {
+            bail!("Empty verification command");
         }
-        if let Some(mut err) = child.stderr {
-            let _ = err.read_to_string(&mut stderr_str);
+        if self.config.container.is_some() && self.config.sandbox.is_some() {
+            bail!("A sandboxed command cannot also run in a container; the container already isolates it");
         }
+        let mut command = self.config.shell.command(cmd)?;
+        if let Some(dir) = &self.config.working_dir {
+            if !Path::new(dir).is_dir() {
+                bail!("Working directory '{dir}' does not exist");
+            }
+        }
+        // A container outlives its killed client, so it is stopped by name.
+        let mut stop = None;
+        let mut command = match &self.config.container {
+            None => {
+                if let Some(dir) = &self.config.working_dir {
+                    command.current_dir(dir);
+                }
+                command.envs(self.config.envs.iter().map(|(k, v)| (k, v)));
+                command.envs(lock::shared());
+                match &self.config.sandbox {
+                    Some(sandbox) => sandbox.wrap(command)?,
+                    None => command,
+                }
+            }
+            Some(container) => {
+                let root = std::env::current_dir().context("Failed to read the current directory")?;
+                let workdir = self.config.working_dir.as_ref().map_or_else(|| root.clone(), |d| root.join(d));
+                let writable: Vec<PathBuf> = self
+                    .config
+                    .envs
+                    .iter()
+                    .filter(|(key, _)| key == artifacts::ENV_VAR)
+                    .map(|(_, dir)| PathBuf::from(dir))
+                    .collect();
+                let prepared = container.wrap(&command, &root, &workdir, &writable, &self.config.envs)?;
+                stop = Some((container.clone(), prepared.name));
+                prepared.command
+            }
+        };
+        let span = tracing::info_span!("verify");
+        let started = Instant::now();
+        let (mut child, guard) = {
+            let _entered = span.enter();
+            command.stdout(Stdio::piped()).stderr(Stdio::piped());
+            match &self.config.sandbox {
+                Some(sandbox) => {
+                    let root = std::env::current_dir().context("Failed to read the current directory")?;
+                    sandbox.spawn(&mut command, &root)?
+                }
+                None => (
+                    command.spawn().context("Failed to spawn verification command")?,
+                    Guard::default(),
+                ),
+            }
+        };
+        // Drain both pipes while the command runs, so a chatty one can't fill them and stall.
+        let stdout = drain(child.stdout.take(), self.config.stream);
+        let stderr = drain(child.stderr.take(), self.config.stream);
 
-        Ok(VerifyResult {
-            success: status_code == Some(0),
-            exit_code: status_code,
-            stdout: stdout_str,
-            stderr: stderr_str,
-            duration,
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&cancelled);
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _entered = span.enter();
+            let on_stop = || {
+                if let Some((container, name)) = &stop {
+                    container.kill(name);
+                }
+            };
+            let outcome = supervise(&mut child, started, timeout, &flag, on_stop);
+            // Whatever confines the command is released only once it has exited.
+            drop(guard);
+            let result = outcome.map(|(exit_code, usage)| {
+                let deadline = usage.timed_out.then(|| Instant::now() + KILL_GRACE);
+                VerifyResult {
+                    success: exit_code == Some(0),
+                    exit_code,
+                    stdout: stdout.collect(deadline),
+                    stderr: stderr.collect(deadline),
+                    usage,
+                    duration: started.elapsed(),
+                    attempts: 1,
+                }
+            });
+            // The caller may have stopped waiting; the result then has nowhere to go.
+            let _ = sender.send(result);
+        });
+        Ok(RunningVerification {
+            receiver,
+            cancelled,
+            started,
         })
     }
 
-    /// Runs verification with user-friendly output on failure.
+    /// Runs verification with user-friendly output on failure, retrying a
+    /// failing command up to the configured number of times.
     ///
     /// # Errors
-    /// Returns error if command fails to execute or times out.
+    /// Returns error if command fails to execute.
     pub fn verify(&self, cmd: &str) -> Result<VerifyResult> {
-        let result = self.run(cmd)?;
+        self.retrying(true, || self.run(cmd))
+            .inspect(|result| Self::report_failure(cmd, result))
+    }
+
+    /// Like [`Self::verify`], printing nothing, for callers that report results themselves.
+    ///
+    /// # Errors
+    /// Returns error if command fails to execute.
+    pub fn verify_quietly(&self, cmd: &str) -> Result<VerifyResult> {
+        self.retrying(false, || self.run(cmd))
+    }
+
+    /// Like [`Self::verify`], with a heartbeat while the command runs.
+    ///
+    /// # Errors
+    /// Returns error if command fails to execute.
+    pub fn verify_with_heartbeat(
+        &self,
+        cmd: &str,
+        every: Duration,
+        on_tick: &mut dyn FnMut(Duration),
+    ) -> Result<VerifyResult> {
+        self.retrying(true, || self.run_with_heartbeat(cmd, every, on_tick))
+            .inspect(|result| Self::report_failure(cmd, result))
+    }
 
+    /// Repeats `run` while it fails and retries remain. A timeout is not retried.
+    fn retrying(&self, announce: bool, mut run: impl FnMut() -> Result<VerifyResult>) -> Result<VerifyResult> {
+        let total = self.config.retries.saturating_add(1);
+        let mut attempt = 1;
+        loop {
+            let mut result = run()?;
+            result.attempts = attempt;
+            // A run that hung once will likely hang again; retrying it only multiplies the wait.
+            if result.passed() || result.usage.timed_out || attempt >= total {
+                return Ok(result);
+            }
+            if announce {
+                eprintln!(
+                    "↻ Attempt {attempt} of {total} failed (exit code {}); retrying",
+                    result.exit_code.map_or_else(|| "none".to_string(), |c| c.to_string())
+                );
+            }
+            attempt += 1;
+        }
+    }
+
+    fn report_failure(cmd: &str, result: &VerifyResult) {
         if !result.passed() {
             eprintln!("? Verification Failed ");
             eprintln!(" Command: {cmd}");
@@ -138,7 +432,91 @@ impl VerifyRunner {
             }
             eprintln!("?");
         }
+    }
+}
 
-        Ok(result)
+/// Waits for the child, killing it on timeout or cancellation. Returns its exit code.
+fn supervise(
+    child: &mut Child,
+    started: Instant,
+    timeout: Duration,
+    cancelled: &AtomicBool,
+    on_stop: impl Fn(),
+) -> Result<(Option<i32>, Usage)> {
+    let watch = usage::Watch::start(child)?;
+    loop {
+        let remaining = timeout.saturating_sub(started.elapsed());
+        if cancelled.load(Ordering::Relaxed) {
+            on_stop();
+            // Reap the process so it does not linger as a zombie.
+            watch.kill(child);
+            bail!("Verification cancelled");
+        }
+        // A timeout is a failed run, with the usage that led to it kept as evidence.
+        if remaining.is_zero() {
+            on_stop();
+            let usage = Usage {
+                timed_out: true,
+                ..watch.kill(child)
+            };
+            return Ok((None, usage));
+        }
+        if let Some(done) = watch.wait(child, remaining.min(POLL))? {
+            return Ok(done);
+        }
     }
-}
\ No newline at end of file
+}
+
+/// Output read from one of a command's pipes on a thread of its own.
+struct Drain {
+    buffer: Arc<Mutex<Vec<u8>>>,
+    /// Disconnects when the pipe closes.
+    closed: mpsc::Receiver<()>,
+}
+
+impl Drain {
+    /// The output once the pipe closes, or with a `deadline`, whatever arrived by then.
+    fn collect(self, deadline: Option<Instant>) -> String {
+        match deadline {
+            Some(deadline) => {
+                let _ = self.closed.recv_timeout(deadline.saturating_duration_since(Instant::now()));
+            }
+            None => {
+                let _ = self.closed.recv();
+            }
+        }
+        let out = self.buffer.lock().map(|out| out.clone()).unwrap_or_default();
+        String::from_utf8_lossy(&out).into_owned()
+    }
+}
+
+/// Reads a pipe to the end on its own thread, echoing it to stderr if `echo` is set.
+fn drain(pipe: Option<impl Read + Send + 'static>, echo: bool) -> Drain {
+    let buffer = Arc::new(Mutex::new(Vec::new()));
+    let (sender, closed) = mpsc::channel();
+    let out = Arc::clone(&buffer);
+    std::thread::spawn(move || {
+        let _closing = sender;
+        let Some(mut pipe) = pipe else {
+            return;
+        };
+        let mut chunk = [0; 8192];
+        loop {
+            match pipe.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(n) => {
+                    if echo {
+                        let mut stderr = std::io::stderr().lock();
+                        let _ = stderr.write_all(&chunk[..n]).and_then(|()| stderr.flush());
+                    }
+                    if let Ok(mut out) = out.lock() {
+                        out.extend_from_slice(&chunk[..n]);
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+    });
+    Drain { buffer, closed }
+}
@@ -0,0 +1,206 @@
+//! Probe: Cheap sanity checks on a verification command before it first runs.
+//!
+//! Nothing here executes the command itself. The probe looks up each program
+//! on PATH and, for `cargo test <filter>`, asks the test harness to list what
+//! the filter would select. Anything it cannot decide is left unreported.
+
+use super::VerifyRunner;
+use crate::engine::shell::{self, Shell};
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use wait_timeout::ChildExt;
+
+/// Listing tests compiles them, so give up rather than stall `add`.
+const LIST_TIMEOUT: Duration = Duration::from_mins(2);
+
+const POSIX_BUILTINS: &[&str] = &[
+    "!", ".", ":", "[", "[[", "{", "(", "alias", "cd", "command", "echo", "eval", "exec",
+    "exit", "export", "false", "for", "if", "printf", "pwd", "read", "set", "source", "test",
+    "true", "type", "ulimit", "umask", "unset", "until", "wait", "while",
+];
+const CMD_BUILTINS: &[&str] = &[
+    "call", "cd", "copy", "del", "dir", "echo", "exit", "for", "if", "md", "mkdir", "move",
+    "rd", "ren", "rmdir", "set", "type",
+];
+/// `cargo test` options whose value is a separate argument, not a filter.
+const CARGO_VALUE_FLAGS: &[&str] = &[
+    "-p", "--package", "--test", "--bin", "--example", "--bench", "-F", "--features",
+    "--target", "--target-dir", "--manifest-path", "-j", "--jobs", "--profile", "--color",
+    "--exclude", "--config", "-Z",
+];
+
+impl VerifyRunner {
+    /// Looks for problems that would make `cmd` fail before it tests anything.
+    ///
+    /// Returns one human-readable warning per problem found; an empty list
+    /// means nothing suspicious, not that the command will pass.
+    #[must_use]
+    pub fn probe(&self, cmd: &str) -> Vec<String> {
+        let mut warnings = Vec::new();
+        // The host's PATH says nothing about what is installed in an image.
+        if self.config.container.is_some() {
+            return warnings;
+        }
+        // Without a shell, `&&` and `|` are arguments, not separators.
+        let segments = if self.config.shell == Shell::Exec { vec![cmd] } else { segments(cmd) };
+        for segment in segments {
+            let words = words(segment);
+            let Some(program) = program(&words) else {
+                continue;
+            };
+            if !self.is_builtin(program) && !self.is_installed(program) {
+                warnings.push(if program.contains(['/', '\\']) {
+                    format!("`{program}` does not exist")
+                } else {
+                    format!("`{program}` is not installed (not found on PATH)")
+                });
+                continue;
+            }
+            if let Some(filter) = cargo_test_filter(&words) {
+                if list_tests(&words) == Some(0) {
+                    warnings.push(format!("`cargo test {filter}` matches no tests"));
+                }
+            }
+        }
+        warnings
+    }
+
+    fn is_builtin(&self, program: &str) -> bool {
+        match self.config.shell {
+            Shell::Sh | Shell::Bash | Shell::Zsh => POSIX_BUILTINS.contains(&program),
+            Shell::Cmd => CMD_BUILTINS.contains(&program.to_lowercase().as_str()),
+            Shell::Exec => false,
+            // Cmdlets and nu commands aren't files; don't second-guess them.
+            Shell::Pwsh | Shell::Nu => true,
+        }
+    }
+
+    fn is_installed(&self, program: &str) -> bool {
+        if program.contains(['/', '\\']) {
+            let base = self.config.working_dir.as_deref().map_or_else(PathBuf::new, PathBuf::from);
+            return base.join(program).exists();
+        }
+        std::env::var_os("PATH").is_none() || shell::which(program).is_some()
+    }
+}
+
+/// Splits a command line at `&&`, `||`, `;`, and `|`, ignoring quoted text.
+fn segments(cmd: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let (mut start, mut quote) = (0, None);
+    let bytes = cmd.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        match quote {
+            Some(q) if b == q => quote = None,
+            None if b == b'\'' || b == b'"' => quote = Some(b),
+            None if b == b'&' && is_redirect(bytes, i) => {}
+            None if b == b';' || b == b'|' || b == b'&' => {
+                out.push(&cmd[start..i]);
+                let doubled = bytes.get(i + 1) == Some(&b);
+                i += if doubled { 2 } else { 1 };
+                start = i;
+                continue;
+            }
+            Some(_) | None => {}
+        }
+        i += 1;
+    }
+    out.push(&cmd[start..]);
+    out.into_iter().filter(|s| !s.trim().is_empty()).collect()
+}
+
+/// `2>&1` and `&>file` use `&` as part of a redirection, not as a separator.
+fn is_redirect(bytes: &[u8], amp: usize) -> bool {
+    (amp > 0 && bytes[amp - 1] == b'>') || bytes.get(amp + 1) == Some(&b'>')
+}
+
+/// Whitespace-separated words with surrounding quotes and subshell parens removed.
+fn words(segment: &str) -> Vec<&str> {
+    segment
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c| matches!(c, '\'' | '"' | '(' | ')')))
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// The program a segment runs, skipping leading `VAR=value` assignments.
+fn program<'a>(words: &[&'a str]) -> Option<&'a str> {
+    words
+        .iter()
+        .find(|w| !is_assignment(w))
+        .copied()
+        .filter(|w| !w.starts_with('$'))
+}
+
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=')
+        .is_some_and(|(name, _)| !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_'))
+}
+
+/// The test name filter of a `cargo [+toolchain] test` invocation, if any.
+fn cargo_test_filter<'a>(words: &[&'a str]) -> Option<&'a str> {
+    let args = cargo_test_args(words)?;
+    let mut skip_value = false;
+    for arg in args {
+        if *arg == "--" {
+            break;
+        }
+        if skip_value {
+            skip_value = false;
+        } else if CARGO_VALUE_FLAGS.contains(arg) {
+            skip_value = true;
+        } else if !arg.starts_with('-') {
+            return Some(arg);
+        }
+    }
+    None
+}
+
+/// The arguments after `test` in a cargo test invocation.
+fn cargo_test_args<'a, 'w>(words: &'w [&'a str]) -> Option<&'w [&'a str]> {
+    let start = words.iter().position(|w| !is_assignment(w))?;
+    let rest = &words[start..];
+    if rest.first() != Some(&"cargo") {
+        return None;
+    }
+    let test = rest.iter().skip(1).position(|w| !w.starts_with('+'))? + 1;
+    (rest.get(test) == Some(&"test")).then(|| &rest[test + 1..])
+}
+
+/// Number of tests the invocation would run, or `None` if listing failed.
+fn list_tests(words: &[&str]) -> Option<usize> {
+    let start = words.iter().position(|w| *w == "cargo")?;
+    let mut args: Vec<&str> = words[start + 1..].to_vec();
+    if !args.contains(&"--") {
+        args.push("--");
+    }
+    args.push("--list");
+
+    let _span = tracing::info_span!("probe", op = "cargo-list").entered();
+    let mut child = Command::new("cargo")
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    // Drain stdout concurrently so a long listing can't fill the pipe.
+    let mut stdout = child.stdout.take()?;
+    let reader = std::thread::spawn(move || {
+        let mut out = String::new();
+        stdout.read_to_string(&mut out).ok().map(|_| out)
+    });
+    let Ok(Some(status)) = child.wait_timeout(LIST_TIMEOUT) else {
+        let _ = child.kill();
+        let _ = child.wait();
+        return None;
+    };
+    let listing = reader.join().ok()??;
+    if !status.success() {
+        return None;
+    }
+    Some(listing.lines().filter(|l| l.ends_with(": test")).count())
+}
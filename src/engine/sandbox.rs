@@ -0,0 +1,395 @@
+//! Sandbox: Confining verification commands you don't fully trust.
+//!
+//! A test command written by an agent or taken from a pull request runs with
+//! the user's full rights. With `[sandbox] enabled` (or `check --sandbox`) it
+//! runs confined instead. On Linux, Landlock makes everything read-only except
+//! the run's artifact directory, a private temporary directory (`TMPDIR`),
+//! and the configured ones, and refuses TCP connections and binds; `.git`
+//! and `.roadmap` are never writable. `prlimit` caps memory, CPU time, and
+//! processes. On Windows, a Job Object caps the same resources; the
+//! filesystem and network are not confined there. Other platforms refuse to
+//! run a sandboxed command rather than run it unconfined.
+
+#[cfg(target_os = "linux")]
+use super::artifacts;
+use super::config::SandboxSettings;
+#[cfg(target_os = "linux")]
+use super::db::DB_DIR;
+use anyhow::{bail, Context, Result};
+use std::fmt;
+#[cfg(target_os = "linux")]
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+
+/// What a sandboxed command may do.
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    /// Allow network access.
+    pub network: bool,
+    /// Directories writable besides the run's own, as configured.
+    pub writable: Vec<String>,
+    pub memory_mb: Option<u64>,
+    pub cpu_secs: Option<u64>,
+    pub processes: Option<u64>,
+}
+
+/// Holds what confines a running command; drop it once the command has exited.
+#[derive(Default)]
+pub struct Guard {
+    #[cfg(target_os = "linux")]
+    _temp: Option<Scratch>,
+    #[cfg(windows)]
+    _job: Option<windows::Job>,
+}
+
+/// The private temporary directory of one sandboxed run, removed with it.
+#[cfg(target_os = "linux")]
+struct Scratch(PathBuf);
+
+#[cfg(target_os = "linux")]
+impl Scratch {
+    fn new() -> Result<Self> {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        static RUNS: AtomicU64 = AtomicU64::new(0);
+        let run = RUNS.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("roadmap-sandbox-{}-{run}", std::process::id()));
+        // A directory left by a crashed run of a recycled process ID is stale.
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        Ok(Self(dir))
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+impl Sandbox {
+    #[must_use]
+    pub fn from_settings(settings: &SandboxSettings) -> Self {
+        Self {
+            network: settings.network,
+            writable: settings.writable.clone(),
+            memory_mb: settings.memory_mb,
+            cpu_secs: settings.cpu_secs,
+            processes: settings.processes,
+        }
+    }
+
+    /// Directories the command may write: its artifact directory, `temp`,
+    /// devices like `/dev/null`, and the configured ones (relative to the
+    /// repository). The rest of the repository stays read-only.
+    ///
+    /// # Errors
+    /// Returns an error if a configured directory is or contains `.git` or `.roadmap`.
+    #[cfg(target_os = "linux")]
+    fn writable_dirs(&self, root: &Path, command: &Command, temp: &Path) -> Result<Vec<PathBuf>> {
+        let resolve = |dir: &Path| {
+            let dir = root.join(dir);
+            dir.canonicalize().unwrap_or(dir)
+        };
+        let protected = [resolve(Path::new(".git")), resolve(Path::new(DB_DIR))];
+        let mut dirs = vec![temp.to_path_buf(), PathBuf::from("/dev")];
+        for dir in &self.writable {
+            let path = resolve(Path::new(dir));
+            if protected.iter().any(|p| p.starts_with(&path) || path.starts_with(p)) {
+                bail!("Sandbox cannot make '{dir}' writable: it would expose .git or {DB_DIR}");
+            }
+            dirs.push(path);
+        }
+        // The run's artifact directory is the one place under `.roadmap` the command may write.
+        dirs.extend(
+            command
+                .get_envs()
+                .filter(|(key, _)| *key == artifacts::ENV_VAR)
+                .filter_map(|(_, dir)| dir.map(|dir| resolve(Path::new(dir)))),
+        );
+        Ok(dirs)
+    }
+
+    /// Applies the resource limits that must wrap the command itself.
+    ///
+    /// Keeps the command's directory and environment; stdio is set afterwards.
+    ///
+    /// # Errors
+    /// Returns an error if limits are set but `prlimit` is not installed.
+    #[cfg(target_os = "linux")]
+    pub fn wrap(&self, command: Command) -> Result<Command> {
+        let limits: Vec<String> = [
+            ("--as", self.memory_mb.map(|mb| mb.saturating_mul(1024 * 1024))),
+            ("--cpu", self.cpu_secs),
+            ("--nproc", self.processes),
+        ]
+        .into_iter()
+        .filter_map(|(flag, limit)| limit.map(|n| format!("{flag}={n}")))
+        .collect();
+        if limits.is_empty() {
+            return Ok(command);
+        }
+        let Some(prlimit) = super::shell::which("prlimit") else {
+            bail!("Sandbox resource limits need `prlimit` (util-linux) on PATH");
+        };
+        let mut wrapped = Command::new(prlimit);
+        wrapped
+            .args(limits)
+            .arg("--")
+            .arg(command.get_program())
+            .args(command.get_args());
+        if let Some(dir) = command.get_current_dir() {
+            wrapped.current_dir(dir);
+        }
+        for (key, value) in command.get_envs() {
+            match value {
+                Some(value) => wrapped.env(key, value),
+                None => wrapped.env_remove(key),
+            };
+        }
+        Ok(wrapped)
+    }
+
+    /// Job Object limits apply once the process exists; see [`Self::spawn`].
+    ///
+    /// # Errors
+    /// Never on this platform.
+    #[cfg(not(target_os = "linux"))]
+    pub fn wrap(&self, command: Command) -> Result<Command> {
+        Ok(command)
+    }
+
+    /// Starts `command` confined to `root`.
+    ///
+    /// Landlock restricts the calling thread and what it starts, so the
+    /// command is started from a thread of its own and roadmap stays free.
+    ///
+    /// # Errors
+    /// Returns an error if the kernel cannot enforce the sandbox or the command cannot start.
+    #[cfg(target_os = "linux")]
+    pub fn spawn(&self, command: &mut Command, root: &Path) -> Result<(Child, Guard)> {
+        use landlock::{
+            path_beneath_rules, Access, AccessFs, AccessNet, Ruleset, RulesetAttr, RulesetCreatedAttr,
+            RulesetStatus, ABI,
+        };
+
+        let abi = ABI::V5;
+        let temp = Scratch::new()?;
+        let writable = self.writable_dirs(root, command, &temp.0)?;
+        for var in ["TMPDIR", "TMP", "TEMP"] {
+            command.env(var, &temp.0);
+        }
+        let confine = || -> Result<RulesetStatus> {
+            let mut ruleset = Ruleset::default().handle_access(AccessFs::from_all(abi))?;
+            if !self.network {
+                ruleset = ruleset.handle_access(AccessNet::BindTcp | AccessNet::ConnectTcp)?;
+            }
+            let status = ruleset
+                .create()?
+                .add_rules(path_beneath_rules(["/"], AccessFs::from_read(abi)))?
+                .add_rules(path_beneath_rules(&writable, AccessFs::from_all(abi)))?
+                .restrict_self()?;
+            Ok(status.ruleset)
+        };
+        let child = std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    match confine()? {
+                        RulesetStatus::FullyEnforced => {}
+                        RulesetStatus::NotEnforced => {
+                            bail!("Sandbox unavailable: this kernel does not support Landlock");
+                        }
+                        // A half-confined command could reach what the sandbox promises it cannot.
+                        RulesetStatus::PartiallyEnforced => {
+                            bail!("Sandbox unavailable: this kernel's Landlock enforces only part of it (Linux 6.7 is needed)");
+                        }
+                    }
+                    command.spawn().context("Failed to spawn verification command")
+                })
+                .join()
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("Sandbox setup panicked")))
+        })?;
+        Ok((child, Guard { _temp: Some(temp) }))
+    }
+
+    /// Starts `command` in a Job Object that caps its resources.
+    ///
+    /// The process starts suspended and resumes only once it is in the job,
+    /// so neither it nor anything it starts ever runs unconfined.
+    ///
+    /// # Errors
+    /// Returns an error if the job cannot be created or the command cannot start.
+    #[cfg(windows)]
+    pub fn spawn(&self, command: &mut Command, _root: &Path) -> Result<(Child, Guard)> {
+        use std::os::windows::process::CommandExt;
+
+        let job = windows::Job::new(self)?;
+        command.creation_flags(windows::CREATE_SUSPENDED);
+        let mut child = command.spawn().context("Failed to spawn verification command")?;
+        if let Err(e) = job.assign(&child).and_then(|()| windows::resume(&child)) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(e);
+        }
+        Ok((child, Guard { _job: Some(job) }))
+    }
+
+    /// No sandbox exists for this platform, so nothing runs.
+    ///
+    /// # Errors
+    /// Always.
+    #[cfg(not(any(target_os = "linux", windows)))]
+    pub fn spawn(&self, _command: &mut Command, _root: &Path) -> Result<(Child, Guard)> {
+        bail!("Sandboxed verification is only supported on Linux and Windows")
+    }
+}
+
+impl fmt::Display for Sandbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        // Only Landlock confines the filesystem and network.
+        if cfg!(target_os = "linux") {
+            parts.push(if self.network { "network" } else { "no network" }.to_string());
+            parts.push("writes only to artifacts and temp".to_string());
+        }
+        if let Some(mb) = self.memory_mb {
+            parts.push(format!("{mb} MB"));
+        }
+        if let Some(secs) = self.cpu_secs {
+            parts.push(format!("{secs}s CPU"));
+        }
+        if let Some(n) = self.processes {
+            parts.push(format!("{n} processes"));
+        }
+        if parts.is_empty() {
+            return write!(f, "no limits");
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use super::Sandbox;
+    use anyhow::{bail, Result};
+    use std::os::windows::io::AsRawHandle;
+    use std::process::Child;
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Thread32First, Thread32Next, TH32CS_SNAPTHREAD, THREADENTRY32,
+    };
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation, SetInformationJobObject,
+        JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_ACTIVE_PROCESS, JOB_OBJECT_LIMIT_JOB_MEMORY,
+        JOB_OBJECT_LIMIT_JOB_TIME, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+    use windows_sys::Win32::System::Threading::{OpenThread, ResumeThread, THREAD_SUSPEND_RESUME};
+    pub use windows_sys::Win32::System::Threading::CREATE_SUSPENDED;
+
+    /// A Job Object; closing it kills whatever is still running in it.
+    pub struct Job(HANDLE);
+
+    // The handle is only used through the Win32 API, which is thread-safe for jobs.
+    unsafe impl Send for Job {}
+
+    impl Job {
+        pub fn new(sandbox: &Sandbox) -> Result<Self> {
+            // SAFETY: null attributes and name create an anonymous job with default security.
+            let handle = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+            if handle.is_null() {
+                bail!("Failed to create a job object: {}", std::io::Error::last_os_error());
+            }
+            let job = Self(handle);
+            // SAFETY: the structure is plain data, for which all zeroes means "no limits".
+            let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { std::mem::zeroed() };
+            let mut flags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            if let Some(mb) = sandbox.memory_mb {
+                flags |= JOB_OBJECT_LIMIT_JOB_MEMORY;
+                info.JobMemoryLimit = usize::try_from(mb.saturating_mul(1024 * 1024)).unwrap_or(usize::MAX);
+            }
+            if let Some(secs) = sandbox.cpu_secs {
+                flags |= JOB_OBJECT_LIMIT_JOB_TIME;
+                // In 100-nanosecond ticks.
+                info.BasicLimitInformation.PerJobUserTimeLimit =
+                    i64::try_from(secs.saturating_mul(10_000_000)).unwrap_or(i64::MAX);
+            }
+            if let Some(n) = sandbox.processes {
+                flags |= JOB_OBJECT_LIMIT_ACTIVE_PROCESS;
+                info.BasicLimitInformation.ActiveProcessLimit = u32::try_from(n).unwrap_or(u32::MAX);
+            }
+            info.BasicLimitInformation.LimitFlags = flags;
+            let size = u32::try_from(std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>()).unwrap_or(u32::MAX);
+            // SAFETY: `info` is a valid extended limit structure of `size` bytes for the call's duration.
+            let set = unsafe {
+                SetInformationJobObject(
+                    job.0,
+                    JobObjectExtendedLimitInformation,
+                    std::ptr::from_ref(&info).cast(),
+                    size,
+                )
+            };
+            if set == 0 {
+                bail!("Failed to set the job's limits: {}", std::io::Error::last_os_error());
+            }
+            Ok(job)
+        }
+
+        pub fn assign(&self, child: &Child) -> Result<()> {
+            // SAFETY: both handles are open: the job is owned by `self` and the process by `child`.
+            if unsafe { AssignProcessToJobObject(self.0, child.as_raw_handle()) } == 0 {
+                bail!("Failed to confine the command to its job: {}", std::io::Error::last_os_error());
+            }
+            Ok(())
+        }
+    }
+
+    /// Resumes the threads of a process started with `CREATE_SUSPENDED`.
+    pub fn resume(child: &Child) -> Result<()> {
+        // SAFETY: a thread snapshot takes no pointers; its handle is closed below.
+        let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0) };
+        if snapshot == INVALID_HANDLE_VALUE {
+            bail!("Failed to list the command's threads: {}", std::io::Error::last_os_error());
+        }
+        // SAFETY: the structure is plain data; `dwSize` is set as the API requires.
+        let mut entry: THREADENTRY32 = unsafe { std::mem::zeroed() };
+        entry.dwSize = u32::try_from(std::mem::size_of::<THREADENTRY32>()).unwrap_or(u32::MAX);
+        let mut resumed = 0;
+        // SAFETY: `snapshot` is open and `entry` is valid for each call.
+        let mut more = unsafe { Thread32First(snapshot, &raw mut entry) } != 0;
+        while more {
+            if entry.th32OwnerProcessID == child.id() {
+                // SAFETY: opening a thread by ID takes no pointers; the handle is closed right after.
+                unsafe {
+                    let thread = OpenThread(THREAD_SUSPEND_RESUME, 0, entry.th32ThreadID);
+                    if !thread.is_null() {
+                        if ResumeThread(thread) != u32::MAX {
+                            resumed += 1;
+                        }
+                        CloseHandle(thread);
+                    }
+                }
+            }
+            // SAFETY: as for `Thread32First`.
+            more = unsafe { Thread32Next(snapshot, &raw mut entry) } != 0;
+        }
+        // SAFETY: the snapshot handle is owned here and closed exactly once.
+        unsafe {
+            CloseHandle(snapshot);
+        }
+        if resumed == 0 {
+            bail!("Failed to resume the confined command: {}", std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            // SAFETY: the handle is owned by this job and closed exactly once.
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Scope Maintenance: Keeps scope globs pointing at files that still exist.
+//!
+//! Scopes are git pathspecs. When a directory is moved, its scope keeps the
+//! old prefix and silently matches nothing, so the task can never go stale
+//! again. Renames recorded by git tell us where the files went.
+
+use anyhow::{bail, Context, Result};
+use std::process::Command;
+
+/// A file moved between two commits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rename {
+    pub from: String,
+    pub to: String,
+}
+
+/// Lists renames git detects between `since` and HEAD.
+///
+/// # Errors
+/// Returns error if git cannot diff the range.
+pub fn find_renames(since: &str) -> Result<Vec<Rename>> {
+    let _span = tracing::info_span!("git", op = "diff").entered();
+    let output = Command::new("git")
+        .args(["diff", "--find-renames", "--name-status", "-z", since, "HEAD"])
+        .output()
+        .context("Failed to run git diff")?;
+    if !output.status.success() {
+        bail!(
+            "git diff {since}..HEAD failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    // -z output: status NUL path [NUL path] NUL ...; renames carry two paths.
+    let raw = String::from_utf8_lossy(&output.stdout);
+    let mut fields = raw.split('\0').filter(|f| !f.is_empty());
+    let mut renames = Vec::new();
+    while let Some(status) = fields.next() {
+        let paths = if status.starts_with('R') || status.starts_with('C') { 2 } else { 1 };
+        let taken: Vec<&str> = fields.by_ref().take(paths).collect();
+        if status.starts_with('R') && taken.len() == 2 {
+            renames.push(Rename {
+                from: taken[0].to_string(),
+                to: taken[1].to_string(),
+            });
+        }
+    }
+    Ok(renames)
+}
+
+/// Returns true if the pathspec matches at least one tracked file at HEAD.
+#[must_use]
+pub fn matches_anything(scope: &str) -> bool {
+    let _span = tracing::info_span!("git", op = "ls-files").entered();
+    Command::new("git")
+        .args(["ls-files", "--", scope])
+        .output()
+        .is_ok_and(|o| !o.stdout.is_empty())
+}
+
+/// Proposes a replacement for `scope` given the renames since it was last valid.
+///
+/// The scope's literal directory prefix is moved only if every renamed file
+/// under it agrees on a single destination; ambiguous splits are left alone.
+#[must_use]
+pub fn remap(scope: &str, renames: &[Rename]) -> Option<String> {
+    let prefix = literal_prefix(scope);
+    if prefix.is_empty() {
+        return None;
+    }
+    let exact = prefix.len() == scope.len();
+
+    let mut target: Option<String> = None;
+    for r in renames {
+        let new_prefix = if exact && r.from == scope {
+            r.to.clone()
+        } else {
+            let dir = if exact { format!("{scope}/") } else { prefix.to_string() };
+            let Some(rest) = r.from.strip_prefix(dir.as_str()) else {
+                continue;
+            };
+            // A file renamed within the move makes the mapping ambiguous.
+            let moved = r.to.strip_suffix(rest)?;
+            if exact {
+                moved.trim_end_matches('/').to_string()
+            } else {
+                moved.to_string()
+            }
+        };
+        match &target {
+            Some(t) if *t != new_prefix => return None,
+            _ => target = Some(new_prefix),
+        }
+    }
+
+    let target = target?;
+    let remapped = if exact {
+        target
+    } else {
+        format!("{target}{}", &scope[prefix.len()..])
+    };
+    (remapped != scope).then_some(remapped)
+}
+
+/// Collapses changed files into directory-level globs (`dir/**`).
+///
+/// Files at the repository root are kept as-is, and a directory is dropped
+/// when one of its ancestors is already included.
+#[must_use]
+pub fn directory_globs(files: &[String]) -> Vec<String> {
+    let mut dirs: Vec<&str> = files
+        .iter()
+        .map(|f| f.rfind('/').map_or(f.as_str(), |i| &f[..i]))
+        .collect();
+    dirs.sort_unstable();
+    dirs.dedup();
+
+    let mut kept: Vec<&str> = Vec::new();
+    for dir in dirs {
+        let covered = kept
+            .iter()
+            .any(|k| dir.strip_prefix(k).is_some_and(|rest| rest.starts_with('/')));
+        if !covered {
+            kept.push(dir);
+        }
+    }
+    kept.into_iter()
+        .map(|d| {
+            if files.iter().any(|f| f == d) {
+                d.to_string()
+            } else {
+                format!("{d}/**")
+            }
+        })
+        .collect()
+}
+
+/// The part of a glob before its first wildcard, cut back to a directory.
+///
+/// A scope without wildcards is returned whole (it names a file or directory).
+fn literal_prefix(scope: &str) -> &str {
+    match scope.find(['*', '?', '[']) {
+        None => scope,
+        Some(i) => scope[..i].rfind('/').map_or("", |slash| &scope[..=slash]),
+    }
+}
@@ -0,0 +1,166 @@
+//! Shell Selection: How verification commands are handed to the OS.
+//!
+//! `exec` is no shell at all: the command is split into words the way a
+//! POSIX shell would quote them, and the first word runs directly with the
+//! rest as its arguments. Pipes, `&&`, variables, and globs are then plain
+//! arguments, which is what makes it predictable across systems.
+
+use anyhow::{bail, Result};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A shell capable of running a verification command string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Sh,
+    Bash,
+    Zsh,
+    Pwsh,
+    Nu,
+    Cmd,
+    /// No shell: the command's words run as one program and its arguments.
+    Exec,
+}
+
+impl Shell {
+    /// Parses a shell name as used in `--shell` and config files.
+    ///
+    /// # Errors
+    /// Returns an error for unsupported shells.
+    pub fn parse(name: &str) -> Result<Self> {
+        Ok(match name.trim().to_lowercase().as_str() {
+            "sh" => Self::Sh,
+            "bash" => Self::Bash,
+            "zsh" => Self::Zsh,
+            "pwsh" | "powershell" => Self::Pwsh,
+            "nu" | "nushell" => Self::Nu,
+            "cmd" => Self::Cmd,
+            "exec" | "none" => Self::Exec,
+            other => bail!("Unsupported shell '{other}' (expected sh, bash, zsh, pwsh, nu, cmd, exec)"),
+        })
+    }
+
+    /// The native shell of the current platform.
+    #[must_use]
+    pub fn platform_default() -> Self {
+        if cfg!(target_os = "windows") {
+            Self::Cmd
+        } else {
+            Self::Sh
+        }
+    }
+
+    /// Builds a command that runs `script` in this shell.
+    ///
+    /// # Errors
+    /// Returns an error if `script` has no program to run or an unclosed quote under [`Shell::Exec`].
+    pub fn command(self, script: &str) -> Result<Command> {
+        let (program, args): (&str, &[&str]) = match self {
+            Self::Sh => ("sh", &["-c"]),
+            Self::Bash => ("bash", &["-c"]),
+            Self::Zsh => ("zsh", &["-c"]),
+            Self::Pwsh => ("pwsh", &["-NoProfile", "-NonInteractive", "-Command"]),
+            Self::Nu => ("nu", &["-c"]),
+            Self::Cmd => ("cmd", &["/C"]),
+            Self::Exec => {
+                let words = split(script)?;
+                let Some((program, args)) = words.split_first() else {
+                    bail!("Empty command");
+                };
+                let mut cmd = Command::new(program);
+                cmd.args(args);
+                return Ok(cmd);
+            }
+        };
+        let mut cmd = Command::new(program);
+        cmd.args(args).arg(script);
+        Ok(cmd)
+    }
+}
+
+impl fmt::Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Sh => "sh",
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+            Self::Pwsh => "pwsh",
+            Self::Nu => "nu",
+            Self::Cmd => "cmd",
+            Self::Exec => "exec",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Splits a command line into words, honouring single and double quotes and backslash escapes.
+///
+/// # Errors
+/// Returns an error if a quote is never closed or the line ends in a lone backslash.
+pub fn split(line: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let text = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => text.push(c),
+                        None => bail!("Unclosed ' in `{line}`"),
+                    }
+                }
+            }
+            '"' => {
+                let text = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => text.push(c),
+                            Some(c) => {
+                                text.push('\\');
+                                text.push(c);
+                            }
+                            None => bail!("Unclosed \" in `{line}`"),
+                        },
+                        Some(c) => text.push(c),
+                        None => bail!("Unclosed \" in `{line}`"),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(c) => word.get_or_insert_with(String::new).push(c),
+                None => bail!("Trailing backslash in `{line}`"),
+            },
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Finds an executable on PATH the way the OS would, honouring `PATHEXT` on Windows.
+#[must_use]
+pub fn which(program: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).find_map(|dir| executable_in(&dir, program))
+}
+
+fn executable_in(dir: &Path, program: &str) -> Option<PathBuf> {
+    let plain = dir.join(program);
+    if plain.is_file() {
+        return Some(plain);
+    }
+    if !cfg!(target_os = "windows") {
+        return None;
+    }
+    let exts = std::env::var("PATHEXT").unwrap_or_else(|_| ".EXE;.CMD;.BAT;.COM".to_string());
+    exts.split(';')
+        .map(|ext| dir.join(format!("{program}{ext}")))
+        .find(|p| p.is_file())
+}
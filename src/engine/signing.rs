@@ -0,0 +1,179 @@
+//! Proof Signing: Detached SSH/GPG signatures over the canonical proof payload.
+//!
+//! Signing and verification shell out to `ssh-keygen -Y` and `gpg`, so keys
+//! never pass through roadmap itself.
+
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Namespace bound into SSH signatures so they can't be replayed elsewhere.
+pub const SSH_NAMESPACE: &str = "roadmap";
+
+/// Prefix selecting a GPG key instead of an SSH key file in `--sign`.
+const GPG_PREFIX: &str = "gpg:";
+
+/// Result of checking one signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureCheck {
+    Valid { signer: String },
+    Invalid { reason: String },
+    Unsigned,
+}
+
+/// Signs `payload` with an SSH private key path or a `gpg:<key-id>` spec.
+///
+/// # Errors
+/// Returns error if the signing tool is missing or fails.
+pub fn sign(key_spec: &str, payload: &str) -> Result<String> {
+    let output = if let Some(key_id) = key_spec.strip_prefix(GPG_PREFIX) {
+        pipe(
+            Command::new("gpg").args(["--batch", "--armor", "--detach-sign", "--local-user", key_id]),
+            payload,
+        )?
+    } else {
+        pipe(
+            Command::new("ssh-keygen")
+                .args(["-Y", "sign", "-n", SSH_NAMESPACE, "-f"])
+                .arg(key_spec),
+            payload,
+        )?
+    };
+
+    if !output.status.success() {
+        bail!(
+            "Signing failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Trust anchors used to verify signatures.
+pub struct TrustStore<'a> {
+    pub allowed_signers: Option<&'a Path>,
+    pub keyring: Option<&'a Path>,
+}
+
+impl TrustStore<'_> {
+    /// Verifies a detached signature over `payload`.
+    #[must_use]
+    pub fn check(&self, payload: &str, signature: Option<&str>) -> SignatureCheck {
+        let Some(sig) = signature else {
+            return SignatureCheck::Unsigned;
+        };
+
+        let result = if sig.contains("BEGIN SSH SIGNATURE") {
+            self.check_ssh(payload, sig)
+        } else if sig.contains("BEGIN PGP SIGNATURE") {
+            self.check_gpg(payload, sig)
+        } else {
+            Err(anyhow::anyhow!("Unrecognized signature format"))
+        };
+
+        result.unwrap_or_else(|e| SignatureCheck::Invalid {
+            reason: e.to_string(),
+        })
+    }
+
+    fn check_ssh(&self, payload: &str, sig: &str) -> Result<SignatureCheck> {
+        let Some(signers) = self.allowed_signers else {
+            bail!("SSH signature found but no --allowed-signers file given");
+        };
+        let sig_file = TempSig::write(sig)?;
+
+        let found = Command::new("ssh-keygen")
+            .args(["-Y", "find-principals", "-f"])
+            .arg(signers)
+            .arg("-s")
+            .arg(&sig_file.0)
+            .output()
+            .context("Failed to run ssh-keygen")?;
+        let principals = String::from_utf8_lossy(&found.stdout);
+        let Some(principal) = principals.lines().next().filter(|_| found.status.success()) else {
+            bail!("Signer is not in the allowed signers file");
+        };
+
+        let verify = pipe(
+            Command::new("ssh-keygen")
+                .args(["-Y", "verify", "-n", SSH_NAMESPACE, "-I", principal, "-f"])
+                .arg(signers)
+                .arg("-s")
+                .arg(&sig_file.0),
+            payload,
+        )?;
+        Ok(verdict(verify.status.success(), principal, "signature does not match proof contents"))
+    }
+
+    fn check_gpg(&self, payload: &str, sig: &str) -> Result<SignatureCheck> {
+        let Some(keyring) = self.keyring else {
+            bail!("GPG signature found but no --keyring given");
+        };
+        let sig_file = TempSig::write(sig)?;
+
+        let verify = pipe(
+            Command::new("gpg")
+                .args(["--batch", "--no-default-keyring", "--status-fd", "1", "--keyring"])
+                .arg(keyring)
+                .arg("--verify")
+                .arg(&sig_file.0)
+                .arg("-"),
+            payload,
+        )?;
+        let status = String::from_utf8_lossy(&verify.stdout);
+        let signer = status
+            .lines()
+            .find_map(|l| l.strip_prefix("[GNUPG:] GOODSIG "))
+            .unwrap_or("unknown");
+        Ok(verdict(verify.status.success(), signer, "signature invalid or key not in keyring"))
+    }
+}
+
+fn verdict(ok: bool, signer: &str, failure: &str) -> SignatureCheck {
+    if ok {
+        SignatureCheck::Valid {
+            signer: signer.to_string(),
+        }
+    } else {
+        SignatureCheck::Invalid {
+            reason: failure.to_string(),
+        }
+    }
+}
+
+/// Runs a command with `input` on stdin and captures its output.
+fn pipe(cmd: &mut Command, input: &str) -> Result<std::process::Output> {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to spawn signing tool")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    child.wait_with_output().context("Signing tool did not complete")
+}
+
+/// A signature written to a temporary file, removed on drop.
+struct TempSig(PathBuf);
+
+impl TempSig {
+    fn write(sig: &str) -> Result<Self> {
+        let path = std::env::temp_dir().join(format!(
+            "roadmap-{}-{}.sig",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        fs::write(&path, sig).context("Failed to write temporary signature file")?;
+        Ok(Self(path))
+    }
+}
+
+impl Drop for TempSig {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
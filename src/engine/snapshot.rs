@@ -0,0 +1,437 @@
+//! Snapshots: The whole roadmap as one portable, slug-keyed document.
+//!
+//! Row IDs are local to a database, so tasks, edges, and proofs are keyed by
+//! slug. Snapshots are what `push-state`/`pull-state` move between clones.
+
+use super::exchange::{self, PortableProof};
+use super::repo::{MilestoneRepo, ProofRepo, TaskRepo};
+use super::types::{Milestone, Task, TaskStatus};
+use anyhow::{bail, Context, Result};
+use petgraph::algo::is_cyclic_directed;
+use petgraph::graphmap::DiGraphMap;
+use rusqlite::Connection;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::hash::BuildHasher;
+
+/// Version of the snapshot layout. Bump on incompatible changes.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// A task's definition, without its proofs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TaskRecord {
+    pub slug: String,
+    pub title: String,
+    pub status: String,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_cmd: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_cmd_unix: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_cmd_windows: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage_min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bench_threshold: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub budgets: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quarantine_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub priority: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<String>,
+    /// Hours.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Slug of the parent task.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub milestone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // serde passes fields by reference
+fn is_zero(n: &i64) -> bool {
+    *n == 0
+}
+
+impl From<&Task> for TaskRecord {
+    fn from(t: &Task) -> Self {
+        Self {
+            slug: t.slug.clone(),
+            title: t.title.clone(),
+            status: t.status.to_string(),
+            created_at: t.created_at.clone(),
+            test_cmd: t.test_cmd.clone(),
+            scopes: t.scopes.clone(),
+            shell: t.shell.clone(),
+            test_cmd_unix: t.test_cmd_unix.clone(),
+            test_cmd_windows: t.test_cmd_windows.clone(),
+            coverage_path: t.coverage_path.clone(),
+            coverage_min: t.coverage_min,
+            bench_threshold: t.bench_threshold,
+            budgets: t.budgets.clone(),
+            quarantine_reason: t.quarantine_reason.clone(),
+            priority: t.priority,
+            due: t.due.clone(),
+            estimate: t.estimate,
+            description: t.description.clone(),
+            parent: t.parent.clone(),
+            milestone: t.milestone.clone(),
+            assignee: t.assignee.clone(),
+            cwd: t.cwd.clone(),
+            tags: t.tags.clone(),
+        }
+    }
+}
+
+/// A dependency edge: `blocker` must be satisfied before `blocked`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema)]
+pub struct Edge {
+    pub blocker: String,
+    pub blocked: String,
+}
+
+/// The full roadmap state.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Snapshot {
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub milestones: Vec<Milestone>,
+    pub tasks: Vec<TaskRecord>,
+    pub edges: Vec<Edge>,
+    pub proofs: Vec<PortableProof>,
+}
+
+/// What applying a snapshot changed.
+#[derive(Debug, Default)]
+pub struct ApplySummary {
+    pub tasks_added: usize,
+    pub edges_added: usize,
+    /// Edges skipped because they would close a dependency cycle.
+    pub edges_rejected: Vec<Edge>,
+    pub proofs_added: usize,
+}
+
+impl Snapshot {
+    /// Captures the current database contents.
+    ///
+    /// # Errors
+    /// Returns error if a database query fails.
+    pub fn capture(conn: &Connection) -> Result<Self> {
+        let mut tasks = TaskRepo::new(conn).get_all()?;
+        tasks.sort_by(|a, b| a.slug.cmp(&b.slug));
+        for task in &mut tasks {
+            task.scopes.sort();
+            task.tags.sort();
+        }
+        let slugs: HashMap<i64, String> = tasks.iter().map(|t| (t.id, t.slug.clone())).collect();
+
+        let mut stmt = conn.prepare("SELECT blocker_id, blocked_id FROM dependencies")?;
+        let mut edges = Vec::new();
+        for row in stmt.query_map([], |r| Ok((r.get::<_, i64>(0)?, r.get::<_, i64>(1)?)))? {
+            let (from, to) = row?;
+            if let (Some(upstream), Some(downstream)) = (slugs.get(&from), slugs.get(&to)) {
+                edges.push(Edge {
+                    blocker: upstream.clone(),
+                    blocked: downstream.clone(),
+                });
+            }
+        }
+        edges.sort();
+
+        // Row order differs between clones; sort so equal state means equal bytes.
+        let mut proofs: Vec<PortableProof> = ProofRepo::new(conn)
+            .get_audit_trail()?
+            .into_iter()
+            .map(|r| PortableProof {
+                task: r.slug,
+                proof: r.proof,
+            })
+            .collect();
+        exchange::sort_proofs(&mut proofs);
+
+        let mut milestones = MilestoneRepo::new(conn).get_all()?;
+        milestones.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(Self {
+            version: SNAPSHOT_VERSION,
+            milestones,
+            tasks: tasks.iter().map(TaskRecord::from).collect(),
+            edges,
+            proofs,
+        })
+    }
+
+    /// Parses a snapshot, rejecting layouts newer than this build understands.
+    ///
+    /// # Errors
+    /// Returns error if the JSON is malformed or from a newer version.
+    pub fn parse(json: &str) -> Result<Self> {
+        let snapshot: Self = serde_json::from_str(json)?;
+        if snapshot.version > SNAPSHOT_VERSION {
+            bail!(
+                "Snapshot version {} is newer than supported ({SNAPSHOT_VERSION})",
+                snapshot.version
+            );
+        }
+        Ok(snapshot)
+    }
+
+    /// Checks the version, slug uniqueness, references, and acyclicity.
+    ///
+    /// Returns every problem found, so one run reports them all.
+    #[must_use]
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.version > SNAPSHOT_VERSION {
+            problems.push(format!(
+                "version {} is newer than supported ({SNAPSHOT_VERSION})",
+                self.version
+            ));
+        }
+        let mut slugs = HashSet::new();
+        for task in &self.tasks {
+            if !slugs.insert(task.slug.as_str()) {
+                problems.push(format!("duplicate task '{}'", task.slug));
+            }
+        }
+        let mut graph: DiGraphMap<&str, ()> = DiGraphMap::new();
+        for edge in &self.edges {
+            let mut known = true;
+            for end in [&edge.blocker, &edge.blocked] {
+                if !slugs.contains(end.as_str()) {
+                    known = false;
+                    problems.push(format!(
+                        "edge {} → {} references unknown task '{end}'",
+                        edge.blocker, edge.blocked
+                    ));
+                }
+            }
+            if known {
+                graph.add_edge(edge.blocker.as_str(), edge.blocked.as_str(), ());
+            }
+        }
+        if is_cyclic_directed(&graph) {
+            problems.push("dependencies form a cycle".to_string());
+        }
+        let mut parents: DiGraphMap<&str, ()> = DiGraphMap::new();
+        for task in &self.tasks {
+            let Some(parent) = &task.parent else { continue };
+            if slugs.contains(parent.as_str()) {
+                parents.add_edge(parent.as_str(), task.slug.as_str(), ());
+            } else {
+                problems.push(format!("task '{}' has unknown parent '{parent}'", task.slug));
+            }
+        }
+        if is_cyclic_directed(&parents) {
+            problems.push("subtasks form a cycle".to_string());
+        }
+        for entry in &self.proofs {
+            if !slugs.contains(entry.task.as_str()) {
+                problems.push(format!("proof references unknown task '{}'", entry.task));
+            }
+        }
+        problems
+    }
+
+    /// Unions this snapshot into the database.
+    ///
+    /// Missing tasks are created, missing edges are linked unless they would
+    /// create a cycle, and proofs not yet stored are appended to the chain with
+    /// their original timestamps. Existing local records are never modified.
+    ///
+    /// # Errors
+    /// Returns error if a database operation fails.
+    pub fn apply(&self, conn: &Connection) -> Result<ApplySummary> {
+        let repo = TaskRepo::new(conn);
+        let mut summary = ApplySummary::default();
+
+        for milestone in &self.milestones {
+            MilestoneRepo::new(conn).restore(milestone)?;
+        }
+        let mut added = Vec::new();
+        for record in &self.tasks {
+            if repo.find_by_slug(&record.slug)?.is_none() {
+                restore_task(&repo, record)?;
+                added.push(record);
+                summary.tasks_added += 1;
+            }
+        }
+
+        let ids = slug_ids(&repo)?;
+        set_parents(&repo, &ids, added)?;
+        let (added, rejected) = link_acyclic(conn, &ids, &self.edges)?;
+        summary.edges_added = added;
+        summary.edges_rejected = rejected;
+        summary.proofs_added = append_proofs(conn, &ids, &self.proofs)?;
+
+        Ok(summary)
+    }
+}
+
+/// Maps every task slug to its local row ID.
+///
+/// # Errors
+/// Returns error if the query fails.
+pub fn slug_ids(repo: &TaskRepo<'_>) -> Result<HashMap<String, i64>> {
+    Ok(repo.get_all()?.into_iter().map(|t| (t.slug, t.id)).collect())
+}
+
+/// Sets the parent of each record's task, once every task it may name exists.
+///
+/// # Errors
+/// Returns error if a parent would make a task its own ancestor, or the update fails.
+pub fn set_parents<'a, S: BuildHasher>(
+    repo: &TaskRepo<'_>,
+    ids: &HashMap<String, i64, S>,
+    records: impl IntoIterator<Item = &'a TaskRecord>,
+) -> Result<()> {
+    let records: Vec<&TaskRecord> = records.into_iter().collect();
+    // Detach first, so moving subtrees around never looks like a cycle halfway through.
+    for record in &records {
+        if let Some(&id) = ids.get(&record.slug) {
+            repo.set_parent(id, None)?;
+        }
+    }
+    for record in records {
+        let Some(&id) = ids.get(&record.slug) else { continue };
+        let parent = record.parent.as_ref().and_then(|p| ids.get(p)).copied();
+        repo.set_parent(id, parent)
+            .with_context(|| format!("Cannot make [{}] a subtask", record.slug))?;
+    }
+    Ok(())
+}
+
+/// Links edges that are not yet present, skipping any that would close a cycle.
+///
+/// Returns the number linked and the edges rejected.
+///
+/// # Errors
+/// Returns error if a database operation fails.
+pub fn link_acyclic<S: BuildHasher>(
+    conn: &Connection,
+    ids: &HashMap<String, i64, S>,
+    edges: &[Edge],
+) -> Result<(usize, Vec<Edge>)> {
+    let mut graph: DiGraphMap<i64, ()> = DiGraphMap::new();
+    let mut stmt = conn.prepare("SELECT blocker_id, blocked_id FROM dependencies")?;
+    for row in stmt.query_map([], |r| Ok((r.get::<_, i64>(0)?, r.get::<_, i64>(1)?)))? {
+        let (from, to) = row?;
+        graph.add_edge(from, to, ());
+    }
+
+    let repo = TaskRepo::new(conn);
+    let (mut added, mut rejected) = (0, Vec::new());
+    for edge in edges {
+        let (Some(&from), Some(&to)) = (ids.get(&edge.blocker), ids.get(&edge.blocked)) else {
+            continue;
+        };
+        if graph.contains_edge(from, to) {
+            continue;
+        }
+        graph.add_edge(from, to, ());
+        if is_cyclic_directed(&graph) {
+            graph.remove_edge(from, to);
+            rejected.push(edge.clone());
+            continue;
+        }
+        repo.link(from, to)?;
+        added += 1;
+    }
+    Ok((added, rejected))
+}
+
+/// Appends proofs not yet stored, oldest first, keeping original timestamps.
+///
+/// Signatures are dropped: they are bound to the emitting database's IDs.
+///
+/// # Errors
+/// Returns error if a database operation fails.
+pub fn append_proofs<S: BuildHasher>(
+    conn: &Connection,
+    ids: &HashMap<String, i64, S>,
+    proofs: &[PortableProof],
+) -> Result<usize> {
+    let proof_repo = ProofRepo::new(conn);
+    let mut ordered: Vec<&PortableProof> = proofs.iter().collect();
+    ordered.sort_by(|a, b| a.proof.timestamp.cmp(&b.proof.timestamp));
+
+    let mut added = 0;
+    for entry in ordered {
+        let Some(&task_id) = ids.get(&entry.task) else { continue };
+        if proof_repo.contains(task_id, &entry.proof)? {
+            continue;
+        }
+        let mut proof = entry.proof.clone();
+        proof.signature = None;
+        proof_repo.save_imported(task_id, &proof)?;
+        added += 1;
+    }
+    Ok(added)
+}
+
+/// Recreates a task from its record.
+///
+/// # Errors
+/// Returns error if a database operation fails.
+pub fn restore_task(repo: &TaskRepo<'_>, record: &TaskRecord) -> Result<i64> {
+    let id = repo.add(&record.slug, &record.title, record.test_cmd.as_deref())?;
+    repo.update_status(id, TaskStatus::from(record.status.clone()))?;
+    repo.set_created_at(id, &record.created_at)?;
+    overwrite_task(repo, id, record)?;
+    Ok(id)
+}
+
+/// Sets every definition field of an existing task from a record.
+///
+/// Status and creation time are left alone; they describe local history.
+/// The parent is set by [`set_parents`], after every task exists.
+///
+/// # Errors
+/// Returns error if a database operation fails.
+pub fn overwrite_task(repo: &TaskRepo<'_>, id: i64, record: &TaskRecord) -> Result<()> {
+    repo.set_title(id, &record.title)?;
+    repo.set_test_cmd(id, record.test_cmd.as_deref())?;
+    repo.set_exec(
+        id,
+        record.shell.as_deref(),
+        record.test_cmd_unix.as_deref(),
+        record.test_cmd_windows.as_deref(),
+    )?;
+    repo.set_cwd(id, record.cwd.as_deref())?;
+    repo.set_coverage(id, record.coverage_path.as_deref(), record.coverage_min)?;
+    repo.set_bench_threshold(id, record.bench_threshold)?;
+    repo.set_quarantine(id, record.quarantine_reason.as_deref())?;
+    repo.set_priority(id, record.priority)?;
+    repo.set_due(id, record.due.as_deref())?;
+    repo.set_estimate(id, record.estimate)?;
+    repo.set_description(id, record.description.as_deref())?;
+    let milestone = record
+        .milestone
+        .as_deref()
+        .map(|name| MilestoneRepo::new(repo.conn()).ensure(name))
+        .transpose()?;
+    repo.set_milestone(id, milestone)?;
+    repo.set_assignee(id, record.assignee.as_deref())?;
+    repo.replace_tags(id, &record.tags)?;
+    repo.replace_scopes(id, &record.scopes)?;
+    repo.replace_budgets(id, &record.budgets)
+}
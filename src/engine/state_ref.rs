@@ -0,0 +1,133 @@
+//! State Ref: Stores roadmap snapshots as commits on a dedicated git ref.
+//!
+//! The ref holds a one-file tree (`state.json`), so state moves with plain
+//! `git push`/`git fetch` and needs no extra infrastructure.
+
+use super::db::Db;
+use anyhow::{bail, Context, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const STATE_FILE: &str = "state.json";
+
+/// Local ref holding this clone's published state of the selected roadmap.
+#[must_use]
+pub fn local_ref() -> String {
+    match Db::namespace() {
+        Some(ns) => format!("refs/roadmap/{ns}/state"),
+        None => "refs/roadmap/state".to_string(),
+    }
+}
+
+/// Tracking ref for the state last fetched from `remote`.
+#[must_use]
+pub fn remote_ref(remote: &str) -> String {
+    match Db::namespace() {
+        Some(ns) => format!("refs/roadmap/remotes/{remote}/{ns}/state"),
+        None => format!("refs/roadmap/remotes/{remote}/state"),
+    }
+}
+
+/// Reads the snapshot JSON stored at `refname`, if the ref exists.
+///
+/// # Errors
+/// Returns error if the ref exists but its content cannot be read.
+pub fn read(refname: &str) -> Result<Option<String>> {
+    let Some(_) = resolve(refname) else {
+        return Ok(None);
+    };
+    let out = git(&["show", &format!("{refname}:{STATE_FILE}")], None)?;
+    Ok(Some(out))
+}
+
+/// Commits `json` onto the [`local_ref`], with `extra_parent` merged in if given.
+///
+/// Returns the new commit SHA.
+///
+/// # Errors
+/// Returns error if any git plumbing command fails.
+pub fn write(json: &str, message: &str, extra_parent: Option<&str>) -> Result<String> {
+    let blob = git(&["hash-object", "-w", "--stdin"], Some(json))?;
+    let tree = git(&["mktree"], Some(&format!("100644 blob {blob}\t{STATE_FILE}\n")))?;
+
+    let mut args = vec!["commit-tree".to_string(), tree, "-m".to_string(), message.to_string()];
+    let local = local_ref();
+    let parents: Vec<String> = [resolve(&local), extra_parent.map(str::to_string)]
+        .into_iter()
+        .flatten()
+        .collect();
+    for (i, parent) in parents.iter().enumerate() {
+        if !parents[..i].contains(parent) {
+            args.push("-p".to_string());
+            args.push(parent.clone());
+        }
+    }
+    let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+    let commit = git(&arg_refs, None)?;
+
+    git(&["update-ref", &local, &commit], None)?;
+    Ok(commit)
+}
+
+/// Pushes the local state ref to `remote`.
+///
+/// # Errors
+/// Returns error if the push is rejected.
+pub fn push(remote: &str) -> Result<()> {
+    let local = local_ref();
+    git(&["push", "--quiet", remote, &format!("{local}:{local}")], None)
+        .with_context(|| format!("Failed to push state to '{remote}'. Run `roadmap pull-state {remote}` first"))?;
+    Ok(())
+}
+
+/// Fetches `remote`'s state into its tracking ref. Returns `false` if the remote has none.
+///
+/// # Errors
+/// Returns error if the remote cannot be reached.
+pub fn fetch(remote: &str) -> Result<bool> {
+    let local = local_ref();
+    let listed = git(&["ls-remote", remote, &local], None)?;
+    if listed.is_empty() {
+        return Ok(false);
+    }
+    git(
+        &["fetch", "--quiet", remote, &format!("+{local}:{}", remote_ref(remote))],
+        None,
+    )?;
+    Ok(true)
+}
+
+/// Finds the common ancestor of two state commits, if they share history.
+#[must_use]
+pub fn merge_base(a: &str, b: &str) -> Option<String> {
+    git(&["merge-base", a, b], None).ok()
+}
+
+/// Resolves a ref to a commit SHA.
+#[must_use]
+pub fn resolve(refname: &str) -> Option<String> {
+    git(&["rev-parse", "--verify", "--quiet", refname], None).ok()
+}
+
+fn git(args: &[&str], stdin: Option<&str>) -> Result<String> {
+    let _span = tracing::info_span!("git", op = args.first().copied().unwrap_or_default()).entered();
+    let mut child = Command::new("git")
+        .args(args)
+        .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run git")?;
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input.as_bytes())?;
+    }
+    let out = child.wait_with_output()?;
+    if !out.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
@@ -0,0 +1,188 @@
+//! Stats: Aggregate figures over the whole proof history, for dashboards.
+//!
+//! Verified runs and attestations are counted apart: an attestation says
+//! someone vouched for a task, not that a command passed, so it never
+//! enters a pass rate or a duration. Weeks are ISO weeks in UTC, listed
+//! without gaps from the first proof to the last.
+
+use super::repo::ProofRecord;
+use super::types::{parse_timestamp, Proof};
+use chrono::{Datelike, Days, NaiveDate};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Figures across every task.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ProofStats {
+    /// Verification runs, passing or not.
+    pub verified: usize,
+    pub passes: usize,
+    /// Passing runs as a percentage of verified runs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pass_rate: Option<f64>,
+    pub attested: usize,
+    /// Attestations as a percentage of all proofs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attested_percent: Option<f64>,
+    /// Runs killed for running past the timeout.
+    pub timeouts: usize,
+    /// Per task, by slug.
+    pub tasks: Vec<TaskStats>,
+    /// Oldest week first.
+    pub weeks: Vec<WeekStats>,
+}
+
+/// Figures for one task's verification runs.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TaskStats {
+    pub slug: String,
+    pub runs: usize,
+    /// Passing runs as a percentage of all runs.
+    pub pass_rate: f64,
+    pub mean_ms: u64,
+    /// 95th percentile duration (nearest rank).
+    pub p95_ms: u64,
+    pub attested: usize,
+    /// Highest peak memory of any run, in KiB; absent when no run measured it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_rss_kb: Option<u64>,
+    /// Mean user plus system CPU time over runs that measured it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_cpu_ms: Option<u64>,
+    pub timeouts: usize,
+}
+
+/// Resource usage gathered over one task's runs.
+#[derive(Default)]
+struct Consumption {
+    peak_rss_kb: Option<u64>,
+    cpu_ms: Vec<u64>,
+    timeouts: usize,
+}
+
+/// Tasks proven in one ISO week.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WeekStats {
+    /// ISO week, e.g. `2026-W42`.
+    pub week: String,
+    /// Distinct tasks with a passing run that week.
+    pub proven: usize,
+}
+
+/// Computes the stats from every proof ever recorded.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn analyze(trail: &[ProofRecord]) -> ProofStats {
+    let mut durations: BTreeMap<&str, (Vec<u64>, usize)> = BTreeMap::new();
+    let mut attestations: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut consumption: BTreeMap<&str, Consumption> = BTreeMap::new();
+    let mut proven_by_week: BTreeMap<NaiveDate, BTreeSet<&str>> = BTreeMap::new();
+    for record in trail {
+        let proof = &record.proof;
+        if proof.attested_reason.is_some() {
+            *attestations.entry(&record.slug).or_default() += 1;
+            continue;
+        }
+        let (runs, passes) = durations.entry(&record.slug).or_default();
+        runs.push(proof.duration_ms);
+        if let Some(usage) = &proof.usage {
+            let used = consumption.entry(&record.slug).or_default();
+            used.peak_rss_kb = used.peak_rss_kb.max(usage.peak_rss_kb);
+            used.cpu_ms.extend(usage.cpu_ms());
+            used.timeouts += usize::from(usage.timed_out);
+        }
+        if passed(proof) {
+            *passes += 1;
+            if let Some(at) = parse_timestamp(&proof.timestamp) {
+                proven_by_week.entry(week_start(at.date_naive())).or_default().insert(&record.slug);
+            }
+        }
+    }
+
+    let verified: usize = durations.values().map(|(runs, _)| runs.len()).sum();
+    let passes: usize = durations.values().map(|(_, passes)| passes).sum();
+    let attested: usize = attestations.values().sum();
+    let slugs: BTreeSet<&str> = durations.keys().chain(attestations.keys()).copied().collect();
+    let tasks = slugs
+        .into_iter()
+        .map(|slug| {
+            let (mut runs, passes) = durations.remove(slug).unwrap_or_default();
+            runs.sort_unstable();
+            let used = consumption.remove(slug).unwrap_or_default();
+            TaskStats {
+                slug: slug.to_string(),
+                runs: runs.len(),
+                pass_rate: percent(passes, runs.len()).unwrap_or_default(),
+                mean_ms: mean(&runs),
+                p95_ms: nearest_rank(&runs, 95),
+                attested: attestations.get(slug).copied().unwrap_or_default(),
+                peak_rss_kb: used.peak_rss_kb,
+                mean_cpu_ms: (!used.cpu_ms.is_empty()).then(|| mean(&used.cpu_ms)),
+                timeouts: used.timeouts,
+            }
+        })
+        .collect::<Vec<TaskStats>>();
+
+    ProofStats {
+        timeouts: tasks.iter().map(|t| t.timeouts).sum(),
+        verified,
+        passes,
+        pass_rate: percent(passes, verified),
+        attested,
+        attested_percent: percent(attested, verified + attested),
+        tasks,
+        weeks: weeks(&proven_by_week),
+    }
+}
+
+fn passed(proof: &Proof) -> bool {
+    proof.exit_code == 0 && proof.failure.is_none()
+}
+
+/// Every week from the first to the last with a passing run, empty ones included.
+fn weeks(proven: &BTreeMap<NaiveDate, BTreeSet<&str>>) -> Vec<WeekStats> {
+    let (Some(first), Some(last)) = (proven.keys().next(), proven.keys().next_back()) else {
+        return Vec::new();
+    };
+    let mut weeks = Vec::new();
+    let mut monday = *first;
+    while monday <= *last {
+        let iso = monday.iso_week();
+        weeks.push(WeekStats {
+            week: format!("{}-W{:02}", iso.year(), iso.week()),
+            proven: proven.get(&monday).map_or(0, BTreeSet::len),
+        });
+        let Some(next) = monday.checked_add_days(Days::new(7)) else {
+            break;
+        };
+        monday = next;
+    }
+    weeks
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    let offset = u64::from(date.weekday().num_days_from_monday());
+    date.checked_sub_days(Days::new(offset)).unwrap_or(date)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn percent(part: usize, whole: usize) -> Option<f64> {
+    (whole > 0).then(|| part as f64 * 100.0 / whole as f64)
+}
+
+fn mean(sorted: &[u64]) -> u64 {
+    match u64::try_from(sorted.len()) {
+        Ok(n) if n > 0 => sorted.iter().sum::<u64>() / n,
+        _ => 0,
+    }
+}
+
+/// The smallest value at or above `pct` percent of `sorted`.
+fn nearest_rank(sorted: &[u64], pct: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
@@ -0,0 +1,222 @@
+//! Plan Sync: Reconciles the database with `roadmap.toml`.
+//!
+//! In plan-file mode the TOML is the source of truth for task definitions
+//! and edges; the database keeps only what the plan cannot: proofs, status,
+//! quarantine, and the active task. Syncing creates and updates tasks to
+//! match the plan and makes edges between planned tasks equal the plan's.
+//! Tasks missing from the plan are removed only when pruning.
+
+use super::plan::{Plan, PlanTask};
+use super::repo::{ProofRepo, TaskRepo};
+use super::snapshot::{self, Edge, Snapshot, TaskRecord};
+use anyhow::Result;
+use rusqlite::Connection;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+
+/// A task whose definition differs from the plan.
+#[derive(Debug)]
+pub struct TaskUpdate {
+    pub record: TaskRecord,
+    /// Names of the fields that change.
+    pub fields: Vec<String>,
+}
+
+/// The changes a sync will make.
+#[derive(Debug, Default)]
+pub struct SyncPlan {
+    pub created: Vec<TaskRecord>,
+    pub updated: Vec<TaskUpdate>,
+    /// Slugs in the database but not in the plan.
+    pub unplanned: Vec<String>,
+    pub edges_added: Vec<Edge>,
+    pub edges_removed: Vec<Edge>,
+    /// Whether `apply` removes the unplanned tasks.
+    pub prune: bool,
+}
+
+/// What a sync changed.
+#[derive(Debug, Default)]
+pub struct SyncSummary {
+    pub removed: usize,
+    /// Proofs deleted along with removed tasks.
+    pub proofs_deleted: usize,
+    /// Edges skipped because they would close a cycle through an unplanned task.
+    pub edges_rejected: Vec<Edge>,
+}
+
+/// Computes the changes that make the database match `plan`.
+///
+/// The plan must already be valid (see `Plan::validate`).
+///
+/// # Errors
+/// Returns error if the database cannot be read.
+pub fn plan(conn: &Connection, plan: &Plan, prune: bool) -> Result<SyncPlan> {
+    let current = Snapshot::capture(conn)?;
+    let existing: HashMap<&str, &TaskRecord> =
+        current.tasks.iter().map(|t| (t.slug.as_str(), t)).collect();
+    let mut sync = SyncPlan {
+        prune,
+        ..SyncPlan::default()
+    };
+
+    for task in &plan.tasks {
+        match existing.get(task.slug.as_str()) {
+            None => sync.created.push(planned_record(task, None)),
+            Some(&current) => {
+                let record = planned_record(task, Some(current));
+                let fields = changed_fields(current, &record)?;
+                if !fields.is_empty() {
+                    sync.updated.push(TaskUpdate { record, fields });
+                }
+            }
+        }
+    }
+
+    let planned: BTreeSet<&str> = plan.tasks.iter().map(|t| t.slug.as_str()).collect();
+    sync.unplanned = current
+        .tasks
+        .iter()
+        .filter(|t| !planned.contains(t.slug.as_str()))
+        .map(|t| t.slug.clone())
+        .collect();
+
+    let wanted: BTreeSet<Edge> = plan
+        .tasks
+        .iter()
+        .flat_map(|t| {
+            t.after.iter().map(|dep| Edge {
+                blocker: dep.clone(),
+                blocked: t.slug.clone(),
+            })
+        })
+        .collect();
+    let have: BTreeSet<Edge> = current.edges.into_iter().collect();
+    sync.edges_added = wanted.difference(&have).cloned().collect();
+    // Edges touching unplanned tasks are not the plan's business.
+    sync.edges_removed = have
+        .difference(&wanted)
+        .filter(|e| planned.contains(e.blocker.as_str()) && planned.contains(e.blocked.as_str()))
+        .cloned()
+        .collect();
+    Ok(sync)
+}
+
+impl SyncPlan {
+    /// True if the database already matches the plan.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty()
+            && self.updated.is_empty()
+            && (self.unplanned.is_empty() || !self.prune)
+            && self.edges_added.is_empty()
+            && self.edges_removed.is_empty()
+    }
+
+    /// Applies the planned changes. Run it inside a transaction.
+    ///
+    /// # Errors
+    /// Returns error if a database operation fails.
+    pub fn apply(&self, conn: &Connection) -> Result<SyncSummary> {
+        let repo = TaskRepo::new(conn);
+        let mut summary = SyncSummary::default();
+
+        for record in &self.created {
+            let id = repo.add(&record.slug, &record.title, record.test_cmd.as_deref())?;
+            snapshot::overwrite_task(&repo, id, record)?;
+        }
+        let ids = snapshot::slug_ids(&repo)?;
+        for update in &self.updated {
+            if let Some(&id) = ids.get(&update.record.slug) {
+                snapshot::overwrite_task(&repo, id, &update.record)?;
+            }
+        }
+        let updated = self.updated.iter().map(|u| &u.record);
+        snapshot::set_parents(&repo, &ids, self.created.iter().chain(updated))?;
+
+        if self.prune {
+            let active = repo.get_active_task_id()?;
+            for slug in &self.unplanned {
+                let Some(&id) = ids.get(slug) else { continue };
+                summary.proofs_deleted += ProofRepo::new(conn).delete_for_task(id, &format!("task [{slug}] pruned by sync"))?;
+                repo.remove(id)?;
+                if active == Some(id) {
+                    repo.clear_active_task()?;
+                }
+                summary.removed += 1;
+            }
+        }
+
+        for edge in &self.edges_removed {
+            if let (Some(&from), Some(&to)) = (ids.get(&edge.blocker), ids.get(&edge.blocked)) {
+                repo.unlink(from, to)?;
+            }
+        }
+        let ids = snapshot::slug_ids(&repo)?;
+        let (_, rejected) = snapshot::link_acyclic(conn, &ids, &self.edges_added)?;
+        summary.edges_rejected = rejected;
+        Ok(summary)
+    }
+}
+
+/// The record a planned task should have; local-only fields come from `current`.
+fn planned_record(task: &PlanTask, current: Option<&TaskRecord>) -> TaskRecord {
+    let mut scopes = task.scopes.clone();
+    scopes.sort();
+    let mut tags = task.tags.clone();
+    tags.sort();
+    tags.dedup();
+    TaskRecord {
+        slug: task.slug.clone(),
+        title: task.title.clone(),
+        status: current.map_or_else(String::new, |c| c.status.clone()),
+        created_at: current.map_or_else(String::new, |c| c.created_at.clone()),
+        test_cmd: task.test.clone(),
+        scopes,
+        shell: task.shell.clone(),
+        test_cmd_unix: task.test_unix.clone(),
+        test_cmd_windows: task.test_windows.clone(),
+        cwd: task.cwd.clone(),
+        coverage_path: task.coverage.clone(),
+        coverage_min: task.min_coverage,
+        bench_threshold: task.bench,
+        budgets: task.budgets.clone(),
+        quarantine_reason: current.and_then(|c| c.quarantine_reason.clone()),
+        priority: task.priority,
+        due: task.due.clone(),
+        estimate: task.estimate,
+        description: task.description.clone(),
+        tags,
+        parent: task.parent.clone(),
+        milestone: task.milestone.clone(),
+        // Assignments change day to day; a plan without one leaves the local one alone.
+        assignee: task.assignee.clone().or_else(|| current.and_then(|c| c.assignee.clone())),
+    }
+}
+
+/// Names of the fields that differ between two records, as the plan file spells them.
+fn changed_fields(current: &TaskRecord, planned: &TaskRecord) -> Result<Vec<String>> {
+    let (Value::Object(current), Value::Object(planned)) =
+        (serde_json::to_value(current)?, serde_json::to_value(planned)?)
+    else {
+        return Ok(Vec::new());
+    };
+    let keys: BTreeSet<&String> = current.keys().chain(planned.keys()).collect();
+    Ok(keys
+        .into_iter()
+        .filter(|k| current.get(*k) != planned.get(*k))
+        .map(|k| plan_field(k).to_string())
+        .collect())
+}
+
+fn plan_field(record_field: &str) -> &str {
+    match record_field {
+        "test_cmd" => "test",
+        "test_cmd_unix" => "test_unix",
+        "test_cmd_windows" => "test_windows",
+        "coverage_path" => "coverage",
+        "coverage_min" => "min_coverage",
+        "bench_threshold" => "bench",
+        other => other,
+    }
+}
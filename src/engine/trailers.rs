@@ -0,0 +1,82 @@
+//! Commit Trailers: Which commits say they worked on a task.
+//!
+//! A commit names a task with a `Roadmap-Task: <slug>` trailer (several
+//! slugs may be comma-separated, or the trailer repeated) or by starting its
+//! subject with `[slug]`. Linking is advisory: it explains a proof's
+//! history, it never changes a status.
+
+use anyhow::{bail, Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+/// The trailer key that links a commit to a task.
+pub const TRAILER: &str = "Roadmap-Task";
+
+/// A commit that names a task.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TaskCommit {
+    pub sha: String,
+    pub author: String,
+    /// Committer date, ISO 8601.
+    pub timestamp: String,
+    pub summary: String,
+}
+
+/// Commits in `range` (e.g. `<proof-sha>..HEAD`) that name any of `slugs`, newest first.
+///
+/// Pass a task's slug together with its aliases so renames keep old links.
+///
+/// # Errors
+/// Returns error if git cannot resolve the range.
+pub fn commits_for(slugs: &[String], range: &str) -> Result<Vec<TaskCommit>> {
+    let _span = tracing::info_span!("git", op = "log").entered();
+    let format = format!("--format=%H%x1f%an%x1f%cI%x1f%s%x1f%(trailers:key={TRAILER},valueonly,separator=%x2C)%x1e");
+    let output = Command::new("git")
+        .args(["log", &format, range, "--"])
+        .output()
+        .context("Failed to run git log")?;
+    if !output.status.success() {
+        bail!(
+            "git log {range} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let raw = String::from_utf8_lossy(&output.stdout);
+    Ok(raw
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').split('\x1f');
+            let sha = fields.next().filter(|s| !s.is_empty())?;
+            let author = fields.next()?;
+            let timestamp = fields.next()?;
+            let summary = fields.next()?;
+            let trailers = fields.next().unwrap_or_default();
+            let named = named_tasks(summary, trailers);
+            slugs.iter().any(|s| named.contains(&s.as_str())).then(|| TaskCommit {
+                sha: sha.to_string(),
+                author: author.to_string(),
+                timestamp: timestamp.to_string(),
+                summary: summary.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// The slugs a commit names through its trailers or a `[slug]` subject prefix.
+fn named_tasks<'a>(summary: &'a str, trailers: &'a str) -> Vec<&'a str> {
+    let mut named: Vec<&str> = trailers
+        .split([',', '\n'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+    if let Some(prefixed) = summary
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+        .map(|(slug, _)| slug.trim())
+    {
+        named.push(prefixed);
+    }
+    named
+}
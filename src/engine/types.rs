@@ -1,6 +1,12 @@
 //! Core types for the Roadmap system.
 
+use super::bench::Metrics;
+use super::budget::BudgetResult;
 use super::context::RepoContext;
+use super::fingerprint::Environment;
+use super::hooks::HookRun;
+use super::usage::Usage;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -44,6 +50,8 @@ pub enum DerivedStatus {
     Stale,
     Broken,
     Attested,
+    /// Known-flaky: failures are tolerated for dependencies until rehabilitated.
+    Quarantined,
 }
 
 impl DerivedStatus {
@@ -56,6 +64,7 @@ impl DerivedStatus {
             Self::Broken => "red",
             Self::Unproven => "dimmed",
             Self::Attested => "blue",
+            Self::Quarantined => "magenta",
         }
     }
 
@@ -68,7 +77,7 @@ impl DerivedStatus {
     /// Returns true if the task fulfills its role as a dependency.
     #[must_use]
     pub fn satisfies_dependency(&self) -> bool {
-        matches!(self, Self::Proven | Self::Attested)
+        matches!(self, Self::Proven | Self::Attested | Self::Quarantined)
     }
 }
 
@@ -78,6 +87,22 @@ impl fmt::Display for DerivedStatus {
     }
 }
 
+impl std::str::FromStr for DerivedStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "unproven" => Ok(Self::Unproven),
+            "proven" => Ok(Self::Proven),
+            "stale" => Ok(Self::Stale),
+            "broken" => Ok(Self::Broken),
+            "attested" => Ok(Self::Attested),
+            "quarantined" => Ok(Self::Quarantined),
+            other => Err(format!("Unknown status '{other}'")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct Task {
     pub id: i64,
@@ -88,12 +113,138 @@ pub struct Task {
     pub created_at: String,
     pub proof: Option<Proof>,
     pub scopes: Vec<String>,
+    /// Shell override for this task's verification command.
+    pub shell: Option<String>,
+    /// Platform-specific command variants, preferred over `test_cmd`.
+    pub test_cmd_unix: Option<String>,
+    pub test_cmd_windows: Option<String>,
+    /// Coverage report (lcov or Cobertura) read after each verification.
+    pub coverage_path: Option<String>,
+    /// Minimum line coverage percentage required to count as Proven.
+    pub coverage_min: Option<f64>,
+    /// Benchmark mode: maximum allowed slowdown (percent) versus the last Proven run.
+    pub bench_threshold: Option<f64>,
+    /// Resource budget specs (e.g. `time<2s`) checked after the command passes.
+    pub budgets: Vec<String>,
+    /// Why the task is quarantined as flaky; `None` when it is not.
+    pub quarantine_reason: Option<String>,
+    /// Whether any run of the verification command has ever passed.
+    #[serde(skip)]
+    pub has_passed: bool,
+    /// Scheduling priority; higher comes first in the frontier. Defaults to 0.
+    pub priority: i64,
+    /// Due date as `YYYY-MM-DD`; `None` when there is no deadline.
+    pub due: Option<String>,
+    /// Expected effort in hours.
+    pub estimate: Option<f64>,
+    /// Long-form context beyond the title: what to do, why, and where to start.
+    pub description: Option<String>,
+    /// Labels for slicing the roadmap by area (`infra`, `ui`).
+    pub tags: Vec<String>,
+    /// Slug of the task this one is a subtask of.
+    pub parent: Option<String>,
+    /// Name of the milestone the task is assigned to.
+    pub milestone: Option<String>,
+    /// Who is working on the task.
+    pub assignee: Option<String>,
+    /// Directory the verification command runs in, relative to the roadmap's root.
+    pub cwd: Option<String>,
+    /// Direct subtasks, loaded with the task.
+    #[serde(skip)]
+    pub subtasks: Vec<Task>,
+}
+
+/// A named group of tasks that ship together, such as a release.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Milestone {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
 }
 
 impl Task {
+    /// Returns true if the task carries every one of `tags`.
+    #[must_use]
+    pub fn has_tags(&self, tags: &[String]) -> bool {
+        tags.iter().all(|tag| self.tags.contains(tag))
+    }
+
+    /// Returns true if the task is assigned to `name` (case-insensitive).
+    #[must_use]
+    pub fn is_assigned_to(&self, name: &str) -> bool {
+        self.assignee.as_deref().is_some_and(|a| a.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the verification command for the current platform.
+    #[must_use]
+    pub fn platform_test_cmd(&self) -> Option<&str> {
+        let variant = if cfg!(target_os = "windows") {
+            &self.test_cmd_windows
+        } else {
+            &self.test_cmd_unix
+        };
+        variant.as_deref().or(self.test_cmd.as_deref())
+    }
+
+    /// Returns true if `coverage` satisfies this task's threshold (if any).
+    #[must_use]
+    pub fn coverage_ok(&self, coverage: Option<f64>) -> bool {
+        match self.coverage_min {
+            Some(min) => coverage.is_some_and(|c| c >= min),
+            None => true,
+        }
+    }
+
     /// Computes the derived truth of the task based on proof history and repo context.
+    ///
+    /// A parent is only Proven once every subtask is done (proven, attested,
+    /// or quarantined). Until then it takes the worst subtask status unless
+    /// its own proof is already Stale or Broken. A parent with no
+    /// verification command of its own is proven by its subtasks alone.
     #[must_use]
     pub fn derive_status(&self, context: &RepoContext) -> DerivedStatus {
+        let own = self.own_status(context);
+        if self.subtasks.is_empty() || own == DerivedStatus::Quarantined {
+            return own;
+        }
+
+        let children: Vec<DerivedStatus> = self.subtasks.iter().map(|t| t.derive_status(context)).collect();
+        if children.iter().all(DerivedStatus::satisfies_dependency) {
+            if self.proof.is_none() && self.platform_test_cmd().is_none() {
+                return DerivedStatus::Proven;
+            }
+            return own;
+        }
+        if matches!(own, DerivedStatus::Stale | DerivedStatus::Broken) {
+            return own;
+        }
+        if children.contains(&DerivedStatus::Broken) {
+            DerivedStatus::Broken
+        } else if children.contains(&DerivedStatus::Stale) {
+            DerivedStatus::Stale
+        } else {
+            DerivedStatus::Unproven
+        }
+    }
+
+    /// Done and total counts over every subtask below this one, at any depth.
+    #[must_use]
+    pub fn subtask_progress(&self, context: &RepoContext) -> (usize, usize) {
+        self.subtasks.iter().fold((0, 0), |(done, total), task| {
+            let (below_done, below_total) = task.subtask_progress(context);
+            let finished = usize::from(task.derive_status(context).satisfies_dependency());
+            (done + finished + below_done, total + 1 + below_total)
+        })
+    }
+
+    /// The status from the task's own proof, ignoring subtasks.
+    #[must_use]
+    pub fn own_status(&self, context: &RepoContext) -> DerivedStatus {
+        // Quarantine excuses a flaky task, not one that has never worked.
+        if self.quarantine_reason.is_some() && self.has_passed {
+            return DerivedStatus::Quarantined;
+        }
+
         let Some(proof) = &self.proof else {
             return DerivedStatus::Unproven;
         };
@@ -102,7 +253,7 @@ impl Task {
             return DerivedStatus::Attested;
         }
 
-        if proof.exit_code != 0 {
+        if proof.exit_code != 0 || proof.failure.is_some() || !self.coverage_ok(proof.coverage) {
             return DerivedStatus::Broken;
         }
 
@@ -128,9 +279,20 @@ pub struct ProofOutcome {
     pub duration_ms: u64,
     pub stdout: String,
     pub stderr: String,
+    pub hooks: Vec<HookRun>,
+    pub coverage: Option<f64>,
+    pub metrics: Metrics,
+    pub budgets: Vec<BudgetResult>,
+    pub failure: Option<String>,
+    pub attempts: Option<u32>,
+    pub environment: Option<Environment>,
+    pub artifacts: Vec<String>,
+    /// Bytes of output cut to fit the configured limits.
+    pub truncated: Option<u64>,
+    pub usage: Option<Usage>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Proof {
     pub cmd: String,
     pub exit_code: i32,
@@ -142,6 +304,42 @@ pub struct Proof {
     pub stdout: String,
     #[serde(default)]
     pub stderr: String,
+    /// Detached SSH or GPG signature over the canonical proof payload.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Pre/post hooks that ran around the verification command.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hooks: Vec<HookRun>,
+    /// Line coverage percentage measured after the verification.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<f64>,
+    /// Benchmark measurements parsed from the command output.
+    #[serde(default, skip_serializing_if = "Metrics::is_empty")]
+    pub metrics: Metrics,
+    /// Resource budgets evaluated for this proof.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub budgets: Vec<BudgetResult>,
+    /// Why a successful command was still rejected (e.g. a benchmark regression).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+    /// Where the proof came from when it was not produced by a local check.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    /// Runs it took when failures were retried; absent when the first run decided it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attempts: Option<u32>,
+    /// OS, toolchain versions, and build-relevant variables where the command ran.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<Environment>,
+    /// Files the run left in its artifact directory, relative to the repository root.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<String>,
+    /// Bytes of output left out of `stdout` and `stderr`; absent when all of it was kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<u64>,
+    /// Peak memory and CPU time of the command, and whether it timed out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
 }
 
 impl Proof {
@@ -157,6 +355,18 @@ impl Proof {
             attested_reason: None,
             stdout: outcome.stdout,
             stderr: outcome.stderr,
+            signature: None,
+            hooks: outcome.hooks,
+            coverage: outcome.coverage,
+            metrics: outcome.metrics,
+            budgets: outcome.budgets,
+            failure: outcome.failure,
+            origin: None,
+            attempts: outcome.attempts,
+            environment: outcome.environment,
+            artifacts: outcome.artifacts,
+            truncated: outcome.truncated,
+            usage: outcome.usage,
         }
     }
 
@@ -172,6 +382,18 @@ impl Proof {
             attested_reason: Some(reason.to_string()),
             stdout: String::new(),
             stderr: String::new(),
+            signature: None,
+            hooks: Vec::new(),
+            coverage: None,
+            metrics: Metrics::new(),
+            budgets: Vec::new(),
+            failure: None,
+            origin: None,
+            attempts: None,
+            environment: None,
+            artifacts: Vec::new(),
+            truncated: None,
+            usage: None,
         }
     }
 }
@@ -182,4 +404,15 @@ fn sha_matches(stored: &str, current: &str) -> bool {
         return false; // Unknowns can't be trusted in strict mode
     }
     stored == current
-}
\ No newline at end of file
+}
+/// Parses a stored timestamp: RFC 3339 from proofs, or the database's
+/// `YYYY-MM-DD HH:MM:SS` (UTC) from `CURRENT_TIMESTAMP` defaults.
+#[must_use]
+pub fn parse_timestamp(raw: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    if let Ok(t) = chrono::DateTime::parse_from_rfc3339(raw) {
+        return Some(t.with_timezone(&chrono::Utc));
+    }
+    chrono::NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|t| t.and_utc())
+}
@@ -0,0 +1,194 @@
+//! Usage: What a verification command consumed while it ran.
+//!
+//! Peak memory and CPU time come from the kernel's accounting when the
+//! command is reaped, so they cover the command and every descendant it
+//! waited for, not just the shell that started it. A run that hit the
+//! timeout is recorded as such instead of failing the check outright, so a
+//! runaway verification leaves evidence. Platforms without `wait4` record
+//! only whether the command timed out.
+
+use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::process::Child;
+#[cfg(unix)]
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Resources one run of a command used.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Usage {
+    /// Peak resident memory of the largest process, in KiB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_rss_kb: Option<u64>,
+    /// CPU time spent in user mode, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_ms: Option<u64>,
+    /// CPU time spent in the kernel on the command's behalf, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_ms: Option<u64>,
+    /// The command ran past its timeout and was killed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+}
+
+impl Usage {
+    /// User and system CPU time together, when measured.
+    #[must_use]
+    pub fn cpu_ms(&self) -> Option<u64> {
+        match (self.user_ms, self.system_ms) {
+            (None, None) => None,
+            (user, system) => Some(user.unwrap_or_default() + system.unwrap_or_default()),
+        }
+    }
+
+    /// A one-line summary like `412 MB peak, 3.2s CPU`.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(kb) = self.peak_rss_kb {
+            parts.push(format!("{} MB peak", kb.div_ceil(1024)));
+        }
+        if let Some(ms) = self.cpu_ms() {
+            parts.push(format!("{}.{}s CPU", ms / 1000, ms % 1000 / 100));
+        }
+        if self.timed_out {
+            parts.push("timed out".to_string());
+        }
+        parts.join(", ")
+    }
+}
+
+/// Watches a running command for its exit without reaping it.
+///
+/// A thread blocks in `waitid` with `WNOWAIT`, which sees the exit but leaves
+/// the process for a single blocking `wait4` to reap once the caller has
+/// decided between waiting and killing. `Child::wait` is never called, so
+/// the kernel's accounting is read exactly once.
+#[cfg(unix)]
+pub struct Watch {
+    pid: libc::pid_t,
+    /// Disconnects once the process has exited.
+    exited: mpsc::Receiver<()>,
+}
+
+#[cfg(unix)]
+impl Watch {
+    /// Starts watching `child`, which must not have been waited on.
+    ///
+    /// # Errors
+    /// Returns error if the process ID is out of range or the thread cannot start.
+    pub fn start(child: &Child) -> Result<Self> {
+        let pid = libc::pid_t::try_from(child.id()).context("Process ID out of range")?;
+        let id = libc::id_t::try_from(pid).context("Process ID out of range")?;
+        let (sender, exited) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("roadmap-wait".to_string())
+            .spawn(move || {
+                let _exited = sender;
+                loop {
+                    // SAFETY: `siginfo_t` is plain data, for which all zeroes is a valid value.
+                    let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+                    // SAFETY: `id` is our own child, and `info` is valid for the call.
+                    let waited = unsafe { libc::waitid(libc::P_PID, id, &raw mut info, libc::WEXITED | libc::WNOWAIT) };
+                    if waited == 0 || std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+                        break;
+                    }
+                }
+            })
+            .context("Failed to start waiting for the command")?;
+        Ok(Self { pid, exited })
+    }
+
+    /// Waits up to `limit` for the command to exit, reaping it if it does.
+    ///
+    /// Returns its exit code and what it used once it has exited, `None` while it runs.
+    ///
+    /// # Errors
+    /// Returns error if the process cannot be reaped.
+    pub fn wait(&self, _child: &mut Child, limit: Duration) -> Result<Option<(Option<i32>, Usage)>> {
+        match self.exited.recv_timeout(limit) {
+            Err(mpsc::RecvTimeoutError::Timeout) => Ok(None),
+            Ok(()) | Err(mpsc::RecvTimeoutError::Disconnected) => reap(self.pid).map(Some),
+        }
+    }
+
+    /// Kills the command and reaps it, keeping what it used up to then.
+    pub fn kill(self, child: &mut Child) -> Usage {
+        let _ = child.kill();
+        // Reap only after the watcher has seen the exit, so it never waits on a recycled ID.
+        let _ = self.exited.recv();
+        reap(self.pid).map(|(_, usage)| usage).unwrap_or_default()
+    }
+}
+
+/// `wait4` on an exited child: unlike `Child::wait`, it also returns the resource usage.
+#[cfg(unix)]
+fn reap(pid: libc::pid_t) -> Result<(Option<i32>, Usage)> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let mut status = 0;
+    // SAFETY: `rusage` is plain data, for which all zeroes is a valid value.
+    let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        // SAFETY: `pid` is our own child, not yet reaped, and both pointers are valid for the call.
+        if unsafe { libc::wait4(pid, &raw mut status, 0, &raw mut rusage) } != -1 {
+            break;
+        }
+        let error = std::io::Error::last_os_error();
+        if error.kind() != std::io::ErrorKind::Interrupted {
+            return Err(error).context("Failed to wait");
+        }
+    }
+    let code = std::process::ExitStatus::from_raw(status).code();
+    Ok((code, from_rusage(&rusage)))
+}
+
+#[cfg(unix)]
+fn from_rusage(rusage: &libc::rusage) -> Usage {
+    let millis = |time: libc::timeval| {
+        u64::try_from(time.tv_sec).unwrap_or_default() * 1000 + u64::try_from(time.tv_usec).unwrap_or_default() / 1000
+    };
+    let maxrss = u64::try_from(rusage.ru_maxrss).unwrap_or_default();
+    // macOS reports bytes where Linux and the BSDs report KiB.
+    let peak_rss_kb = if cfg!(target_os = "macos") { maxrss / 1024 } else { maxrss };
+    Usage {
+        peak_rss_kb: Some(peak_rss_kb),
+        user_ms: Some(millis(rusage.ru_utime)),
+        system_ms: Some(millis(rusage.ru_stime)),
+        timed_out: false,
+    }
+}
+
+/// Watches a running command for its exit; usage is not measured on this platform.
+#[cfg(not(unix))]
+pub struct Watch;
+
+#[cfg(not(unix))]
+impl Watch {
+    /// Starts watching `child`.
+    ///
+    /// # Errors
+    /// Never fails on this platform.
+    pub fn start(_child: &Child) -> Result<Self> {
+        Ok(Self)
+    }
+
+    /// Waits up to `limit` for the command to exit.
+    ///
+    /// # Errors
+    /// Returns error if the process cannot be waited on.
+    pub fn wait(&self, child: &mut Child, limit: Duration) -> Result<Option<(Option<i32>, Usage)>> {
+        use wait_timeout::ChildExt;
+
+        let status = child.wait_timeout(limit).context("Failed to wait")?;
+        Ok(status.map(|s| (s.code(), Usage::default())))
+    }
+
+    /// Kills the command and reaps it.
+    pub fn kill(self, child: &mut Child) -> Usage {
+        let _ = child.kill();
+        let _ = child.wait();
+        Usage::default()
+    }
+}
@@ -0,0 +1,70 @@
+//! Errors callers can tell apart.
+//!
+//! Library functions return `anyhow::Result`; where a failure is one a
+//! caller may want to handle (the roadmap is missing, a task reference
+//! matched nothing, a dependency would close a cycle), the error inside is
+//! an [`Error`], reachable with `err.downcast_ref::<roadmap::Error>()` even
+//! under added context. The CLI turns each into its own exit code.
+
+use thiserror::Error;
+
+/// A failure with a meaning of its own.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// No database for the selected roadmap.
+    #[error("{}", not_initialized(.roadmap.as_deref()))]
+    NotInitialized {
+        /// The named roadmap, if one was selected.
+        roadmap: Option<String>,
+    },
+
+    /// A task reference matched no task.
+    #[error("No task matches '{0}'")]
+    TaskNotFound(String),
+
+    /// The command works on the active task, and none is set.
+    #[error("No active task. Run `roadmap do <task>` first.")]
+    NoActiveTask,
+
+    /// The dependency would make a task (indirectly) block itself.
+    #[error("Adding this dependency would create a cycle!")]
+    Cycle { blocker: String, blocked: String },
+
+    /// The task waits on dependencies that are not done.
+    #[error("Task [{task}] is blocked by: {}", .blockers.join(", "))]
+    Blocked { task: String, blockers: Vec<String> },
+
+    /// Another roadmap process kept the write lock too long.
+    #[error("Another roadmap process holds the lock ({holder}). It was still running after {waited_secs}s; try again once it finishes.")]
+    Locked { holder: String, waited_secs: u64 },
+
+    /// The database stayed locked by another writer through every retry.
+    #[error("The roadmap database stayed busy: another roadmap process is writing to it")]
+    Busy,
+
+    /// The database was written by a newer roadmap.
+    #[error("This roadmap's database is at schema version {found}, but this roadmap only knows up to {supported}. Upgrade roadmap.")]
+    SchemaTooNew { found: u32, supported: u32 },
+}
+
+impl Error {
+    /// The process exit code for the error. 1 is any other failure and 2 a usage error.
+    #[must_use]
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::NotInitialized { .. } => 3,
+            Self::TaskNotFound(_) | Self::NoActiveTask => 4,
+            Self::Cycle { .. } => 5,
+            Self::Blocked { .. } => 6,
+            Self::Locked { .. } | Self::Busy => 7,
+            Self::SchemaTooNew { .. } => 8,
+        }
+    }
+}
+
+fn not_initialized(roadmap: Option<&str>) -> String {
+    match roadmap {
+        Some(ns) => format!("Roadmap '{ns}' not initialized. Run `roadmap --roadmap {ns} init` first."),
+        None => "Roadmap not initialized. Run `roadmap init` first.".to_string(),
+    }
+}
@@ -1,78 +1,369 @@
 //! Handler for the `add` command.
 
-use anyhow::{bail, Result};
-use colored::Colorize;
+use anyhow::{bail, Context, Result};
+use crate::editor;
+use super::hooks;
+use crate::theme::Paint;
+use roadmap::engine::budget::Budget;
+use roadmap::engine::config::Config;
 use roadmap::engine::db::Db;
+use roadmap::engine::due;
+use roadmap::engine::estimate;
 use roadmap::engine::graph::TaskGraph;
+use roadmap::engine::lifecycle::{Event, Payload};
 use roadmap::engine::repo::TaskRepo;
-use roadmap::engine::resolver::{slugify, TaskResolver};
+use roadmap::engine::resolver::{normalize_tags, slugify, TaskResolver};
+use roadmap::engine::runner::{self, RunnerConfig, VerifyRunner};
+use roadmap::engine::shell::{self, Shell};
+use roadmap::engine::types::Task;
+use roadmap::Error;
+use rusqlite::Connection;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Everything needed to define a new task.
+#[derive(Clone)]
+pub struct AddOptions<'a> {
+    pub title: &'a str,
+    pub blocks: Option<&'a str>,
+    pub after: Option<&'a str>,
+    pub test_cmd: Option<&'a str>,
+    pub scopes: Option<Vec<String>>,
+    pub shell: Option<&'a str>,
+    pub test_unix: Option<&'a str>,
+    pub test_windows: Option<&'a str>,
+    /// Directory the verification command runs in.
+    pub cwd: Option<&'a str>,
+    pub coverage: Option<&'a str>,
+    pub min_coverage: Option<f64>,
+    pub bench_threshold: Option<f64>,
+    pub budgets: Vec<String>,
+    pub priority: i64,
+    /// Due date as typed (`2026-11-01`, `+3d`, ...).
+    pub due: Option<&'a str>,
+    /// Effort as typed (`4h`, `2d`, ...).
+    pub estimate: Option<&'a str>,
+    pub tags: Vec<String>,
+    pub description: Option<String>,
+    /// Task this one is a subtask of.
+    pub parent: Option<&'a str>,
+    /// Who will work on it; `me` is the current user.
+    pub assignee: Option<&'a str>,
+    /// Compose the description in `$EDITOR` (starting from `description`) before adding.
+    pub edit: bool,
+    /// Skip the pre-flight probe of the verification command.
+    pub no_probe: bool,
+}
+
+/// A created task and the edges linked along with it.
+pub(crate) struct Added {
+    pub task: Task,
+    /// (blocker, blocked) slug pairs.
+    pub links: Vec<(String, String)>,
+}
 
 /// Handles adding a new task and its dependencies.
 ///
 /// # Errors
 /// Returns error if task exists, database is locked, or dependency creates a cycle.
-pub fn handle(
-    title: &str,
-    blocks: Option<&str>,
-    after: Option<&str>,
-    test_cmd: Option<&str>,
-    scopes: Option<Vec<String>>,
-) -> Result<()> {
+pub fn handle(mut opts: AddOptions<'_>) -> Result<()> {
+    if opts.edit {
+        opts.description = editor::compose(&format!("'{}'", opts.title), opts.description.as_deref())?;
+    }
     let mut conn = Db::connect()?;
+    let added = create(&mut conn, &opts)?;
+
+    for (blocker, blocked) in &added.links {
+        println!("   {} [{blocker}] blocks [{blocked}]", " ".info());
+    }
+    if let Some(parent) = &added.task.parent {
+        println!("   {} subtask of [{parent}]", "↳".info());
+    }
+    println!(
+        "{} Added task [{}] {}",
+        "✓".success(),
+        added.task.slug.warning(),
+        added.task.title
+    );
+
+    if !opts.no_probe {
+        warn_on_probe(&added.task)?;
+    }
+    Ok(())
+}
+
+/// Adds one task per line of a plan file, or of stdin for `-`, in one transaction.
+///
+/// Each line is `title | after-ref | test_cmd`; the last two are optional,
+/// blank lines and `#` comments are skipped. An after-ref may name a task added
+/// by an earlier line. Everything in `template` but the title, `--after`, and
+/// `--test` applies to every task. Nothing is added if any line fails.
+///
+/// # Errors
+/// Returns error if the file cannot be read, a line is malformed or fails to add, or the database fails.
+pub fn from_file(path: &Path, template: &AddOptions<'_>) -> Result<()> {
+    let text = if path.as_os_str() == "-" {
+        io::read_to_string(io::stdin()).context("Failed to read tasks from stdin")?
+    } else {
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?
+    };
+    let lines = parse_lines(&text)?;
+    if lines.is_empty() {
+        bail!("No tasks in {} (expected lines of `title | after-ref | test_cmd`)", path.display());
+    }
+
+    let conn = Db::connect()?;
+    let tx = Db::begin_write(&conn)?;
+    let mut added = Vec::new();
+    for line in &lines {
+        let opts = AddOptions {
+            title: line.title,
+            after: line.after,
+            test_cmd: line.test_cmd,
+            ..template.clone()
+        };
+        added.push(insert(&tx, &opts).with_context(|| format!("Line {}: '{}'", line.number, line.title))?);
+    }
+    tx.commit()?;
+
+    for entry in &added {
+        hooks::fire(&Payload::task(Event::PostAdd, &entry.task));
+    }
+    for entry in &added {
+        println!("{} Added task [{}] {}", "✓".success(), entry.task.slug.warning(), entry.task.title);
+        for (blocker, _) in &entry.links {
+            println!("   {} after [{blocker}]", " ".info());
+        }
+        if !template.no_probe {
+            warn_on_probe(&entry.task)?;
+        }
+    }
+    println!("{} tasks added", added.len());
+    Ok(())
+}
+
+/// One task line of a plan file.
+struct PlanLine<'a> {
+    /// 1-based, for error messages.
+    number: usize,
+    title: &'a str,
+    after: Option<&'a str>,
+    test_cmd: Option<&'a str>,
+}
+
+fn parse_lines(text: &str) -> Result<Vec<PlanLine<'_>>> {
+    let mut lines = Vec::new();
+    for (index, raw) in text.lines().enumerate() {
+        let number = index + 1;
+        let raw = raw.trim();
+        if raw.is_empty() || raw.starts_with('#') {
+            continue;
+        }
+        // The test command is last and may itself contain `|`.
+        let mut fields = raw.splitn(3, '|').map(str::trim);
+        let title = fields.next().unwrap_or_default();
+        if title.is_empty() {
+            bail!("Line {number}: missing title (expected `title | after-ref | test_cmd`)");
+        }
+        let after = fields.next().filter(|f| !f.is_empty());
+        let test_cmd = fields.next().filter(|f| !f.is_empty());
+        lines.push(PlanLine { number, title, after, test_cmd });
+    }
+    Ok(lines)
+}
+
+/// Validates the options and creates the task with its links in one transaction.
+///
+/// # Errors
+/// Returns error if an option is invalid, the slug is taken, a dependency cannot be resolved or creates a cycle, or the database fails.
+pub(crate) fn create(conn: &mut Connection, opts: &AddOptions<'_>) -> Result<Added> {
+    let tx = Db::begin_write(conn)?;
+    let added = insert(&tx, opts)?;
+    tx.commit()?;
+    hooks::fire(&Payload::task(Event::PostAdd, &added.task));
+    Ok(added)
+}
+
+/// Validates the options and creates the task with its links, inside the caller's transaction.
+fn insert(tx: &Connection, opts: &AddOptions<'_>) -> Result<Added> {
+    if let Some(shell) = opts.shell {
+        if let (Shell::Exec, Some(cmd)) = (Shell::parse(shell)?, opts.test_cmd) {
+            shell::split(cmd)?;
+        }
+    }
+    for spec in &opts.budgets {
+        Budget::parse(spec)?;
+    }
+    if opts.min_coverage.is_some() && opts.coverage.is_none() {
+        bail!("--min-coverage requires --coverage <report path>");
+    }
+    let due = opts
+        .due
+        .map(|d| due::parse(d, due::today()).map(|date| date.format(due::DATE_FORMAT).to_string()))
+        .transpose()?;
+    let estimate = opts.estimate.map(estimate::parse).transpose()?;
+    let tags = normalize_tags(&opts.tags)?;
+    let assignee = opts.assignee.map(assignee).transpose()?;
+    let cwd = opts.cwd.map(runner::working_dir).transpose()?;
+
+    let title = opts.title;
     let slug = slugify(title);
 
-    let tx = conn.transaction()?;
-    let repo = TaskRepo::new(&tx);
+    let repo = TaskRepo::new(tx);
 
     if repo.find_by_slug(&slug)?.is_some() {
         bail!("Task with slug '{slug}' already exists");
     }
+    if let Some(owner) = repo.find_by_alias(&slug)? {
+        bail!("Slug '{slug}' is an alias of [{}]", owner.slug);
+    }
 
-    let task_id = repo.add(&slug, title, test_cmd)?;
+    let task_id = repo.add(&slug, title, opts.test_cmd)?;
+
+    if opts.shell.is_some() || opts.test_unix.is_some() || opts.test_windows.is_some() {
+        repo.set_exec(task_id, opts.shell, opts.test_unix, opts.test_windows)?;
+    }
+    if cwd.is_some() {
+        repo.set_cwd(task_id, cwd.as_deref())?;
+    }
+
+    if opts.coverage.is_some() {
+        repo.set_coverage(task_id, opts.coverage, opts.min_coverage)?;
+    }
+    if opts.bench_threshold.is_some() {
+        repo.set_bench_threshold(task_id, opts.bench_threshold)?;
+    }
+    for spec in &opts.budgets {
+        repo.add_budget(task_id, spec)?;
+    }
+    if opts.priority != 0 {
+        repo.set_priority(task_id, opts.priority)?;
+    }
+    if due.is_some() {
+        repo.set_due(task_id, due.as_deref())?;
+    }
+    if estimate.is_some() {
+        repo.set_estimate(task_id, estimate)?;
+    }
+    if assignee.is_some() {
+        repo.set_assignee(task_id, assignee.as_deref())?;
+    }
+    if let Some(text) = opts.description.as_deref().filter(|t| !t.trim().is_empty()) {
+        repo.set_description(task_id, Some(text))?;
+    }
+    for tag in &tags {
+        repo.add_tag(task_id, tag)?;
+    }
 
-    if let Some(scope_list) = scopes {
+    if let Some(scope_list) = &opts.scopes {
         for scope in scope_list {
-            repo.add_scope(task_id, &scope)?;
+            repo.add_scope(task_id, scope)?;
         }
     }
 
-    if let Some(after_ref) = after {
-        let resolver = TaskResolver::new(&tx);
+    if let Some(parent_ref) = opts.parent {
+        let parent = TaskResolver::new(tx).resolve(parent_ref)?.task;
+        repo.set_parent(task_id, Some(parent.id))?;
+    }
+
+    let links = link_new(tx, task_id, &slug, opts)?;
+
+    let task = repo
+        .find_by_id(task_id)?
+        .context("Task vanished while it was being added")?;
+    Ok(Added { task, links })
+}
+
+/// Links the new task behind `--after` and ahead of `--blocks`, refusing a link that would close a cycle.
+fn link_new(
+    tx: &Connection,
+    task_id: i64,
+    slug: &str,
+    opts: &AddOptions<'_>,
+) -> Result<Vec<(String, String)>> {
+    let mut links = Vec::new();
+    if let Some(after_ref) = opts.after {
+        let resolver = TaskResolver::new(tx);
         let after_task = resolver.resolve(after_ref)?;
 
-        let graph = TaskGraph::build(&tx)?;
+        let graph = TaskGraph::build(tx)?;
         if graph.would_create_cycle(after_task.task.id, task_id) {
-            bail!("Adding this dependency would create a cycle!");
+            bail!(Error::Cycle {
+                blocker: after_task.task.slug,
+                blocked: slug.to_string(),
+            });
         }
 
-        repo.link(after_task.task.id, task_id)?;
-        println!(
-            "   {} [{}] blocks [{}]",
-            " ".cyan(),
-            after_task.task.slug,
-            slug
-        );
+        TaskRepo::new(tx).link(after_task.task.id, task_id)?;
+        links.push((after_task.task.slug, slug.to_string()));
     }
 
-    if let Some(blocks_ref) = blocks {
-        let resolver = TaskResolver::new(&tx);
+    if let Some(blocks_ref) = opts.blocks {
+        let resolver = TaskResolver::new(tx);
         let blocks_task = resolver.resolve(blocks_ref)?;
 
-        let graph = TaskGraph::build(&tx)?;
+        let graph = TaskGraph::build(tx)?;
         if graph.would_create_cycle(task_id, blocks_task.task.id) {
-            bail!("Adding this dependency would create a cycle!");
+            bail!(Error::Cycle {
+                blocker: slug.to_string(),
+                blocked: blocks_task.task.slug,
+            });
         }
 
-        repo.link(task_id, blocks_task.task.id)?;
-        println!(
-            "   {} [{}] blocks [{}]",
-            " ".cyan(),
-            slug,
-            blocks_task.task.slug
-        );
+        TaskRepo::new(tx).link(task_id, blocks_task.task.id)?;
+        links.push((slug.to_string(), blocks_task.task.slug));
     }
+    Ok(links)
+}
 
-    tx.commit()?;
-    println!("{} Added task [{}] {}", "✓".green(), slug.yellow(), title);
+/// An assignee as typed, with `me` replaced by the current user's name.
+///
+/// # Errors
+/// Returns error if the name is empty, or it is `me` and no identity is configured.
+pub(crate) fn assignee(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("Assignee must not be empty");
+    }
+    if name != "me" {
+        return Ok(name.to_string());
+    }
+    identity()
+}
+
+/// The current user's name, for `--assign me` and `next --mine`.
+///
+/// # Errors
+/// Returns error if neither `user.name` in config nor git's `user.name` is set.
+pub(crate) fn identity() -> Result<String> {
+    Config::load()?
+        .identity()
+        .context("Who are you? Set `roadmap config set user.name <name> --global` or git's user.name")
+}
+
+/// Probes the task's verification command and prints what looks broken.
+///
+/// Problems are warnings only: the command may be installed later, or the
+/// tests may not have been written yet.
+///
+/// # Errors
+/// Returns error if the config cannot be loaded or names an unsupported shell.
+pub(crate) fn warn_on_probe(task: &Task) -> Result<()> {
+    for warning in probe_warnings(task)? {
+        println!("   {} {warning}", "⚠".warning());
+    }
     Ok(())
-}
\ No newline at end of file
+}
+
+/// What the probe finds wrong with the task's verification command.
+///
+/// # Errors
+/// Returns error if the config cannot be loaded or names an unsupported shell.
+pub(crate) fn probe_warnings(task: &Task) -> Result<Vec<String>> {
+    let Some(cmd) = task.platform_test_cmd() else {
+        return Ok(Vec::new());
+    };
+    let runner = VerifyRunner::new(RunnerConfig::for_task(task, &Config::load()?)?);
+    Ok(runner.probe(cmd))
+}
@@ -0,0 +1,132 @@
+//! Handler for the `artifacts` command.
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::artifacts;
+use roadmap::engine::db::Db;
+use roadmap::engine::repo::ProofRepo;
+use roadmap::engine::resolver::TaskResolver;
+use roadmap::engine::types::Proof;
+use schemars::{JsonSchema, Schema};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(Vec<ArtifactRun>)
+}
+
+/// A verification run that kept artifacts.
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ArtifactRun {
+    timestamp: String,
+    git_sha: String,
+    passed: bool,
+    files: Vec<ArtifactFile>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct ArtifactFile {
+    path: String,
+    /// Size in bytes; absent once the file has been deleted.
+    bytes: Option<u64>,
+}
+
+/// Lists the artifacts a task's verification runs kept, newest first, or opens the latest run's.
+///
+/// # Errors
+/// Returns error if the task does not exist, there is nothing to open, or the database fails.
+pub fn handle(task_ref: &str, open: bool, json: bool) -> Result<()> {
+    let conn = Db::connect()?;
+    let task = TaskResolver::new(&conn).resolve(task_ref)?.task;
+    let runs: Vec<ArtifactRun> = ProofRepo::new(&conn)
+        .get_history(task.id)?
+        .iter()
+        .filter(|p| !p.artifacts.is_empty())
+        .map(run)
+        .collect();
+
+    if open {
+        let Some(latest) = runs.first() else {
+            bail!("[{}] has no artifacts to open", task.slug);
+        };
+        let dir = artifacts::run_dir(&latest.files[0].path);
+        if !dir.exists() {
+            bail!("{} no longer exists", dir.display());
+        }
+        return open_path(&dir);
+    }
+    if json {
+        println!("{}", serde_json::to_string_pretty(&runs)?);
+        return Ok(());
+    }
+
+    println!("{} Artifacts of [{}] {}", "📎".info(), task.slug.warning(), task.title);
+    if runs.is_empty() {
+        println!(
+            "   (None yet; a verification command keeps files by writing them to ${})",
+            artifacts::ENV_VAR
+        );
+        return Ok(());
+    }
+    for run in &runs {
+        let verdict = if run.passed { "PASS".success() } else { "FAIL".failure() };
+        let sha = &run.git_sha[..7.min(run.git_sha.len())];
+        println!("   {}  {}  {}", run.timestamp.dimmed(), sha.warning(), verdict);
+        for file in &run.files {
+            let size = file.bytes.map_or_else(|| "(deleted)".to_string(), human_size);
+            println!("      {}  {}", file.path, size.dimmed());
+        }
+    }
+    Ok(())
+}
+
+fn run(proof: &Proof) -> ArtifactRun {
+    ArtifactRun {
+        timestamp: proof.timestamp.clone(),
+        git_sha: proof.git_sha.clone(),
+        passed: proof.exit_code == 0 && proof.failure.is_none(),
+        files: proof
+            .artifacts
+            .iter()
+            .map(|path| ArtifactFile {
+                path: path.clone(),
+                bytes: fs::metadata(path).ok().map(|m| m.len()),
+            })
+            .collect(),
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn human_size(bytes: u64) -> String {
+    match bytes {
+        b if b < 1024 => format!("{b} B"),
+        b if b < 1024 * 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+/// Hands a path to the desktop's default application.
+fn open_path(path: &Path) -> Result<()> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut c = Command::new("cmd");
+        c.args(["/C", "start", ""]);
+        c
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+    let status = command
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    if !status.success() {
+        bail!("Failed to open {}", path.display());
+    }
+    println!("{} Opened {}", "✓".success(), path.display());
+    Ok(())
+}
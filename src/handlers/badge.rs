@@ -0,0 +1,48 @@
+//! Handler for the `badge` command.
+
+use anyhow::{Context, Result};
+use crate::theme::Paint;
+use roadmap::engine::badge::{Badge, Endpoint};
+use roadmap::engine::db::Db;
+use roadmap::engine::graph::TaskGraph;
+use schemars::Schema;
+use std::path::Path;
+
+/// Writes a "proven N/M" badge as SVG, or with `json` as a shields.io endpoint.
+///
+/// Without `out`, the badge is printed to stdout.
+///
+/// # Errors
+/// Returns error if the database fails or the file cannot be written.
+pub fn handle(out: Option<&Path>, json: bool) -> Result<()> {
+    let conn = Db::connect()?;
+    let graph = TaskGraph::build(&conn)?;
+    let counts = graph.status_counts();
+    let badge = Badge::progress(counts.proven, counts.total());
+    let content = if json {
+        serde_json::to_string_pretty(&badge.endpoint())? + "\n"
+    } else {
+        badge.to_svg()
+    };
+
+    match out {
+        Some(path) if path != Path::new("-") => {
+            std::fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+            println!(
+                "{} Wrote {} badge ({} {}) to {}",
+                "✓".success(),
+                if json { "endpoint" } else { "SVG" },
+                badge.label,
+                badge.message,
+                path.display()
+            );
+        }
+        _ => print!("{content}"),
+    }
+    Ok(())
+}
+
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(Endpoint)
+}
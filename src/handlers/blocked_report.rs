@@ -0,0 +1,89 @@
+//! Handler for the `blocked-report` command.
+
+use anyhow::Result;
+use colored::Colorize;
+use crate::table::{Align, Cell, Table};
+use crate::theme::Paint;
+use roadmap::engine::blocked::{self, BlockedReport, RootFlag};
+use roadmap::engine::db::Db;
+use roadmap::engine::graph::TaskGraph;
+use schemars::Schema;
+
+/// Lists work blocked for at least `days` and the root blockers holding it.
+///
+/// # Errors
+/// Returns error if the database or git context cannot be loaded.
+pub fn handle(days: i64, json: bool) -> Result<()> {
+    let conn = Db::connect()?;
+    let graph = TaskGraph::build(&conn)?;
+    let report = blocked::analyze(&graph, days, chrono::Utc::now());
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_human(&report);
+    }
+    Ok(())
+}
+
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(BlockedReport)
+}
+
+fn print_human(report: &BlockedReport) {
+    if report.tasks.is_empty() {
+        println!(
+            "{} Nothing has been blocked for {} day(s) or more.",
+            "✓".success(),
+            report.threshold_days
+        );
+        return;
+    }
+
+    println!(
+        "{} Blocked for {}+ days: {} task(s)",
+        "⛓".warning(),
+        report.threshold_days,
+        report.tasks.len()
+    );
+    let mut table = Table::new(&[Align::Left, Align::Left, Align::Right, Align::Left]).flex(1);
+    for task in &report.tasks {
+        let roots: Vec<String> = task.roots.iter().map(|r| format!("[{r}]")).collect();
+        table.row(vec![
+            Cell::new(format!("[{}]", task.slug), |s| s.accent()),
+            Cell::plain(&task.title),
+            Cell::new(format!("{}d", task.blocked_days), |s| s.warning()),
+            Cell::new(format!("← {}", roots.join(" ")), |s| s.dimmed()),
+        ]);
+    }
+    for line in table.render() {
+        println!("{line}");
+    }
+
+    println!();
+    println!("{}", "Root blockers:".bold());
+    let mut table = Table::new(&[Align::Left, Align::Left, Align::Right, Align::Left]).flex(1);
+    for root in &report.roots {
+        let flags: Vec<&str> = root.flags.iter().map(|f| describe(*f)).collect();
+        table.row(vec![
+            Cell::new(format!("[{}]", root.slug), |s| s.accent()),
+            Cell::plain(format!("{} ({})", root.title, root.status)),
+            Cell::plain(format!("holds {}", root.stuck)),
+            Cell::new(
+                if flags.is_empty() { String::new() } else { format!("⚠ {}", flags.join(", ")) },
+                |s| s.failure(),
+            ),
+        ]);
+    }
+    for line in table.render() {
+        println!("{line}");
+    }
+}
+
+fn describe(flag: RootFlag) -> &'static str {
+    match flag {
+        RootFlag::Broken => "broken",
+        RootFlag::NoTest => "no test command",
+    }
+}
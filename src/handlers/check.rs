@@ -1,158 +1,587 @@
 //! Handler for the `check` command.
 
+/// Prints a human progress line: to stdout normally, to stderr under `--json`.
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if crate::handlers::check::events::enabled() {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
+mod batch;
+mod cascade;
+mod dry_run;
+pub(crate) mod events;
+mod infer;
+mod policy;
+
 use anyhow::{bail, Result};
 use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::artifacts;
+use roadmap::engine::bench::Metrics;
+use roadmap::engine::chain;
+use roadmap::engine::config::Config;
+use roadmap::engine::container::Container;
 use roadmap::engine::context::RepoContext;
 use roadmap::engine::db::Db;
+use roadmap::engine::exchange::{PortableProof, ProofFile};
+use roadmap::engine::fingerprint::{self, Environment};
 use roadmap::engine::graph::TaskGraph;
+use roadmap::engine::hooks::{self, HookPhase, HookRun};
+use roadmap::engine::output::{self, Limits};
+use roadmap::engine::lifecycle::Payload;
+use roadmap::engine::remote::{self, ProofStore};
 use roadmap::engine::repo::{ProofRepo, TaskRepo};
-use roadmap::engine::runner::VerifyRunner;
+use roadmap::engine::retention::{self, Policy};
+use roadmap::engine::sandbox::Sandbox;
+use roadmap::engine::runner::{RunnerConfig, VerifyResult, VerifyRunner};
+use roadmap::engine::signing;
 use roadmap::engine::types::{Proof, ProofOutcome, Task, TaskStatus};
+use roadmap::engine::usage::Usage;
+use roadmap::Error;
+use std::cell::RefCell;
+use events::CheckEvent;
+use std::path::Path;
+use std::time::Duration;
+
+/// Records proofs produced by this check, signing them when a key is given.
+struct Recorder<'a> {
+    repo: &'a TaskRepo<'a>,
+    sign_key: Option<&'a str>,
+    /// Every proof stored during this run, as persisted, for `--emit-proof`.
+    recorded: RefCell<Vec<PortableProof>>,
+    /// Shared team store that receives each proof, if configured.
+    remote: Option<Box<dyn ProofStore>>,
+    /// Overrides of the `[runner]` settings for every command run.
+    flags: RunFlags<'a>,
+}
 
-/// Runs verification for the active task.
+/// `check` flags that override the `[runner]` settings.
+#[derive(Clone, Copy)]
+struct RunFlags<'a> {
+    /// `--retries`, overriding `runner.retries`.
+    retries: Option<u32>,
+    /// `--verbose`, turning on `runner.stream`.
+    stream: bool,
+    /// `--in-container`, overriding `runner.container`.
+    container: Option<&'a str>,
+    /// `--sandbox`, turning on `sandbox.enabled`.
+    sandbox: bool,
+}
+
+impl<'a> RunFlags<'a> {
+    fn new(opts: &CheckOptions<'a>) -> Self {
+        Self {
+            retries: opts.retries,
+            stream: opts.verbose,
+            container: opts.in_container,
+            sandbox: opts.sandbox,
+        }
+    }
+}
+
+impl Recorder<'_> {
+    fn record(&self, task: &Task, proof: &Proof) -> Result<()> {
+        let proof_repo = ProofRepo::new(self.repo.conn());
+        let id = proof_repo.save(task.id, proof)?;
+
+        if let Some(key) = self.sign_key {
+            let stored = proof_repo.get_by_id(id)?;
+            let signature = signing::sign(key, &chain::payload(task.id, &stored))?;
+            proof_repo.set_signature(id, &signature)?;
+            say!("   {} proof signed", "🔏".dimmed());
+        }
+
+        let stored = proof_repo.get_by_id(id)?;
+        if let Some(store) = &self.remote {
+            // The local proof is the source of truth; a flaky network must not fail the check.
+            match remote::push(store.as_ref(), &task.slug, &stored) {
+                Ok(true) => say!("   {} proof pushed to remote", "☁".dimmed()),
+                Ok(false) => say!("   {} remote already has a newer proof", "☁".dimmed()),
+                Err(e) => say!("   {} remote push failed: {e:#}", "⚠".warning()),
+            }
+        }
+
+        self.recorded.borrow_mut().push(PortableProof {
+            task: task.slug.clone(),
+            proof: stored,
+        });
+        Ok(())
+    }
+
+    fn emit(&self, path: &Path) -> Result<()> {
+        let proofs = self.recorded.take();
+        let count = proofs.len();
+        ProofFile::new(proofs).write(path)?;
+        say!(
+            "   {} {} proof(s) written to {}",
+            "📦".dimmed(),
+            count,
+            path.display()
+        );
+        Ok(())
+    }
+}
+
+/// Flags controlling a `check` run.
+#[allow(clippy::struct_excessive_bools)] // one field per command-line flag
+pub struct CheckOptions<'a> {
+    pub force: bool,
+    pub reason: Option<&'a str>,
+    pub sign_key: Option<&'a str>,
+    pub cascade: bool,
+    pub emit_proof: Option<&'a Path>,
+    /// Stream NDJSON progress events on stdout.
+    pub json: bool,
+    /// Save inferred scopes without asking.
+    pub adopt_scopes: bool,
+    /// Verify every task with something to run instead of the active one.
+    pub all: bool,
+    /// With `all`, only tasks whose proof is stale or broken.
+    pub stale: bool,
+    /// With `all`, how many verification commands may run at once.
+    pub jobs: usize,
+    /// With `all`, skip tasks still waiting on unmet dependencies.
+    pub unblocked: bool,
+    /// Extra runs for a failing command, overriding `runner.retries`.
+    pub retries: Option<u32>,
+    /// Show the command's output while it runs.
+    pub verbose: bool,
+    /// Image to run the command in, overriding `runner.container`.
+    pub in_container: Option<&'a str>,
+    /// Confine the command, as if `sandbox.enabled` were set.
+    pub sandbox: bool,
+    /// Show what would run instead of running it.
+    pub dry_run: bool,
+}
+
+/// Runs verification for the active task, or for every task with `all`.
 ///
 /// # Errors
-/// Returns error if no task is active or database fails.
-pub fn handle(force: bool, reason: Option<&str>) -> Result<()> {
+/// Returns error if no task is active, signing fails, or database fails.
+pub fn handle(opts: &CheckOptions<'_>) -> Result<()> {
+    if opts.json {
+        events::enable();
+    }
     let context = RepoContext::new()?;
+    if opts.dry_run {
+        return dry_run::show(opts, &context);
+    }
 
     // LAW OF HYGIENE: The Dirty Lie
     if context.is_dirty {
         bail!(
             "Repository is dirty. You must commit your changes before verifying.\n   {}", 
-            "Roadmap enforces strict hygiene: Truth is a property of a Commit, not a Worktree.".yellow()
+            "Roadmap enforces strict hygiene: Truth is a property of a Commit, not a Worktree.".warning()
         );
     }
 
     let conn = Db::connect()?;
     let repo = TaskRepo::new(&conn);
 
+    let config = Config::load()?;
+    let retention = Policy::from_settings(&config.retention)?;
+    let remote_settings = config.remote;
+    let recorder = Recorder {
+        repo: &repo,
+        sign_key: opts.sign_key,
+        recorded: RefCell::new(Vec::new()),
+        remote: remote_settings
+            .url
+            .filter(|_| remote_settings.push_on_check)
+            .map(|url| remote::open(&url)),
+        flags: RunFlags::new(opts),
+    };
+    if opts.all {
+        let selection = batch::Selection {
+            stale: opts.stale,
+            unblocked: opts.unblocked,
+        };
+        batch::run(&recorder, context.head_sha(), selection, opts.jobs.max(1))?;
+        return finish(&recorder, opts, retention.as_ref());
+    }
+
     let task = get_active_task(&repo)?;
     let derived = task.derive_status(&context);
 
-    println!(
+    say!(
         "🔍 Checking: [{}] {} ({})",
-        task.slug.yellow(),
+        task.slug.warning(),
         task.title,
         derived.to_string().dimmed()
     );
 
-    if force {
-        return handle_force(&repo, &task, reason, context.head_sha());
+    if opts.force {
+        let reason = match opts.reason {
+            Some(reason) => reason,
+            None if config.check.lenient_attestation => "Manual attestation",
+            None => bail!("Attestation needs --reason (check.lenient_attestation is off)"),
+        };
+        handle_force(&recorder, &task, reason, context.head_sha())?;
+    } else {
+        let Some(proven) = verify_task(&recorder, &task, context.head_sha())? else {
+            events::emit(&CheckEvent::Finished {
+                task: &task.slug,
+                result: "skipped",
+                exit_code: None,
+                duration_ms: None,
+            })?;
+            say!("{} No verification command defined.", "?".warning());
+            say!("   Use --force --reason \"...\" to mark as ATTESTED");
+            return Ok(());
+        };
+
+        if proven {
+            infer::offer(&repo, &task, opts.adopt_scopes)?;
+        }
+        if proven && opts.cascade {
+            cascade::run(&recorder, &task, context.head_sha())?;
+        }
+        if proven {
+            show_unblocked(&repo, task.id)?;
+        }
     }
 
-    let Some(test_cmd) = &task.test_cmd else {
-        println!("{} No verification command defined.", "?".yellow());
-        println!("   Use --force --reason \"...\" to mark as ATTESTED");
+    finish(&recorder, opts, retention.as_ref())
+}
+
+fn finish(rec: &Recorder<'_>, opts: &CheckOptions<'_>, retention: Option<&Policy>) -> Result<()> {
+    if let Some(path) = opts.emit_proof {
+        rec.emit(path)?;
+    }
+    let Some(policy) = retention else {
         return Ok(());
     };
+    let pruned = retention::prune(rec.repo.conn(), policy, false)?;
+    if pruned.proofs > 0 {
+        say!("   {} pruned {} old proof(s)", "🧹".dimmed(), pruned.proofs);
+    }
+    Ok(())
+}
 
-    run_verification(&repo, &task, test_cmd, context.head_sha())
+/// Verifies a task and records the proof; `None` if it has nothing to run.
+fn verify_task(rec: &Recorder<'_>, task: &Task, head_sha: &str) -> Result<Option<bool>> {
+    if let Some(test_cmd) = task.platform_test_cmd() {
+        return run_verification(rec, task, test_cmd, head_sha).map(Some);
+    }
+    if !task.budgets.is_empty() {
+        return run_budgets_only(rec, task, head_sha).map(Some);
+    }
+    Ok(None)
 }
 
 fn handle_force(
-    repo: &TaskRepo<'_>,
+    rec: &Recorder<'_>,
     task: &Task,
-    reason: Option<&str>,
+    reason: &str,
     git_sha: &str,
 ) -> Result<()> {
-    let reason = reason.unwrap_or("Manual attestation");
     let proof = Proof::attested(reason, git_sha);
-    
-    let proof_repo = ProofRepo::new(repo.conn());
-    proof_repo.save(task.id, &proof)?;
-    
+    rec.record(task, &proof)?;
+
+    let repo = rec.repo;
     repo.update_status(task.id, TaskStatus::Attested)?;
+    fire_post_check(repo, task.id)?;
+    events::emit(&CheckEvent::Finished {
+        task: &task.slug,
+        result: "attested",
+        exit_code: None,
+        duration_ms: None,
+    })?;
 
-    println!(
+    say!(
         "{} Task [{}] marked ATTESTED (not verified)",
-        "!".yellow(),
-        task.slug.yellow()
+        "!".warning(),
+        task.slug.warning()
     );
     show_unblocked(repo, task.id)
 }
 
 fn get_active_task(repo: &TaskRepo<'_>) -> Result<Task> {
     let Some(active_id) = repo.get_active_task_id()? else {
-        bail!("No active task. Run `roadmap do <task>` first.");
+        bail!(Error::NoActiveTask);
     };
     repo.find_by_id(active_id)?
         .ok_or_else(|| anyhow::anyhow!("Active task not found"))
 }
 
 fn run_verification(
-    repo: &TaskRepo<'_>,
+    rec: &Recorder<'_>,
     task: &Task,
     test_cmd: &str,
     head_sha: &str,
-) -> Result<()> {
-    println!("   {} {}", "running:".dimmed(), test_cmd);
-    let runner = VerifyRunner::default_runner();
-    let result = runner.verify(test_cmd)?;
+) -> Result<bool> {
+    let execution = execute(task, test_cmd, head_sha, rec.flags)?;
+    settle(rec, task, test_cmd, head_sha, execution)
+}
+
+/// A verification command that has run, before its outcome is judged and recorded.
+struct Execution {
+    result: VerifyResult,
+    hooks: Vec<HookRun>,
+    environment: Environment,
+    artifacts: Vec<String>,
+    /// How much output the proof keeps.
+    limits: Limits,
+}
 
+/// The runner settings for a task's command, with the `check` flags applied.
+fn runner_config(task: &Task, config: &Config, head_sha: &str, flags: RunFlags<'_>) -> Result<RunnerConfig> {
+    let mut runner_config = RunnerConfig::for_task(task, config)?;
+    if let Some(retries) = flags.retries {
+        runner_config.retries = retries;
+    }
+    runner_config.stream |= flags.stream;
+    if let Some(image) = flags.container {
+        runner_config.container = Some(Container::new(image, config.runner.container_engine.as_deref()));
+    }
+    if flags.sandbox {
+        runner_config.sandbox = Some(Sandbox::from_settings(&config.sandbox));
+    }
+    runner_config.add_task_env(task, head_sha);
+    Ok(runner_config)
+}
+
+/// Runs the pre-hooks, the verification command, and the post-hooks.
+///
+/// Touches no database, so a batch can run it on worker threads.
+fn execute(task: &Task, test_cmd: &str, head_sha: &str, flags: RunFlags<'_>) -> Result<Execution> {
+    let config = Config::load()?;
+    let mut runner_config = runner_config(task, &config, head_sha, flags)?;
+    let container = runner_config.container.clone();
+    let sandboxed = runner_config.sandbox.is_some();
+    let timeout = runner_config.timeout_secs;
+    let artifact_dir = artifacts::prepare(&task.slug)?;
+    runner_config
+        .envs
+        .push((artifacts::ENV_VAR.to_string(), artifact_dir.display().to_string()));
+    let shell = runner_config.shell;
+    // Hooks set up the whole repository and come from its own config, so
+    // they run from its root, on the host, and unconfined, whatever the task's
+    // directory, container, and sandbox.
+    let hook_runner = VerifyRunner::new(RunnerConfig {
+        working_dir: None,
+        container: None,
+        sandbox: None,
+        ..runner_config.clone()
+    });
+    let runner = VerifyRunner::new(runner_config);
+
+    let mut hook_runs = hooks::run_phase(HookPhase::Pre, &config.hooks.pre, &hook_runner)?;
+    print_hooks(&hook_runs);
+    if let Some(failed) = hook_runs.iter().find(|h| !h.passed()) {
+        // Drops the run's directory unless the hooks left something in it.
+        artifacts::collect(&artifact_dir)?;
+        bail!(
+            "Pre-verification hook failed: `{}`\n   {}",
+            failed.cmd,
+            "The verification command was not run and no proof was recorded.".warning()
+        );
+    }
+
+    let mut place = container.as_ref().map_or_else(|| shell.to_string(), |c| format!("{shell} in {}", c.image));
+    if sandboxed {
+        place.push_str(", sandboxed");
+    }
+    say!(
+        "   {} {} {}",
+        "running:".dimmed(),
+        test_cmd,
+        format!("({place})").dimmed()
+    );
+
+    events::emit(&CheckEvent::Started {
+        task: &task.slug,
+        cmd: test_cmd,
+        git_sha: head_sha,
+    })?;
+    let mut heartbeat = |elapsed: Duration| {
+        // A heartbeat that can't be written is not worth aborting the run for.
+        let _ = events::emit(&CheckEvent::Heartbeat {
+            task: &task.slug,
+            elapsed_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+        });
+    };
+
+    // Post-hooks are cleanup: they run even if the verification itself errored.
+    let result = runner.verify_with_heartbeat(test_cmd, events::HEARTBEAT, &mut heartbeat);
+    if result.as_ref().is_ok_and(|r| r.usage.timed_out) {
+        say!("   {} timed out after {timeout}s and was killed", "⏱".failure());
+    }
+    let post_runs = hooks::run_phase(HookPhase::Post, &config.hooks.post, &hook_runner)?;
+    print_hooks(&post_runs);
+    hook_runs.extend(post_runs);
+    if let Some(report) = &task.coverage_path {
+        artifacts::keep(&artifact_dir, Path::new(report))?;
+    }
+    let limits = Limits::from_settings(&config.runner);
+    if let (Ok(result), true) = (&result, config.runner.spill_output) {
+        output::spill(&artifact_dir, limits, &result.stdout, &result.stderr)?;
+    }
+    let kept = artifacts::collect(&artifact_dir)?;
+    if !kept.is_empty() {
+        say!("   {} {} artifact(s) kept", "📎".dimmed(), kept.len());
+    }
+    Ok(Execution {
+        result: result?,
+        hooks: hook_runs,
+        environment: container.map_or_else(|| fingerprint::capture(test_cmd), |c| c.environment()),
+        artifacts: kept,
+        limits,
+    })
+}
+
+/// Judges an execution against the task's coverage, benchmark, and budget
+/// policies and records the proof. Returns whether the task is proven.
+fn settle(
+    rec: &Recorder<'_>,
+    task: &Task,
+    test_cmd: &str,
+    head_sha: &str,
+    execution: Execution,
+) -> Result<bool> {
+    let Execution {
+        result,
+        hooks,
+        environment,
+        artifacts,
+        limits,
+    } = execution;
+    let mut outcome = outcome(&result, hooks, environment);
+    outcome.artifacts = artifacts;
+    outcome.coverage = policy::measure_coverage(task);
     if result.passed() {
-        mark_proven(repo, task, test_cmd, &result, head_sha)
+        policy::guard_benchmarks(rec.repo.conn(), task, &mut outcome)?;
+        policy::enforce_budgets(task, &mut outcome, Some(result.duration))?;
+    }
+
+    let proven = result.passed() && outcome.failure.is_none() && task.coverage_ok(outcome.coverage);
+    // Benchmarks read the whole output; only the stored copy is cut.
+    limits.apply(&mut outcome);
+    let proof = Proof::new(test_cmd, head_sha, outcome);
+    if proven {
+        mark_proven(rec, task, &proof)
     } else {
-        mark_broken(repo.conn(), task, test_cmd, &result, head_sha)
+        mark_broken(rec, task, &proof)
     }
 }
 
-#[allow(clippy::cast_possible_truncation)]
-fn mark_proven(
-    repo: &TaskRepo<'_>,
-    task: &Task,
-    cmd: &str,
-    result: &roadmap::engine::runner::VerifyResult,
-    git_sha: &str,
-) -> Result<()> {
-    let outcome = ProofOutcome {
-        exit_code: result.exit_code.unwrap_or(0),
-        duration_ms: result.duration.as_millis() as u64,
-        stdout: result.stdout.clone(),
-        stderr: result.stderr.clone(),
+/// Verifies a task whose only predicates are resource budgets.
+fn run_budgets_only(rec: &Recorder<'_>, task: &Task, head_sha: &str) -> Result<bool> {
+    let mut outcome = ProofOutcome {
+        exit_code: 0,
+        duration_ms: 0,
+        stdout: String::new(),
+        stderr: String::new(),
+        hooks: Vec::new(),
+        coverage: None,
+        metrics: Metrics::new(),
+        budgets: Vec::new(),
+        failure: None,
+        attempts: None,
+        environment: Some(fingerprint::capture("")),
+        artifacts: Vec::new(),
+        truncated: None,
+        usage: None,
     };
+    events::emit(&CheckEvent::Started {
+        task: &task.slug,
+        cmd: "(budgets)",
+        git_sha: head_sha,
+    })?;
+    policy::enforce_budgets(task, &mut outcome, None)?;
 
-    let proof = Proof::new(cmd, git_sha, outcome);
-    let proof_repo = ProofRepo::new(repo.conn());
-    proof_repo.save(task.id, &proof)?;
-    
-    repo.update_status(task.id, TaskStatus::Done)?;
-
-    println!(
-        "{} PROVEN! Task [{}] verified",
-        "✓".green(),
-        task.slug.green()
-    );
-    show_unblocked(repo, task.id)
+    let proven = outcome.failure.is_none();
+    let proof = Proof::new("(budgets)", head_sha, outcome);
+    if proven {
+        mark_proven(rec, task, &proof)
+    } else {
+        mark_broken(rec, task, &proof)
+    }
 }
 
 #[allow(clippy::cast_possible_truncation)]
-fn mark_broken(
-    conn: &rusqlite::Connection,
-    task: &Task,
-    cmd: &str,
-    result: &roadmap::engine::runner::VerifyResult,
-    git_sha: &str,
-) -> Result<()> {
-    let outcome = ProofOutcome {
+fn outcome(result: &VerifyResult, hooks: Vec<HookRun>, environment: Environment) -> ProofOutcome {
+    ProofOutcome {
         exit_code: result.exit_code.unwrap_or(1),
         duration_ms: result.duration.as_millis() as u64,
         stdout: result.stdout.clone(),
         stderr: result.stderr.clone(),
-    };
+        hooks,
+        coverage: None,
+        metrics: Metrics::new(),
+        budgets: Vec::new(),
+        failure: None,
+        attempts: (result.attempts > 1).then_some(result.attempts),
+        environment: Some(environment),
+        artifacts: Vec::new(),
+        truncated: None,
+        usage: (result.usage != Usage::default()).then(|| result.usage.clone()),
+    }
+}
+
+fn print_hooks(runs: &[HookRun]) {
+    for run in runs {
+        let mark = if run.passed() { "✓".success() } else { "✗".failure() };
+        say!(
+            "   {} {} {}",
+            mark,
+            format!("hook({}):", run.phase).dimmed(),
+            run.cmd
+        );
+    }
+}
+
+fn mark_proven(rec: &Recorder<'_>, task: &Task, proof: &Proof) -> Result<bool> {
+    rec.record(task, proof)?;
+    rec.repo.update_status(task.id, TaskStatus::Done)?;
+    fire_post_check(rec.repo, task.id)?;
+    emit_finished(task, proof, "proven")?;
+
+    say!(
+        "{} PROVEN! Task [{}] verified",
+        "✓".success(),
+        task.slug.success()
+    );
+    Ok(true)
+}
 
-    let proof = Proof::new(cmd, git_sha, outcome);
-    let proof_repo = ProofRepo::new(conn);
-    proof_repo.save(task.id, &proof)?;
+fn mark_broken(rec: &Recorder<'_>, task: &Task, proof: &Proof) -> Result<bool> {
+    rec.record(task, proof)?;
+    fire_post_check(rec.repo, task.id)?;
+    emit_finished(task, proof, "broken")?;
 
-    println!(
+    say!(
         "{} BROKEN! Task [{}] verification failed",
-        "✗".red(),
-        task.slug.red()
+        "✗".failure(),
+        task.slug.failure()
     );
+    Ok(false)
+}
+
+/// Runs the `post-check` script with the task as it now stands, its new proof included.
+fn fire_post_check(repo: &TaskRepo<'_>, task_id: i64) -> Result<()> {
+    if let Some(task) = repo.find_by_id(task_id)? {
+        if let Some(proof) = &task.proof {
+            super::hooks::fire(&Payload::check(&task, proof));
+        }
+    }
     Ok(())
 }
 
+fn emit_finished(task: &Task, proof: &Proof, result: &str) -> Result<()> {
+    events::emit(&CheckEvent::Finished {
+        task: &task.slug,
+        result,
+        exit_code: Some(proof.exit_code),
+        duration_ms: Some(proof.duration_ms),
+    })
+}
+
 fn show_unblocked(repo: &TaskRepo<'_>, done_id: i64) -> Result<()> {
     let graph = TaskGraph::build(repo.conn())?;
     let frontier = graph.get_frontier();
@@ -164,9 +593,9 @@ fn show_unblocked(repo: &TaskRepo<'_>, done_id: i64) -> Result<()> {
         .collect();
 
     if !available.is_empty() {
-        println!("\n✨ Now available:");
+        say!("\n✨ Now available:");
         for t in available {
-            println!("   - [{}] {}", t.slug.yellow(), t.title);
+            say!("   - [{}] {}", t.slug.warning(), t.title);
         }
     }
     
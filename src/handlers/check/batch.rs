@@ -0,0 +1,190 @@
+//! Batch: verifies every task with something to run, e.g. after a big refactor.
+//!
+//! Up to `jobs` verification commands run at once on worker threads. A task
+//! starts only once the batch's tasks it depends on have finished, and each
+//! outcome is judged and recorded on this thread as soon as it arrives.
+
+use super::{execute, run_budgets_only, settle, Execution, Recorder};
+use anyhow::Result;
+use colored::Colorize;
+use crate::table::{Align, Cell, Table};
+use crate::theme::Paint;
+use roadmap::engine::context::RepoContext;
+use roadmap::engine::graph::TaskGraph;
+use roadmap::engine::types::{DerivedStatus, Task};
+use std::collections::HashSet;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Which tasks with something to run the batch takes.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Selection {
+    /// Only tasks whose proof is Stale or Broken.
+    pub stale: bool,
+    /// Only tasks whose dependencies are all satisfied.
+    pub unblocked: bool,
+}
+
+impl Selection {
+    /// What the selection takes, for when it takes nothing.
+    pub fn describe(self) -> &'static str {
+        match (self.stale, self.unblocked) {
+            (true, true) => "unblocked stale or broken tasks",
+            (true, false) => "stale or broken tasks",
+            (false, true) => "unblocked tasks with a test command",
+            (false, false) => "tasks with a test command",
+        }
+    }
+}
+
+/// How one task fared in the batch.
+struct Row {
+    /// Position in dependency order, so the summary doesn't depend on timing.
+    order: usize,
+    slug: String,
+    /// Status before the run, to tell a recovery from a fresh proof.
+    before: DerivedStatus,
+    proven: bool,
+    elapsed: Duration,
+}
+
+/// Verifies tasks in dependency order, recording a proof for each, then prints a summary.
+pub(super) fn run(rec: &Recorder<'_>, head_sha: &str, selection: Selection, jobs: usize) -> Result<()> {
+    let graph = TaskGraph::build(rec.repo.conn())?;
+    let tasks = select(&graph, selection);
+    if tasks.is_empty() {
+        say!("{} No {} to verify", "✓".success(), selection.describe());
+        return Ok(());
+    }
+
+    let mut rows = schedule(rec, &graph, &tasks, head_sha, jobs)?;
+    rows.sort_by_key(|r| r.order);
+    print_summary(&rows);
+    Ok(())
+}
+
+/// The tasks the batch runs, in dependency order.
+pub(super) fn select(graph: &TaskGraph, selection: Selection) -> Vec<&Task> {
+    let context = graph.context();
+    graph
+        .in_dependency_order()
+        .into_iter()
+        .filter(|t| t.platform_test_cmd().is_some() || !t.budgets.is_empty())
+        .filter(|t| {
+            !selection.stale || matches!(t.derive_status(context), DerivedStatus::Stale | DerivedStatus::Broken)
+        })
+        .filter(|t| {
+            !selection.unblocked
+                || graph.get_blockers(t.id).iter().all(|b| b.derive_status(context).satisfies_dependency())
+        })
+        .collect()
+}
+
+/// Runs `tasks` (in dependency order) on up to `jobs` threads; returns rows in completion order.
+fn schedule(rec: &Recorder<'_>, graph: &TaskGraph, tasks: &[&Task], head_sha: &str, jobs: usize) -> Result<Vec<Row>> {
+    let selected: HashSet<i64> = tasks.iter().map(|t| t.id).collect();
+    let mut pending: Vec<(usize, &Task)> = tasks.iter().copied().enumerate().collect();
+    let mut finished = HashSet::new();
+    let mut rows = Vec::new();
+    let context = graph.context();
+    let (sender, receiver) = mpsc::channel::<(usize, &Task, Result<Execution>, Duration)>();
+
+    thread::scope(|scope| -> Result<()> {
+        let (mut running, mut launched) = (0, 0);
+        loop {
+            while running < jobs {
+                let ready = pending.iter().position(|(_, task)| {
+                    graph
+                        .get_blockers(task.id)
+                        .iter()
+                        .all(|b| !selected.contains(&b.id) || finished.contains(&b.id))
+                });
+                let Some(index) = ready else { break };
+                let (order, task) = pending.remove(index);
+                launched += 1;
+                say!(
+                    "\n🔍 [{}/{}] Checking: [{}] {}",
+                    launched,
+                    tasks.len(),
+                    task.slug.warning(),
+                    task.title
+                );
+                let started = Instant::now();
+                let Some(test_cmd) = task.platform_test_cmd() else {
+                    // Budgets alone have no command to wait for.
+                    let proven = run_budgets_only(rec, task, head_sha)?;
+                    rows.push(row(order, task, context, proven, started.elapsed()));
+                    finished.insert(task.id);
+                    continue;
+                };
+                let (sender, flags) = (sender.clone(), rec.flags);
+                scope.spawn(move || {
+                    let execution = execute(task, test_cmd, head_sha, flags);
+                    // The receiver only goes away once this run has already failed.
+                    let _ = sender.send((order, task, execution, started.elapsed()));
+                });
+                running += 1;
+            }
+            if running == 0 {
+                return Ok(());
+            }
+
+            let (order, task, execution, elapsed) = receiver.recv()?;
+            running -= 1;
+            let test_cmd = task.platform_test_cmd().unwrap_or_default();
+            if jobs > 1 {
+                say!("\n{} [{}] finished", "◆".info(), task.slug.warning());
+            }
+            let proven = settle(rec, task, test_cmd, head_sha, execution?)?;
+            rows.push(row(order, task, context, proven, elapsed));
+            finished.insert(task.id);
+        }
+    })?;
+    Ok(rows)
+}
+
+/// `task` still carries the proof it had before the batch, so its status is the one it started from.
+fn row(order: usize, task: &Task, context: &RepoContext, proven: bool, elapsed: Duration) -> Row {
+    Row {
+        order,
+        slug: task.slug.clone(),
+        before: task.derive_status(context),
+        proven,
+        elapsed,
+    }
+}
+
+fn print_summary(rows: &[Row]) {
+    say!("\n📋 Summary:");
+    let mut table = Table::new(&[Align::Left, Align::Left, Align::Left, Align::Right]).flex(1);
+    for row in rows {
+        let proven = row.proven;
+        let outcome = match (proven, row.before) {
+            (true, DerivedStatus::Stale | DerivedStatus::Broken) => "recovered",
+            (true, _) => "proven",
+            (false, DerivedStatus::Broken) => "still broken",
+            (false, _) => "broken",
+        };
+        table.row(vec![
+            Cell::new(if proven { "✓" } else { "✗" }, move |s| if proven { s.success() } else { s.failure() }),
+            Cell::new(format!("[{}]", row.slug), |s| s.warning()),
+            Cell::new(outcome, move |s| if proven { s.success() } else { s.failure() }),
+            Cell::new(format!("{:.1}s", row.elapsed.as_secs_f64()), |s| s.dimmed()),
+        ]);
+    }
+    for line in table.render() {
+        say!("{line}");
+    }
+    let broken = rows.iter().filter(|r| !r.proven).count();
+    let recovered = rows
+        .iter()
+        .filter(|r| r.proven && matches!(r.before, DerivedStatus::Stale | DerivedStatus::Broken))
+        .count();
+    say!(
+        "\n{} proven{}, {} broken",
+        (rows.len() - broken).to_string().success(),
+        if recovered > 0 { format!(" ({recovered} recovered)") } else { String::new() },
+        broken.to_string().failure()
+    );
+}
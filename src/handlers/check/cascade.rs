@@ -0,0 +1,61 @@
+//! Cascade: re-verifies dependents made stale only by the checked task's files.
+
+use super::{verify_task, Recorder};
+use anyhow::Result;
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::graph::TaskGraph;
+use roadmap::engine::types::{DerivedStatus, Task};
+
+/// Re-verifies stale downstream tasks whose proofs were invalidated solely by
+/// changes to files owned by `root` (or by a task refreshed earlier in the wave).
+pub(super) fn run(rec: &Recorder<'_>, root: &Task, head_sha: &str) -> Result<()> {
+    let graph = TaskGraph::build(rec.repo.conn())?;
+    let context = graph.context();
+
+    // An unscoped task is sensitive to the whole tree, so it owns every change.
+    let owns_everything = root.scopes.is_empty();
+    let mut owned = root.scopes.clone();
+    let (mut refreshed, mut broken, mut skipped) = (0, 0, 0);
+
+    for task in graph.get_downstream(root.id) {
+        if task.derive_status(context) != DerivedStatus::Stale {
+            continue;
+        }
+        let Some(proof) = &task.proof else { continue };
+
+        let explained =
+            owns_everything || !context.has_changes_outside(&proof.git_sha, &task.scopes, &owned);
+        if !explained {
+            say!(
+                "   {} [{}] stale for other reasons, skipped",
+                "↷".dimmed(),
+                task.slug.dimmed()
+            );
+            skipped += 1;
+            continue;
+        }
+
+        say!("\n↻ Cascading: [{}] {}", task.slug.warning(), task.title);
+        match verify_task(rec, task, head_sha)? {
+            Some(true) => {
+                refreshed += 1;
+                owned.extend(task.scopes.iter().cloned());
+            }
+            Some(false) => broken += 1,
+            None => skipped += 1,
+        }
+    }
+
+    if refreshed + broken + skipped == 0 {
+        say!("   {} no stale dependents to cascade", "↻".dimmed());
+    } else {
+        say!(
+            "\n↻ Cascade: {} refreshed, {} broken, {} skipped",
+            refreshed.to_string().success(),
+            broken.to_string().failure(),
+            skipped
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,175 @@
+//! Dry run: shows what `check` would run and what it would change, running nothing.
+//!
+//! Each plan is resolved exactly as a real check resolves it, so the command,
+//! shell, directory, timeout, and environment shown are the ones that would
+//! be used. Nothing is executed, no proof is written, and no hook fires.
+
+use super::{batch, get_active_task, runner_config, CheckOptions, RunFlags};
+use anyhow::Result;
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::artifacts;
+use roadmap::engine::config::Config;
+use roadmap::engine::context::RepoContext;
+use roadmap::engine::db::Db;
+use roadmap::engine::graph::TaskGraph;
+use roadmap::engine::repo::TaskRepo;
+use roadmap::engine::types::{DerivedStatus, Task};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// What checking one task would do.
+#[derive(Debug, Serialize)]
+struct Plan {
+    task: String,
+    title: String,
+    status: String,
+    /// `None` when only the task's budgets would be judged.
+    command: Option<String>,
+    shell: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    container: Option<String>,
+    /// What confines the command, when it runs sandboxed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sandbox: Option<String>,
+    /// Relative to the repository root.
+    cwd: String,
+    timeout_secs: u64,
+    retries: u32,
+    env: BTreeMap<String, String>,
+    pre_hooks: Vec<String>,
+    post_hooks: Vec<String>,
+    /// The status the task would have if the check passed, and if it failed.
+    if_passed: String,
+    if_failed: String,
+}
+
+/// Prints the plan for the active task, or for every task `--all` would verify.
+///
+/// # Errors
+/// Returns error if no task is active, the config is invalid, or the database fails.
+pub(super) fn show(opts: &CheckOptions<'_>, context: &RepoContext) -> Result<()> {
+    let conn = Db::connect()?;
+    let config = Config::load()?;
+    let flags = RunFlags::new(opts);
+    let graph;
+    let active;
+    let tasks: Vec<&Task> = if opts.all {
+        graph = TaskGraph::build(&conn)?;
+        batch::select(
+            &graph,
+            batch::Selection {
+                stale: opts.stale,
+                unblocked: opts.unblocked,
+            },
+        )
+    } else {
+        active = get_active_task(&TaskRepo::new(&conn))?;
+        vec![&active]
+    };
+    let plans = tasks
+        .into_iter()
+        .filter(|t| t.platform_test_cmd().is_some() || !t.budgets.is_empty())
+        .map(|t| plan(t, &config, context, flags))
+        .collect::<Result<Vec<_>>>()?;
+
+    if context.is_dirty {
+        eprintln!(
+            "{} Repository is dirty: a real check would refuse to run until the changes are committed.",
+            "!".warning()
+        );
+    }
+    if opts.json {
+        println!("{}", serde_json::to_string_pretty(&plans)?);
+        return Ok(());
+    }
+    if plans.is_empty() {
+        println!("{} Nothing would run: no verification command or budgets defined.", "?".warning());
+        return Ok(());
+    }
+    for (i, plan) in plans.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        print_plan(plan);
+    }
+    println!();
+    println!("{} Dry run: nothing was executed and no proof was written.", "ℹ".info());
+    Ok(())
+}
+
+fn plan(task: &Task, config: &Config, context: &RepoContext, flags: RunFlags<'_>) -> Result<Plan> {
+    let runner = runner_config(task, config, context.head_sha(), flags)?;
+    let mut env: BTreeMap<String, String> = runner.envs.into_iter().collect();
+    // The run's directory is only created when the check starts.
+    env.insert(
+        artifacts::ENV_VAR.to_string(),
+        artifacts::task_dir(&task.slug).join("<run>").display().to_string(),
+    );
+    let status = task.derive_status(context);
+    let (if_passed, if_failed) = if status == DerivedStatus::Quarantined {
+        (status, status)
+    } else {
+        (DerivedStatus::Proven, DerivedStatus::Broken)
+    };
+    Ok(Plan {
+        task: task.slug.clone(),
+        title: task.title.clone(),
+        status: status.to_string(),
+        command: task.platform_test_cmd().map(str::to_string),
+        shell: runner.shell.to_string(),
+        container: runner.container.map(|c| c.image),
+        sandbox: runner.sandbox.map(|s| s.to_string()),
+        cwd: runner.working_dir.unwrap_or_else(|| ".".to_string()),
+        timeout_secs: runner.timeout_secs,
+        retries: runner.retries,
+        env,
+        pre_hooks: config.hooks.pre.clone(),
+        post_hooks: config.hooks.post.clone(),
+        if_passed: if_passed.to_string(),
+        if_failed: if_failed.to_string(),
+    })
+}
+
+fn print_plan(plan: &Plan) {
+    println!(
+        "🔍 Would check: [{}] {} ({})",
+        plan.task.warning(),
+        plan.title,
+        plan.status.dimmed()
+    );
+    let command = plan.command.as_deref().unwrap_or("(none: budgets only)");
+    println!("   {:<10} {command}", "command:".dimmed());
+    match &plan.container {
+        Some(image) => println!("   {:<10} {} in {image}", "shell:".dimmed(), plan.shell),
+        None => println!("   {:<10} {}", "shell:".dimmed(), plan.shell),
+    }
+    if let Some(sandbox) = &plan.sandbox {
+        println!("   {:<10} {sandbox}", "sandbox:".dimmed());
+    }
+    println!("   {:<10} {}", "cwd:".dimmed(), plan.cwd);
+    println!("   {:<10} {}s", "timeout:".dimmed(), plan.timeout_secs);
+    if plan.retries > 0 {
+        println!("   {:<10} {}", "retries:".dimmed(), plan.retries);
+    }
+    for (phase, hooks) in [("pre-hook:", &plan.pre_hooks), ("post-hook:", &plan.post_hooks)] {
+        for hook in hooks {
+            println!("   {:<10} {hook}", phase.dimmed());
+        }
+    }
+    for (i, (name, value)) in plan.env.iter().enumerate() {
+        let label = if i == 0 { "env:" } else { "" };
+        println!("   {:<10} {}={value}", label.dimmed(), name.info());
+    }
+    if plan.if_passed == plan.if_failed {
+        println!("   {:<10} stays {} either way", "status:".dimmed(), plan.if_passed);
+    } else {
+        println!(
+            "   {:<10} {} → {} if it passes, {} if it fails",
+            "status:".dimmed(),
+            plan.status,
+            plan.if_passed.success(),
+            plan.if_failed.failure()
+        );
+    }
+}
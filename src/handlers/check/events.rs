@@ -0,0 +1,66 @@
+//! Progress events: NDJSON on stdout for `check --json`.
+//!
+//! Orchestrators read one object per line: `started` when a verification
+//! command launches, a `heartbeat` every few seconds while it runs, and
+//! `finished` once the outcome is recorded. Human output moves to stderr.
+
+use anyhow::Result;
+use roadmap::engine::canonical;
+use schemars::{JsonSchema, Schema};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// How often a running command reports that it is still alive.
+pub(super) const HEARTBEAT: Duration = Duration::from_secs(5);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub(super) fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// True when stdout is reserved for events.
+pub(crate) fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// One line of `check --json` output.
+#[derive(Serialize, JsonSchema)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(crate) enum CheckEvent<'a> {
+    Started {
+        task: &'a str,
+        cmd: &'a str,
+        git_sha: &'a str,
+    },
+    Heartbeat {
+        task: &'a str,
+        elapsed_ms: u64,
+    },
+    Finished {
+        task: &'a str,
+        /// `proven`, `broken`, `attested`, or `skipped` (nothing to run).
+        result: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        exit_code: Option<i32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        duration_ms: Option<u64>,
+    },
+}
+
+/// Writes an event line if `--json` is active.
+///
+/// # Errors
+/// Returns error if the event cannot be serialized.
+pub(super) fn emit(event: &CheckEvent<'_>) -> Result<()> {
+    if enabled() {
+        println!("{}", canonical::to_line(event)?);
+    }
+    Ok(())
+}
+
+/// JSON Schema of a single event line.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(CheckEvent<'static>)
+}
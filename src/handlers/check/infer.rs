@@ -0,0 +1,66 @@
+//! Scope inference: offers the files touched while a task was active as its scopes.
+//!
+//! The diff starts where `do` activated the task. When that shows nothing
+//! (the task was never activated, or its work predates `do`), it starts at the
+//! commit of the task's previous proof instead.
+
+use anyhow::Result;
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::impact;
+use roadmap::engine::repo::TaskRepo;
+use roadmap::engine::scopes;
+use roadmap::engine::types::Task;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// After a passing check on an unscoped task, proposes directory globs for the
+/// files changed since `do` (or the task's previous proof) and saves them if adopted.
+///
+/// `task` is as loaded before the check, so its proof is the previous one.
+pub(super) fn offer(repo: &TaskRepo<'_>, task: &Task, adopt: bool) -> Result<()> {
+    if !task.scopes.is_empty() {
+        return Ok(());
+    }
+    let anchors = [
+        repo.get_active_since()?.map(|sha| (sha, "while working on")),
+        task.proof.as_ref().map(|p| (p.git_sha.clone(), "since the last proof of")),
+    ];
+    let Some((globs, when)) = anchors.into_iter().flatten().find_map(|(since, when)| {
+        // A commit git no longer knows (e.g. rewritten by a rebase) suggests nothing.
+        let files = impact::changed_files(&format!("{since}..HEAD"), &[]).ok()?;
+        let globs = scopes::directory_globs(&files);
+        (!globs.is_empty()).then_some((globs, when))
+    }) else {
+        return Ok(());
+    };
+
+    say!(
+        "\n{} Files changed {when} [{}] suggest these scopes:",
+        "🎯".info(),
+        task.slug
+    );
+    for glob in &globs {
+        say!("   {glob}");
+    }
+
+    let interactive = io::stdin().is_terminal() && !super::events::enabled();
+    let accepted = adopt || (interactive && confirm()?);
+    if !accepted {
+        if !interactive {
+            say!("   {}", "Re-run with --adopt-scopes to save them.".dimmed());
+        }
+        return Ok(());
+    }
+
+    repo.replace_scopes(task.id, &globs)?;
+    say!("{} Scopes saved", "✓".success());
+    Ok(())
+}
+
+fn confirm() -> Result<bool> {
+    eprint!("   Save as the task's scopes? [y/N] ");
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
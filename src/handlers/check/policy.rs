@@ -0,0 +1,106 @@
+//! Post-run policies: coverage, benchmark, and resource budget checks.
+
+use anyhow::Result;
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::bench;
+use roadmap::engine::budget::Budget;
+use roadmap::engine::coverage;
+use roadmap::engine::repo::ProofRepo;
+use roadmap::engine::types::{ProofOutcome, Task};
+use rusqlite::Connection;
+use std::path::Path;
+use std::time::Duration;
+
+/// Reads the task's coverage report, if configured. Unreadable reports count as no data.
+pub fn measure_coverage(task: &Task) -> Option<f64> {
+    let path = task.coverage_path.as_deref()?;
+    match coverage::read_percentage(Path::new(path)) {
+        Ok(pct) => {
+            let line = format!("{pct:.1}%");
+            let shown = if task.coverage_ok(Some(pct)) { line.success() } else { line.failure() };
+            match task.coverage_min {
+                Some(min) => say!("   {} {} (min {min:.1}%)", "coverage:".dimmed(), shown),
+                None => say!("   {} {}", "coverage:".dimmed(), shown),
+            }
+            Some(pct)
+        }
+        Err(e) => {
+            say!("   {} {}", "coverage:".dimmed(), e.to_string().warning());
+            None
+        }
+    }
+}
+
+
+/// In benchmark mode, records metrics and rejects regressions against the last Proven run.
+pub fn guard_benchmarks(conn: &Connection, task: &Task, outcome: &mut ProofOutcome) -> Result<()> {
+    let Some(threshold) = task.bench_threshold else {
+        return Ok(());
+    };
+
+    outcome.metrics = bench::parse_metrics(&outcome.stdout);
+    if outcome.metrics.is_empty() {
+        outcome.failure = Some("no benchmark results found in output".to_string());
+        say!("   {} {}", "bench:".dimmed(), "no results found".failure());
+        return Ok(());
+    }
+
+    let baseline = ProofRepo::new(conn).get_baseline_metrics(task.id)?;
+    let Some(baseline) = baseline else {
+        say!(
+            "   {} {} metrics recorded as baseline",
+            "bench:".dimmed(),
+            outcome.metrics.len()
+        );
+        return Ok(());
+    };
+
+    let regressions = bench::find_regressions(&baseline, &outcome.metrics, threshold);
+    for r in &regressions {
+        say!(
+            "   {} {} {:.1} → {:.1} ({})",
+            "bench:".dimmed(),
+            r.name,
+            r.baseline,
+            r.current,
+            format!("+{:.1}%", r.percent).failure()
+        );
+    }
+    if regressions.is_empty() {
+        say!("   {} no regressions over {threshold}%", "bench:".dimmed());
+    } else {
+        let names: Vec<_> = regressions.iter().map(|r| r.name.as_str()).collect();
+        outcome.failure = Some(format!(
+            "benchmark regression over {threshold}%: {}",
+            names.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Evaluates the task's resource budgets, rejecting the outcome if any is exceeded.
+///
+/// `elapsed` is the verification command's runtime, or `None` for budget-only tasks.
+///
+/// # Errors
+/// Returns error if a stored budget spec cannot be parsed.
+pub fn enforce_budgets(task: &Task, outcome: &mut ProofOutcome, elapsed: Option<Duration>) -> Result<()> {
+    for spec in &task.budgets {
+        let result = Budget::parse(spec)?.evaluate(spec, elapsed);
+        let mark = if result.passed { "✓".success() } else { "✗".failure() };
+        say!("   {} {} {} ({})", mark, "budget:".dimmed(), result.budget, result.actual);
+        outcome.budgets.push(result);
+    }
+
+    let exceeded: Vec<_> = outcome
+        .budgets
+        .iter()
+        .filter(|b| !b.passed)
+        .map(|b| b.budget.as_str())
+        .collect();
+    if !exceeded.is_empty() && outcome.failure.is_none() {
+        outcome.failure = Some(format!("budget exceeded: {}", exceeded.join(", ")));
+    }
+    Ok(())
+}
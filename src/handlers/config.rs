@@ -0,0 +1,46 @@
+//! Handlers for the `config` command group.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::config::{Config, Scope, KEYS};
+
+/// Prints the effective value of `key`, or every key with its value.
+///
+/// # Errors
+/// Returns error if the key is unknown or unset, or a config file is invalid.
+pub fn get(key: Option<&str>) -> Result<()> {
+    let Some(key) = key else {
+        for key in KEYS {
+            match Config::get(key)? {
+                Some(value) => println!("{key} = {}", render(&value)),
+                None => println!("{}", format!("{key} (unset)").dimmed()),
+            }
+        }
+        return Ok(());
+    };
+    match Config::get(key)? {
+        Some(value) => println!("{}", render(&value)),
+        None => bail!("{key} is not set"),
+    }
+    Ok(())
+}
+
+/// Sets `key` to `value` in the repository config, or the user config if `global`.
+///
+/// # Errors
+/// Returns error if the key is unknown, the value is invalid, or the file cannot be written.
+pub fn set(key: &str, value: &str, global: bool) -> Result<()> {
+    let scope = if global { Scope::User } else { Scope::Repo };
+    let path = Config::set(scope, key, value)?;
+    println!("{} Set {} in {}", "✓".success(), key.accent(), path.display());
+    Ok(())
+}
+
+/// Strings print bare; everything else as TOML.
+fn render(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
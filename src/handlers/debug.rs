@@ -0,0 +1,135 @@
+//! Handler for the `debug` command group.
+
+use anyhow::Result;
+use colored::Colorize;
+use crate::table::{Align, Cell, Table};
+use crate::theme::Paint;
+use crate::trace::{self, Invocation};
+use schemars::{JsonSchema, Schema};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Summarizes phase timings recorded by `--trace` / `ROADMAP_TRACE=1`.
+///
+/// # Errors
+/// Returns error if the timings log cannot be read.
+pub fn timings(limit: usize, json: bool) -> Result<()> {
+    let log = trace::read_log()?;
+    let recent = &log[log.len().saturating_sub(limit)..];
+    let summary = summarize(recent);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        return Ok(());
+    }
+
+    print_human(&summary);
+    Ok(())
+}
+
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(TimingSummary)
+}
+
+#[derive(Serialize, JsonSchema)]
+struct TimingSummary {
+    invocations: usize,
+    commands: Vec<Aggregate>,
+    phases: Vec<Aggregate>,
+}
+
+/// Totals for one command or phase across invocations.
+#[derive(Default, Serialize, JsonSchema)]
+struct Aggregate {
+    name: String,
+    count: u64,
+    total_ms: u64,
+    max_ms: u64,
+}
+
+impl Aggregate {
+    fn add(&mut self, count: u64, ms: u64) {
+        self.count += count;
+        self.total_ms += ms;
+        self.max_ms = self.max_ms.max(ms);
+    }
+
+    fn avg_ms(&self) -> u64 {
+        self.total_ms / self.count.max(1)
+    }
+}
+
+fn summarize(invocations: &[Invocation]) -> TimingSummary {
+    let mut commands: BTreeMap<&str, Aggregate> = BTreeMap::new();
+    let mut phases: BTreeMap<&str, Aggregate> = BTreeMap::new();
+    for inv in invocations {
+        commands.entry(&inv.command).or_default().add(1, inv.total_ms);
+        for phase in &inv.phases {
+            phases
+                .entry(&phase.name)
+                .or_default()
+                .add(u64::from(phase.count), phase.total_ms);
+        }
+    }
+
+    let collect = |map: BTreeMap<&str, Aggregate>| {
+        let mut out: Vec<Aggregate> = map
+            .into_iter()
+            .map(|(name, agg)| Aggregate {
+                name: name.to_string(),
+                ..agg
+            })
+            .collect();
+        out.sort_by(|a, b| b.total_ms.cmp(&a.total_ms).then_with(|| a.name.cmp(&b.name)));
+        out
+    };
+    TimingSummary {
+        invocations: invocations.len(),
+        commands: collect(commands),
+        phases: collect(phases),
+    }
+}
+
+fn print_human(summary: &TimingSummary) {
+    println!(
+        "{} Timings ({} traced invocations)",
+        "⏱".info(),
+        summary.invocations
+    );
+    if summary.invocations == 0 {
+        println!("   (Nothing recorded yet. Run commands with --trace or ROADMAP_TRACE=1.)");
+        return;
+    }
+
+    println!("\n   {}", "Commands (runs, avg, max):".dimmed());
+    print_aggregates(&summary.commands);
+    println!("\n   {}", "Phases (calls, avg per call, total):".dimmed());
+    let mut table = Table::new(&[Align::Left, Align::Right, Align::Right, Align::Right]);
+    for p in &summary.phases {
+        table.row(vec![
+            Cell::plain(&p.name),
+            Cell::new(format!("{}×", p.count), |s| s.dimmed()),
+            Cell::plain(format!("{}ms", p.avg_ms())),
+            Cell::new(format!("{}ms", p.total_ms), |s| s.bold()),
+        ]);
+    }
+    for line in table.render() {
+        println!("{line}");
+    }
+}
+
+fn print_aggregates(rows: &[Aggregate]) {
+    let mut table = Table::new(&[Align::Left, Align::Right, Align::Right, Align::Right]);
+    for a in rows {
+        table.row(vec![
+            Cell::new(&a.name, |s| s.bold()),
+            Cell::new(format!("{}×", a.count), |s| s.dimmed()),
+            Cell::plain(format!("{}ms", a.avg_ms())),
+            Cell::new(format!("{}ms", a.max_ms), |s| s.warning()),
+        ]);
+    }
+    for line in table.render() {
+        println!("{line}");
+    }
+}
@@ -0,0 +1,156 @@
+//! Handlers for the `dep` command group.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use crate::theme::{self, Paint};
+use roadmap::engine::db::Db;
+use roadmap::engine::graph::TaskGraph;
+use roadmap::engine::repo::TaskRepo;
+use roadmap::engine::resolver::TaskResolver;
+use roadmap::engine::types::Task;
+use roadmap::Error;
+use rusqlite::Connection;
+use schemars::{JsonSchema, Schema};
+use serde::Serialize;
+
+/// Makes `upstream` a dependency of `downstream`.
+///
+/// # Errors
+/// Returns error if either task cannot be resolved, the edge would create a cycle, or the database fails.
+pub fn add(upstream_ref: &str, downstream_ref: &str) -> Result<()> {
+    let mut conn = Db::connect()?;
+    let (upstream, downstream, added) = link(&mut conn, upstream_ref, downstream_ref)?;
+    if added {
+        println!("{} [{}] blocks [{}]", "✓".success(), upstream.slug, downstream.slug);
+    } else {
+        println!("   [{}] already blocks [{}]", upstream.slug, downstream.slug);
+    }
+    Ok(())
+}
+
+/// Adds the edge unless it already exists; the flag tells whether it was added.
+pub(crate) fn link(conn: &mut Connection, upstream_ref: &str, downstream_ref: &str) -> Result<(Task, Task, bool)> {
+    let tx = Db::begin_write(conn)?;
+    let resolver = TaskResolver::new(&tx);
+    let upstream = resolver.resolve(upstream_ref)?.task;
+    let downstream = resolver.resolve(downstream_ref)?.task;
+
+    if upstream.id == downstream.id {
+        bail!("A task cannot block itself");
+    }
+    let graph = TaskGraph::build(&tx)?;
+    if graph.get_blockers(downstream.id).iter().any(|t| t.id == upstream.id) {
+        return Ok((upstream, downstream, false));
+    }
+    if graph.would_create_cycle(upstream.id, downstream.id) {
+        bail!(Error::Cycle {
+            blocker: upstream.slug,
+            blocked: downstream.slug,
+        });
+    }
+
+    TaskRepo::new(&tx).link(upstream.id, downstream.id)?;
+    tx.commit()?;
+    Ok((upstream, downstream, true))
+}
+
+/// Removes the dependency of `downstream` on `upstream`.
+///
+/// # Errors
+/// Returns error if either task cannot be resolved, no such edge exists, or the database fails.
+pub fn rm(upstream_ref: &str, downstream_ref: &str) -> Result<()> {
+    let mut conn = Db::connect()?;
+    let (upstream, downstream) = unlink(&mut conn, upstream_ref, downstream_ref)?;
+    println!("{} [{}] no longer blocks [{}]", "✓".success(), upstream.slug, downstream.slug);
+    Ok(())
+}
+
+/// Removes an existing edge, failing if there is none.
+pub(crate) fn unlink(conn: &mut Connection, upstream_ref: &str, downstream_ref: &str) -> Result<(Task, Task)> {
+    let tx = Db::begin_write(conn)?;
+    let resolver = TaskResolver::new(&tx);
+    let upstream = resolver.resolve(upstream_ref)?.task;
+    let downstream = resolver.resolve(downstream_ref)?.task;
+
+    let graph = TaskGraph::build(&tx)?;
+    if !graph.get_blockers(downstream.id).iter().any(|t| t.id == upstream.id) {
+        bail!("[{}] does not block [{}]", upstream.slug, downstream.slug);
+    }
+
+    TaskRepo::new(&tx).unlink(upstream.id, downstream.id)?;
+    tx.commit()?;
+    Ok((upstream, downstream))
+}
+
+/// Shows what a task waits on and what waits on it.
+///
+/// # Errors
+/// Returns error if the task cannot be resolved or the database fails.
+pub fn list(task_ref: &str, json: bool) -> Result<()> {
+    let conn = Db::connect()?;
+    let task = TaskResolver::new(&conn).resolve(task_ref)?.task;
+    let graph = TaskGraph::build(&conn)?;
+    let mut blockers = graph.get_blockers(task.id);
+    let mut blocks = graph.get_blocked_by(task.id);
+    blockers.sort_by(|a, b| a.slug.cmp(&b.slug));
+    blocks.sort_by(|a, b| a.slug.cmp(&b.slug));
+
+    if json {
+        let view = DepList {
+            task: task.slug.clone(),
+            blocked_by: blockers.iter().map(|t| edge_view(t, &graph)).collect(),
+            blocks: blocks.iter().map(|t| edge_view(t, &graph)).collect(),
+        };
+        println!("{}", serde_json::to_string_pretty(&view)?);
+        return Ok(());
+    }
+
+    println!("{} [{}] {}", "🔗".info(), task.slug.accent(), task.title);
+    print_side("Blocked by:", &blockers, &graph);
+    print_side("Blocks:", &blocks, &graph);
+    Ok(())
+}
+
+/// JSON Schema of the `dep list --json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(DepList)
+}
+
+#[derive(Serialize, JsonSchema)]
+struct DepList {
+    task: String,
+    blocked_by: Vec<EdgeView>,
+    blocks: Vec<EdgeView>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct EdgeView {
+    slug: String,
+    title: String,
+    status: String,
+}
+
+fn edge_view(task: &Task, graph: &TaskGraph) -> EdgeView {
+    EdgeView {
+        slug: task.slug.clone(),
+        title: task.title.clone(),
+        status: task.derive_status(graph.context()).to_string(),
+    }
+}
+
+fn print_side(label: &str, tasks: &[&Task], graph: &TaskGraph) {
+    println!("\n   {}", label.bold());
+    if tasks.is_empty() {
+        println!("     {}", "(none)".dimmed());
+    }
+    for task in tasks {
+        let status = task.derive_status(graph.context());
+        println!(
+            "     {} [{}] {} {}",
+            theme::status_icon(status),
+            task.slug,
+            task.title,
+            format!("({status})").status(status)
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Handler for the `diff` command.
+
+use anyhow::Result;
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::db::Db;
+use roadmap::engine::diff::{self, StatusChange};
+use roadmap::engine::repo::TaskRepo;
+use roadmap::engine::types::DerivedStatus;
+use schemars::{JsonSchema, Schema};
+use serde::Serialize;
+
+/// Shows which tasks' derived status differs between two commits.
+///
+/// # Errors
+/// Returns error if either commit cannot be resolved or the database query fails.
+pub fn handle(from: &str, to: &str, json: bool) -> Result<()> {
+    let from_sha = diff::resolve(from)?;
+    let to_sha = diff::resolve(to)?;
+    let conn = Db::connect()?;
+    let tasks = TaskRepo::new(&conn).get_all()?;
+    let changes = diff::between(&conn, &tasks, &from_sha, &to_sha)?;
+
+    if json {
+        let report = DiffReport {
+            from: from_sha,
+            to: to_sha,
+            changes: changes
+                .iter()
+                .map(|c| ChangeView {
+                    id: c.task.id,
+                    slug: c.task.slug.clone(),
+                    title: c.task.title.clone(),
+                    before: c.before.to_string(),
+                    after: c.after.to_string(),
+                })
+                .collect(),
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    print_human(&format!("{from}..{to}"), &changes);
+    Ok(())
+}
+
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(DiffReport)
+}
+
+#[derive(Serialize, JsonSchema)]
+struct DiffReport {
+    from: String,
+    to: String,
+    /// Only the tasks whose status differs.
+    changes: Vec<ChangeView>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct ChangeView {
+    id: i64,
+    slug: String,
+    title: String,
+    before: String,
+    after: String,
+}
+
+fn print_human(range: &str, changes: &[StatusChange<'_>]) {
+    if changes.is_empty() {
+        println!("{} No task changes status across {}", "✓".success(), range.dimmed());
+        return;
+    }
+
+    println!("🔀 {}: {}\n", range.info(), summary(changes));
+    for change in changes {
+        println!(
+            "   [{}] {}  {} → {}",
+            change.task.slug.warning(),
+            change.task.title,
+            change.before.to_string().status(change.before),
+            change.after.to_string().status(change.after)
+        );
+    }
+}
+
+/// `proves 2, makes 3 stale`: what the range does, by the status tasks end up in.
+fn summary(changes: &[StatusChange<'_>]) -> String {
+    let outcomes = [
+        (DerivedStatus::Proven, "proves", ""),
+        (DerivedStatus::Stale, "makes", " stale"),
+        (DerivedStatus::Broken, "breaks", ""),
+        (DerivedStatus::Attested, "attests", ""),
+        (DerivedStatus::Unproven, "leaves", " unproven"),
+        (DerivedStatus::Quarantined, "quarantines", ""),
+    ];
+    outcomes
+        .iter()
+        .filter_map(|(status, verb, suffix)| {
+            let count = changes.iter().filter(|c| c.after == *status).count();
+            (count > 0).then(|| format!("{verb} {count}{suffix}"))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
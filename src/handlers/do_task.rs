@@ -1,13 +1,18 @@
 //! Handler for the `do` command.
 
 use anyhow::{bail, Result};
-use colored::Colorize;
+use crate::theme::Paint;
+use super::hooks;
+use roadmap::engine::config::Config;
 use roadmap::engine::context::RepoContext;
 use roadmap::engine::db::Db;
 use roadmap::engine::graph::TaskGraph;
+use roadmap::engine::lifecycle::{Event, Payload};
 use roadmap::engine::repo::TaskRepo;
 use roadmap::engine::resolver::TaskResolver;
-use roadmap::engine::types::{DerivedStatus, TaskStatus};
+use roadmap::engine::types::{Task, TaskStatus};
+use roadmap::Error;
+use rusqlite::Connection;
 
 /// Sets a task as the active focus.
 ///
@@ -15,52 +20,71 @@ use roadmap::engine::types::{DerivedStatus, TaskStatus};
 /// Returns error if task is blocked or not found.
 pub fn handle(task_ref: &str, strict: bool) -> Result<()> {
     let conn = Db::connect()?;
+    let config = Config::load()?;
+    let strict = strict || config.resolve.strict;
+    let task = activate(&conn, task_ref, strict)?;
+
+    println!(
+        "{} Now working on: [{}] {}",
+        "→".warning(),
+        task.slug.warning(),
+        task.title
+    );
+    if let Some(assignee) = &task.assignee {
+        if config.identity().is_none_or(|me| !task.is_assigned_to(&me)) {
+            println!("   {} [{}] is assigned to {}", "⚠".warning(), task.slug, assignee.accent());
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolves a task and makes it the active one, refusing if it is blocked.
+///
+/// # Errors
+/// Returns error if the task is blocked or not found, or the database fails.
+pub(crate) fn activate(conn: &Connection, task_ref: &str, strict: bool) -> Result<Task> {
     let context = RepoContext::new()?;
 
     let resolver = if strict {
-        TaskResolver::strict(&conn)
+        TaskResolver::strict(conn)
     } else {
-        TaskResolver::new(&conn)
+        TaskResolver::new(conn)
     };
 
-    let result = resolver.resolve(task_ref)?;
-    let task = &result.task;
+    let task = resolver.resolve(task_ref)?.task;
 
-    check_not_blocked(&conn, task, &context)?;
+    check_not_blocked(conn, &task, &context)?;
 
-    let repo = TaskRepo::new(&conn);
+    let repo = TaskRepo::new(conn);
+    // Re-running `do` on the same task keeps the original starting point.
+    if repo.get_active_task_id()? != Some(task.id) {
+        repo.set_active_since(context.head_sha())?;
+    }
     repo.update_status(task.id, TaskStatus::Active)?;
     repo.set_active_task(task.id)?;
-
-    println!(
-        "{} Now working on: [{}] {}",
-        "→".yellow(),
-        task.slug.yellow(),
-        task.title
-    );
-
-    Ok(())
+    let task = Task {
+        status: TaskStatus::Active,
+        ..task
+    };
+    hooks::fire(&Payload::task(Event::PostDo, &task));
+    Ok(task)
 }
 
-fn check_not_blocked(
-    conn: &rusqlite::Connection,
-    task: &roadmap::engine::types::Task,
-    context: &RepoContext,
-) -> Result<()> {
+fn check_not_blocked(conn: &Connection, task: &Task, context: &RepoContext) -> Result<()> {
     let graph = TaskGraph::build(conn)?;
     let blockers = graph.get_blockers(task.id);
 
     let incomplete: Vec<_> = blockers
         .into_iter()
-        .filter(|t| {
-            let status = t.derive_status(context);
-            !matches!(status, DerivedStatus::Proven | DerivedStatus::Attested)
-        })
+        .filter(|t| !t.derive_status(context).satisfies_dependency())
         .collect();
 
     if !incomplete.is_empty() {
-        let names: Vec<_> = incomplete.iter().map(|t| t.slug.as_str()).collect();
-        bail!("Task [{}] is blocked by: {}", task.slug, names.join(", "));
+        bail!(Error::Blocked {
+            task: task.slug.clone(),
+            blockers: incomplete.iter().map(|t| t.slug.clone()).collect(),
+        });
     }
     Ok(())
 }
\ No newline at end of file
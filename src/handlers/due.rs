@@ -0,0 +1,66 @@
+//! Handler for the `due` command.
+
+use anyhow::Result;
+use colored::{ColoredString, Colorize};
+use crate::table::{Align, Cell, Table};
+use crate::theme::Paint;
+use roadmap::engine::db::Db;
+use roadmap::engine::due::{self, DueTask};
+use roadmap::engine::graph::TaskGraph;
+use schemars::Schema;
+
+/// Lists unfinished tasks with due dates, most overdue first.
+///
+/// # Errors
+/// Returns error if the database or git context cannot be loaded.
+pub fn handle(json: bool) -> Result<()> {
+    let conn = Db::connect()?;
+    let graph = TaskGraph::build(&conn)?;
+    let tasks = due::analyze(&graph, due::today());
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&tasks)?);
+        return Ok(());
+    }
+
+    if tasks.is_empty() {
+        println!("{} No unfinished task has a due date.", "✓".success());
+        return Ok(());
+    }
+    let overdue = tasks.iter().filter(|t| t.overdue).count();
+    println!(
+        "{} {} task(s) with due dates, {} overdue",
+        "📅".info(),
+        tasks.len(),
+        overdue
+    );
+    let mut table = Table::new(&[Align::Left, Align::Left, Align::Left, Align::Left, Align::Left]).flex(1);
+    for task in &tasks {
+        let blocked = if task.blocked { "blocked" } else { "" };
+        table.row(vec![
+            Cell::new(format!("[{}]", task.slug), |s| s.warning()),
+            Cell::plain(&task.title),
+            Cell::new(&task.due, |s| s.dimmed()),
+            Cell::new(due::relative(task.days_left), urgency(task.days_left)),
+            Cell::new(blocked, |s| s.dimmed()),
+        ]);
+    }
+    for line in table.render() {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(Vec<DueTask>)
+}
+
+/// How to color a due date that is `days_left` away.
+pub(crate) fn urgency(days_left: i64) -> fn(&str) -> ColoredString {
+    match days_left {
+        n if n < 0 => |s| s.failure(),
+        0..=2 => |s| s.warning(),
+        _ => |s: &str| s.dimmed(),
+    }
+}
@@ -0,0 +1,177 @@
+//! Handler for the `edit` command.
+
+use anyhow::{bail, Result};
+use crate::editor;
+use crate::theme::Paint;
+use roadmap::engine::db::Db;
+use roadmap::engine::due;
+use roadmap::engine::estimate;
+use roadmap::engine::repo::TaskRepo;
+use roadmap::engine::resolver::{normalize_tags, TaskResolver};
+use roadmap::engine::runner;
+use roadmap::engine::shell::Shell;
+
+/// Fields to change on an existing task; `None` leaves a field as it is.
+pub struct EditOptions<'a> {
+    pub task: &'a str,
+    pub title: Option<&'a str>,
+    pub test_cmd: Option<&'a str>,
+    pub shell: Option<&'a str>,
+    pub test_unix: Option<&'a str>,
+    pub test_windows: Option<&'a str>,
+    /// New working directory for the command; `none` runs it from the root again.
+    pub cwd: Option<&'a str>,
+    pub priority: Option<i64>,
+    /// New due date as typed; `none` clears it.
+    pub due: Option<&'a str>,
+    /// New effort estimate as typed; `none` clears it.
+    pub estimate: Option<&'a str>,
+    pub tags: &'a [String],
+    pub untags: &'a [String],
+    /// New description; `none` clears it.
+    pub description: Option<&'a str>,
+    /// Rewrite the description in `$EDITOR`.
+    pub describe: bool,
+    /// New parent task; `none` makes the task top-level.
+    pub parent: Option<&'a str>,
+    /// New assignee; `me` is the current user, `none` unassigns.
+    pub assignee: Option<&'a str>,
+    /// Skip the pre-flight probe of the verification command.
+    pub no_probe: bool,
+}
+
+impl EditOptions<'_> {
+    /// Whether the shell or a per-platform command changes.
+    fn exec_changed(&self) -> bool {
+        self.shell.is_some() || self.test_unix.is_some() || self.test_windows.is_some()
+    }
+
+    /// Whether no field would change.
+    fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.test_cmd.is_none()
+            && self.cwd.is_none()
+            && self.priority.is_none()
+            && self.due.is_none()
+            && self.estimate.is_none()
+            && self.tags.is_empty()
+            && self.untags.is_empty()
+            && self.description.is_none()
+            && !self.describe
+            && self.parent.is_none()
+            && self.assignee.is_none()
+            && !self.exec_changed()
+    }
+}
+
+/// Handles changing the definition of an existing task.
+///
+/// The slug is kept when the title changes, so references stay valid.
+///
+/// # Errors
+/// Returns error if the task cannot be resolved, nothing would change, or the database fails.
+pub fn handle(opts: &EditOptions<'_>) -> Result<()> {
+    let exec_changed = opts.exec_changed();
+    if opts.is_empty() {
+        bail!("Nothing to edit (pass --title, --test, --shell, --test-unix, --test-windows, --cwd, --priority, --due, --estimate, --tag, --untag, --description, --describe, --parent, or --assign)");
+    }
+    let (tags, untags) = (normalize_tags(opts.tags)?, normalize_tags(opts.untags)?);
+    let due = match opts.due {
+        Some("none") => Some(None),
+        Some(input) => Some(Some(due::parse(input, due::today())?.format(due::DATE_FORMAT).to_string())),
+        None => None,
+    };
+    let estimate = match opts.estimate {
+        Some("none") => Some(None),
+        Some(input) => Some(Some(estimate::parse(input)?)),
+        None => None,
+    };
+    let assignee = match opts.assignee {
+        Some("none") => Some(None),
+        Some(name) => Some(Some(super::add::assignee(name)?)),
+        None => None,
+    };
+    if let Some(shell) = opts.shell {
+        Shell::parse(shell)?;
+    }
+    let cwd = match opts.cwd {
+        Some("none") => Some(None),
+        Some(dir) => Some(Some(runner::working_dir(dir)?)),
+        None => None,
+    };
+
+    let conn = Db::connect()?;
+    // The editor runs before the transaction opens, so a long edit holds no lock.
+    let description = if opts.describe {
+        let task = TaskResolver::strict(&conn).resolve(opts.task)?.task;
+        let subject = format!("[{}] {}", task.slug, task.title);
+        Some(editor::compose(&subject, task.description.as_deref())?)
+    } else {
+        match opts.description {
+            Some("none") => Some(None),
+            Some(text) => Some(Some(text.to_string()).filter(|t| !t.trim().is_empty())),
+            None => None,
+        }
+    };
+
+    let tx = Db::begin_write(&conn)?;
+    let task = TaskResolver::strict(&tx).resolve(opts.task)?.task;
+    let repo = TaskRepo::new(&tx);
+
+    if let Some(title) = opts.title {
+        repo.set_title(task.id, title)?;
+    }
+    if let Some(cmd) = opts.test_cmd {
+        repo.set_test_cmd(task.id, Some(cmd))?;
+    }
+    if let Some(cwd) = &cwd {
+        repo.set_cwd(task.id, cwd.as_deref())?;
+    }
+    if let Some(priority) = opts.priority {
+        repo.set_priority(task.id, priority)?;
+    }
+    if let Some(due) = &due {
+        repo.set_due(task.id, due.as_deref())?;
+    }
+    if let Some(hours) = estimate {
+        repo.set_estimate(task.id, hours)?;
+    }
+    match opts.parent {
+        Some("none") => repo.set_parent(task.id, None)?,
+        Some(parent_ref) => {
+            let parent = TaskResolver::new(&tx).resolve(parent_ref)?.task;
+            repo.set_parent(task.id, Some(parent.id))?;
+        }
+        None => {}
+    }
+    if let Some(assignee) = &assignee {
+        repo.set_assignee(task.id, assignee.as_deref())?;
+    }
+    if let Some(description) = &description {
+        repo.set_description(task.id, description.as_deref())?;
+    }
+    for tag in &tags {
+        repo.add_tag(task.id, tag)?;
+    }
+    for tag in &untags {
+        repo.remove_tag(task.id, tag)?;
+    }
+    if exec_changed {
+        repo.set_exec(
+            task.id,
+            opts.shell.or(task.shell.as_deref()),
+            opts.test_unix.or(task.test_cmd_unix.as_deref()),
+            opts.test_windows.or(task.test_cmd_windows.as_deref()),
+        )?;
+    }
+
+    let updated = repo.find_by_id(task.id)?;
+    tx.commit()?;
+    println!("{} Updated task [{}]", "✓".success(), task.slug.warning());
+
+    let probe = opts.test_cmd.is_some() || opts.cwd.is_some() || exec_changed;
+    if let (Some(updated), true, false) = (updated, probe, opts.no_probe) {
+        super::add::warn_on_probe(&updated)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,214 @@
+//! Handler for the `export` command.
+
+use anyhow::{bail, Context, Result};
+use crate::theme::Paint;
+use roadmap::engine::attestation;
+use roadmap::engine::audit;
+use roadmap::engine::canonical;
+use roadmap::engine::db::Db;
+use roadmap::engine::graph::TaskGraph;
+use roadmap::engine::snapshot::Snapshot;
+use roadmap::engine::types::{DerivedStatus, Task};
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Default destination of `--format md`.
+const MARKDOWN_FILE: &str = "ROADMAP.md";
+
+/// Default destination of `--format json`.
+const BUNDLE_FILE: &str = "roadmap-bundle.json";
+
+/// Default destination of `--attestations`, named the way SLSA tooling looks for bundles.
+const ATTESTATIONS_FILE: &str = "roadmap.intoto.jsonl";
+
+/// Sections of the Markdown roadmap, in order. Empty sections are left out.
+const SECTIONS: &[DerivedStatus] = &[
+    DerivedStatus::Proven,
+    DerivedStatus::Stale,
+    DerivedStatus::Unproven,
+    DerivedStatus::Broken,
+    DerivedStatus::Attested,
+    DerivedStatus::Quarantined,
+];
+
+/// Exports roadmap data in the requested format.
+///
+/// `attest_key` selects the attestation export, signed with that key.
+///
+/// # Errors
+/// Returns error if no export target is given, the format is unknown, the database fails, or the file cannot be written.
+pub fn handle(
+    audit_log: Option<&Path>,
+    format: Option<&str>,
+    output: Option<&Path>,
+    attest_key: Option<&str>,
+) -> Result<()> {
+    if audit_log.is_some() && output.is_some() {
+        bail!("--output is for --format and --attestations; --audit-log takes its own file");
+    }
+    match (audit_log, format, attest_key) {
+        (Some(path), None, None) => export_audit_log(path),
+        (None, Some("md"), None) => export_markdown(output.unwrap_or(Path::new(MARKDOWN_FILE))),
+        (None, Some("json"), None) => export_bundle(output.unwrap_or(Path::new(BUNDLE_FILE))),
+        (None, Some(other), None) => bail!("Unknown export format '{other}' (expected: md, json)"),
+        (None, None, Some(key)) => export_attestations(output.unwrap_or(Path::new(ATTESTATIONS_FILE)), key),
+        (None, None, None) => {
+            bail!("Nothing to export. Use --audit-log <file>, --format md|json, or --attestations --sign <key>.")
+        }
+        _ => bail!("Choose one export: --audit-log, --format, or --attestations"),
+    }
+}
+
+/// Writes one signed in-toto statement per proven task as JSON Lines (`-` writes to stdout).
+fn export_attestations(path: &Path, key: &str) -> Result<()> {
+    let conn = Db::connect()?;
+    let graph = TaskGraph::build(&conn)?;
+    let mut proven: Vec<&Task> = graph
+        .tasks()
+        .filter(|t| t.derive_status(graph.context()) == DerivedStatus::Proven)
+        .collect();
+    proven.sort_by_key(|t| t.id);
+
+    let mut envelopes = Vec::new();
+    for task in proven {
+        let Some(proof) = &task.proof else { continue };
+        let statement = attestation::statement(task, proof)
+            .with_context(|| format!("Failed to attest [{}]", task.slug))?;
+        envelopes.push(attestation::sign(&statement, key)?);
+    }
+
+    if path == Path::new("-") {
+        return write_jsonl(&mut io::stdout().lock(), &envelopes);
+    }
+    let file = File::create(path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    write_jsonl(&mut BufWriter::new(file), &envelopes)?;
+    println!(
+        "{} Exported {} signed attestation(s) to {}",
+        "✓".success(),
+        envelopes.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Writes tasks, edges, scopes, and proofs as one versioned snapshot (`-` writes to stdout).
+///
+/// `roadmap import` reads it back, e.g. on another machine.
+fn export_bundle(path: &Path) -> Result<()> {
+    let conn = Db::connect()?;
+    let snapshot = Snapshot::capture(&conn)?;
+    let json = serde_json::to_string_pretty(&snapshot)?;
+
+    if path == Path::new("-") {
+        println!("{json}");
+        return Ok(());
+    }
+
+    std::fs::write(path, json + "\n")
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    println!(
+        "{} Exported {} tasks, {} edges, and {} proofs to {}",
+        "✓".success(),
+        snapshot.tasks.len(),
+        snapshot.edges.len(),
+        snapshot.proofs.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Writes a human-readable plan grouped by status (`-` writes to stdout).
+fn export_markdown(path: &Path) -> Result<()> {
+    let conn = Db::connect()?;
+    let graph = TaskGraph::build(&conn)?;
+    let markdown = render_markdown(&graph)?;
+
+    if path == Path::new("-") {
+        print!("{markdown}");
+        return Ok(());
+    }
+
+    std::fs::write(path, markdown)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    println!(
+        "{} Exported {} tasks to {}",
+        "✓".success(),
+        graph.status_counts().total(),
+        path.display()
+    );
+    Ok(())
+}
+
+fn render_markdown(graph: &TaskGraph) -> Result<String> {
+    let counts = graph.status_counts();
+    let head = graph.head_sha();
+    let mut out = String::from("# Roadmap\n\n");
+    writeln!(
+        out,
+        "_Generated by `roadmap export --format md` at `{}`: {} of {} tasks proven._",
+        &head[..head.len().min(7)],
+        counts.proven,
+        counts.total()
+    )?;
+
+    let mut tasks: Vec<(&Task, DerivedStatus)> = graph
+        .tasks()
+        .map(|t| (t, t.derive_status(graph.context())))
+        .collect();
+    tasks.sort_by_key(|(t, _)| t.id);
+
+    for section in SECTIONS {
+        let members: Vec<&Task> = tasks
+            .iter()
+            .filter(|(_, status)| status == section)
+            .map(|(t, _)| *t)
+            .collect();
+        if members.is_empty() {
+            continue;
+        }
+        writeln!(out, "\n## {section} ({})\n", members.len())?;
+        for task in members {
+            let check = if section.satisfies_dependency() { "x" } else { " " };
+            write!(out, "- [{check}] **{}** (`{}`)", task.title, task.slug)?;
+            match &task.test_cmd {
+                Some(cmd) => writeln!(out, " — verify: `{cmd}`")?,
+                None => writeln!(out, " — _no verification command_")?,
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Writes every audit event as one JSON object per line (`-` writes to stdout).
+fn export_audit_log(path: &Path) -> Result<()> {
+    let conn = Db::connect()?;
+    let events = audit::collect_events(&conn)?;
+
+    if path == Path::new("-") {
+        return write_jsonl(&mut io::stdout().lock(), &events);
+    }
+
+    let file = File::create(path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    write_jsonl(&mut BufWriter::new(file), &events)?;
+
+    println!(
+        "{} Exported {} audit events to {}",
+        "✓".success(),
+        events.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+fn write_jsonl<T: Serialize>(out: &mut impl Write, events: &[T]) -> Result<()> {
+    for event in events {
+        writeln!(out, "{}", canonical::to_line(event)?)?;
+    }
+    out.flush()?;
+    Ok(())
+}
@@ -0,0 +1,66 @@
+//! Narrowing `graph`, `next`, and `list` to one area of the roadmap.
+
+use anyhow::{bail, Result};
+use roadmap::engine::config::Config;
+use roadmap::engine::graph::TaskGraph;
+use roadmap::engine::milestone;
+use roadmap::engine::repo::MilestoneRepo;
+use roadmap::engine::resolver::{normalize_tags, TaskResolver};
+use roadmap::engine::types::Task;
+use rusqlite::Connection;
+use std::collections::HashSet;
+
+/// Which tasks a command shows: those carrying every tag, in the milestone,
+/// and related to the root task.
+pub struct TaskFilter {
+    tags: Vec<String>,
+    milestone: Option<String>,
+    /// The root and its ancestors and descendants.
+    lineage: Option<HashSet<i64>>,
+}
+
+impl TaskFilter {
+    /// Validates the filters, resolving `root` against `graph`.
+    ///
+    /// # Errors
+    /// Returns error if a tag is invalid, the milestone does not exist, or
+    /// the root task cannot be resolved.
+    pub fn new(
+        conn: &Connection,
+        graph: &TaskGraph,
+        tags: &[String],
+        milestone: Option<&str>,
+        root: Option<&str>,
+    ) -> Result<Self> {
+        let tags = normalize_tags(tags)?;
+        let milestone = milestone.map(milestone::normalize).transpose()?;
+        if let Some(name) = &milestone {
+            if MilestoneRepo::new(conn).find(name)?.is_none() {
+                bail!("Unknown milestone '{name}'");
+            }
+        }
+        let lineage = match root {
+            Some(root) => {
+                let resolver = if Config::load()?.resolve.strict {
+                    TaskResolver::strict(conn)
+                } else {
+                    TaskResolver::new(conn)
+                };
+                Some(graph.get_lineage(resolver.resolve(root)?.task.id))
+            }
+            None => None,
+        };
+        Ok(Self {
+            tags,
+            milestone,
+            lineage,
+        })
+    }
+
+    #[must_use]
+    pub fn matches(&self, task: &Task) -> bool {
+        task.has_tags(&self.tags)
+            && self.milestone.as_ref().is_none_or(|name| task.milestone.as_ref() == Some(name))
+            && self.lineage.as_ref().is_none_or(|ids| ids.contains(&task.id))
+    }
+}
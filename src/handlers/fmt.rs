@@ -0,0 +1,42 @@
+//! Handler for the `fmt` command.
+
+use anyhow::{bail, Context, Result};
+use crate::theme::Paint;
+use roadmap::engine::plan::{self, Plan};
+use std::fs;
+use std::path::Path;
+
+/// Rewrites the plan file in canonical form, or only checks it with `check`.
+///
+/// Comments are not preserved; the canonical file is data only.
+///
+/// # Errors
+/// Returns error if the plan is invalid, non-canonical under `check`, or cannot be written.
+pub fn handle(path: &Path, check: bool) -> Result<()> {
+    let original = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut plan = Plan::read(path)?;
+    plan::ensure_valid(path, &plan.validate())?;
+
+    plan.normalize();
+    let canonical = plan.render()?;
+    if canonical == original {
+        println!("{} {} is canonical", "✓".success(), path.display());
+        return Ok(());
+    }
+
+    if check {
+        bail!(
+            "{} is not canonical. Run `roadmap fmt` to fix it.",
+            path.display()
+        );
+    }
+    fs::write(path, canonical).with_context(|| format!("Failed to write {}", path.display()))?;
+    println!(
+        "{} Formatted {} ({} tasks)",
+        "✓".success(),
+        path.display(),
+        plan.tasks.len()
+    );
+    Ok(())
+}
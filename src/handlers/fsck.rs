@@ -0,0 +1,75 @@
+//! Handler for the `fsck` command.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::chain::{self, ChainReport};
+use roadmap::engine::db::Db;
+use roadmap::engine::repo::ProofRepo;
+use schemars::Schema;
+
+/// Verifies the proof hash chain.
+///
+/// # Errors
+/// Returns error if the database fails or the chain is broken.
+pub fn handle(json: bool) -> Result<()> {
+    let conn = Db::connect()?;
+    let repo = ProofRepo::new(&conn);
+    let report = chain::verify(&repo.get_chain()?, &repo.get_tombstones()?);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_human(&report);
+    }
+
+    if !report.is_intact() {
+        bail!("Proof chain is broken: {} issue(s) found", report.issues.len());
+    }
+    Ok(())
+}
+
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(ChainReport)
+}
+
+fn print_human(report: &ChainReport) {
+    println!("{} Proof Chain", "🔗".info());
+    println!("   Linked proofs: {}", report.checked);
+    if report.legacy > 0 {
+        println!(
+            "   Legacy proofs: {} {}",
+            report.legacy,
+            "(recorded before chaining, not covered)".dimmed()
+        );
+    }
+
+    if !report.removed.is_empty() {
+        println!("   Removed proofs: {}", report.removed.len());
+        for tombstone in &report.removed {
+            println!(
+                "     {}",
+                format!(
+                    "proof #{} of task #{}, {}: {}",
+                    tombstone.proof_id, tombstone.task_id, tombstone.removed_at, tombstone.reason
+                )
+                .dimmed()
+            );
+        }
+    }
+
+    if report.is_intact() {
+        println!("{} Chain intact. No retroactive edits detected.", "✓".success());
+        return;
+    }
+
+    for issue in &report.issues {
+        println!(
+            "   {} proof #{}: {}",
+            "✗".failure(),
+            issue.proof_id,
+            issue.problem.failure()
+        );
+    }
+}
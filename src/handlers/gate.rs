@@ -0,0 +1,77 @@
+//! Handler for the `gate` command.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::context::RepoContext;
+use roadmap::engine::db::Db;
+use roadmap::engine::gate::{self, GateReport, Policy, Selector};
+use roadmap::engine::repo::TaskRepo;
+use roadmap::engine::types::DerivedStatus;
+use schemars::Schema;
+
+/// Evaluates the roadmap against the given policy and fails if any rule is violated.
+///
+/// # Errors
+/// Returns error if a rule is malformed, the database fails, or the gate fails.
+pub fn handle(
+    require_proven: &[String],
+    forbid: &[String],
+    max_stale: Option<usize>,
+    json: bool,
+) -> Result<()> {
+    let policy = Policy {
+        require_proven: require_proven
+            .iter()
+            .map(|s| Selector::parse(s))
+            .collect::<Result<_>>()?,
+        forbid: forbid
+            .iter()
+            .map(|s| s.parse::<DerivedStatus>().map_err(anyhow::Error::msg))
+            .collect::<Result<_>>()?,
+        max_stale,
+    };
+    if policy.require_proven.is_empty() && policy.forbid.is_empty() && policy.max_stale.is_none() {
+        bail!("No rules given. Use --require-proven, --forbid, or --max-stale.");
+    }
+
+    let conn = Db::connect()?;
+    let tasks = TaskRepo::new(&conn).get_all()?;
+    let context = RepoContext::new()?;
+    let report = gate::evaluate(&tasks, &context, &policy)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_human(&report);
+    }
+
+    if !report.passed {
+        bail!("Gate failed: {} violation(s)", report.violations.len());
+    }
+    Ok(())
+}
+
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(GateReport)
+}
+
+fn print_human(report: &GateReport) {
+    if report.passed {
+        println!("{} Gate passed ({} tasks checked)", "✓".success(), report.checked);
+        return;
+    }
+
+    println!("{} Gate failed ({} tasks checked)", "✗".failure(), report.checked);
+    for v in &report.violations {
+        let subject = v.task.as_ref().map(|t| format!("[{t}] ")).unwrap_or_default();
+        println!(
+            "   {} {}{}  {}",
+            "✗".failure(),
+            subject.bold(),
+            v.detail,
+            format!("({})", v.rule).dimmed()
+        );
+    }
+}
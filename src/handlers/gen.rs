@@ -0,0 +1,52 @@
+//! Handler for the `gen` command.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::db::Db;
+use roadmap::engine::gen::{self, GenSpec};
+use std::time::Instant;
+
+/// Commits sampled as proof targets; older ones make their proofs stale.
+const COMMIT_SAMPLE: usize = 16;
+
+/// Fills an empty roadmap with a synthetic workload.
+///
+/// # Errors
+/// Returns error if the roadmap is not empty, HEAD cannot be resolved, or the database fails.
+pub fn handle(tasks: usize, edges: usize, seed: u64) -> Result<()> {
+    let conn = Db::connect()?;
+    let commits = gen::recent_commits(COMMIT_SAMPLE);
+    if commits.is_empty() {
+        bail!("gen needs at least one commit to record proofs against");
+    }
+
+    let started = Instant::now();
+    let summary = gen::populate(
+        &conn,
+        &GenSpec {
+            tasks,
+            edges,
+            seed,
+            commits,
+        },
+    )?;
+
+    println!(
+        "{} Generated {} tasks, {} edges, {} proofs {}",
+        "✓".success(),
+        summary.tasks,
+        summary.edges,
+        summary.proofs,
+        format!("(seed {seed}, {:.1}s)", started.elapsed().as_secs_f64()).dimmed()
+    );
+    if summary.edges < edges {
+        println!(
+            "   {} only {} distinct acyclic edges fit {} tasks",
+            "⚠".warning(),
+            summary.edges,
+            summary.tasks
+        );
+    }
+    Ok(())
+}
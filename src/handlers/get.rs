@@ -0,0 +1,60 @@
+//! Handler for the `get` command.
+
+use anyhow::{bail, Result};
+use roadmap::engine::context::RepoContext;
+use roadmap::engine::db::Db;
+use roadmap::engine::resolver::TaskResolver;
+use serde_json::Value;
+
+/// Prints one raw field of a task, for shell scripts.
+///
+/// `status` is the derived status. Nested proof fields use dots
+/// (`proof.git_sha`). Lists print one item per line; a missing value prints
+/// nothing. The task must match exactly (strict resolution).
+///
+/// # Errors
+/// Returns error if the task or field does not exist.
+pub fn handle(task_ref: &str, field: &str) -> Result<()> {
+    let conn = Db::connect()?;
+    let task = TaskResolver::strict(&conn).resolve(task_ref)?.task;
+    let context = RepoContext::new()?;
+
+    let mut view = serde_json::to_value(&task)?;
+    if let Value::Object(map) = &mut view {
+        map.insert(
+            "status".to_string(),
+            Value::String(task.derive_status(&context).to_string()),
+        );
+    }
+
+    // Top-level names are a fixed schema, so a typo there is an error, not a blank.
+    let mut parts = field.split('.');
+    let head = parts.next().unwrap_or_default();
+    let Some(mut value) = view.get(head) else {
+        bail!("Unknown field '{head}'");
+    };
+    for part in parts {
+        value = match value {
+            Value::Object(map) => map.get(part).unwrap_or(&Value::Null),
+            Value::Null => &Value::Null,
+            _ => bail!("Field '{field}' has no member '{part}'"),
+        };
+    }
+
+    print_raw(value);
+    Ok(())
+}
+
+fn print_raw(value: &Value) {
+    match value {
+        Value::Null => {}
+        Value::String(s) => println!("{s}"),
+        Value::Array(items) => {
+            for item in items {
+                print_raw(item);
+            }
+        }
+        Value::Object(_) => println!("{value}"),
+        other => println!("{other}"),
+    }
+}
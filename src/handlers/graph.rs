@@ -0,0 +1,247 @@
+//! Handler for the `graph` command.
+
+use super::filter::TaskFilter;
+use anyhow::{bail, Result};
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::db::Db;
+use roadmap::engine::graph::TaskGraph;
+use roadmap::engine::types::{DerivedStatus, Task};
+use schemars::{JsonSchema, Schema};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// Components listed in full up to this size; larger ones are abbreviated.
+const COMPONENT_PREVIEW: usize = 6;
+
+/// Prints the task DAG as a diagram source in the requested format.
+///
+/// With `tags`, a milestone, or a `root`, only the tasks matching all of
+/// them and the edges between them are drawn.
+///
+/// # Errors
+/// Returns error if the format is unknown, a filter is invalid, or the database query fails.
+pub fn handle(format: &str, tags: &[String], milestone: Option<&str>, root: Option<&str>) -> Result<()> {
+    let render: fn(&TaskGraph, &[&Task]) -> String = match format {
+        "dot" => dot,
+        "mermaid" => mermaid,
+        other => bail!("Unknown graph format '{other}' (expected: dot, mermaid)"),
+    };
+
+    let conn = Db::connect()?;
+    let graph = TaskGraph::build(&conn)?;
+    let filter = TaskFilter::new(&conn, &graph, tags, milestone, root)?;
+    let mut tasks: Vec<&Task> = graph.tasks().filter(|t| filter.matches(t)).collect();
+    tasks.sort_by_key(|t| t.id);
+    print!("{}", render(&graph, &tasks));
+    Ok(())
+}
+
+/// Lists tasks with no links at all and the islands the roadmap splits into.
+///
+/// Components are found across the whole roadmap; with `tags` or a
+/// milestone, only the tasks matching them are listed.
+///
+/// # Errors
+/// Returns error if a filter is invalid or the database query fails.
+pub fn orphans(tags: &[String], milestone: Option<&str>, json: bool) -> Result<()> {
+    let conn = Db::connect()?;
+    let graph = TaskGraph::build(&conn)?;
+    let filter = TaskFilter::new(&conn, &graph, tags, milestone, None)?;
+    let mut orphans = Vec::new();
+    let mut components = Vec::new();
+    for component in graph.components() {
+        let shown: Vec<&Task> = component.iter().copied().filter(|t| filter.matches(t)).collect();
+        if shown.is_empty() {
+            continue;
+        }
+        if component.len() == 1 {
+            orphans.extend(shown);
+        } else {
+            components.push((component.len(), shown));
+        }
+    }
+
+    if json {
+        let report = OrphansReport {
+            orphans: orphans.iter().map(|t| t.slug.clone()).collect(),
+            components: components
+                .iter()
+                .map(|(size, tasks)| ComponentView {
+                    size: *size,
+                    tasks: tasks.iter().map(|t| t.slug.clone()).collect(),
+                })
+                .collect(),
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+    print_orphans(&orphans, &components);
+    Ok(())
+}
+
+/// JSON Schema of the `--orphans --json` output.
+pub(crate) fn orphans_schema() -> Schema {
+    schemars::schema_for!(OrphansReport)
+}
+
+#[derive(Serialize, JsonSchema)]
+struct OrphansReport {
+    /// Tasks with no dependencies, no dependents, no parent, and no subtasks.
+    orphans: Vec<String>,
+    /// Groups of two or more linked tasks, largest first.
+    components: Vec<ComponentView>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct ComponentView {
+    /// Tasks in the whole component, including any filtered out of `tasks`.
+    size: usize,
+    tasks: Vec<String>,
+}
+
+fn print_orphans(orphans: &[&Task], components: &[(usize, Vec<&Task>)]) {
+    if orphans.is_empty() {
+        println!("{} No orphans: every task is linked to another", "✓".success());
+    } else {
+        println!(
+            "{} {} orphaned (no dependencies, dependents, parent, or subtasks):",
+            "!".warning(),
+            plural(orphans.len())
+        );
+        for task in orphans {
+            println!("   [{}] {}", task.slug.warning(), task.title);
+        }
+    }
+    println!();
+    match components.len() {
+        0 => {}
+        1 => println!("{} Linked tasks form one connected component", "✓".success()),
+        n => {
+            println!("{} Linked tasks form {n} disconnected components:", "!".warning());
+            for (i, (size, tasks)) in components.iter().enumerate() {
+                let mut names: Vec<String> = tasks
+                    .iter()
+                    .take(COMPONENT_PREVIEW)
+                    .map(|t| format!("[{}]", t.slug))
+                    .collect();
+                if tasks.len() > COMPONENT_PREVIEW {
+                    names.push(format!("… and {} more", tasks.len() - COMPONENT_PREVIEW));
+                }
+                println!("   {}. {}: {}", i + 1, plural(*size).bold(), names.join(", ").dimmed());
+            }
+        }
+    }
+}
+
+fn plural(count: usize) -> String {
+    if count == 1 {
+        "1 task".to_string()
+    } else {
+        format!("{count} tasks")
+    }
+}
+
+/// Edges between the shown tasks as (blocker, blocked) pairs in a stable order.
+fn edges<'a>(graph: &'a TaskGraph, tasks: &[&'a Task]) -> Vec<(&'a Task, &'a Task)> {
+    let shown: HashSet<i64> = tasks.iter().map(|t| t.id).collect();
+    let mut edges = Vec::new();
+    for task in tasks {
+        let mut blocked = graph.get_blocked_by(task.id);
+        blocked.retain(|t| shown.contains(&t.id));
+        blocked.sort_by_key(|t| t.id);
+        edges.extend(blocked.into_iter().map(|b| (*task, b)));
+    }
+    edges
+}
+
+/// Graphviz source; pipe it to `dot -Tpng`.
+fn dot(graph: &TaskGraph, tasks: &[&Task]) -> String {
+    let mut out = String::from(
+        "digraph roadmap {\n    rankdir=LR;\n    node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];\n\n",
+    );
+    for task in tasks {
+        let status = task.derive_status(graph.context());
+        let _ = writeln!(
+            out,
+            "    \"{}\" [label=\"{}\\n{}\\n({status})\", fillcolor=\"{}\"];",
+            dot_escape(&task.slug),
+            dot_escape(&task.slug),
+            dot_escape(&task.title),
+            fill_color(status)
+        );
+    }
+    out.push('\n');
+    for (from, to) in edges(graph, tasks) {
+        let _ = writeln!(out, "    \"{}\" -> \"{}\";", dot_escape(&from.slug), dot_escape(&to.slug));
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// A fenced Mermaid flowchart, ready to paste into Markdown that renders it (GitHub does).
+///
+/// Nodes are keyed by task ID because Mermaid reads `--` inside bare IDs as an edge.
+fn mermaid(graph: &TaskGraph, tasks: &[&Task]) -> String {
+    let mut out = String::from("```mermaid\nflowchart TD\n");
+    for task in tasks {
+        let status = task.derive_status(graph.context());
+        let _ = writeln!(
+            out,
+            "    t{}[\"<b>{}</b><br/>{}\"]:::{}",
+            task.id,
+            mermaid_escape(&task.slug),
+            mermaid_escape(&task.title),
+            status_class(status)
+        );
+    }
+    for (from, to) in edges(graph, tasks) {
+        let _ = writeln!(out, "    t{} --> t{}", from.id, to.id);
+    }
+    for status in [
+        DerivedStatus::Proven,
+        DerivedStatus::Stale,
+        DerivedStatus::Broken,
+        DerivedStatus::Unproven,
+        DerivedStatus::Attested,
+        DerivedStatus::Quarantined,
+    ] {
+        let _ = writeln!(
+            out,
+            "    classDef {} fill:{},stroke:#555,color:#000",
+            status_class(status),
+            fill_color(status)
+        );
+    }
+    out.push_str("```\n");
+    out
+}
+
+/// Mermaid labels are HTML: quotes and angle brackets become entities.
+fn mermaid_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "#quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn status_class(status: DerivedStatus) -> String {
+    status.to_string().to_lowercase()
+}
+
+/// Light fills so the black labels stay readable on every status.
+fn fill_color(status: DerivedStatus) -> &'static str {
+    match status {
+        DerivedStatus::Proven => "#c8e6c9",
+        DerivedStatus::Stale => "#fff3b0",
+        DerivedStatus::Broken => "#ffcdd2",
+        DerivedStatus::Unproven => "#eeeeee",
+        DerivedStatus::Attested => "#bbdefb",
+        DerivedStatus::Quarantined => "#e1bee7",
+    }
+}
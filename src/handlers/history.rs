@@ -1,73 +1,202 @@
 //! Handler for the `history` command.
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use chrono::{Days, NaiveDate};
 use colored::Colorize;
+use crate::table::{Align, Cell, Table};
+use crate::theme::Paint;
 use roadmap::engine::db::Db;
-use roadmap::engine::repo::ProofRepo;
+use roadmap::engine::due::DATE_FORMAT;
+use roadmap::engine::repo::{HistoryFilter, ProofRepo, TaskRepo};
+use roadmap::engine::resolver::TaskResolver;
+use roadmap::engine::retention;
+use roadmap::engine::trailers::{self, TaskCommit};
 use roadmap::engine::types::Proof;
+use rusqlite::Connection;
+use schemars::{JsonSchema, Schema};
 use serde::Serialize;
+use std::collections::HashMap;
 
-/// Displays the global verification history.
+/// Which proofs to show, as typed.
+pub struct HistoryQuery<'a> {
+    /// A task reference; only its proofs are shown.
+    pub task: Option<&'a str>,
+    pub failed: bool,
+    /// `YYYY-MM-DD`, or an age like `7d` or `2w`.
+    pub since: Option<&'a str>,
+    /// A commit SHA or prefix.
+    pub sha: Option<&'a str>,
+}
+
+/// Displays the verification history, newest first, narrowed by `query`.
+///
+/// With `commits`, each proof lists the commits naming its task (see
+/// [`trailers`]) between the task's previous proof and this one: the work
+/// the proof verified.
 ///
 /// # Errors
-/// Returns error if database query fails.
-pub fn handle(limit: usize, json: bool) -> Result<()> {
+/// Returns error if the task cannot be resolved, the date is invalid, or the database query fails.
+pub fn handle(query: &HistoryQuery<'_>, limit: usize, json: bool, commits: bool) -> Result<()> {
     let conn = Db::connect()?;
     let proof_repo = ProofRepo::new(&conn);
-    
-    let history = proof_repo.get_global_history(limit)?;
+
+    let task = query
+        .task
+        .map(|r| TaskResolver::new(&conn).resolve(r).map(|result| result.task))
+        .transpose()?;
+    let filter = HistoryFilter {
+        task_id: task.as_ref().map(|t| t.id),
+        failed: query.failed,
+        since: query.since.map(parse_since).transpose()?,
+        sha: query.sha.map(str::to_lowercase),
+    };
+    let history = proof_repo.get_global_history(&filter, limit)?;
+    let linked = if commits {
+        Some(correlate(&conn, &history)?)
+    } else {
+        None
+    };
 
     if json {
-        return print_json(&history);
+        return print_json(&history, linked.as_deref());
     }
 
-    print_human(&history, limit);
+    let title = match &task {
+        Some(task) => format!("History of [{}] (last {limit})", task.slug),
+        None => format!("Project History (last {limit})"),
+    };
+    let filtered = task.is_some() || query.failed || query.since.is_some() || query.sha.is_some();
+    print_human(&title, &history, linked.as_deref(), filtered);
     Ok(())
 }
 
-#[derive(Serialize)]
+/// The first day `--since` admits: a date, or that many days before today (UTC, like proof timestamps).
+pub(crate) fn parse_since(input: &str) -> Result<String> {
+    if let Ok(date) = NaiveDate::parse_from_str(input.trim(), DATE_FORMAT) {
+        return Ok(date.format(DATE_FORMAT).to_string());
+    }
+    let Ok(days) = retention::parse_age(input) else {
+        bail!("Invalid --since '{input}' (expected YYYY-MM-DD, or an age like 7d or 2w)");
+    };
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let days = Days::new(days as u64);
+    let today = chrono::Utc::now().date_naive();
+    Ok(today.checked_sub_days(days).unwrap_or(NaiveDate::MIN).format(DATE_FORMAT).to_string())
+}
+
+/// The commits each proof in `history` covers, in the same order.
+fn correlate(conn: &Connection, history: &[(String, Proof)]) -> Result<Vec<Vec<TaskCommit>>> {
+    let tasks = TaskRepo::new(conn);
+    let proofs = ProofRepo::new(conn);
+    // slug -> (names incl. aliases, the task's proofs newest first)
+    let mut cache: HashMap<&str, (Vec<String>, Vec<Proof>)> = HashMap::new();
+    let mut linked = Vec::with_capacity(history.len());
+    for (slug, proof) in history {
+        if !cache.contains_key(slug.as_str()) {
+            let entry = match tasks.find_by_slug(slug)? {
+                Some(task) => {
+                    let mut names = tasks.get_aliases(task.id)?;
+                    names.push(task.slug);
+                    (names, proofs.get_history(task.id)?)
+                }
+                None => (vec![slug.clone()], Vec::new()),
+            };
+            cache.insert(slug, entry);
+        }
+        let (names, task_proofs) = &cache[slug.as_str()];
+        let previous = task_proofs
+            .iter()
+            .skip_while(|p| p.timestamp != proof.timestamp || p.git_sha != proof.git_sha)
+            .nth(1)
+            .filter(|p| p.git_sha != proof.git_sha);
+        let range = previous.map_or_else(
+            || proof.git_sha.clone(),
+            |p| format!("{}..{}", p.git_sha, proof.git_sha),
+        );
+        // Proofs from other clones may name commits this one lacks.
+        linked.push(trailers::commits_for(names, &range).unwrap_or_default());
+    }
+    Ok(linked)
+}
+
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(Vec<HistoryEntry>)
+}
+
+#[derive(Serialize, JsonSchema)]
 struct HistoryEntry {
     slug: String,
     proof: Proof,
+    /// With `--commits`: commits naming the task since its previous proof.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commits: Option<Vec<TaskCommit>>,
 }
 
-fn print_json(history: &[(String, Proof)]) -> Result<()> {
-    let entries: Vec<HistoryEntry> = history.iter().map(|(slug, proof)| {
+fn print_json(history: &[(String, Proof)], linked: Option<&[Vec<TaskCommit>]>) -> Result<()> {
+    let entries: Vec<HistoryEntry> = history.iter().enumerate().map(|(i, (slug, proof))| {
         HistoryEntry {
             slug: slug.clone(),
             proof: proof.clone(),
+            commits: linked.map(|l| l[i].clone()),
         }
     }).collect();
     println!("{}", serde_json::to_string_pretty(&entries)?);
     Ok(())
 }
 
-fn print_human(history: &[(String, Proof)], limit: usize) {
-    println!("{} Project History (last {})", "📜".cyan(), limit);
+fn print_human(title: &str, history: &[(String, Proof)], linked: Option<&[Vec<TaskCommit>]>, filtered: bool) {
+    println!("{} {title}", "📜".info());
     println!();
 
     if history.is_empty() {
-        println!("   (No history recorded yet)");
+        println!("   {}", if filtered { "(No proofs match)" } else { "(No history recorded yet)" });
         return;
     }
 
-    for (slug, proof) in history {
-        let timestamp = &proof.timestamp[..19.min(proof.timestamp.len())].replace('T', " ");
-        
+    let mut columns = vec![Align::Left, Align::Left, Align::Left, Align::Right];
+    if linked.is_some() {
+        columns.push(Align::Left);
+    }
+    let mut table = Table::new(&columns).flex(2);
+    for (i, (slug, proof)) in history.iter().enumerate() {
+        let timestamp = proof.timestamp[..19.min(proof.timestamp.len())].replace('T', " ");
+
         let status = if proof.attested_reason.is_some() {
-            "ATTESTED".blue()
-        } else if proof.exit_code == 0 {
-            "PASS    ".green()
+            Cell::new("ATTESTED", |s| s.accent())
+        } else if proof.exit_code == 0 && proof.failure.is_none() {
+            Cell::new("PASS", |s| s.success())
         } else {
-            "FAIL    ".red()
+            Cell::new("FAIL", |s| s.failure())
         };
 
-        println!(
-            "   {}  {}  {}  {}",
-            timestamp.dimmed(),
+        let mut row = vec![
+            Cell::new(timestamp, |s| s.dimmed()),
             status,
-            slug.bold(),
-            format!("{}ms", proof.duration_ms).dimmed()
-        );
+            Cell::new(slug, |s| s.bold()),
+            Cell::new(format!("{}ms", proof.duration_ms), |s| s.dimmed()),
+        ];
+        if let Some(linked) = linked {
+            row.push(Cell::new(commit_summary(&linked[i]), |s| s.warning()));
+        }
+        table.row(row);
+    }
+    for line in table.render() {
+        println!("{line}");
+    }
+}
+
+/// Short SHAs of up to three commits, with a count of the rest.
+fn commit_summary(commits: &[TaskCommit]) -> String {
+    let mut shown: Vec<&str> = commits
+        .iter()
+        .take(3)
+        .map(|c| &c.sha[..7.min(c.sha.len())])
+        .collect();
+    let more = commits.len().saturating_sub(shown.len());
+    let extra = format!("+{more}");
+    if more > 0 {
+        shown.push(&extra);
     }
-}
\ No newline at end of file
+    shown.join(" ")
+}
@@ -0,0 +1,174 @@
+//! Handlers for the `hooks` command group: git hooks that guard proofs.
+//! Also runs the `.roadmap/hooks` lifecycle scripts for the commands that fire them.
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::lifecycle::{self, Payload};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Identifies hooks written by `roadmap`, so they can be replaced or removed safely.
+const MARKER: &str = "# roadmap-managed-hook";
+
+const HOOKS: &[&str] = &["pre-push", "pre-commit"];
+
+/// Runs the lifecycle script for the payload's event, warning on stderr if it fails.
+///
+/// The command it follows has already succeeded, so nothing here is an error.
+pub(crate) fn fire(payload: &Payload<'_>) {
+    let script = lifecycle::script(payload.event);
+    match lifecycle::fire(payload) {
+        Ok(None | Some(true)) => {}
+        Ok(Some(false)) => eprintln!("{} {} failed", "⚠".warning(), script.display()),
+        Err(e) => eprintln!("{} {e:#}", "⚠".warning()),
+    }
+}
+
+/// Installs git hooks that refuse to commit or push while proofs are stale or broken.
+///
+/// An existing hook that roadmap did not write is left alone unless `force`
+/// is set, in which case it is kept next to ours as `<hook>.local` and still
+/// runs first.
+///
+/// # Errors
+/// Returns error if a hook name is unknown, this is not a git repository, or a hook cannot be written.
+pub fn install(hooks: &[String], force: bool) -> Result<()> {
+    let hooks = validate(hooks)?;
+    let dir = hooks_dir()?;
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let roadmap = std::env::current_exe().context("Failed to locate the roadmap binary")?;
+
+    for hook in hooks {
+        let path = dir.join(hook);
+        let existing = fs::read_to_string(&path).ok();
+        let chained = match &existing {
+            Some(content) if !content.contains(MARKER) => {
+                if !force {
+                    bail!(
+                        "{} already exists and was not written by roadmap. Use --force to keep it as {hook}.local and install ours in front.",
+                        path.display()
+                    );
+                }
+                let local = dir.join(format!("{hook}.local"));
+                fs::rename(&path, &local)
+                    .with_context(|| format!("Failed to move {} aside", path.display()))?;
+                println!("   Kept the existing hook as {}", local.display());
+                true
+            }
+            _ => dir.join(format!("{hook}.local")).exists(),
+        };
+
+        fs::write(&path, script(hook, &roadmap.display().to_string(), chained))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        make_executable(&path)?;
+        let ours = existing.as_deref().is_some_and(|c| c.contains(MARKER));
+        let verb = if ours { "Updated" } else { "Installed" };
+        println!("{} {verb} {} hook at {}", "✓".success(), hook.accent(), path.display());
+    }
+    Ok(())
+}
+
+/// Removes the hooks `install` wrote, restoring any hook it moved aside.
+///
+/// # Errors
+/// Returns error if a hook name is unknown, this is not a git repository, or a file cannot be changed.
+pub fn uninstall(hooks: &[String]) -> Result<()> {
+    let hooks = validate(hooks)?;
+    let dir = hooks_dir()?;
+    for hook in hooks {
+        let path = dir.join(hook);
+        match fs::read_to_string(&path) {
+            Ok(content) if content.contains(MARKER) => {
+                fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+                let local = dir.join(format!("{hook}.local"));
+                if local.exists() {
+                    fs::rename(&local, &path)
+                        .with_context(|| format!("Failed to restore {}", local.display()))?;
+                    println!("{} Removed {} hook, restored the previous one", "✓".success(), hook.accent());
+                } else {
+                    println!("{} Removed {} hook", "✓".success(), hook.accent());
+                }
+            }
+            Ok(_) => println!("   {} hook was not written by roadmap; left alone", hook.dimmed()),
+            Err(_) => println!("   No {} hook installed", hook.dimmed()),
+        }
+    }
+    Ok(())
+}
+
+fn validate(hooks: &[String]) -> Result<Vec<&'static str>> {
+    if hooks.is_empty() {
+        return Ok(vec!["pre-push"]);
+    }
+    hooks
+        .iter()
+        .map(|h| {
+            HOOKS
+                .iter()
+                .copied()
+                .find(|known| known == h)
+                .with_context(|| format!("Unknown hook '{h}' (expected: {})", HOOKS.join(", ")))
+        })
+        .collect()
+}
+
+/// The hooks directory git actually uses, honoring `core.hooksPath`.
+fn hooks_dir() -> Result<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-path", "hooks"])
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!("Not a git repository");
+    }
+    Ok(PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+/// A POSIX shell hook that blocks on stale or broken proofs.
+///
+/// It prefers `roadmap` on PATH and falls back to the binary that installed
+/// it. A roadmap that cannot run (not initialized, binary gone) never blocks.
+fn script(hook: &str, installer: &str, chained: bool) -> String {
+    let action = if hook == "pre-push" { "push" } else { "commit" };
+    let local = if chained {
+        format!("\"$(dirname \"$0\")/{hook}.local\" \"$@\" || exit $?\n")
+    } else {
+        String::new()
+    };
+    format!(
+        r#"#!/bin/sh
+{MARKER}
+# Installed by `roadmap hooks install`; remove with `roadmap hooks uninstall`.
+# Refuses to {action} while proofs covering changed code are stale or broken.
+{local}roadmap=$(command -v roadmap || echo '{installer}')
+[ -x "$roadmap" ] || exit 0
+report=$("$roadmap" stale --json 2>/dev/null) || exit 0
+if printf '%s' "$report" | grep -q '"stale_count": 0,' &&
+   printf '%s' "$report" | grep -q '"broken_count": 0,'; then
+    exit 0
+fi
+
+echo "roadmap: refusing to {action}; these proofs no longer hold:" >&2
+"$roadmap" stale >&2
+echo >&2
+echo "Re-verify with \`roadmap do <task> && roadmap check\`," >&2
+echo "or skip this check once with \`git {action} --no-verify\`." >&2
+exit 1
+"#
+    )
+}
+
+#[cfg(unix)]
+fn make_executable(path: &std::path::Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("Failed to make {} executable", path.display()))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &std::path::Path) -> Result<()> {
+    Ok(())
+}
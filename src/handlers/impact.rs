@@ -0,0 +1,127 @@
+//! Handler for the `impact` command.
+
+use anyhow::Result;
+use colored::Colorize;
+use super::why::{self, WhyOptions};
+use crate::theme::Paint;
+use roadmap::engine::context::RepoContext;
+use roadmap::engine::db::Db;
+use roadmap::engine::impact::{self, TaskImpact};
+use roadmap::engine::repo::TaskRepo;
+use roadmap::engine::resolver::TaskResolver;
+use schemars::{JsonSchema, Schema};
+use serde::Serialize;
+
+/// Lists tasks whose scopes intersect the files changed in a commit range,
+/// or for a task rather than a range, what proving it unblocks.
+///
+/// An exact task ID, slug, or alias wins over a git revision of the same
+/// name; anything git cannot parse is matched against tasks fuzzily.
+///
+/// # Errors
+/// Returns error if the range is invalid, the task cannot be resolved, or the
+/// database query fails.
+pub fn handle(target: &str, json: bool) -> Result<()> {
+    let conn = Db::connect()?;
+    if TaskResolver::strict(&conn).resolve(target).is_ok() || !impact::is_revision(target) {
+        let opts = WhyOptions {
+            json,
+            impact: true,
+            ..WhyOptions::default()
+        };
+        return why::handle(target, &opts);
+    }
+    let range = target;
+    let tasks = TaskRepo::new(&conn).get_all()?;
+    let context = RepoContext::new()?;
+    let impacts = impact::assess(&tasks, &context, range)?;
+
+    if json {
+        return print_json(range, &impacts);
+    }
+
+    print_human(range, &impacts);
+    Ok(())
+}
+
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(ImpactReport<'static>)
+}
+
+#[derive(Serialize, JsonSchema)]
+struct ImpactReport<'a> {
+    range: &'a str,
+    touched_count: usize,
+    tasks: Vec<ImpactView<'a>>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct ImpactView<'a> {
+    id: i64,
+    slug: &'a str,
+    title: &'a str,
+    current: String,
+    projected: String,
+    files: &'a [String],
+}
+
+fn print_json(range: &str, impacts: &[TaskImpact<'_>]) -> Result<()> {
+    let report = ImpactReport {
+        range,
+        touched_count: impacts.len(),
+        tasks: impacts
+            .iter()
+            .map(|i| ImpactView {
+                id: i.task.id,
+                slug: &i.task.slug,
+                title: &i.task.title,
+                current: i.current.to_string(),
+                projected: i.projected.to_string(),
+                files: &i.files,
+            })
+            .collect(),
+    };
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+fn print_human(range: &str, impacts: &[TaskImpact<'_>]) {
+    if impacts.is_empty() {
+        println!(
+            "{} No task scopes touched by {}",
+            "✓".success(),
+            range.dimmed()
+        );
+        return;
+    }
+
+    println!(
+        "🎯 Impact of {}: {} tasks touched\n",
+        range.info(),
+        impacts.len()
+    );
+    for i in impacts {
+        let transition = if i.current == i.projected {
+            i.current.to_string().dimmed().to_string()
+        } else {
+            format!(
+                "{} → {}",
+                i.current.to_string().success(),
+                i.projected.to_string().warning()
+            )
+        };
+        println!(
+            "   [{}] {}  {}",
+            i.task.slug.warning(),
+            i.task.title,
+            transition
+        );
+        for file in i.files.iter().take(5) {
+            println!("     {} {}", "·".dimmed(), file.dimmed());
+        }
+        if i.files.len() > 5 {
+            println!("     {} … and {} more", "·".dimmed(), i.files.len() - 5);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Handler for the `import` command.
+
+use anyhow::{bail, Context, Result};
+use crate::theme::Paint;
+use roadmap::engine::db::Db;
+use roadmap::engine::plan;
+use roadmap::engine::repo::TaskRepo;
+use roadmap::engine::snapshot::Snapshot;
+use std::fs;
+use std::path::Path;
+
+/// Restores a bundle written by `export --format json`.
+///
+/// The whole bundle is checked first: it must be internally consistent and
+/// acyclic, and none of its slugs may already exist here. Only then are its
+/// tasks, edges, and proofs written, in one transaction.
+///
+/// # Errors
+/// Returns error if the format is unsupported, the bundle is invalid, a slug collides, or the database fails.
+pub fn handle(path: &Path, format: &str) -> Result<()> {
+    if format != "json" {
+        bail!("Unknown import format '{format}' (expected: json)");
+    }
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let bundle: Snapshot = serde_json::from_str(&raw)
+        .with_context(|| format!("{} is not a roadmap bundle", path.display()))?;
+    plan::ensure_valid(path, &bundle.validate())?;
+
+    let conn = Db::connect()?;
+    let tx = Db::begin_write(&conn)?;
+    let repo = TaskRepo::new(&tx);
+    let mut collisions = Vec::new();
+    for task in &bundle.tasks {
+        if repo.find_by_slug(&task.slug)?.is_some() || repo.find_by_alias(&task.slug)?.is_some() {
+            collisions.push(format!("[{}]", task.slug));
+        }
+    }
+    if !collisions.is_empty() {
+        bail!(
+            "{} slug(s) already exist here: {}. Nothing was imported.",
+            collisions.len(),
+            collisions.join(", ")
+        );
+    }
+
+    let summary = bundle.apply(&tx)?;
+    tx.commit()?;
+
+    println!(
+        "{} Imported {} tasks, {} edges, and {} proofs from {}",
+        "✓".success(),
+        summary.tasks_added,
+        summary.edges_added,
+        summary.proofs_added,
+        path.display()
+    );
+    Ok(())
+}
@@ -1,7 +1,7 @@
 //! Handler for the `init` command.
 
 use anyhow::Result;
-use colored::Colorize;
+use crate::theme::Paint;
 use roadmap::engine::db::Db;
 
 /// Initializes the roadmap repository.
@@ -10,6 +10,6 @@ use roadmap::engine::db::Db;
 /// Returns error if database initialization fails.
 pub fn handle() -> Result<()> {
     Db::init()?;
-    println!("{} Initialized .roadmap/state.db", "✓".green());
+    println!("{} Initialized {}", "✓".success(), Db::path().display());
     Ok(())
 }
\ No newline at end of file
@@ -1,53 +1,108 @@
 //! Handler for the `list` command.
 
+use super::filter::TaskFilter;
 use anyhow::Result;
 use colored::Colorize;
+use crate::table::{Align, Cell, Table};
+use crate::theme::Paint;
 use roadmap::engine::context::RepoContext;
+use roadmap::engine::graph::TaskGraph;
 use roadmap::engine::db::Db;
 use roadmap::engine::repo::TaskRepo;
 use roadmap::engine::types::Task;
+use schemars::{JsonSchema, Schema};
 use serde::Serialize;
+use std::collections::HashSet;
 
-/// Lists all tasks in the repository.
+/// Lists all tasks in the repository, or those carrying every one of `tags`,
+/// in the given milestone and related to the `root` task.
+///
+/// With `tree`, subtasks are indented under their parents, which show how
+/// many of the tasks below them are done.
 ///
 /// # Errors
-/// Returns error if database query fails.
-pub fn handle(json: bool) -> Result<()> {
+/// Returns error if a tag is invalid, the milestone does not exist, the root
+/// cannot be resolved, or the database query fails.
+pub fn handle(json: bool, tags: &[String], milestone: Option<&str>, root: Option<&str>, tree: bool) -> Result<()> {
     let conn = Db::connect()?;
+    let graph = TaskGraph::build(&conn)?;
+    let filter = TaskFilter::new(&conn, &graph, tags, milestone, root)?;
     let repo = TaskRepo::new(&conn);
-    let tasks = repo.get_all()?;
-    let context = RepoContext::new()?;
+    let mut tasks = repo.get_all()?;
+    tasks.retain(|t| filter.matches(t));
+    let context = graph.context();
+    let rows: Vec<(&Task, usize)> = if tree {
+        tree_order(&tasks)
+    } else {
+        tasks.iter().map(|t| (t, 0)).collect()
+    };
 
     if json {
-        return print_json(&tasks, &context);
+        let ordered: Vec<Task> = rows.into_iter().map(|(t, _)| t.clone()).collect();
+        return print_json(&ordered, context);
     }
 
-    println!("{} All Tasks:", "📋".cyan());
+    println!("{} All Tasks:", "📋".info());
 
-    for task in tasks {
-        let derived = task.derive_status(&context);
-        println!(
-            "   [{}] {} ({})",
-            task.slug.blue(),
-            task.title,
-            derived.to_string().dimmed()
-        );
+    // The tags column only appears once some task is tagged.
+    let tagged = tasks.iter().any(|t| !t.tags.is_empty());
+    let aligns: &[Align] = if tagged {
+        &[Align::Left, Align::Left, Align::Left, Align::Left]
+    } else {
+        &[Align::Left, Align::Left, Align::Left]
+    };
+    let mut table = Table::new(aligns).flex(1);
+    for (task, depth) in rows {
+        let derived = task.derive_status(context);
+        let status = if tree && !task.subtasks.is_empty() {
+            let (done, total) = task.subtask_progress(context);
+            format!("({derived}, {done}/{total} done)")
+        } else {
+            format!("({derived})")
+        };
+        let mut cells = vec![
+            Cell::new(format!("{}[{}]", "  ".repeat(depth), task.slug), |s| s.accent()),
+            Cell::plain(&task.title),
+            Cell::new(status, |s| s.dimmed()),
+        ];
+        if tagged {
+            cells.push(Cell::new(tag_labels(&task.tags), |s| s.info()));
+        }
+        table.row(cells);
+    }
+    for line in table.render() {
+        println!("{line}");
     }
     Ok(())
 }
 
-#[derive(Serialize)]
-struct TaskView {
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(Vec<TaskView>)
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct TaskView {
     id: i64,
     slug: String,
     title: String,
     status: String,
     test_cmd: Option<String>,
     scopes: Vec<String>,
+    tags: Vec<String>,
+    /// Slug of the parent task.
+    parent: Option<String>,
+    assignee: Option<String>,
 }
 
 fn print_json(tasks: &[Task], context: &RepoContext) -> Result<()> {
-    let views: Vec<TaskView> = tasks.iter().map(|t| {
+    println!("{}", serde_json::to_string_pretty(&views(tasks, context))?);
+    Ok(())
+}
+
+/// The `--json` rows for a task list.
+pub(crate) fn views(tasks: &[Task], context: &RepoContext) -> Vec<TaskView> {
+    tasks.iter().map(|t| {
         let status = t.derive_status(context);
         TaskView {
             id: t.id,
@@ -56,9 +111,36 @@ fn print_json(tasks: &[Task], context: &RepoContext) -> Result<()> {
             status: format!("{status:?}"), // Serialize enum variant name
             test_cmd: t.test_cmd.clone(),
             scopes: t.scopes.clone(),
+            tags: t.tags.clone(),
+            parent: t.parent.clone(),
+            assignee: t.assignee.clone(),
+        }
+    }).collect()
+}
+
+/// `#infra #ui`-style labels for a task's tags.
+pub(crate) fn tag_labels(tags: &[String]) -> String {
+    tags.iter().map(|t| format!("#{t}")).collect::<Vec<_>>().join(" ")
+}
+
+/// Tasks depth-first from the top-level ones, each with its nesting depth.
+///
+/// A task whose parent is not in `tasks` (filtered out) starts a tree of its own.
+fn tree_order(tasks: &[Task]) -> Vec<(&Task, usize)> {
+    fn visit<'a>(task: &'a Task, depth: usize, tasks: &'a [Task], out: &mut Vec<(&'a Task, usize)>) {
+        out.push((task, depth));
+        for child in tasks.iter().filter(|t| t.parent.as_deref() == Some(task.slug.as_str())) {
+            visit(child, depth + 1, tasks, out);
         }
-    }).collect();
+    }
 
-    println!("{}", serde_json::to_string_pretty(&views)?);
-    Ok(())
-}
\ No newline at end of file
+    let shown: HashSet<&str> = tasks.iter().map(|t| t.slug.as_str()).collect();
+    let mut out = Vec::new();
+    for root in tasks
+        .iter()
+        .filter(|t| t.parent.as_deref().is_none_or(|p| !shown.contains(p)))
+    {
+        visit(root, 0, tasks, &mut out);
+    }
+    out
+}
@@ -0,0 +1,78 @@
+//! Handler for the `log` command.
+
+use anyhow::Result;
+use colored::Colorize;
+use crate::table::{Align, Cell, Table};
+use crate::theme::Paint;
+use roadmap::engine::db::Db;
+use roadmap::engine::journal::{self, Event, EventFilter};
+use schemars::Schema;
+
+/// Which events to show, as typed.
+pub struct LogQuery<'a> {
+    pub actor: Option<&'a str>,
+    pub failed: bool,
+    /// `YYYY-MM-DD`, or an age like `7d` or `2w`.
+    pub since: Option<&'a str>,
+}
+
+/// Shows the journal of commands run against the roadmap, newest first.
+///
+/// # Errors
+/// Returns error if the date is invalid or the database query fails.
+pub fn handle(query: &LogQuery<'_>, limit: usize, json: bool) -> Result<()> {
+    let conn = Db::connect()?;
+    let filter = EventFilter {
+        actor: query.actor.map(str::to_string),
+        failed: query.failed,
+        since: query.since.map(super::history::parse_since).transpose()?,
+    };
+    let events = journal::recent(&conn, &filter, limit)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&events)?);
+        return Ok(());
+    }
+
+    println!("{} Activity (last {limit})", "📜".info());
+    if events.is_empty() {
+        let filtered = query.actor.is_some() || query.failed || query.since.is_some();
+        println!("   {}", if filtered { "(No events match)" } else { "(No commands recorded yet)" });
+        return Ok(());
+    }
+    let mut table = Table::new(&[Align::Left, Align::Left, Align::Left, Align::Left, Align::Right, Align::Left])
+        .flex(3);
+    for event in &events {
+        table.row(vec![
+            Cell::new(event.timestamp.clone(), |s| s.dimmed()),
+            outcome(event),
+            Cell::new(event.actor.clone(), |s| s.accent()),
+            Cell::plain(event.command.clone()),
+            Cell::plain(changes(event)),
+            Cell::new(event.error.clone().unwrap_or_default(), |s| s.failure()),
+        ]);
+    }
+    for line in table.render() {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+fn outcome(event: &Event) -> Cell {
+    if event.ok {
+        Cell::new("✓".to_string(), |s| s.success())
+    } else {
+        Cell::new("✗".to_string(), |s| s.failure())
+    }
+}
+
+fn changes(event: &Event) -> String {
+    match (event.changes, event.undone) {
+        (0, _) => String::new(),
+        (n, true) => format!("{n}× undone"),
+        (n, false) => format!("{n}×"),
+    }
+}
+
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(Vec<Event>)
+}
@@ -0,0 +1,128 @@
+//! Handler for the `merge-state` command.
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::db::Db;
+use roadmap::engine::merge::{self, MergePlan, Side};
+use roadmap::engine::snapshot::Snapshot;
+use roadmap::engine::state_ref;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+
+/// Merges another roadmap state into the local database.
+///
+/// # Errors
+/// Returns error if a state cannot be loaded, conflicts remain, or the database fails.
+pub fn handle(theirs: &str, base: Option<&Path>, side: Option<Side>) -> Result<()> {
+    let (theirs_snapshot, found_base) = load_theirs(theirs)?;
+    let base_snapshot = match base {
+        Some(path) => Some(read_file(path)?),
+        None => found_base,
+    };
+    if base_snapshot.is_none() {
+        println!(
+            "{} No common ancestor; every differing field is a conflict",
+            "⚠".warning()
+        );
+    }
+
+    let conn = Db::connect()?;
+    let ours = Snapshot::capture(&conn)?;
+    let mut plan = merge::plan(base_snapshot.as_ref(), &ours, &theirs_snapshot)?;
+
+    resolve_conflicts(&mut plan, side)?;
+    plan.ensure_resolved()?;
+
+    let summary = plan.apply(&conn)?;
+    println!(
+        "{} Merged {}: {} tasks added, {} updated, {} edges added, {} removed, {} proofs added",
+        "🔀".info(),
+        theirs.warning(),
+        summary.tasks_added,
+        summary.tasks_updated,
+        summary.edges_added,
+        summary.edges_removed,
+        summary.proofs_added
+    );
+    for edge in &summary.edges_rejected {
+        println!(
+            "   {} dropped edge {} → {} (would create a cycle)",
+            "⚠".warning(),
+            edge.blocker,
+            edge.blocked
+        );
+    }
+    Ok(())
+}
+
+/// Loads the incoming snapshot and, for git refs, the snapshot at the merge base.
+fn load_theirs(theirs: &str) -> Result<(Snapshot, Option<Snapshot>)> {
+    let path = Path::new(theirs);
+    if path.is_file() {
+        return Ok((read_file(path)?, None));
+    }
+
+    let Some((refname, sha)) = [theirs.to_string(), state_ref::remote_ref(theirs)]
+        .into_iter()
+        .find_map(|r| state_ref::resolve(&r).map(|sha| (r, sha)))
+    else {
+        bail!("'{theirs}' is not a snapshot file, state ref, or fetched remote");
+    };
+
+    let Some(json) = state_ref::read(&refname)? else {
+        bail!("No roadmap state at {refname}");
+    };
+    let base = match state_ref::resolve(&state_ref::local_ref()).and_then(|ours| state_ref::merge_base(&ours, &sha)) {
+        Some(commit) => state_ref::read(&commit)?.map(|j| Snapshot::parse(&j)).transpose()?,
+        None => None,
+    };
+    Ok((Snapshot::parse(&json)?, base))
+}
+
+fn read_file(path: &Path) -> Result<Snapshot> {
+    let json = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read snapshot {}", path.display()))?;
+    Snapshot::parse(&json).with_context(|| format!("Invalid snapshot {}", path.display()))
+}
+
+/// Applies a blanket strategy, or asks on the terminal for each conflict.
+fn resolve_conflicts(plan: &mut MergePlan, side: Option<Side>) -> Result<()> {
+    if plan.conflicts.is_empty() {
+        return Ok(());
+    }
+    if let Some(side) = side {
+        for i in 0..plan.conflicts.len() {
+            plan.resolve(i, side);
+        }
+        return Ok(());
+    }
+    if !io::stdin().is_terminal() {
+        return Ok(());
+    }
+
+    println!("{} {} conflict(s) to resolve:", "⚔".failure(), plan.conflicts.len());
+    let mut input = io::stdin().lock();
+    for i in 0..plan.conflicts.len() {
+        let c = &plan.conflicts[i];
+        println!("\n   [{}] {}", c.slug.warning(), c.field.bold());
+        println!("     (o)urs:   {}", c.ours);
+        println!("     (t)heirs: {}", c.theirs);
+        let side = loop {
+            print!("   keep which? [o/t] ");
+            io::stdout().flush()?;
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                bail!("Merge aborted: no answer for [{}] {}", c.slug, c.field);
+            }
+            match line.trim() {
+                "o" | "ours" => break Side::Ours,
+                "t" | "theirs" => break Side::Theirs,
+                _ => println!("   please answer o or t"),
+            }
+        };
+        plan.resolve(i, side);
+    }
+    Ok(())
+}
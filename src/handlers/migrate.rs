@@ -0,0 +1,45 @@
+//! Handler for the `migrate` command.
+
+use anyhow::Result;
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::db::{migrations, Db};
+
+/// Upgrades the database schema, or with `dry_run` lists what an upgrade would apply.
+///
+/// Every command upgrades the schema on connect; this makes it explicit, e.g.
+/// to see what a new release will change before running it on shared state.
+///
+/// # Errors
+/// Returns error if the roadmap is not initialized, was written by a newer roadmap, or a migration fails.
+pub fn handle(dry_run: bool) -> Result<()> {
+    let conn = Db::connect_unmigrated()?;
+    let current = migrations::current(&conn)?;
+    let pending = migrations::pending(&conn)?;
+    if pending.is_empty() {
+        println!("{} Schema is up to date (version {current})", "✓".success());
+        return Ok(());
+    }
+
+    let from = if current == 0 { "unversioned".to_string() } else { format!("version {current}") };
+    if dry_run {
+        println!(
+            "{} {} migration(s) pending ({from} → {}):",
+            "🗄".info(),
+            pending.len(),
+            migrations::latest()
+        );
+    } else {
+        migrations::apply(&conn)?;
+        println!(
+            "{} Applied {} migration(s) ({from} → {}):",
+            "✓".success(),
+            pending.len(),
+            migrations::latest()
+        );
+    }
+    for migration in pending {
+        println!("   {:>3}  {}", migration.version.to_string().dimmed(), migration.name);
+    }
+    Ok(())
+}
@@ -0,0 +1,198 @@
+//! Handlers for the `milestone` command group.
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use crate::table::{Align, Cell, Table};
+use crate::theme::{status_glyph, Paint};
+use roadmap::engine::db::Db;
+use roadmap::engine::graph::TaskGraph;
+use roadmap::engine::milestone::{self, MilestoneProgress};
+use roadmap::engine::repo::{MilestoneRepo, TaskRepo};
+use roadmap::engine::resolver::TaskResolver;
+use rusqlite::Connection;
+use schemars::{JsonSchema, Schema};
+use serde::Serialize;
+
+/// Width of a progress bar in cells.
+const BAR_WIDTH: usize = 10;
+
+/// Creates a milestone.
+///
+/// # Errors
+/// Returns error if the name is invalid or taken, or the database fails.
+pub fn add(name: &str, title: Option<&str>) -> Result<()> {
+    let name = milestone::normalize(name)?;
+    let conn = Db::connect()?;
+    MilestoneRepo::new(&conn).add(&name, title)?;
+    println!("{} Added milestone {}", "✓".success(), name.accent());
+    println!("   Assign tasks with `roadmap milestone assign {name} <task>...`");
+    Ok(())
+}
+
+/// Moves tasks into a milestone, out of whichever one they were in.
+///
+/// # Errors
+/// Returns error if the milestone or a task does not exist, or the database fails.
+pub fn assign(name: &str, task_refs: &[String]) -> Result<()> {
+    let name = milestone::normalize(name)?;
+    let conn = Db::connect()?;
+    let tx = Db::begin_write(&conn)?;
+    let id = MilestoneRepo::new(&tx)
+        .find(&name)?
+        .with_context(|| format!("Unknown milestone '{name}' (create it with `roadmap milestone add {name}`)"))?;
+    for slug in set_milestone(&tx, task_refs, Some(id))? {
+        println!("{} [{}] is in milestone {}", "✓".success(), slug.warning(), name.accent());
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Takes tasks out of their milestone.
+///
+/// # Errors
+/// Returns error if a task does not exist or the database fails.
+pub fn unassign(task_refs: &[String]) -> Result<()> {
+    let conn = Db::connect()?;
+    let tx = Db::begin_write(&conn)?;
+    for slug in set_milestone(&tx, task_refs, None)? {
+        println!("{} [{}] is in no milestone", "✓".success(), slug.warning());
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Resolves every task first, so a typo changes nothing. Returns the slugs.
+fn set_milestone(conn: &Connection, task_refs: &[String], milestone_id: Option<i64>) -> Result<Vec<String>> {
+    let resolver = TaskResolver::new(conn);
+    let tasks = task_refs
+        .iter()
+        .map(|r| resolver.resolve(r).map(|result| result.task))
+        .collect::<Result<Vec<_>>>()?;
+    let repo = TaskRepo::new(conn);
+    for task in &tasks {
+        repo.set_milestone(task.id, milestone_id)?;
+    }
+    Ok(tasks.into_iter().map(|t| t.slug).collect())
+}
+
+/// JSON Schema of the `status --json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(Vec<MilestoneReport>)
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct MilestoneReport {
+    #[serde(flatten)]
+    progress: MilestoneProgress,
+    tasks: Vec<MilestoneTask>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct MilestoneTask {
+    slug: String,
+    title: String,
+    status: String,
+}
+
+/// Shows the completion of every milestone, or the tasks of one.
+///
+/// # Errors
+/// Returns error if the named milestone does not exist or the database fails.
+pub fn status(name: Option<&str>, json: bool) -> Result<()> {
+    let conn = Db::connect()?;
+    let mut milestones = MilestoneRepo::new(&conn).get_all()?;
+    if let Some(name) = name {
+        let name = milestone::normalize(name)?;
+        milestones.retain(|m| m.name == name);
+        if milestones.is_empty() {
+            bail!("Unknown milestone '{name}'");
+        }
+    }
+    let graph = TaskGraph::build(&conn)?;
+    let reports: Vec<MilestoneReport> = milestone::progress(&graph, &milestones)
+        .into_iter()
+        .map(|progress| {
+            let tasks = milestone::tasks(&graph, &progress.name)
+                .into_iter()
+                .map(|t| MilestoneTask {
+                    slug: t.slug.clone(),
+                    title: t.title.clone(),
+                    status: t.derive_status(graph.context()).to_string(),
+                })
+                .collect();
+            MilestoneReport { progress, tasks }
+        })
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+        return Ok(());
+    }
+    if name.is_some() {
+        print_tasks(&reports[0], &graph);
+    } else {
+        print_overview(&reports);
+    }
+    Ok(())
+}
+
+fn print_overview(reports: &[MilestoneReport]) {
+    println!("{} Milestones:", "🏁".info());
+    if reports.is_empty() {
+        println!("   (None yet; create one with `roadmap milestone add <name>`)");
+        return;
+    }
+    let mut table = Table::new(&[Align::Left, Align::Left, Align::Left, Align::Right, Align::Right]).flex(1);
+    for report in reports {
+        table.row(progress_cells(&report.progress));
+    }
+    for line in table.render() {
+        println!("{line}");
+    }
+}
+
+fn print_tasks(report: &MilestoneReport, graph: &TaskGraph) {
+    let progress = &report.progress;
+    println!(
+        "{} {} {} {}",
+        "🏁".info(),
+        progress.name.accent().bold(),
+        progress.title.as_deref().unwrap_or_default(),
+        format!("({}/{} done, {}%)", progress.done, progress.total, progress.percent).dimmed()
+    );
+    if report.tasks.is_empty() {
+        println!("   (No tasks; assign some with `roadmap milestone assign {} <task>...`)", progress.name);
+        return;
+    }
+    let mut table = Table::new(&[Align::Left, Align::Left, Align::Left, Align::Left]).flex(2);
+    for task in milestone::tasks(graph, &progress.name) {
+        let derived = task.derive_status(graph.context());
+        table.row(vec![
+            Cell::new(status_glyph(derived), move |s| s.status(derived)),
+            Cell::new(format!("[{}]", task.slug), |s| s.warning()),
+            Cell::plain(&task.title),
+            Cell::new(format!("({derived})"), |s| s.dimmed()),
+        ]);
+    }
+    for line in table.render() {
+        println!("{line}");
+    }
+}
+
+/// Name, title, bar, count, and percent cells for one milestone.
+pub(crate) fn progress_cells(progress: &MilestoneProgress) -> Vec<Cell> {
+    let complete = progress.total > 0 && progress.done == progress.total;
+    vec![
+        Cell::new(&progress.name, |s| s.accent()),
+        Cell::plain(progress.title.as_deref().unwrap_or_default()),
+        Cell::new(bar(progress.percent), move |s| if complete { s.success() } else { s.info() }),
+        Cell::new(format!("{}/{}", progress.done, progress.total), |s| s.dimmed()),
+        Cell::new(format!("{}%", progress.percent), move |s| if complete { s.success() } else { s.normal() }),
+    ]
+}
+
+/// A fixed-width bar filled to `percent`.
+fn bar(percent: usize) -> String {
+    let filled = (percent.min(100) * BAR_WIDTH + 50) / 100;
+    format!("{}{}", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled))
+}
@@ -0,0 +1,207 @@
+//! Handlers for the `mirror` command group: a git-mergeable copy of the state.
+
+use anyhow::{bail, Context, Result};
+use crate::theme::Paint;
+use roadmap::engine::db::Db;
+use roadmap::engine::merge;
+use roadmap::engine::mirror;
+use roadmap::engine::shell;
+use roadmap::engine::snapshot::Snapshot;
+use rusqlite::Connection;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Name of the merge driver in git config and `.gitattributes`.
+const DRIVER: &str = "roadmap";
+
+/// Writes the database to the mirror file, replacing whatever it holds.
+///
+/// # Errors
+/// Returns error if the database cannot be read or the file cannot be written.
+pub fn write() -> Result<()> {
+    let conn = Db::connect()?;
+    let changed = write_file(&conn)?;
+    let path = mirror::path();
+    if changed {
+        println!("{} Wrote {}", "✓".success(), path.display());
+    } else {
+        println!("{} {} is up to date", "✓".success(), path.display());
+    }
+    Ok(())
+}
+
+/// Rewrites the mirror after a mutating command, if the repository uses one.
+///
+/// A mirror changed on disk since the last sync is left alone with a
+/// warning, so a fresh clone or a merge never loses the file's contents.
+///
+/// # Errors
+/// Returns error if the database cannot be read or the file cannot be written.
+pub fn refresh() -> Result<()> {
+    let path = mirror::path();
+    let Ok(current) = fs::read_to_string(&path) else {
+        return Ok(());
+    };
+    if !Db::path().exists() {
+        return Ok(());
+    }
+    let conn = Db::connect()?;
+    if !mirror::is_synced(&conn, &current)? {
+        eprintln!(
+            "{} {} changed since this database last synced with it; run `roadmap mirror load` to apply it (or `mirror write` to overwrite it)",
+            "⚠".warning(),
+            path.display()
+        );
+        return Ok(());
+    }
+    write_file(&conn)?;
+    Ok(())
+}
+
+/// Returns whether the file changed.
+fn write_file(conn: &Connection) -> Result<bool> {
+    let text = mirror::render(&Snapshot::capture(conn)?)?;
+    let path = mirror::path();
+    let changed = !fs::read_to_string(&path).is_ok_and(|current| current == text);
+    if changed {
+        fs::write(&path, &text).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    mirror::mark_synced(conn, &text)?;
+    Ok(changed)
+}
+
+/// Brings the database up to date with the mirror, e.g. after a pull or merge.
+///
+/// The file is treated as a descendant of the database: task definitions and
+/// edges it changed are taken, tasks and proofs it adds are inserted. Nothing
+/// is deleted, and local proofs missing from the file are kept.
+///
+/// # Errors
+/// Returns error if the mirror cannot be parsed or the database fails.
+pub fn load() -> Result<()> {
+    let path = mirror::path();
+    let text = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {} (create it with `roadmap mirror write`)", path.display()))?;
+    let file = mirror::parse(&text)?;
+    let problems = file.validate();
+    if !problems.is_empty() {
+        bail!("{} is invalid:\n  {}", path.display(), problems.join("\n  "));
+    }
+
+    let conn = Db::connect()?;
+    let tx = Db::begin_write(&conn)?;
+    let ours = Snapshot::capture(&tx)?;
+    let summary = merge::plan(Some(&ours), &ours, &file)?.apply(&tx)?;
+    mirror::mark_synced(&tx, &text)?;
+    tx.commit()?;
+
+    println!(
+        "{} Loaded {}: {} tasks added, {} updated, {} edges added, {} removed, {} proofs added",
+        "⬇".info(),
+        path.display(),
+        summary.tasks_added,
+        summary.tasks_updated,
+        summary.edges_added,
+        summary.edges_removed,
+        summary.proofs_added
+    );
+    for edge in &summary.edges_rejected {
+        println!(
+            "   {} dropped edge {} → {} (would create a cycle)",
+            "⚠".warning(),
+            edge.blocker,
+            edge.blocked
+        );
+    }
+    Ok(())
+}
+
+/// Registers the merge driver in git config and `.gitattributes`, and writes the mirror.
+///
+/// # Errors
+/// Returns error if git config cannot be set or a file cannot be written.
+pub fn install() -> Result<()> {
+    let program = if shell::which("roadmap").is_some() {
+        "roadmap".to_string()
+    } else {
+        std::env::current_exe()
+            .context("Failed to locate the roadmap binary")?
+            .display()
+            .to_string()
+    };
+    git_config(&format!("merge.{DRIVER}.name"), "roadmap state three-way merge")?;
+    git_config(
+        &format!("merge.{DRIVER}.driver"),
+        &format!("{program} mirror merge %O %A %B"),
+    )?;
+    println!("{} Registered the {} merge driver in .git/config", "✓".success(), DRIVER.accent());
+
+    let attributes = Path::new(".gitattributes");
+    let rule = format!("{} merge={DRIVER}", mirror::path().display());
+    let existing = fs::read_to_string(attributes).unwrap_or_default();
+    if existing.lines().any(|l| l.trim() == rule) {
+        println!("   .gitattributes already routes the mirror to it");
+    } else {
+        let separator = if existing.is_empty() || existing.ends_with('\n') { "" } else { "\n" };
+        fs::write(attributes, format!("{existing}{separator}{rule}\n"))
+            .context("Failed to update .gitattributes")?;
+        println!("{} Added `{rule}` to .gitattributes", "✓".success());
+    }
+
+    write()?;
+    println!("   Commit both files. Every clone runs `roadmap mirror install` once to register the driver;");
+    println!("   after pulling, `roadmap mirror load` applies teammates' changes.");
+    Ok(())
+}
+
+/// The git merge driver: merges `theirs` into `ours` in place, given the common `base`.
+///
+/// Exits with an error, leaving `ours` untouched, when both sides changed a
+/// field differently, so git reports a conflict.
+///
+/// # Errors
+/// Returns error if a side cannot be parsed or conflicts remain.
+pub fn merge_driver(base: &Path, ours: &Path, theirs: &Path) -> Result<()> {
+    let read = |path: &Path| -> Result<Snapshot> {
+        let text = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        mirror::parse(&text).with_context(|| format!("In {}", path.display()))
+    };
+    // An empty base means the file was added on both branches.
+    let base = fs::read_to_string(base)
+        .ok()
+        .filter(|t| !t.trim().is_empty())
+        .map(|t| mirror::parse(&t))
+        .transpose()?;
+    let (our_snapshot, their_snapshot) = (read(ours)?, read(theirs)?);
+
+    match mirror::merge(base.as_ref(), &our_snapshot, &their_snapshot)? {
+        Ok(merged) => {
+            fs::write(ours, mirror::render(&merged)?)
+                .with_context(|| format!("Failed to write {}", ours.display()))?;
+            Ok(())
+        }
+        Err(conflicts) => {
+            let list: Vec<String> = conflicts
+                .iter()
+                .map(|c| format!("  [{}] {}: ours={} theirs={}", c.slug, c.field, c.ours, c.theirs))
+                .collect();
+            bail!(
+                "roadmap: {} conflicting field(s) in the state mirror:\n{}\nEdit the task lines by hand, or merge with `roadmap merge-state`",
+                conflicts.len(),
+                list.join("\n")
+            )
+        }
+    }
+}
+
+fn git_config(key: &str, value: &str) -> Result<()> {
+    let status = Command::new("git")
+        .args(["config", key, value])
+        .status()
+        .context("Failed to run git config")?;
+    if !status.success() {
+        bail!("git config {key} failed (is this a git repository?)");
+    }
+    Ok(())
+}
@@ -1,10 +1,56 @@
 pub mod add;
+pub mod artifacts;
+pub mod badge;
+pub mod blocked_report;
 pub mod check;
+pub mod config;
+pub mod debug;
+pub mod dep;
+pub mod diff;
 pub mod do_task;
+pub mod due;
+pub mod edit;
+pub mod export;
+pub mod filter;
+pub mod fmt;
+pub mod fsck;
+pub mod gate;
+pub mod gen;
+pub mod get;
+pub mod graph;
 pub mod history;
+pub mod hooks;
+pub mod impact;
+pub mod import;
 pub mod init;
 pub mod list;
+pub mod log;
+pub mod merge_state;
+pub mod migrate;
+pub mod milestone;
+pub mod mirror;
 pub mod next;
+pub mod plugin;
+pub mod proof;
+pub mod prune;
+pub mod quarantine;
+pub mod rename;
+pub mod report;
+pub mod rm;
+pub mod rpc;
+pub mod schema;
+pub mod scopes;
+pub mod search;
+pub mod serve;
 pub mod stale;
+pub mod state_sync;
+pub mod stats;
 pub mod status;
+pub mod sync;
+pub mod tree;
+pub mod tutorial;
+pub mod ui;
+pub mod undo;
+pub mod verify_signatures;
+pub mod watch;
 pub mod why;
\ No newline at end of file
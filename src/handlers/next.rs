@@ -1,53 +1,203 @@
 //! Handler for the `next` command.
 
+use super::filter::TaskFilter;
 use anyhow::Result;
 use colored::Colorize;
+use crate::table::{Align, Cell, Table};
+use crate::theme::{status_glyph, Paint};
 use roadmap::engine::context::RepoContext;
 use roadmap::engine::db::Db;
+use roadmap::engine::due;
+use roadmap::engine::estimate;
 use roadmap::engine::graph::TaskGraph;
-use roadmap::engine::types::{DerivedStatus, Task};
+use roadmap::engine::types::Task;
+use schemars::{JsonSchema, Schema};
+use serde::Serialize;
 
-/// Shows the frontier of actionable tasks.
+/// Flags controlling a `next` listing.
+pub struct NextOptions<'a> {
+    pub json: bool,
+    /// Only tasks carrying every one of these tags.
+    pub tags: &'a [String],
+    pub milestone: Option<&'a str>,
+    /// Only tasks related to this task.
+    pub root: Option<&'a str>,
+    /// Only tasks assigned to the current user.
+    pub mine: bool,
+    /// Every unfinished task grouped into waves instead of the frontier.
+    pub waves: bool,
+}
+
+/// Shows the frontier of actionable tasks, narrowed by `opts`, or with
+/// `waves`, every unfinished task grouped into waves.
 ///
 /// # Errors
-/// Returns error if database query fails.
-pub fn handle(json: bool) -> Result<()> {
+/// Returns error if a tag is invalid, the milestone does not exist, the root
+/// cannot be resolved, `mine` is set without a known identity, or the
+/// database query fails.
+pub fn handle(opts: &NextOptions<'_>) -> Result<()> {
+    let me = if opts.mine { Some(super::add::identity()?) } else { None };
     let conn = Db::connect()?;
     let graph = TaskGraph::build(&conn)?;
-    let frontier = graph.get_frontier();
+    let filter = TaskFilter::new(&conn, &graph, opts.tags, opts.milestone, opts.root)?;
+    let wanted = |t: &Task| filter.matches(t) && me.as_ref().is_none_or(|me| t.is_assigned_to(me));
+    let json = opts.json;
+
+    if opts.waves {
+        let waves: Vec<Vec<&Task>> = graph
+            .waves()
+            .into_iter()
+            .map(|wave| wave.into_iter().filter(|t| wanted(t)).collect())
+            .collect();
+        if json {
+            println!("{}", serde_json::to_string_pretty(&wave_views(&waves, &graph))?);
+        } else {
+            print_waves(&waves, &graph);
+        }
+        return Ok(());
+    }
+
+    let mut frontier = graph.get_frontier();
+    frontier.retain(|t| wanted(t));
 
     if json {
-        return print_json(&frontier, graph.head_sha());
+        return print_json(&frontier, &graph);
     }
 
     print_human(&frontier, &graph);
     Ok(())
 }
 
-fn print_json(tasks: &[&Task], head_sha: &str) -> Result<()> {
-    // Reconstruct context from the provided SHA to derive status for JSON output.
-    // This allows agents to see if a task is Unproven vs Stale.
-    let context = RepoContext::from_sha(head_sha.to_string());
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(Vec<NextView>)
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct NextView {
+    id: i64,
+    slug: String,
+    title: String,
+    status: String,
+    priority: i64,
+    due: Option<String>,
+    /// Hours.
+    estimate: Option<f64>,
+    /// On the chain with the most effort left: delaying it delays the whole
+    /// roadmap. Other tasks can proceed in parallel without doing so.
+    critical: bool,
+    tags: Vec<String>,
+    assignee: Option<String>,
+    test_cmd: Option<String>,
+}
+
+/// The `--json` rows for a frontier.
+pub(crate) fn views(tasks: &[&Task], graph: &TaskGraph) -> Vec<NextView> {
+    let critical = graph.critical_tasks();
+
+    tasks
+        .iter()
+        .map(|t| NextView {
+            id: t.id,
+            slug: t.slug.clone(),
+            title: t.title.clone(),
+            status: t.derive_status(graph.context()).to_string(),
+            priority: t.priority,
+            due: t.due.clone(),
+            estimate: t.estimate,
+            critical: critical.contains(&t.id),
+            tags: t.tags.clone(),
+            assignee: t.assignee.clone(),
+            test_cmd: t.test_cmd.clone(),
+        })
+        .collect()
+}
+
+/// JSON Schema of the `--waves --json` output.
+pub(crate) fn waves_schema() -> Schema {
+    schemars::schema_for!(Vec<WaveView>)
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct WaveView {
+    /// 1 for the frontier; each wave unlocks once the ones before it are done.
+    wave: usize,
+    tasks: Vec<NextView>,
+}
 
-    let output: Vec<_> = tasks
+/// The `--waves --json` rows, skipping waves the filters emptied.
+fn wave_views(waves: &[Vec<&Task>], graph: &TaskGraph) -> Vec<WaveView> {
+    waves
         .iter()
-        .map(|t| {
-            let status = t.derive_status(&context);
-            serde_json::json!({
-                "id": t.id,
-                "slug": t.slug,
-                "title": t.title,
-                "status": status.to_string(),
-                "test_cmd": t.test_cmd
-            })
+        .enumerate()
+        .filter(|(_, tasks)| !tasks.is_empty())
+        .map(|(i, tasks)| WaveView {
+            wave: i + 1,
+            tasks: views(tasks, graph),
         })
-        .collect();
-    println!("{}", serde_json::to_string_pretty(&output)?);
+        .collect()
+}
+
+/// Each wave's tasks side by side: everything in one wave can proceed in parallel.
+fn print_waves(waves: &[Vec<&Task>], graph: &TaskGraph) {
+    println!("{} Work in waves (each can proceed in parallel):", "🌊".info());
+    if waves.iter().all(Vec::is_empty) {
+        println!("   (All claims proven or none defined)");
+        return;
+    }
+
+    let estimated = waves.iter().flatten().any(|t| t.estimate.is_some());
+    let mut aligns = vec![Align::Left, Align::Left, Align::Left];
+    if estimated {
+        aligns.push(Align::Right);
+    }
+    let mut table = Table::new(&aligns).flex(2);
+    for task in waves.iter().flatten() {
+        let derived = task.derive_status(graph.context());
+        let mut cells = vec![
+            Cell::new(status_glyph(derived), move |s| s.status(derived)),
+            Cell::new(format!("[{}]", task.slug), |s| s.warning()),
+            Cell::plain(&task.title),
+        ];
+        if estimated {
+            let effort = task.estimate.map(estimate::format).unwrap_or_default();
+            cells.push(Cell::new(effort, |s| s.dimmed()));
+        }
+        table.row(cells);
+    }
+
+    let mut lines = table.render().into_iter();
+    for (i, wave) in waves.iter().enumerate().filter(|(_, w)| !w.is_empty()) {
+        let when = if i == 0 {
+            "ready now".to_string()
+        } else {
+            format!("after wave {i}")
+        };
+        let hours: f64 = wave.iter().filter_map(|t| t.estimate).sum();
+        let effort = if hours > 0.0 {
+            format!(", {}", estimate::format(hours))
+        } else {
+            String::new()
+        };
+        let count = if wave.len() == 1 { "task" } else { "tasks" };
+        println!(
+            "\n   {} {}",
+            format!("Wave {}", i + 1).bold(),
+            format!("({} {count}{effort}, {when})", wave.len()).dimmed()
+        );
+        for line in lines.by_ref().take(wave.len()) {
+            println!("{line}");
+        }
+    }
+}
+
+fn print_json(tasks: &[&Task], graph: &TaskGraph) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(&views(tasks, graph))?);
     Ok(())
 }
 
 fn print_human(tasks: &[&Task], graph: &TaskGraph) {
-    println!("{} Actionable Tasks (frontier):", "🚀".cyan());
+    println!("{} Actionable Tasks (frontier):", "🚀".info());
 
     if tasks.is_empty() {
         println!("   (All claims proven or none defined)");
@@ -58,17 +208,71 @@ fn print_human(tasks: &[&Task], graph: &TaskGraph) {
     // Ideally TaskGraph would expose its context, but constructing one here is low cost.
     let context = RepoContext::from_sha(graph.head_sha().to_string());
 
+    // Optional columns only appear once some task uses them.
+    let prioritized = tasks.iter().any(|t| t.priority != 0);
+    let estimated = tasks.iter().any(|t| t.estimate.is_some());
+    let dated = tasks.iter().any(|t| due::date(t).is_some());
+    let assigned = tasks.iter().any(|t| t.assignee.is_some());
+    let critical = graph.critical_tasks();
+    let today = due::today();
+    let mut aligns = vec![Align::Left, Align::Left];
+    if prioritized {
+        aligns.push(Align::Right);
+    }
+    aligns.push(Align::Left);
+    if estimated {
+        aligns.push(Align::Right);
+    }
+    aligns.push(Align::Left);
+    if !critical.is_empty() {
+        aligns.push(Align::Left);
+    }
+    if dated {
+        aligns.push(Align::Left);
+    }
+    if assigned {
+        aligns.push(Align::Left);
+    }
+    let title_column = if prioritized { 3 } else { 2 };
+    let mut table = Table::new(&aligns).flex(title_column);
     for task in tasks {
         let derived = task.derive_status(&context);
-        let icon = status_icon(derived);
-        println!(
-            "   {} [{}] {} ({})",
-            icon,
-            task.slug.yellow(),
-            task.title,
-            derived.to_string().dimmed()
-        );
+        let mut cells = vec![
+            Cell::new(status_glyph(derived), move |s| s.status(derived)),
+            Cell::new(format!("[{}]", task.slug), |s| s.warning()),
+        ];
+        if prioritized {
+            cells.push(Cell::new(priority_label(task.priority), |s| s.accent()));
+        }
+        cells.push(Cell::plain(&task.title));
+        if estimated {
+            let effort = task.estimate.map(estimate::format).unwrap_or_default();
+            cells.push(Cell::new(effort, |s| s.dimmed()));
+        }
+        cells.push(Cell::new(format!("({derived})"), |s| s.dimmed()));
+        if !critical.is_empty() {
+            cells.push(if critical.contains(&task.id) {
+                Cell::new("critical", |s| s.warning())
+            } else {
+                Cell::new("parallel-ok", |s| s.dimmed())
+            });
+        }
+        if dated {
+            let days_left = due::date(task).map(|d| (d - today).num_days());
+            cells.push(match days_left {
+                Some(days) => Cell::new(due::relative(days), super::due::urgency(days)),
+                None => Cell::plain(""),
+            });
+        }
+        if assigned {
+            let owner = task.assignee.as_ref().map(|a| format!("@{a}")).unwrap_or_default();
+            cells.push(Cell::new(owner, |s| s.info()));
+        }
+        table.row(cells);
+    }
 
+    for (task, line) in tasks.iter().zip(table.render()) {
+        println!("{line}");
         let blocked = graph.get_blocked_by(task.id);
         if !blocked.is_empty() {
             let names: Vec<_> = blocked.iter().map(|t| t.slug.as_str()).collect();
@@ -78,14 +282,41 @@ fn print_human(tasks: &[&Task], graph: &TaskGraph) {
             );
         }
     }
+    print_critical_path(tasks, graph);
 }
 
-fn status_icon(status: DerivedStatus) -> colored::ColoredString {
-    match status {
-        DerivedStatus::Broken => "✗".red(),
-        DerivedStatus::Stale => "⚡".yellow(),
-        DerivedStatus::Unproven => "○".dimmed(),
-        DerivedStatus::Proven => "✓".green(),
-        DerivedStatus::Attested => "!".blue(),
+/// The chain with the most effort left, once there is one: in hours when
+/// anything on it is estimated or has been timed, else in tasks.
+///
+/// Left out when it starts outside the shown tasks, so a filtered view
+/// isn't pointed at unrelated work.
+fn print_critical_path(tasks: &[&Task], graph: &TaskGraph) {
+    let (path, hours) = graph.critical_path();
+    if hours <= 0.0 && path.len() < 2 {
+        return;
     }
-}
\ No newline at end of file
+    if !path.first().is_some_and(|start| tasks.iter().any(|t| t.id == start.id)) {
+        return;
+    }
+    let left = if hours > 0.0 {
+        estimate::format(hours)
+    } else {
+        format!("{} tasks", path.len())
+    };
+    let chain: Vec<String> = path.iter().map(|t| format!("[{}]", t.slug)).collect();
+    println!(
+        "\n   {} Critical path ({} left): {}",
+        "⏱".info(),
+        left.bold(),
+        chain.join(" → ").warning()
+    );
+}
+
+/// `P2`-style label for a non-default priority; blank at the default.
+fn priority_label(priority: i64) -> String {
+    if priority == 0 {
+        String::new()
+    } else {
+        format!("P{priority}")
+    }
+}
@@ -0,0 +1,95 @@
+//! Handler for external `roadmap-<name>` subcommands.
+//!
+//! Like git, an unknown subcommand runs the matching executable from PATH
+//! with the remaining arguments. The plugin learns where the roadmap lives
+//! from the environment rather than re-deriving it:
+//!
+//! | Variable              | Value                                          |
+//! |-----------------------|------------------------------------------------|
+//! | `ROADMAP_BIN`         | The `roadmap` executable that dispatched it    |
+//! | `ROADMAP_DIR`         | Absolute path of the `.roadmap` directory      |
+//! | `ROADMAP_DB`          | Absolute path of the `SQLite` database         |
+//! | `ROADMAP_REPO_ROOT`   | Top level of the git working tree              |
+//! | `ROADMAP_HEAD`        | Current HEAD commit                            |
+//! | `ROADMAP_ACTIVE_TASK` | Slug of the active task, if one is set         |
+//!
+//! Variables that don't apply (e.g. outside a git repository) are left unset.
+
+use anyhow::{bail, Context, Result};
+use roadmap::engine::db::{Db, DB_DIR};
+use roadmap::engine::repo::TaskRepo;
+use roadmap::engine::shell;
+use std::path::Path;
+use std::process::Command;
+
+/// Runs `roadmap-<name>` with the given arguments and exits with its status.
+///
+/// # Errors
+/// Returns error if no such plugin is on PATH or it cannot be started.
+pub fn handle(args: &[String]) -> Result<()> {
+    let Some((name, rest)) = args.split_first() else {
+        bail!("No subcommand given. Run `roadmap --help`.");
+    };
+    let program = format!("roadmap-{name}");
+    let Some(path) = shell::which(&program) else {
+        bail!("Unknown command '{name}' (no `{program}` on PATH). Run `roadmap --help`.");
+    };
+
+    let mut cmd = Command::new(&path);
+    cmd.args(rest);
+    for (key, value) in context_env() {
+        cmd.env(key, value);
+    }
+    let status = cmd
+        .status()
+        .with_context(|| format!("Failed to run {}", path.display()))?;
+
+    if !status.success() {
+        // The plugin has already reported its own error; just pass the code on.
+        std::process::exit(status.code().unwrap_or(1));
+    }
+    Ok(())
+}
+
+fn context_env() -> Vec<(&'static str, String)> {
+    let mut env = Vec::new();
+    if let Ok(exe) = std::env::current_exe() {
+        env.push(("ROADMAP_BIN", exe.display().to_string()));
+    }
+    if let Ok(dir) = Path::new(DB_DIR).canonicalize() {
+        env.push(("ROADMAP_DIR", dir.display().to_string()));
+    }
+    if let Ok(db) = Db::path().canonicalize() {
+        env.push(("ROADMAP_DB", db.display().to_string()));
+    }
+    if let Some(root) = git(&["rev-parse", "--show-toplevel"]) {
+        env.push(("ROADMAP_REPO_ROOT", root));
+    }
+    if let Some(head) = git(&["rev-parse", "HEAD"]) {
+        env.push(("ROADMAP_HEAD", head));
+    }
+    if let Some(slug) = active_task() {
+        env.push(("ROADMAP_ACTIVE_TASK", slug));
+    }
+    env
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let _span = tracing::info_span!("git", op = "rev-parse").entered();
+    Command::new("git")
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+}
+
+fn active_task() -> Option<String> {
+    if !Db::path().exists() {
+        return None;
+    }
+    let conn = Db::connect().ok()?;
+    let repo = TaskRepo::new(&conn);
+    let id = repo.get_active_task_id().ok()??;
+    repo.find_by_id(id).ok()?.map(|t| t.slug)
+}
@@ -0,0 +1,91 @@
+//! Handler for the `proof` command group.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::config::Config;
+use roadmap::engine::db::Db;
+use roadmap::engine::exchange::{self, ImportOutcome, ProofFile};
+use roadmap::engine::remote;
+use roadmap::engine::repo::TaskRepo;
+use std::path::Path;
+
+/// Imports proofs emitted by `check --emit-proof` into the local roadmap.
+///
+/// # Errors
+/// Returns error if the file is invalid or the database fails.
+pub fn import(file: &Path) -> Result<()> {
+    let proof_file = ProofFile::read(file)?;
+    let conn = Db::connect()?;
+    let origin = format!("import:{}", file.display());
+    let results = exchange::import(&conn, &proof_file.proofs, &origin)?;
+    print_results(&results, &file.display().to_string());
+    Ok(())
+}
+
+/// Publishes each task's latest proof to the configured remote.
+///
+/// # Errors
+/// Returns error if no remote is configured or it cannot be reached.
+pub fn push() -> Result<()> {
+    let url = remote_url()?;
+    let store = remote::open(&url);
+    let conn = Db::connect()?;
+
+    let mut pushed = 0;
+    for task in TaskRepo::new(&conn).get_all()? {
+        let Some(proof) = &task.proof else { continue };
+        if remote::push(store.as_ref(), &task.slug, proof)? {
+            pushed += 1;
+            println!("   {} [{}] pushed", "✓".success(), task.slug.success());
+        } else {
+            println!("   {} [{}] remote is newer", "↷".dimmed(), task.slug.warning());
+        }
+    }
+    println!("{} Pushed {} proofs to {}", "☁".info(), pushed, url);
+    Ok(())
+}
+
+/// Imports teammates' proofs from the configured remote.
+///
+/// # Errors
+/// Returns error if no remote is configured or it cannot be reached.
+pub fn pull() -> Result<()> {
+    let url = remote_url()?;
+    let store = remote::open(&url);
+    let conn = Db::connect()?;
+    let tasks = TaskRepo::new(&conn).get_all()?;
+    let results = remote::pull(&conn, store.as_ref(), &tasks, &format!("remote:{url}"))?;
+    print_results(&results, &url);
+    Ok(())
+}
+
+fn remote_url() -> Result<String> {
+    let Some(url) = Config::load()?.remote.url else {
+        bail!("No remote configured. Set [remote] url in .roadmap/config.toml");
+    };
+    Ok(url)
+}
+
+fn print_results(results: &[(String, ImportOutcome)], source: &str) {
+    let mut imported = 0;
+    for (slug, outcome) in results {
+        match outcome {
+            ImportOutcome::Imported => {
+                imported += 1;
+                println!("   {} [{}] imported", "✓".success(), slug.success());
+            }
+            ImportOutcome::Skipped(reason) => {
+                println!("   {} [{}] skipped: {}", "↷".dimmed(), slug.warning(), reason);
+            }
+        }
+    }
+
+    println!(
+        "{} Imported {} of {} proofs from {}",
+        "📦".info(),
+        imported,
+        results.len(),
+        source
+    );
+}
@@ -0,0 +1,66 @@
+//! Handler for the `prune` command.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::config::Config;
+use roadmap::engine::db::Db;
+use roadmap::engine::retention::{self, Policy};
+
+/// Deletes old proofs, keeping the latest (and latest passing) proof of every task.
+///
+/// Without limits, the `[retention]` config applies.
+///
+/// # Errors
+/// Returns error if no limit is given or configured, a limit is invalid, or the database fails.
+pub fn handle(keep_last: Option<usize>, older_than: Option<&str>, dry_run: bool) -> Result<()> {
+    let policy = match Policy::new(keep_last, older_than)? {
+        Some(policy) => policy,
+        None => match Policy::from_settings(&Config::load()?.retention)? {
+            Some(policy) => policy,
+            None => bail!(
+                "Nothing to prune by: pass --keep-last N and/or --older-than AGE\n   (or set retention.keep_last / retention.older_than with `roadmap config set`)"
+            ),
+        },
+    };
+
+    let conn = Db::connect()?;
+    let summary = retention::prune(&conn, &policy, dry_run)?;
+    if summary.proofs == 0 {
+        println!("   Nothing to prune.");
+        return Ok(());
+    }
+    let verb = if dry_run { "Would prune" } else { "Pruned" };
+    println!(
+        "{} {verb} {} proof(s) from {} task(s) {}",
+        "🧹".success(),
+        summary.proofs,
+        summary.tasks,
+        format!(
+            "({} of output, {} artifact file(s))",
+            format_bytes(summary.output_bytes),
+            summary.artifacts
+        )
+        .dimmed()
+    );
+    if dry_run {
+        println!("   Run again without --dry-run to delete them.");
+    }
+    Ok(())
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
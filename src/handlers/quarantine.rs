@@ -0,0 +1,47 @@
+//! Handler for the `quarantine` command.
+
+use anyhow::{bail, Result};
+use crate::theme::Paint;
+use roadmap::engine::db::Db;
+use roadmap::engine::repo::TaskRepo;
+use roadmap::engine::resolver::TaskResolver;
+
+/// Quarantines a flaky task, or rehabilitates it with `release`.
+///
+/// # Errors
+/// Returns error if the task is not found, no reason is given, or the update fails.
+pub fn handle(task_ref: &str, reason: Option<&str>, release: bool) -> Result<()> {
+    let conn = Db::connect()?;
+    let task = TaskResolver::new(&conn).resolve(task_ref)?.task;
+    let repo = TaskRepo::new(&conn);
+
+    if release {
+        if task.quarantine_reason.is_none() {
+            bail!("Task [{}] is not quarantined", task.slug);
+        }
+        repo.set_quarantine(task.id, None)?;
+        println!(
+            "{} Task [{}] rehabilitated; its proofs count again",
+            "✓".success(),
+            task.slug.warning()
+        );
+        return Ok(());
+    }
+
+    let Some(reason) = reason else {
+        bail!("A --reason is required to quarantine a task");
+    };
+    repo.set_quarantine(task.id, Some(reason))?;
+    println!(
+        "{} Task [{}] quarantined: {}",
+        "☣".special(),
+        task.slug.warning(),
+        reason
+    );
+    if task.has_passed {
+        println!("   Failures no longer block dependents. Release with --release.");
+    } else {
+        println!("   It has never passed, so it keeps blocking dependents until a run does.");
+    }
+    Ok(())
+}
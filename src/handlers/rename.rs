@@ -0,0 +1,59 @@
+//! Handler for the `rename` command.
+
+use anyhow::{bail, Result};
+use crate::theme::Paint;
+use roadmap::engine::db::Db;
+use roadmap::engine::repo::TaskRepo;
+use roadmap::engine::resolver::{slugify, TaskResolver};
+
+/// Changes a task's slug, optionally keeping the old one as an alias.
+///
+/// Dependencies, proofs, and the active task refer to the task by ID, so
+/// only the slug itself changes.
+///
+/// # Errors
+/// Returns error if the task cannot be resolved, the new slug is invalid or taken, or the database fails.
+pub fn handle(task_ref: &str, new_slug: &str, alias: bool) -> Result<()> {
+    let slug = slugify(new_slug);
+    if slug.is_empty() {
+        bail!("'{new_slug}' has no usable slug characters");
+    }
+
+    let conn = Db::connect()?;
+    let tx = Db::begin_write(&conn)?;
+    let task = TaskResolver::strict(&tx).resolve(task_ref)?.task;
+    let repo = TaskRepo::new(&tx);
+
+    if slug == task.slug {
+        bail!("Task is already called [{slug}]");
+    }
+    if let Some(other) = repo.find_by_slug(&slug)? {
+        bail!("Slug '{slug}' is already used by [{}] {}", other.slug, other.title);
+    }
+    match repo.find_by_alias(&slug)? {
+        // Renaming back to an old slug: it is a slug again, not an alias.
+        Some(owner) if owner.id == task.id => repo.remove_alias(&slug)?,
+        Some(owner) => bail!("'{slug}' is an alias of [{}]; pick another slug", owner.slug),
+        None => {}
+    }
+
+    repo.set_slug(task.id, &slug)?;
+    if alias {
+        repo.add_alias(task.id, &task.slug)?;
+    }
+    tx.commit()?;
+
+    println!(
+        "{} Renamed [{}] to [{}]",
+        "✓".success(),
+        task.slug,
+        slug.warning()
+    );
+    if alias {
+        println!("   {} [{}] still resolves to it", "ℹ".info(), task.slug);
+    }
+    if slug != new_slug {
+        println!("   {} '{new_slug}' was normalized to '{slug}'", "ℹ".info());
+    }
+    Ok(())
+}
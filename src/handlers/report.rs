@@ -0,0 +1,106 @@
+//! Handlers for the `report` command group.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use crate::table;
+use crate::theme::Paint;
+use roadmap::engine::burndown::{self, Burndown};
+use roadmap::engine::db::Db;
+use roadmap::engine::estimate;
+use roadmap::engine::repo::{ProofRepo, TaskRepo};
+use schemars::Schema;
+use std::fmt::Write as _;
+
+/// Sparkline levels, lowest first.
+const TICKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Widest sparkline when the terminal width is unknown.
+const SPARK_WIDTH: usize = 60;
+
+/// Prints the remaining work per day as a sparkline, CSV, or JSON series.
+///
+/// # Errors
+/// Returns error if the format is unknown or the database fails.
+pub fn burndown(format: &str) -> Result<()> {
+    if !matches!(format, "spark" | "csv" | "json") {
+        bail!("Unknown burndown format '{format}' (expected: spark, csv, json)");
+    }
+    let conn = Db::connect()?;
+    let tasks = TaskRepo::new(&conn).get_all()?;
+    let trail = ProofRepo::new(&conn).get_audit_trail()?;
+    let report = burndown::compute(&tasks, &trail, chrono::Utc::now().date_naive());
+
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+        "csv" => print!("{}", render_csv(&report)?),
+        _ => print_spark(&report),
+    }
+    Ok(())
+}
+
+/// JSON Schema of the `burndown --format json` output.
+pub(crate) fn burndown_schema() -> Schema {
+    schemars::schema_for!(Burndown)
+}
+
+fn render_csv(report: &Burndown) -> Result<String> {
+    let mut out = String::from("date,total,remaining,remaining_hours\n");
+    for point in &report.points {
+        writeln!(out, "{},{},{},{}", point.date, point.total, point.remaining, point.remaining_hours)?;
+    }
+    Ok(out)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn print_spark(report: &Burndown) {
+    println!("{} Burndown", "📉".info());
+    let (Some(first), Some(last)) = (report.points.first(), report.points.last()) else {
+        println!("   (No tasks yet)");
+        return;
+    };
+    let width = table::available_width().map_or(SPARK_WIDTH, |w| w.saturating_sub(6).clamp(10, 200));
+
+    let counts: Vec<f64> = report.points.iter().map(|p| p.remaining as f64).collect();
+    println!(
+        "   tasks  {}  {} → {} remaining",
+        sparkline(&counts, width).accent(),
+        first.remaining,
+        last.remaining.to_string().bold()
+    );
+    if report.points.iter().any(|p| p.remaining_hours > 0.0) {
+        let hours: Vec<f64> = report.points.iter().map(|p| p.remaining_hours).collect();
+        println!(
+            "   effort {}  {} → {} remaining",
+            sparkline(&hours, width).accent(),
+            estimate::format(first.remaining_hours),
+            estimate::format(last.remaining_hours).bold()
+        );
+    }
+    println!("   {}", format!("{} … {}", first.date, last.date).dimmed());
+    println!();
+    println!("   Velocity: {:.1} task(s)/week over the last 4 weeks", report.velocity_per_week);
+    match &report.projected_finish {
+        Some(date) => println!("   Projected finish: {}", date.bold()),
+        None if last.remaining == 0 => println!("   {} Nothing left to do.", "✓".success()),
+        None => println!("   Projected finish: {}", "unknown (nothing finished recently)".dimmed()),
+    }
+}
+
+/// One tick per value, or per bucket of values when there are more than `width`.
+fn sparkline(values: &[f64], width: usize) -> String {
+    let buckets = values.len().div_ceil(width).max(1);
+    // A bucket shows its last day: what was left at the end of that stretch.
+    let sampled: Vec<f64> = values.chunks(buckets).filter_map(|c| c.last().copied()).collect();
+    let max = sampled.iter().copied().fold(0.0, f64::max);
+    sampled
+        .iter()
+        .map(|v| {
+            if max <= 0.0 {
+                return TICKS[0];
+            }
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+            let level = (v / max * (TICKS.len() - 1) as f64).round() as usize;
+            TICKS[level.min(TICKS.len() - 1)]
+        })
+        .collect()
+}
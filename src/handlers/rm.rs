@@ -0,0 +1,62 @@
+//! Handler for the `rm` command.
+
+use anyhow::{bail, Result};
+use crate::theme::Paint;
+use roadmap::engine::db::Db;
+use roadmap::engine::repo::{ProofRepo, TaskRepo};
+use roadmap::engine::resolver::TaskResolver;
+
+/// Removes a task with its edges, scopes, budgets, and proofs in one transaction.
+///
+/// Deleting proofs throws away verification evidence, so a task that has any
+/// is only removed with `force`.
+///
+/// # Errors
+/// Returns error if the task cannot be resolved, has proofs without `force`, or the database fails.
+pub fn handle(task_ref: &str, force: bool) -> Result<()> {
+    let conn = Db::connect()?;
+    let tx = Db::begin_write(&conn)?;
+    let task = TaskResolver::strict(&tx).resolve(task_ref)?.task;
+    let repo = TaskRepo::new(&tx);
+    let proofs = ProofRepo::new(&tx);
+
+    let proof_count = proofs.get_history(task.id)?.len();
+    if proof_count > 0 && !force {
+        bail!(
+            "Task [{}] has {proof_count} proof(s). Pass --force to delete its evidence too.",
+            task.slug
+        );
+    }
+
+    let dependents: Vec<(String, String)> = tx
+        .prepare(
+            "SELECT t.slug, t.title FROM dependencies d JOIN tasks t ON t.id = d.blocked_id
+             WHERE d.blocker_id = ?1 ORDER BY t.slug",
+        )?
+        .query_map([task.id], |r| Ok((r.get(0)?, r.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    let was_active = repo.get_active_task_id()? == Some(task.id);
+
+    proofs.delete_for_task(task.id, &format!("task [{}] removed", task.slug))?;
+    repo.remove(task.id)?;
+    if was_active {
+        repo.clear_active_task()?;
+    }
+    tx.commit()?;
+
+    println!("{} Removed task [{}] {}", "✓".success(), task.slug.warning(), task.title);
+    if proof_count > 0 {
+        println!("   {} Deleted {proof_count} proof(s); tombstones keep the chain intact (see `fsck`)", "ℹ".info());
+    }
+    if was_active {
+        println!("   {} It was the active task; none is active now", "ℹ".info());
+    }
+    for (slug, title) in &dependents {
+        println!(
+            "   {} [{slug}] {title} no longer waits on [{}]",
+            "⚠".warning(),
+            task.slug
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,33 @@
+//! Handler for the `rpc` command.
+
+use super::serve::jsonrpc::{self, Request, METHOD_NOT_FOUND};
+use super::serve::ops;
+use anyhow::Result;
+use roadmap::engine::db::Db;
+use serde_json::{json, Value};
+
+/// Answers newline-delimited JSON-RPC 2.0 requests on stdin until it closes.
+///
+/// Methods are the operation names (`add`, `link`, `next`, ...) with their
+/// arguments as named `params`; `methods` lists them with their schemas.
+/// One process and one database connection serve the whole session.
+///
+/// # Errors
+/// Returns error if the roadmap is not initialized or stdin or stdout fails.
+pub fn handle() -> Result<()> {
+    let mut conn = Db::connect()?;
+    jsonrpc::run(|request: &Request| match request.method.as_str() {
+        "methods" => Ok(Value::Array(
+            ops::catalog()
+                .into_iter()
+                .map(|(name, description, params)| {
+                    json!({ "name": name, "description": description, "params": params })
+                })
+                .collect(),
+        )),
+        name if ops::exists(name) => {
+            ops::call(&mut conn, name, &request.params).map_err(|e| (jsonrpc::server_error(&e), format!("{e:#}")))
+        }
+        other => Err((METHOD_NOT_FOUND, format!("Method not found: {other}"))),
+    })
+}
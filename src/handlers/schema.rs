@@ -0,0 +1,123 @@
+//! Handlers for the `schema` and `validate` commands.
+
+use super::{artifacts, badge, blocked_report, check, debug, dep, diff, due, fsck, gate, graph, history, impact, list, log, milestone, next, report, search, stale, stats, status, verify_signatures, why};
+use anyhow::{bail, Context, Result};
+use crate::theme::Paint;
+use roadmap::engine::audit::AuditEvent;
+use roadmap::engine::exchange::ProofFile;
+use roadmap::engine::plan::{self, Plan};
+use roadmap::engine::snapshot::Snapshot;
+use schemars::Schema;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Builds one published schema.
+type SchemaFn = fn() -> Schema;
+
+/// Every published schema: `--json` outputs first, then input formats.
+const SCHEMAS: &[(&str, SchemaFn)] = &[
+    ("next", next::json_schema),
+    ("next-waves", next::waves_schema),
+    ("list", list::json_schema),
+    ("status", status::json_schema),
+    ("why", why::json_schema),
+    ("why-impact", why::impact_schema),
+    ("artifacts", artifacts::json_schema),
+    ("stale", stale::json_schema),
+    ("history", history::json_schema),
+    ("log", log::json_schema),
+    ("impact", impact::json_schema),
+    ("graph-orphans", graph::orphans_schema),
+    ("diff", diff::json_schema),
+    ("dep-list", dep::json_schema),
+    ("search", search::json_schema),
+    ("check-event", check::events::json_schema),
+    ("fsck", fsck::json_schema),
+    ("debug-timings", debug::json_schema),
+    ("blocked-report", blocked_report::json_schema),
+    ("due", due::json_schema),
+    ("milestone-status", milestone::json_schema),
+    ("badge", badge::json_schema),
+    ("report-burndown", report::burndown_schema),
+    ("stats", stats::json_schema),
+    ("stats-reliability", stats::reliability_schema),
+    ("gate", gate::json_schema),
+    ("verify-signatures", verify_signatures::json_schema),
+    ("audit-event", || schemars::schema_for!(AuditEvent)),
+    ("plan", || schemars::schema_for!(Plan)),
+    ("proof-file", || schemars::schema_for!(ProofFile)),
+    ("snapshot", || schemars::schema_for!(Snapshot)),
+];
+
+/// Prints a schema by name, or lists the available names.
+///
+/// # Errors
+/// Returns error if the name is unknown.
+pub fn show(name: Option<&str>) -> Result<()> {
+    let Some(name) = name else {
+        for (name, _) in SCHEMAS {
+            println!("{name}");
+        }
+        return Ok(());
+    };
+    let Some((_, schema)) = SCHEMAS.iter().find(|(n, _)| *n == name) else {
+        bail!("Unknown schema '{name}'. Run `roadmap schema` to list them.");
+    };
+    println!("{}", serde_json::to_string_pretty(&schema())?);
+    Ok(())
+}
+
+/// Validates a plan, proof file, or snapshot before it is applied.
+///
+/// The kind is inferred from the file unless `kind` names it.
+///
+/// # Errors
+/// Returns error describing why the file is invalid.
+pub fn validate(path: &Path, kind: Option<&str>) -> Result<()> {
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let kind = match kind {
+        Some(k) => k.to_string(),
+        None => detect(path, &raw)?,
+    };
+
+    let problems = match kind.as_str() {
+        "plan" => Plan::read(path)?.validate(),
+        "proof-file" => {
+            let file: ProofFile = serde_json::from_str(&raw).context("Not a valid proof file")?;
+            check_version(file.version, roadmap::engine::exchange::FORMAT_VERSION)
+        }
+        "snapshot" => serde_json::from_str::<Snapshot>(&raw).context("Not a valid snapshot")?.validate(),
+        other => bail!("Cannot validate '{other}'; expected plan, proof-file, or snapshot"),
+    };
+    plan::ensure_valid(path, &problems)?;
+
+    println!("{} {} is a valid {}", "✓".success(), path.display(), kind);
+    Ok(())
+}
+
+fn detect(path: &Path, raw: &str) -> Result<String> {
+    if path.extension().is_some_and(|e| e == "toml") {
+        return Ok("plan".to_string());
+    }
+    let value: Value = serde_json::from_str(raw).context("File is neither TOML nor JSON")?;
+    let kind = if value.get("tasks").is_some() && value.get("edges").is_some() {
+        "snapshot"
+    } else if value.get("proofs").is_some() {
+        "proof-file"
+    } else {
+        bail!("Cannot tell what kind of file this is; pass --kind");
+    };
+    Ok(kind.to_string())
+}
+
+fn check_version(found: u32, supported: u32) -> Vec<String> {
+    if found > supported {
+        return vec![format!(
+            "version {found} is newer than supported ({supported})"
+        )];
+    }
+    Vec::new()
+}
+
@@ -0,0 +1,111 @@
+//! Handlers for the `scopes` command group.
+
+use anyhow::Result;
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::db::Db;
+use roadmap::engine::repo::TaskRepo;
+use roadmap::engine::scopes::{self, Rename};
+use std::collections::HashMap;
+
+/// A task whose scopes would change.
+struct Proposal {
+    task_id: i64,
+    slug: String,
+    changes: Vec<(String, String)>,
+    scopes: Vec<String>,
+}
+
+/// Finds scopes orphaned by file moves and proposes (or applies) new globs.
+///
+/// Renames are looked up since `since`, or else since each task's last proof.
+///
+/// # Errors
+/// Returns error if git cannot diff a range or the database fails.
+pub fn remap(since: Option<&str>, apply: bool) -> Result<()> {
+    let conn = Db::connect()?;
+    let repo = TaskRepo::new(&conn);
+    let mut renames_by_base: HashMap<String, Vec<Rename>> = HashMap::new();
+    let mut proposals = Vec::new();
+    let mut unanchored = 0;
+
+    for task in repo.get_all()? {
+        if task.scopes.is_empty() {
+            continue;
+        }
+        let Some(base) = since.or(task.proof.as_ref().map(|p| p.git_sha.as_str())) else {
+            unanchored += 1;
+            continue;
+        };
+        if !renames_by_base.contains_key(base) {
+            renames_by_base.insert(base.to_string(), scopes::find_renames(base)?);
+        }
+        let renames = &renames_by_base[base];
+
+        let mut changes = Vec::new();
+        let updated: Vec<String> = task
+            .scopes
+            .iter()
+            .map(|scope| {
+                if scopes::matches_anything(scope) {
+                    return scope.clone();
+                }
+                match scopes::remap(scope, renames) {
+                    Some(new) => {
+                        changes.push((scope.clone(), new.clone()));
+                        new
+                    }
+                    None => scope.clone(),
+                }
+            })
+            .collect();
+        if !changes.is_empty() {
+            proposals.push(Proposal {
+                task_id: task.id,
+                slug: task.slug,
+                changes,
+                scopes: updated,
+            });
+        }
+    }
+
+    print_proposals(&proposals);
+    if unanchored > 0 {
+        println!(
+            "   {} {} scoped task(s) have no proof to diff from; pass --since <rev>",
+            "ℹ".dimmed(),
+            unanchored
+        );
+    }
+    if proposals.is_empty() {
+        return Ok(());
+    }
+
+    if !apply {
+        println!("\n   Run with --apply to update these scopes.");
+        return Ok(());
+    }
+    for p in &proposals {
+        repo.replace_scopes(p.task_id, &p.scopes)?;
+    }
+    println!(
+        "\n{} Updated scopes on {} task(s)",
+        "✓".success(),
+        proposals.len()
+    );
+    Ok(())
+}
+
+fn print_proposals(proposals: &[Proposal]) {
+    if proposals.is_empty() {
+        println!("{} No orphaned scopes with a detectable rename.", "✓".success());
+        return;
+    }
+    println!("{} Scopes orphaned by moved files:", "↪".info());
+    for p in proposals {
+        println!("   [{}]", p.slug.warning());
+        for (old, new) in &p.changes {
+            println!("     {} → {}", old.dimmed(), new.success());
+        }
+    }
+}
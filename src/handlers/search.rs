@@ -0,0 +1,72 @@
+//! Handler for the `search` command.
+
+use anyhow::Result;
+use colored::Colorize;
+use crate::table::{Align, Cell, Table};
+use crate::theme::{status_glyph, Paint};
+use roadmap::engine::context::RepoContext;
+use roadmap::engine::db::Db;
+use roadmap::engine::repo::TaskRepo;
+use roadmap::engine::types::Task;
+use schemars::{JsonSchema, Schema};
+use serde::Serialize;
+
+/// Lists tasks whose slug or title matches `query`, best matches first.
+///
+/// # Errors
+/// Returns error if the database query fails.
+pub fn handle(query: &str, limit: usize, json: bool) -> Result<()> {
+    let conn = Db::connect()?;
+    let tasks = TaskRepo::new(&conn).search(query, limit)?;
+    let context = RepoContext::new()?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&hits(&tasks, &context))?);
+        return Ok(());
+    }
+
+    println!("{} Search: {}", "🔍".info(), query.bold());
+    if tasks.is_empty() {
+        println!("   (No matching tasks)");
+        return Ok(());
+    }
+
+    let mut table = Table::new(&[Align::Left, Align::Left, Align::Left, Align::Left]).flex(2);
+    for task in &tasks {
+        let status = task.derive_status(&context);
+        table.row(vec![
+            Cell::new(status_glyph(status), move |s| s.status(status)),
+            Cell::new(format!("[{}]", task.slug), |s| s.accent()),
+            Cell::plain(&task.title),
+            Cell::new(format!("({status})"), move |s| s.status(status)),
+        ]);
+    }
+    for line in table.render() {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(Vec<SearchHit>)
+}
+
+/// JSON views of search results, in rank order.
+pub(crate) fn hits(tasks: &[Task], context: &RepoContext) -> Vec<SearchHit> {
+    tasks
+        .iter()
+        .map(|t| SearchHit {
+            slug: t.slug.clone(),
+            title: t.title.clone(),
+            status: t.derive_status(context).to_string(),
+        })
+        .collect()
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SearchHit {
+    slug: String,
+    title: String,
+    status: String,
+}
@@ -0,0 +1,21 @@
+//! Handler for the `serve` command: long-running machine interfaces.
+
+use anyhow::{bail, Result};
+
+mod http;
+pub(crate) mod jsonrpc;
+mod mcp;
+pub(crate) mod ops;
+
+/// Serves the roadmap over the selected protocol until the client disconnects.
+///
+/// # Errors
+/// Returns error if not exactly one protocol is selected or the transport fails.
+pub fn handle(mcp: bool, http: Option<u16>, host: &str, origin: Option<&str>) -> Result<()> {
+    match (mcp, http) {
+        (true, None) => mcp::serve(),
+        (false, Some(port)) => http::serve(host, port, origin),
+        (true, Some(_)) => bail!("Choose one protocol: --mcp or --http"),
+        (false, None) => bail!("Choose a protocol: --mcp or --http <port>"),
+    }
+}
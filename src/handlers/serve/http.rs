@@ -0,0 +1,256 @@
+//! HTTP API: roadmap state for dashboards and bots, as JSON.
+//!
+//! Reads are open to anyone who can reach the port (it binds to localhost
+//! unless told otherwise). Writes need `Authorization: Bearer <token>`
+//! matching `ROADMAP_API_TOKEN`, and are disabled when it is unset.
+//! Browsers are only let in from the one origin given with `--allow-origin`,
+//! and then every request needs the token, reads included.
+//!
+//! Requests are served one at a time on a single connection, except
+//! `check`: it runs the task's verification, so it gets a thread and a
+//! connection of its own and the rest keep being answered meanwhile.
+
+use super::ops;
+use anyhow::{anyhow, bail, Result};
+use roadmap::engine::db::Db;
+use roadmap::engine::repo::TaskRepo;
+use rusqlite::Connection;
+use serde_json::{json, Value};
+use std::io::Read;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Environment variable holding the bearer token that unlocks writes.
+const TOKEN_ENV: &str = "ROADMAP_API_TOKEN";
+
+/// Request bodies larger than this are rejected.
+const MAX_BODY: u64 = 1 << 20;
+
+/// An HTTP status and the message reported with it.
+type Failure = (u16, String);
+
+/// Serves requests until the process is stopped.
+///
+/// # Errors
+/// Returns error if the roadmap is not initialized or the port cannot be bound.
+pub(super) fn serve(host: &str, port: u16, origin: Option<&str>) -> Result<()> {
+    let token = std::env::var(TOKEN_ENV).ok().filter(|t| !t.is_empty());
+    if origin.is_some() && token.is_none() {
+        bail!("--allow-origin needs {TOKEN_ENV}: cross-origin reads must be authorized");
+    }
+    let mut conn = Db::connect()?;
+    let server = Server::http((host, port))
+        .map_err(|e| anyhow!("Failed to listen on {host}:{port}: {e}"))?;
+    eprintln!("roadmap HTTP API listening on http://{}", server.server_addr());
+    if token.is_none() {
+        eprintln!("   Writes are disabled: set {TOKEN_ENV} to enable them");
+    }
+
+    // Once browsers may call in, reads are no longer only for those on this machine.
+    let guarded = origin.is_some();
+    for mut request in server.incoming_requests() {
+        let cors = cors_headers(&request, origin);
+        if *request.method() == Method::Options {
+            respond(request, Ok(json!({})), cors);
+            continue;
+        }
+        if is_check(&request) {
+            let token = token.clone();
+            std::thread::spawn(move || {
+                let result = Db::connect()
+                    .map_err(|e| internal(&e))
+                    .and_then(|mut conn| answer(&mut conn, &mut request, token.as_deref(), guarded));
+                respond(request, result, cors);
+            });
+            continue;
+        }
+        let result = answer(&mut conn, &mut request, token.as_deref(), guarded);
+        respond(request, result, cors);
+    }
+    Ok(())
+}
+
+fn respond(request: Request, result: Result<Value, Failure>, cors: Option<Vec<Header>>) {
+    let (status, body) = match result {
+        Ok(value) => (200, value),
+        Err((status, message)) => (status, json!({ "error": message })),
+    };
+    let mut response = Response::from_string(body.to_string()).with_status_code(status);
+    if let Ok(header) = Header::from_bytes("Content-Type", "application/json") {
+        response.add_header(header);
+    }
+    for header in cors.into_iter().flatten() {
+        response.add_header(header);
+    }
+    if let Err(e) = request.respond(response) {
+        eprintln!("Failed to send response: {e}");
+    }
+}
+
+/// CORS headers for a request from the allowed origin; `None` for any other request.
+fn cors_headers(request: &Request, origin: Option<&str>) -> Option<Vec<Header>> {
+    let origin = origin?;
+    let sent = request.headers().iter().find(|h| h.field.equiv("Origin"))?;
+    if sent.value.as_str() != origin {
+        return None;
+    }
+    let headers = [
+        ("Access-Control-Allow-Origin", origin),
+        ("Access-Control-Allow-Methods", "GET, POST, DELETE"),
+        ("Access-Control-Allow-Headers", "Authorization, Content-Type"),
+        ("Vary", "Origin"),
+    ];
+    Some(headers.iter().filter_map(|(name, value)| Header::from_bytes(*name, *value).ok()).collect())
+}
+
+/// Whether the request runs a verification, which can take as long as the test suite.
+fn is_check(request: &Request) -> bool {
+    let path = request.url().split('?').next().unwrap_or_default();
+    *request.method() == Method::Post && path.trim_end_matches('/').ends_with("/check")
+}
+
+fn answer(
+    conn: &mut Connection,
+    request: &mut Request,
+    token: Option<&str>,
+    reads_guarded: bool,
+) -> Result<Value, Failure> {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let segments: Vec<String> = path.split('/').filter(|s| !s.is_empty()).map(decode).collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    let method = request.method().clone();
+
+    if method != Method::Get || reads_guarded {
+        authorize(request, token)?;
+    }
+    if let ["tasks", slug, ..] = segments.as_slice() {
+        let exists = TaskRepo::new(conn).find_by_slug(slug).map_err(|e| internal(&e))?.is_some();
+        if !exists {
+            return Err((404, format!("No task '{slug}'")));
+        }
+    }
+
+    let (operation, args) = match (&method, segments.as_slice()) {
+        (Method::Get, ["status"]) => ("status", json!({})),
+        (Method::Get, ["next"]) => ("next", json!({})),
+        (Method::Get, ["tasks"]) => ("list", json!({})),
+        (Method::Get, ["tasks", slug]) => ("why", json!({ "task": slug })),
+        (Method::Get, ["search"]) => ("search", search_args(query)?),
+        (Method::Post, ["tasks"]) => ("add", body(request)?),
+        (Method::Post, ["tasks", slug, "do"]) => ("do", json!({ "task": slug })),
+        (Method::Post, ["tasks", slug, "check"]) => ("check", json!({ "task": slug })),
+        (Method::Post, ["dependencies"]) => ("link", body(request)?),
+        (Method::Delete, ["dependencies"]) => ("unlink", body(request)?),
+        _ if known(&segments) => return Err((405, format!("{method} is not allowed on {path}"))),
+        _ => return Err((404, format!("No endpoint {path}"))),
+    };
+    ops::call(conn, operation, &args).map_err(|e| (status(&e), format!("{e:#}")))
+}
+
+/// The status for a failed operation: what kind of failure, where roadmap says.
+fn status(e: &anyhow::Error) -> u16 {
+    match e.downcast_ref::<roadmap::Error>() {
+        Some(roadmap::Error::TaskNotFound(_) | roadmap::Error::NoActiveTask) => 404,
+        Some(roadmap::Error::Cycle { .. } | roadmap::Error::Blocked { .. }) => 409,
+        Some(roadmap::Error::Locked { .. } | roadmap::Error::Busy) => 503,
+        _ => 400,
+    }
+}
+
+/// Whether the path exists under some other method.
+fn known(segments: &[&str]) -> bool {
+    matches!(
+        segments,
+        ["status" | "next" | "tasks" | "search" | "dependencies"]
+            | ["tasks", _]
+            | ["tasks", _, "do" | "check"]
+    )
+}
+
+fn authorize(request: &Request, token: Option<&str>) -> Result<(), Failure> {
+    let Some(token) = token else {
+        return Err((403, format!("Writes are disabled: the server has no {TOKEN_ENV}")));
+    };
+    let presented = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .and_then(|h| h.value.as_str().strip_prefix("Bearer "));
+    match presented {
+        Some(presented) if constant_time_eq(presented.as_bytes(), token.as_bytes()) => Ok(()),
+        _ => Err((401, "Missing or invalid bearer token".to_string())),
+    }
+}
+
+/// Compares without exiting early, so response timing does not leak the token.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// The JSON object in the request body; an empty body is an empty object.
+fn body(request: &mut Request) -> Result<Value, Failure> {
+    let mut raw = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY + 1)
+        .read_to_string(&mut raw)
+        .map_err(|e| (400, format!("Unreadable body: {e}")))?;
+    if raw.len() as u64 > MAX_BODY {
+        return Err((413, "Request body too large".to_string()));
+    }
+    if raw.trim().is_empty() {
+        return Ok(json!({}));
+    }
+    match serde_json::from_str(&raw) {
+        Ok(value @ Value::Object(_)) => Ok(value),
+        Ok(_) => Err((400, "Body must be a JSON object".to_string())),
+        Err(e) => Err((400, format!("Invalid JSON body: {e}"))),
+    }
+}
+
+/// `?q=<query>&limit=<n>` as search arguments.
+fn search_args(query: &str) -> Result<Value, Failure> {
+    let mut args = json!({});
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        match key {
+            "q" => args["query"] = json!(decode(value)),
+            "limit" => {
+                let limit: u64 = value
+                    .parse()
+                    .map_err(|_| (400, format!("Invalid limit '{value}'")))?;
+                args["limit"] = json!(limit);
+            }
+            _ => {}
+        }
+    }
+    Ok(args)
+}
+
+/// Decodes `%XX` escapes and `+` in a URL component.
+fn decode(component: &str) -> String {
+    let bytes = component.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (escaped, bytes[i]) {
+            (Some(byte), _) => {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+            (None, b'+') => out.push(b' '),
+            (None, byte) => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn internal(e: &anyhow::Error) -> Failure {
+    (500, format!("{e:#}"))
+}
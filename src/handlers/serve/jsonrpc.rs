@@ -0,0 +1,87 @@
+//! Newline-delimited JSON-RPC 2.0 over stdin/stdout.
+//!
+//! One message per line in, one response per line out. A line holding an
+//! array is a batch and is answered with an array. Notifications (messages
+//! without an `id`) are handled but never answered.
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+pub(crate) const PARSE_ERROR: i64 = -32700;
+pub(crate) const INVALID_REQUEST: i64 = -32600;
+pub(crate) const METHOD_NOT_FOUND: i64 = -32601;
+pub(crate) const INVALID_PARAMS: i64 = -32602;
+/// Start of the range reserved for implementation-defined server errors.
+pub(crate) const SERVER_ERROR: i64 = -32000;
+
+/// The code for a failed call: [`SERVER_ERROR`] less the CLI's exit code
+/// for failures roadmap can name (`-32004` for an unknown task), or
+/// [`SERVER_ERROR`] itself.
+pub(crate) fn server_error(e: &anyhow::Error) -> i64 {
+    SERVER_ERROR - i64::from(e.downcast_ref::<roadmap::Error>().map_or(0, roadmap::Error::exit_code))
+}
+
+pub(crate) struct Request {
+    pub method: String,
+    pub params: Value,
+}
+
+/// The outcome of one request: a result, or an error code and message.
+pub(crate) type Reply = std::result::Result<Value, (i64, String)>;
+
+/// Answers requests from stdin with `handler` until stdin closes.
+///
+/// # Errors
+/// Returns error if stdin or stdout fails.
+pub(crate) fn run(mut handler: impl FnMut(&Request) -> Reply) -> Result<()> {
+    let stdout = io::stdout();
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Value>(&line) {
+            Err(e) => Some(error(&Value::Null, PARSE_ERROR, &format!("Parse error: {e}"))),
+            Ok(Value::Array(batch)) if batch.is_empty() => {
+                Some(error(&Value::Null, INVALID_REQUEST, "Empty batch"))
+            }
+            Ok(Value::Array(batch)) => {
+                let responses: Vec<Value> = batch
+                    .iter()
+                    .filter_map(|message| respond(message, &mut handler))
+                    .collect();
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            Ok(message) => respond(&message, &mut handler),
+        };
+        if let Some(response) = response {
+            let mut out = stdout.lock();
+            writeln!(out, "{response}")?;
+            out.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// The response to one message, or `None` for notifications.
+fn respond(message: &Value, handler: &mut impl FnMut(&Request) -> Reply) -> Option<Value> {
+    let id = message.get("id").cloned();
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return Some(error(&id.unwrap_or(Value::Null), INVALID_REQUEST, "Missing `method`"));
+    };
+    let request = Request {
+        method: method.to_string(),
+        params: message.get("params").cloned().unwrap_or_else(|| json!({})),
+    };
+    let reply = handler(&request);
+    let id = id?;
+    Some(match reply {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error(&id, code, &message),
+    })
+}
+
+fn error(id: &Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
@@ -0,0 +1,70 @@
+//! Model Context Protocol server over stdio.
+//!
+//! Every roadmap operation is offered as a tool whose result is plain JSON
+//! text, so agents never have to parse colored terminal output. Diagnostics
+//! go to stderr.
+
+use super::jsonrpc::{self, Request, INVALID_PARAMS, METHOD_NOT_FOUND};
+use super::ops;
+use anyhow::Result;
+use roadmap::engine::db::Db;
+use rusqlite::Connection;
+use serde_json::{json, Value};
+
+/// Protocol revisions this server speaks, newest first.
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// Answers requests from stdin until it closes.
+///
+/// # Errors
+/// Returns error if the roadmap is not initialized or stdin or stdout fails.
+pub(super) fn serve() -> Result<()> {
+    let mut conn = Db::connect()?;
+    eprintln!("roadmap MCP server ready on stdio");
+    jsonrpc::run(|request: &Request| match request.method.as_str() {
+        "initialize" => Ok(initialize(&request.params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => {
+            let Some(name) = request.params.get("name").and_then(Value::as_str) else {
+                return Err((INVALID_PARAMS, "tools/call needs a tool `name`".to_string()));
+            };
+            let args = request.params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+            Ok(call_tool(&mut conn, name, &args))
+        }
+        // e.g. `notifications/initialized`: nothing to do, and no reply is sent.
+        method if method.starts_with("notifications/") => Ok(Value::Null),
+        other => Err((METHOD_NOT_FOUND, format!("Method not found: {other}"))),
+    })
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|v| PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": { "name": "roadmap", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "A proof-carrying roadmap. Call `next` to see what is actionable, `do` to pick a task, and `check` to verify it and record a proof."
+    })
+}
+
+fn tools() -> Vec<Value> {
+    ops::catalog()
+        .into_iter()
+        .map(|(name, description, schema)| {
+            json!({ "name": name, "description": description, "inputSchema": schema })
+        })
+        .collect()
+}
+
+/// Tool failures are results with `isError`, so the agent sees the message.
+fn call_tool(conn: &mut Connection, name: &str, args: &Value) -> Value {
+    let (text, is_error) = match ops::call(conn, name, args) {
+        Ok(value) => (serde_json::to_string_pretty(&value).unwrap_or_default(), false),
+        Err(e) => (format!("{e:#}"), true),
+    };
+    json!({ "content": [{ "type": "text", "text": text }], "isError": is_error })
+}
@@ -0,0 +1,267 @@
+//! Operations the servers expose, run in-process and answered as JSON.
+//!
+//! Callers keep one connection open across calls; each call is recorded as
+//! its own operation, so `roadmap undo` reverts one call at a time. Verification runs this same
+//! binary as `check --json`: its hooks, signing, and cascade stay identical
+//! to the CLI, and a hanging test cannot wedge the server.
+
+use anyhow::{bail, Context, Result};
+use crate::handlers::add::{self, AddOptions};
+use crate::handlers::{dep, do_task, list, next, search, status, why};
+use roadmap::engine::context::RepoContext;
+use roadmap::engine::graph::TaskGraph;
+use roadmap::engine::journal;
+use roadmap::engine::lock;
+use roadmap::engine::oplog;
+use roadmap::engine::repo::TaskRepo;
+use rusqlite::Connection;
+use serde_json::{json, Value};
+use std::process::{Command, Stdio};
+use std::time::Instant;
+
+/// Operations that can change the roadmap, journaled as they are called.
+/// `check` is absent: it runs `roadmap check --json`, which journals itself.
+const JOURNALED: &[&str] = &["add", "link", "unlink", "do"];
+
+/// Operations that take the write lock (see [`lock`]) for their duration.
+const LOCKED: &[&str] = &["add", "link", "unlink", "do", "check"];
+
+/// Name, description, and JSON Schema of the arguments of every operation.
+pub(crate) fn catalog() -> Vec<(&'static str, &'static str, Value)> {
+    let task = json!({
+        "type": "object",
+        "properties": { "task": { "type": "string", "description": "Slug, ID, or fuzzy reference" } },
+        "required": ["task"]
+    });
+    let edge = json!({
+        "type": "object",
+        "properties": {
+            "blocker": { "type": "string", "description": "Task that must be done first" },
+            "blocked": { "type": "string", "description": "Task that waits on it" }
+        },
+        "required": ["blocker", "blocked"]
+    });
+    vec![
+        (
+            "status",
+            "Status counts, the active task, and the first few frontier tasks.",
+            json!({ "type": "object", "properties": {} }),
+        ),
+        (
+            "next",
+            "Actionable tasks: unblocked and Unproven, Stale, or Broken.",
+            json!({ "type": "object", "properties": {} }),
+        ),
+        (
+            "list",
+            "Every task with its derived status, test command, and scopes.",
+            json!({ "type": "object", "properties": {} }),
+        ),
+        (
+            "search",
+            "Tasks whose slug or title matches a query, best matches first.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "limit": { "type": "integer", "minimum": 1, "default": 20 }
+                },
+                "required": ["query"]
+            }),
+        ),
+        (
+            "why",
+            "Explain a task's status with its latest proof and proof history.",
+            task.clone(),
+        ),
+        (
+            "add",
+            "Add a task. The slug is derived from the title.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string" },
+                    "after": { "type": "string", "description": "Task this one depends on" },
+                    "parent": { "type": "string", "description": "Task this one is a subtask of" },
+                    "assignee": { "type": "string", "description": "Who will work on it" },
+                    "blocks": { "type": "string", "description": "Task that depends on this one" },
+                    "test": { "type": "string", "description": "Verification command" },
+                    "scopes": { "type": "array", "items": { "type": "string" }, "description": "Globs of the files this task covers" },
+                    "priority": { "type": "integer", "description": "Higher comes first in `next`" },
+                    "due": { "type": "string", "description": "Due date: YYYY-MM-DD, today, tomorrow, +3d, or +2w" },
+                    "estimate": { "type": "string", "description": "Expected effort: 30m, 4h, 2d, or 1w" },
+                    "tags": { "type": "array", "items": { "type": "string" }, "description": "Labels such as `infra` or `ui`" },
+                    "description": { "type": "string", "description": "Long-form context: what to do, why, and where to start" }
+                },
+                "required": ["title"]
+            }),
+        ),
+        ("link", "Make `blocker` a dependency of `blocked`. Adding an existing edge is a no-op.", edge.clone()),
+        ("unlink", "Remove the dependency of `blocked` on `blocker`.", edge),
+        ("do", "Make a task the active one. Fails if it is blocked.", task),
+        (
+            "check",
+            "Run the active task's verification and record the proof. Pass `task` to make it active first.",
+            json!({
+                "type": "object",
+                "properties": { "task": { "type": "string" } }
+            }),
+        ),
+    ]
+}
+
+/// Runs an operation by name.
+///
+/// # Errors
+/// Returns error if the operation is unknown, an argument is missing, or the operation fails.
+pub(crate) fn call(conn: &mut Connection, name: &str, args: &Value) -> Result<Value> {
+    let command = format!("{name} {args}");
+    let _lock = LOCKED.contains(&name).then(|| lock::acquire(&command)).transpose()?;
+    oplog::start(conn, &command)?;
+    let started = Instant::now();
+    let result = run(conn, name, args);
+    if JOURNALED.contains(&name) {
+        let outcome = result.as_ref().map(|_| ()).map_err(|e| format!("{e:#}"));
+        journal::record(conn, &command, outcome, started.elapsed())?;
+    }
+    result
+}
+
+fn run(conn: &mut Connection, name: &str, args: &Value) -> Result<Value> {
+    match name {
+        "status" => {
+            let graph = TaskGraph::build(conn)?;
+            let report = status::report(&TaskRepo::new(conn), &graph, &RepoContext::new()?)?;
+            Ok(serde_json::to_value(report)?)
+        }
+        "next" => {
+            let graph = TaskGraph::build(conn)?;
+            Ok(serde_json::to_value(next::views(&graph.get_frontier(), &graph))?)
+        }
+        "list" => {
+            let tasks = TaskRepo::new(conn).get_all()?;
+            Ok(serde_json::to_value(list::views(&tasks, &RepoContext::new()?))?)
+        }
+        "search" => {
+            let limit = match args.get("limit") {
+                None | Some(Value::Null) => 20,
+                Some(value) => value
+                    .as_u64()
+                    .filter(|&n| n > 0)
+                    .context("Argument `limit` must be a positive integer")?,
+            };
+            let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+            let tasks = TaskRepo::new(conn).search(required(args, "query")?, limit)?;
+            Ok(serde_json::to_value(search::hits(&tasks, &RepoContext::new()?))?)
+        }
+        "why" => Ok(serde_json::to_value(why::report(conn, required(args, "task")?)?)?),
+        "add" => add_task(conn, args),
+        "link" => {
+            let (upstream, downstream, added) =
+                dep::link(conn, required(args, "blocker")?, required(args, "blocked")?)?;
+            Ok(json!({ "blocker": upstream.slug, "blocked": downstream.slug, "added": added }))
+        }
+        "unlink" => {
+            let (upstream, downstream) =
+                dep::unlink(conn, required(args, "blocker")?, required(args, "blocked")?)?;
+            Ok(json!({ "blocker": upstream.slug, "blocked": downstream.slug }))
+        }
+        "do" => {
+            let task = do_task::activate(conn, required(args, "task")?, false)?;
+            Ok(json!({ "active": task.slug, "title": task.title }))
+        }
+        "check" => check(conn, optional(args, "task")?),
+        other => bail!("Unknown operation '{other}'"),
+    }
+}
+
+/// Whether `name` is an operation in the catalog.
+pub(crate) fn exists(name: &str) -> bool {
+    catalog().iter().any(|(n, _, _)| *n == name)
+}
+
+fn add_task(conn: &mut Connection, args: &Value) -> Result<Value> {
+    let scopes = match args.get("scopes") {
+        None | Some(Value::Null) => None,
+        Some(value) => Some(
+            serde_json::from_value::<Vec<String>>(value.clone())
+                .context("`scopes` must be an array of strings")?,
+        ),
+    };
+    let opts = AddOptions {
+        title: required(args, "title")?,
+        blocks: optional(args, "blocks")?,
+        after: optional(args, "after")?,
+        test_cmd: optional(args, "test")?,
+        scopes,
+        shell: None,
+        test_unix: None,
+        test_windows: None,
+        cwd: None,
+        coverage: None,
+        min_coverage: None,
+        bench_threshold: None,
+        budgets: Vec::new(),
+        priority: match args.get("priority") {
+            None | Some(Value::Null) => 0,
+            Some(value) => value.as_i64().context("`priority` must be an integer")?,
+        },
+        due: optional(args, "due")?,
+        estimate: optional(args, "estimate")?,
+        tags: match args.get("tags") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(value) => serde_json::from_value(value.clone()).context("`tags` must be an array of strings")?,
+        },
+        description: optional(args, "description")?.map(str::to_string),
+        edit: false,
+        parent: optional(args, "parent")?,
+        assignee: optional(args, "assignee")?,
+        no_probe: false,
+    };
+    let added = add::create(conn, &opts)?;
+    Ok(json!({
+        "slug": added.task.slug,
+        "title": added.task.title,
+        "links": added.links.iter().map(|(blocker, blocked)| json!({ "blocker": blocker, "blocked": blocked })).collect::<Vec<_>>(),
+        "warnings": add::probe_warnings(&added.task)?,
+    }))
+}
+
+/// Runs `check --json` and returns its events; the last one carries the result.
+fn check(conn: &Connection, task: Option<&str>) -> Result<Value> {
+    if let Some(task) = task {
+        do_task::activate(conn, task, false)?;
+    }
+    let exe = std::env::current_exe().context("Failed to locate the roadmap binary")?;
+    let output = Command::new(exe)
+        .args(["check", "--json"])
+        .env("NO_COLOR", "1")
+        // The server holds the lock for this call; the check runs under it.
+        .envs(lock::shared())
+        .stdin(Stdio::null())
+        .output()
+        .context("Failed to run roadmap check")?;
+    let events: Vec<Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    if events.is_empty() && !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(json!({
+        "exit_code": output.status.code(),
+        "events": events,
+    }))
+}
+
+fn required<'a>(args: &'a Value, key: &str) -> Result<&'a str> {
+    optional(args, key)?.with_context(|| format!("Missing argument `{key}`"))
+}
+
+fn optional<'a>(args: &'a Value, key: &str) -> Result<Option<&'a str>> {
+    match args.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s)),
+        Some(_) => bail!("Argument `{key}` must be a string"),
+    }
+}
@@ -1,45 +1,103 @@
 //! Handler for the `stale` command.
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use colored::Colorize;
+use crate::theme::Paint;
 use roadmap::engine::context::RepoContext;
 use roadmap::engine::db::Db;
+use roadmap::engine::graph::StatusCounts;
 use roadmap::engine::repo::TaskRepo;
-use roadmap::engine::types::DerivedStatus;
+use roadmap::engine::types::{DerivedStatus, Task};
+use schemars::{JsonSchema, Schema};
 use serde::Serialize;
 
-/// Scans for and lists all tasks with stale proofs.
+/// Statuses `--check` fails on unless `--fail-on` says otherwise.
+const DEFAULT_FAIL_ON: [DerivedStatus; 2] = [DerivedStatus::Stale, DerivedStatus::Broken];
+
+/// Statuses `--fail-on` accepts.
+const FAILABLE: [DerivedStatus; 3] = [DerivedStatus::Broken, DerivedStatus::Stale, DerivedStatus::Attested];
+
+/// Scans for and lists all tasks with stale proofs, and those whose latest verification failed.
+///
+/// With `check`, fails once the listing is printed if any task has a status in
+/// `fail_on` (stale and broken when empty), so CI can gate on the exit code.
 ///
 /// # Errors
-/// Returns error if database query fails.
-pub fn handle(json: bool) -> Result<()> {
+/// Returns error if database query fails, or the check fails.
+pub fn handle(json: bool, check: bool, fail_on: &[String]) -> Result<()> {
+    let fail_on = parse_fail_on(fail_on)?;
     let conn = Db::connect()?;
     let repo = TaskRepo::new(&conn);
     let tasks = repo.get_all()?;
     let context = RepoContext::new()?;
     let head_sha = context.head_sha();
+    let counts: StatusCounts = tasks.iter().map(|t| t.derive_status(&context)).collect();
 
-    let stale_tasks: Vec<_> = tasks
+    let (stale_tasks, broken_tasks): (Vec<_>, Vec<_>) = tasks
         .into_iter()
-        .filter(|t| matches!(t.derive_status(&context), DerivedStatus::Stale))
-        .collect();
+        .filter(|t| matches!(t.derive_status(&context), DerivedStatus::Stale | DerivedStatus::Broken))
+        .partition(|t| t.derive_status(&context) == DerivedStatus::Stale);
 
     if json {
-        return print_json(&stale_tasks, head_sha);
+        print_json(&stale_tasks, &broken_tasks, head_sha)?;
+    } else {
+        print_human(&stale_tasks, &broken_tasks, head_sha);
     }
+    if check {
+        enforce(&counts, &fail_on)?;
+    }
+    Ok(())
+}
 
-    print_human(&stale_tasks, head_sha);
+/// The `--fail-on` statuses, or the default ones when none are given.
+///
+/// # Errors
+/// Returns error if a value is not broken, stale, or attested.
+pub(crate) fn parse_fail_on(values: &[String]) -> Result<Vec<DerivedStatus>> {
+    if values.is_empty() {
+        return Ok(DEFAULT_FAIL_ON.to_vec());
+    }
+    values
+        .iter()
+        .map(|v| match v.parse::<DerivedStatus>() {
+            Ok(status) if FAILABLE.contains(&status) => Ok(status),
+            _ => bail!("Invalid --fail-on '{v}' (expected: broken, stale, attested)"),
+        })
+        .collect()
+}
+
+/// Fails, naming the counts, if any task has one of the `fail_on` statuses.
+///
+/// # Errors
+/// Returns error if the check fails.
+pub(crate) fn enforce(counts: &StatusCounts, fail_on: &[DerivedStatus]) -> Result<()> {
+    let found: Vec<String> = fail_on
+        .iter()
+        .filter(|s| counts.of(**s) > 0)
+        .map(|s| format!("{} {}", counts.of(*s), s.to_string().to_lowercase()))
+        .collect();
+    if !found.is_empty() {
+        bail!("Check failed: {} task(s)", found.join(", "));
+    }
     Ok(())
 }
 
-#[derive(Serialize)]
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(StaleReport)
+}
+
+#[derive(Serialize, JsonSchema)]
 struct StaleReport {
     head_sha: String,
     stale_count: usize,
     tasks: Vec<StaleTaskView>,
+    /// Tasks whose latest verification failed.
+    broken_count: usize,
+    broken: Vec<StaleTaskView>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 struct StaleTaskView {
     id: i64,
     slug: String,
@@ -47,37 +105,43 @@ struct StaleTaskView {
     proof_sha: Option<String>,
 }
 
-fn print_json(tasks: &[roadmap::engine::types::Task], head_sha: &str) -> Result<()> {
-    let views: Vec<StaleTaskView> = tasks.iter().map(|t| {
-        StaleTaskView {
-            id: t.id,
-            slug: t.slug.clone(),
-            title: t.title.clone(),
-            proof_sha: t.proof.as_ref().map(|p| p.git_sha.clone()),
-        }
-    }).collect();
+fn print_json(tasks: &[Task], broken: &[Task], head_sha: &str) -> Result<()> {
+    let views = |tasks: &[Task]| -> Vec<StaleTaskView> {
+        tasks.iter().map(|t| {
+            StaleTaskView {
+                id: t.id,
+                slug: t.slug.clone(),
+                title: t.title.clone(),
+                proof_sha: t.proof.as_ref().map(|p| p.git_sha.clone()),
+            }
+        }).collect()
+    };
 
     let report = StaleReport {
         head_sha: head_sha.to_string(),
         stale_count: tasks.len(),
-        tasks: views,
+        tasks: views(tasks),
+        broken_count: broken.len(),
+        broken: views(broken),
     };
 
     println!("{}", serde_json::to_string_pretty(&report)?);
     Ok(())
 }
 
-fn print_human(tasks: &[roadmap::engine::types::Task], head_sha: &str) {
+fn print_human(tasks: &[Task], broken: &[Task], head_sha: &str) {
     let short_head = &head_sha[..7.min(head_sha.len())];
 
-    if tasks.is_empty() {
-        println!("{} No stale tasks found. The truth is fresh.", "✓".green());
+    if tasks.is_empty() && broken.is_empty() {
+        println!("{} No stale tasks found. The truth is fresh.", "✓".success());
         return;
     }
 
-    println!("{} Found {} stale tasks:", "⚡".yellow(), tasks.len());
-    println!("   Current HEAD: {}", short_head.dimmed());
-    println!();
+    if !tasks.is_empty() {
+        println!("{} Found {} stale tasks:", "⚡".warning(), tasks.len());
+        println!("   Current HEAD: {}", short_head.dimmed());
+        println!();
+    }
 
     for task in tasks {
         if let Some(proof) = &task.proof {
@@ -85,14 +149,24 @@ fn print_human(tasks: &[roadmap::engine::types::Task], head_sha: &str) {
 
             println!(
                 "   [{}] {}",
-                task.slug.yellow().bold(),
+                task.slug.warning().bold(),
                 task.title
             );
             println!(
                 "     last proven at: {}  (diff: {})",
                 proof_sha.dimmed(),
-                "HEAD moved".red()
+                "HEAD moved".failure()
             );
         }
     }
-}
\ No newline at end of file
+
+    if !broken.is_empty() {
+        if !tasks.is_empty() {
+            println!();
+        }
+        println!("{} Found {} broken tasks:", "✗".failure(), broken.len());
+        for task in broken {
+            println!("   [{}] {}", task.slug.failure().bold(), task.title);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Handlers for the `push-state` and `pull-state` commands.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::canonical;
+use roadmap::engine::db::Db;
+use roadmap::engine::snapshot::Snapshot;
+use roadmap::engine::state_ref;
+
+/// Publishes the roadmap state to the state ref, pushing it to `remote` if given.
+///
+/// # Errors
+/// Returns error if git plumbing fails or the push is rejected.
+pub fn push(remote: Option<&str>) -> Result<()> {
+    let conn = Db::connect()?;
+    let snapshot = Snapshot::capture(&conn)?;
+    let json = canonical::to_pretty(&snapshot)?;
+    let local = state_ref::local_ref();
+
+    if state_ref::read(&local)?.as_deref() == Some(json.trim_end()) {
+        println!("{} State unchanged at {}", "✓".success(), local.dimmed());
+    } else {
+        let message = format!(
+            "roadmap state: {} tasks, {} edges, {} proofs",
+            snapshot.tasks.len(),
+            snapshot.edges.len(),
+            snapshot.proofs.len()
+        );
+        let tracking = remote.and_then(|r| state_ref::resolve(&state_ref::remote_ref(r)));
+        let commit = state_ref::write(&json, &message, tracking.as_deref())?;
+        println!(
+            "{} {} → {}",
+            "📌".info(),
+            message,
+            format!("{local} @ {}", &commit[..7.min(commit.len())]).dimmed()
+        );
+    }
+
+    match remote {
+        Some(remote) => {
+            state_ref::push(remote)?;
+            println!("{} Pushed state to {}", "☁".info(), remote.warning());
+        }
+        None => println!("   Share it with `roadmap push-state <remote>`."),
+    }
+    Ok(())
+}
+
+/// Merges state from `remote` (or the local state ref) into the database.
+///
+/// # Errors
+/// Returns error if fetching or applying the state fails.
+pub fn pull(remote: Option<&str>) -> Result<()> {
+    let refname = match remote {
+        Some(remote) => {
+            if !state_ref::fetch(remote)? {
+                println!("{} '{}' has no roadmap state yet", "?".warning(), remote);
+                return Ok(());
+            }
+            state_ref::remote_ref(remote)
+        }
+        None => state_ref::local_ref(),
+    };
+
+    let Some(json) = state_ref::read(&refname)? else {
+        bail!("No roadmap state at {refname}");
+    };
+    let snapshot = Snapshot::parse(&json)?;
+    let conn = Db::connect()?;
+    let summary = snapshot.apply(&conn)?;
+
+    println!(
+        "{} Pulled state from {}: {} tasks, {} edges, {} proofs added",
+        "⬇".info(),
+        refname.dimmed(),
+        summary.tasks_added,
+        summary.edges_added,
+        summary.proofs_added
+    );
+    for edge in &summary.edges_rejected {
+        println!(
+            "   {} skipped edge {} → {} (would create a cycle)",
+            "⚠".warning(),
+            edge.blocker,
+            edge.blocked
+        );
+    }
+    Ok(())
+}
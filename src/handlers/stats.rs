@@ -0,0 +1,203 @@
+//! Handler for the `stats` command.
+
+use anyhow::Result;
+use colored::Colorize;
+use crate::table::{Align, Cell, Table};
+use crate::theme::Paint;
+use roadmap::engine::db::Db;
+use roadmap::engine::reliability::{self, TaskReliability};
+use roadmap::engine::repo::ProofRepo;
+use roadmap::engine::stats::{self, ProofStats};
+use schemars::Schema;
+
+/// Tasks that broke at least this often are called out for hardening.
+const HARDEN_AT_BREAKS: usize = 2;
+/// How many hardening candidates to name.
+const HARDEN_LIMIT: usize = 3;
+/// Cells in the widest weekly bar.
+const WEEK_BAR_WIDTH: usize = 20;
+
+/// Prints analytics computed from the proof history: the overview, or with
+/// `reliability` the per-task reliability report.
+///
+/// # Errors
+/// Returns error if the database query fails.
+pub fn handle(reliability: bool, json: bool) -> Result<()> {
+    let conn = Db::connect()?;
+    let trail = ProofRepo::new(&conn).get_audit_trail()?;
+    if !reliability {
+        let report = stats::analyze(&trail);
+        if json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            print_overview(&report);
+        }
+        return Ok(());
+    }
+    let report = reliability::analyze(&trail);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_reliability(&report);
+    }
+    Ok(())
+}
+
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(ProofStats)
+}
+
+/// JSON Schema of the `--reliability --json` output.
+pub(crate) fn reliability_schema() -> Schema {
+    schemars::schema_for!(Vec<TaskReliability>)
+}
+
+fn print_overview(report: &ProofStats) {
+    println!("{} Stats", "📈".info());
+    if report.tasks.is_empty() {
+        println!("   (No proofs recorded yet)");
+        return;
+    }
+    let rate = report.pass_rate.map_or("-".to_string(), |r| format!("{r:.0}%"));
+    println!(
+        "   {} verified run(s), {} passing {}",
+        report.verified,
+        rate.bold(),
+        format!(
+            "· {} attested ({:.0}% of proofs)",
+            report.attested,
+            report.attested_percent.unwrap_or_default()
+        )
+        .dimmed()
+    );
+    if report.timeouts > 0 {
+        println!("   {} {} run(s) hit the timeout", "⏱".failure(), report.timeouts);
+    }
+    println!();
+
+    let mut table = Table::new(&[
+        Align::Left,
+        Align::Right,
+        Align::Right,
+        Align::Right,
+        Align::Right,
+        Align::Right,
+        Align::Right,
+        Align::Right,
+    ])
+    .flex(0);
+    table.row(
+        ["task", "runs", "pass", "mean", "p95", "cpu", "peak mem", "attested"]
+            .into_iter()
+            .map(|h| Cell::new(h, |s| s.dimmed()))
+            .collect(),
+    );
+    for task in &report.tasks {
+        let verified = task.runs > 0;
+        table.row(vec![
+            Cell::new(format!("[{}]", task.slug), |s| s.accent()),
+            Cell::plain(task.runs.to_string()),
+            Cell::plain(if verified { format!("{:.0}%", task.pass_rate) } else { "-".to_string() }),
+            Cell::plain(if verified { format_ms(task.mean_ms) } else { "-".to_string() }),
+            Cell::plain(if verified { format_ms(task.p95_ms) } else { "-".to_string() }),
+            Cell::plain(task.mean_cpu_ms.map_or_else(|| "-".to_string(), format_ms)),
+            Cell::plain(task.peak_rss_kb.map_or_else(|| "-".to_string(), |kb| format!("{} MB", kb.div_ceil(1024)))),
+            Cell::new(task.attested.to_string(), |s| s.dimmed()),
+        ]);
+    }
+    for line in table.render() {
+        println!("{line}");
+    }
+
+    if report.weeks.is_empty() {
+        return;
+    }
+    println!();
+    println!("   Tasks proven per week:");
+    let most = report.weeks.iter().map(|w| w.proven).max().unwrap_or_default().max(1);
+    let mut weeks = Table::new(&[Align::Left, Align::Right, Align::Left]).flex(0);
+    for week in &report.weeks {
+        let filled = (week.proven * WEEK_BAR_WIDTH).div_ceil(most);
+        weeks.row(vec![
+            Cell::new(&week.week, |s| s.dimmed()),
+            Cell::plain(week.proven.to_string()),
+            Cell::new("█".repeat(filled), |s| s.success()),
+        ]);
+    }
+    for line in weeks.render() {
+        println!("{line}");
+    }
+}
+
+fn format_ms(ms: u64) -> String {
+    if ms >= 60_000 {
+        format!("{}m{:02}s", ms / 60_000, ms % 60_000 / 1000)
+    } else if ms >= 1000 {
+        format!("{}.{}s", ms / 1000, ms % 1000 / 100)
+    } else {
+        format!("{ms}ms")
+    }
+}
+
+fn print_reliability(report: &[TaskReliability]) {
+    println!("{} Reliability", "📈".info());
+    if report.is_empty() {
+        println!("   (No verifications recorded yet)");
+        return;
+    }
+
+    let mut table = Table::new(&[
+        Align::Left,
+        Align::Right,
+        Align::Right,
+        Align::Right,
+        Align::Right,
+        Align::Right,
+    ])
+    .flex(0);
+    table.row(
+        ["task", "runs", "pass", "breaks", "between", "repair"]
+            .into_iter()
+            .map(|h| Cell::new(h, |s| s.dimmed()))
+            .collect(),
+    );
+    for task in report {
+        let fragile = task.breaks >= HARDEN_AT_BREAKS;
+        table.row(vec![
+            Cell::new(format!("[{}]", task.slug), |s| s.accent()),
+            Cell::plain(task.runs.to_string()),
+            Cell::plain(format!("{:.0}%", task.pass_rate)),
+            Cell::new(task.breaks.to_string(), move |s| {
+                if fragile { s.failure() } else { s.into() }
+            }),
+            Cell::plain(task.mean_hours_between_breaks.map_or("-".to_string(), format_hours)),
+            Cell::plain(task.mean_hours_to_repair.map_or("-".to_string(), format_hours)),
+        ]);
+    }
+    for line in table.render() {
+        println!("{line}");
+    }
+
+    let harden: Vec<String> = report
+        .iter()
+        .filter(|t| t.breaks >= HARDEN_AT_BREAKS)
+        .take(HARDEN_LIMIT)
+        .map(|t| format!("[{}]", t.slug))
+        .collect();
+    if !harden.is_empty() {
+        println!();
+        println!("   {} Harden first: {}", "⚠".warning(), harden.join(", ").bold());
+    }
+}
+
+fn format_hours(hours: f64) -> String {
+    if hours >= 48.0 {
+        format!("{:.1}d", hours / 24.0)
+    } else if hours >= 1.0 {
+        format!("{hours:.1}h")
+    } else {
+        format!("{:.0}m", hours * 60.0)
+    }
+}
@@ -2,38 +2,102 @@
 
 use anyhow::Result;
 use colored::Colorize;
+use crate::table::{Align, Table};
+use crate::theme::Paint;
+use super::stale;
+use roadmap::engine::config::Config;
 use roadmap::engine::context::RepoContext;
 use roadmap::engine::db::Db;
+use roadmap::engine::due::{self, DueTask};
+use roadmap::engine::exchange::ImportOutcome;
 use roadmap::engine::graph::{StatusCounts, TaskGraph};
-use roadmap::engine::repo::TaskRepo;
+use roadmap::engine::milestone::{self, MilestoneProgress};
+use roadmap::engine::remote;
+use roadmap::engine::repo::{MilestoneRepo, TaskRepo};
+use roadmap::engine::types::DerivedStatus;
+use schemars::{JsonSchema, Schema};
 use serde::Serialize;
 
 /// Displays the current project status.
 ///
+/// With `check`, fails after printing if any task has a status in `fail_on`
+/// (stale and broken when empty), like `stale --check`.
+///
 /// # Errors
-/// Returns error if database query fails.
-pub fn handle(json: bool) -> Result<()> {
+/// Returns error if database query fails, or the check fails.
+pub fn handle(json: bool, check: bool, fail_on: &[String]) -> Result<()> {
+    let fail_on = stale::parse_fail_on(fail_on)?;
     let conn = Db::connect()?;
     let repo = TaskRepo::new(&conn);
+    sync_remote(&repo, json)?;
     let graph = TaskGraph::build(&conn)?;
     let context = RepoContext::new()?;
-    
+
     if json {
-        return print_json(&repo, &graph, &context);
+        print_json(&repo, &graph, &context)?;
+    } else {
+        print_human(&repo, &graph, &context)?;
     }
+    if check {
+        stale::enforce(&graph.status_counts(), &fail_on)?;
+    }
+    Ok(())
+}
 
-    print_human(&repo, &graph, &context)
+/// Pulls teammates' proofs before rendering, when a remote is configured.
+fn sync_remote(repo: &TaskRepo<'_>, quiet: bool) -> Result<()> {
+    let settings = Config::load()?.remote;
+    let Some(url) = settings.url.filter(|_| settings.pull_on_status) else {
+        return Ok(());
+    };
+
+    let store = remote::open(&url);
+    let tasks = repo.get_all()?;
+    match remote::pull(repo.conn(), store.as_ref(), &tasks, &format!("remote:{url}")) {
+        Ok(results) => {
+            let pulled = results
+                .iter()
+                .filter(|(_, o)| matches!(o, ImportOutcome::Imported))
+                .count();
+            if pulled > 0 && !quiet {
+                println!("{} Pulled {} proof(s) from remote\n", "☁".info(), pulled);
+            }
+        }
+        Err(e) if !quiet => println!("{} Remote unavailable: {e:#}\n", "⚠".warning()),
+        Err(_) => {}
+    }
+    Ok(())
 }
 
-#[derive(Serialize)]
-struct StatusReport {
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(StatusReport)
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct StatusReport {
     head_sha: String,
     counts: StatusCounts,
     focus: Option<TaskView>,
     frontier: Vec<TaskView>,
+    /// Unfinished tasks past their due date, most overdue first.
+    overdue: Vec<DueTask>,
+    /// Top-level tasks with subtasks, and how far along those are.
+    progress: Vec<ParentProgress>,
+    milestones: Vec<MilestoneProgress>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
+struct ParentProgress {
+    slug: String,
+    title: String,
+    status: String,
+    /// Subtasks at any depth that are proven, attested, or quarantined.
+    done: usize,
+    total: usize,
+}
+
+#[derive(Serialize, JsonSchema)]
 struct TaskView {
     id: i64,
     slug: String,
@@ -42,6 +106,12 @@ struct TaskView {
 }
 
 fn print_json(repo: &TaskRepo<'_>, graph: &TaskGraph, context: &RepoContext) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(&report(repo, graph, context)?)?);
+    Ok(())
+}
+
+/// The `--json` report: counts, focus, and the first few frontier tasks.
+pub(crate) fn report(repo: &TaskRepo<'_>, graph: &TaskGraph, context: &RepoContext) -> Result<StatusReport> {
     let head_sha = context.head_sha().to_string();
     let counts = graph.status_counts();
     
@@ -69,33 +139,68 @@ fn print_json(repo: &TaskRepo<'_>, graph: &TaskGraph, context: &RepoContext) ->
         }
     }).collect();
 
-    let report = StatusReport {
+    let overdue = due::analyze(graph, due::today())
+        .into_iter()
+        .filter(|t| t.overdue)
+        .collect();
+
+    Ok(StatusReport {
         head_sha,
         counts,
         focus,
         frontier,
-    };
+        overdue,
+        progress: progress(graph),
+        milestones: milestone::progress(graph, &MilestoneRepo::new(repo.conn()).get_all()?),
+    })
+}
 
-    println!("{}", serde_json::to_string_pretty(&report)?);
-    Ok(())
+/// Subtask progress of every top-level parent, oldest first.
+fn progress(graph: &TaskGraph) -> Vec<ParentProgress> {
+    let mut parents: Vec<_> = graph
+        .tasks()
+        .filter(|t| t.parent.is_none() && !t.subtasks.is_empty())
+        .collect();
+    parents.sort_by_key(|t| t.id);
+    parents
+        .into_iter()
+        .map(|t| {
+            let (done, total) = t.subtask_progress(graph.context());
+            ParentProgress {
+                slug: t.slug.clone(),
+                title: t.title.clone(),
+                status: format!("{:?}", t.derive_status(graph.context())),
+                done,
+                total,
+            }
+        })
+        .collect()
 }
 
 fn print_human(repo: &TaskRepo<'_>, graph: &TaskGraph, context: &RepoContext) -> Result<()> {
     let head_sha = context.head_sha();
 
-    println!("{} Roadmap Status", "📊".cyan());
+    match Db::namespace() {
+        Some(ns) => println!("{} Roadmap Status ({})", "📊".info(), ns.accent()),
+        None => println!("{} Roadmap Status", "📊".info()),
+    }
 
     if let Some(id) = repo.get_active_task_id()? {
         if let Some(task) = repo.find_by_id(id)? {
             println!(
                 "   Focus: [{}] {} ({})",
-                task.slug.yellow(),
+                task.slug.warning(),
                 task.title,
                 task.derive_status(context).to_string().dimmed()
             );
         }
     }
 
+    print_quarantined(repo, context)?;
+    print_overdue(graph);
+    print_progress(graph);
+    print_milestones(repo, graph)?;
+
     let frontier = graph.get_frontier();
     if !frontier.is_empty() {
         println!("\n   Next up:");
@@ -106,5 +211,91 @@ fn print_human(repo: &TaskRepo<'_>, graph: &TaskGraph, context: &RepoContext) ->
 
     println!("\n   Repo HEAD: {}", &head_sha[..7.min(head_sha.len())].dimmed());
 
+    Ok(())
+}
+
+/// Shows how far along each top-level parent's subtasks are.
+fn print_progress(graph: &TaskGraph) {
+    let parents = progress(graph);
+    if parents.is_empty() {
+        return;
+    }
+
+    println!("\n   Progress:");
+    for parent in parents {
+        let percent = parent.done * 100 / parent.total;
+        let summary = format!("{}/{} subtasks done ({percent}%)", parent.done, parent.total);
+        let summary = if parent.done == parent.total { summary.success() } else { summary.dimmed() };
+        println!("     - [{}] {} {summary}", parent.slug.accent(), parent.title);
+    }
+}
+
+/// Shows the completion of every milestone.
+fn print_milestones(repo: &TaskRepo<'_>, graph: &TaskGraph) -> Result<()> {
+    let milestones = MilestoneRepo::new(repo.conn()).get_all()?;
+    if milestones.is_empty() {
+        return Ok(());
+    }
+
+    println!("\n   Milestones:");
+    let mut table = Table::new(&[Align::Left, Align::Left, Align::Left, Align::Right, Align::Right]).flex(1);
+    for progress in milestone::progress(graph, &milestones) {
+        table.row(super::milestone::progress_cells(&progress));
+    }
+    for line in table.render() {
+        println!("  {line}");
+    }
+    Ok(())
+}
+
+/// Lists unfinished tasks past their due date.
+fn print_overdue(graph: &TaskGraph) {
+    let overdue: Vec<_> = due::analyze(graph, due::today())
+        .into_iter()
+        .filter(|t| t.overdue)
+        .collect();
+    if overdue.is_empty() {
+        return;
+    }
+
+    println!(
+        "\n   {} {} overdue:",
+        "⏰".failure(),
+        overdue.len().to_string().failure().bold()
+    );
+    for task in overdue {
+        println!(
+            "     - [{}] {} {}",
+            task.slug.failure(),
+            task.title,
+            format!("({})", due::relative(task.days_left)).dimmed()
+        );
+    }
+}
+
+/// Flags quarantined tasks so they are not forgotten.
+fn print_quarantined(repo: &TaskRepo<'_>, context: &RepoContext) -> Result<()> {
+    let quarantined: Vec<_> = repo
+        .get_all()?
+        .into_iter()
+        .filter(|t| matches!(t.derive_status(context), DerivedStatus::Quarantined))
+        .collect();
+    if quarantined.is_empty() {
+        return Ok(());
+    }
+
+    println!(
+        "\n   {} {} quarantined (flaky, not blocking):",
+        "☣".special(),
+        quarantined.len().to_string().special().bold()
+    );
+    for task in quarantined {
+        println!(
+            "     - [{}] {} {}",
+            task.slug.special(),
+            task.title,
+            format!("({})", task.quarantine_reason.unwrap_or_default()).dimmed()
+        );
+    }
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,101 @@
+//! Handler for the `sync` command.
+
+use anyhow::Result;
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::db::Db;
+use roadmap::engine::plan::{self, Plan};
+use roadmap::engine::sync::{self as plan_sync, SyncPlan};
+use std::path::Path;
+
+/// Reconciles the database with the plan file; proofs are never touched.
+///
+/// Tasks that exist only in the database are reported, and removed (with
+/// their proofs) under `prune`. With `dry_run`, only the changes are shown.
+///
+/// # Errors
+/// Returns error if the plan is unreadable or invalid, or the database fails.
+pub fn handle(path: &Path, prune: bool, dry_run: bool) -> Result<()> {
+    let plan = Plan::read(path)?;
+    plan::ensure_valid(path, &plan.validate())?;
+
+    let conn = Db::connect()?;
+    let tx = Db::begin_write(&conn)?;
+    let changes = plan_sync::plan(&tx, &plan, prune)?;
+
+    if changes.is_empty() {
+        println!("{} Database matches {}", "✓".success(), path.display());
+        print_unplanned(&changes);
+        return Ok(());
+    }
+
+    print_changes(&changes);
+    if dry_run {
+        println!("\n   {} Dry run: nothing was changed", "ℹ".info());
+        return Ok(());
+    }
+
+    let summary = changes.apply(&tx)?;
+    tx.commit()?;
+
+    println!("\n{} Synced from {}", "✓".success(), path.display());
+    if summary.proofs_deleted > 0 {
+        println!(
+            "   {} Deleted {} proof(s) of {} removed task(s)",
+            "ℹ".info(),
+            summary.proofs_deleted,
+            summary.removed
+        );
+    }
+    for edge in &summary.edges_rejected {
+        println!(
+            "   {} Skipped [{}] → [{}]: it would close a cycle through tasks outside the plan",
+            "⚠".warning(),
+            edge.blocker,
+            edge.blocked
+        );
+    }
+    Ok(())
+}
+
+fn print_changes(changes: &SyncPlan) {
+    for record in &changes.created {
+        println!("   {} [{}] {}", "+".success(), record.slug, record.title);
+    }
+    for update in &changes.updated {
+        println!(
+            "   {} [{}] {}",
+            "~".warning(),
+            update.record.slug,
+            update.fields.join(", ").dimmed()
+        );
+    }
+    if changes.prune {
+        for slug in &changes.unplanned {
+            println!("   {} [{slug}]", "-".failure());
+        }
+    }
+    for edge in &changes.edges_added {
+        println!("   {} [{}] → [{}]", "+".success(), edge.blocker, edge.blocked);
+    }
+    for edge in &changes.edges_removed {
+        println!("   {} [{}] → [{}]", "-".failure(), edge.blocker, edge.blocked);
+    }
+    print_unplanned(changes);
+}
+
+fn print_unplanned(changes: &SyncPlan) {
+    if changes.prune || changes.unplanned.is_empty() {
+        return;
+    }
+    println!(
+        "   {} Not in the plan: {} (--prune to remove)",
+        "⚠".warning(),
+        changes
+            .unplanned
+            .iter()
+            .map(|s| format!("[{s}]"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+}
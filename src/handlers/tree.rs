@@ -0,0 +1,59 @@
+//! Handler for the `tree` command.
+
+use anyhow::Result;
+use colored::Colorize;
+use crate::theme::{status_icon, Paint};
+use roadmap::engine::db::Db;
+use roadmap::engine::graph::TaskGraph;
+use roadmap::engine::types::Task;
+use std::collections::HashSet;
+
+/// Prints the dependency DAG as an indented tree, from roots to leaves.
+///
+/// A task reachable along several paths (a diamond) is expanded where it
+/// first appears; later occurrences are marked and not expanded again.
+///
+/// # Errors
+/// Returns error if the database query fails.
+pub fn handle() -> Result<()> {
+    let conn = Db::connect()?;
+    let graph = TaskGraph::build(&conn)?;
+
+    println!("{} Dependency Tree:", "🌳".info());
+    let roots = graph.roots();
+    if roots.is_empty() {
+        println!("   (No tasks)");
+        return Ok(());
+    }
+
+    let mut seen = HashSet::new();
+    for root in roots {
+        print_node(&graph, root, "   ", "   ", &mut seen);
+    }
+    Ok(())
+}
+
+/// Prints `task` after `lead`, and its children indented by `indent`.
+fn print_node(graph: &TaskGraph, task: &Task, lead: &str, indent: &str, seen: &mut HashSet<i64>) {
+    let status = task.derive_status(graph.context());
+    let label = format!("{lead}{} [{}] {}", status_icon(status), task.slug.accent(), task.title);
+    if !seen.insert(task.id) {
+        println!("{label} {}", "(↑ shown above)".dimmed());
+        return;
+    }
+    println!("{label}");
+
+    let mut children = graph.get_blocked_by(task.id);
+    children.sort_by(|a, b| a.slug.cmp(&b.slug));
+    let last = children.len().saturating_sub(1);
+    for (i, child) in children.into_iter().enumerate() {
+        let (branch, continuation) = if i == last { ("└── ", "    ") } else { ("├── ", "│   ") };
+        print_node(
+            graph,
+            child,
+            &format!("{indent}{branch}"),
+            &format!("{indent}{continuation}"),
+            seen,
+        );
+    }
+}
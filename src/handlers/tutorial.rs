@@ -0,0 +1,183 @@
+//! Handler for the `tutorial` command.
+//!
+//! Runs the real CLI against a throwaway git repository, so everything the
+//! tutorial shows is exactly what the user will see in their own project.
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use crate::theme::Paint;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+use std::process::Command;
+
+/// One lesson: what it teaches, then the commands that demonstrate it.
+struct Step {
+    title: &'static str,
+    text: &'static str,
+    actions: &'static [Action],
+}
+
+enum Action {
+    Roadmap(&'static [&'static str]),
+    Write(&'static str, &'static str),
+    Commit(&'static str),
+}
+
+const STEPS: &[Step] = &[
+    Step {
+        title: "Initialize",
+        text: "A roadmap lives next to your code in .roadmap/, inside a git repository.",
+        actions: &[Action::Roadmap(&["init"])],
+    },
+    Step {
+        title: "Add two claims",
+        text: "Each task is a claim with a command that proves it. The second one\n\
+               depends on the first, and scopes tie each claim to the files it covers.",
+        actions: &[
+            Action::Roadmap(&[
+                "add", "Write greeting", "--scope", "greeting.txt",
+                "--test", "echo verifying greeting && test -s greeting.txt",
+            ]),
+            Action::Roadmap(&[
+                "add", "Shout greeting", "--after", "write-greeting", "--scope", "shout.txt",
+                "--test", "echo verifying shout && grep -q HELLO shout.txt",
+            ]),
+        ],
+    },
+    Step {
+        title: "See what's next",
+        text: "Only unblocked work shows up: the shout waits for the greeting.",
+        actions: &[Action::Roadmap(&["next"])],
+    },
+    Step {
+        title: "Do the work and prove it",
+        text: "Pick the task with `do`, make the change, commit, then `check` runs\n\
+               the test command and records a proof against the commit.",
+        actions: &[
+            Action::Roadmap(&["do", "write-greeting"]),
+            Action::Write("greeting.txt", "hello\n"),
+            Action::Commit("Write greeting"),
+            Action::Roadmap(&["check"]),
+            Action::Roadmap(&["next"]),
+        ],
+    },
+    Step {
+        title: "Watch a proof go stale",
+        text: "Proofs belong to commits. Changing a file in a task's scope makes its\n\
+               proof stale until it is checked again.",
+        actions: &[
+            Action::Write("greeting.txt", "goodbye\n"),
+            Action::Commit("Change greeting"),
+            Action::Roadmap(&["stale"]),
+            Action::Roadmap(&["list"]),
+        ],
+    },
+];
+
+/// Walks through the core workflow in a temporary sandbox roadmap.
+///
+/// # Errors
+/// Returns error if the sandbox cannot be created or a step fails.
+pub fn handle(keep: bool) -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("roadmap-tutorial-{}", std::process::id()));
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    println!("{} Roadmap tutorial", "🎓".info());
+    println!("   Sandbox: {}", dir.display().to_string().dimmed());
+
+    let result = run(&dir);
+    if keep {
+        println!("\n   Sandbox kept at {}", dir.display().to_string().bold());
+    } else {
+        let _ = fs::remove_dir_all(&dir);
+    }
+    result?;
+
+    println!("\n{} That's the loop: add claims, do, check, and let staleness tell you", "✓".success());
+    println!("   what needs re-proving. Run `roadmap init` in your own repository to start.");
+    Ok(())
+}
+
+fn run(dir: &Path) -> Result<()> {
+    let exe = std::env::current_exe().context("Failed to locate the roadmap binary")?;
+    git(dir, &["init", "--quiet"])?;
+    git(dir, &["config", "user.name", "Roadmap Tutorial"])?;
+    git(dir, &["config", "user.email", "tutorial@roadmap.invalid"])?;
+    fs::write(dir.join("README.md"), "# Tutorial sandbox\n")?;
+    // Roadmap state is local; keeping it out of git keeps the worktree clean for `check`.
+    fs::write(dir.join(".gitignore"), ".roadmap/\n")?;
+    git(dir, &["add", "-A"])?;
+    git(dir, &["commit", "--quiet", "-m", "Initial commit"])?;
+
+    let interactive = io::stdin().is_terminal();
+    for (i, step) in STEPS.iter().enumerate() {
+        println!("\n{} {}", format!("{}/{}", i + 1, STEPS.len()).accent(), step.title.bold());
+        for line in step.text.lines() {
+            println!("   {}", line.trim_start());
+        }
+        if interactive {
+            pause()?;
+        }
+        for action in step.actions {
+            perform(dir, &exe, action)?;
+        }
+    }
+    Ok(())
+}
+
+fn perform(dir: &Path, exe: &Path, action: &Action) -> Result<()> {
+    match action {
+        Action::Roadmap(args) => {
+            println!("\n{} roadmap {}", "$".dimmed(), quoted(args));
+            let status = Command::new(exe)
+                .args(*args)
+                .current_dir(dir)
+                .status()
+                .context("Failed to run roadmap")?;
+            if !status.success() {
+                bail!("Tutorial step `roadmap {}` failed", args.join(" "));
+            }
+        }
+        Action::Write(file, contents) => {
+            println!("\n{} echo {:?} > {file}", "$".dimmed(), contents.trim_end());
+            fs::write(dir.join(file), contents)?;
+        }
+        Action::Commit(message) => {
+            println!("{} git commit -am {message:?}", "$".dimmed());
+            git(dir, &["add", "-A"])?;
+            git(dir, &["commit", "--quiet", "-m", message])?;
+        }
+    }
+    Ok(())
+}
+
+fn quoted(args: &[&str]) -> String {
+    args.iter()
+        .map(|a| if a.contains(' ') { format!("\"{a}\"") } else { (*a).to_string() })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<()> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .context("Failed to run git (is it installed?)")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+fn pause() -> Result<()> {
+    print!("   {}", "Press Enter to continue…".dimmed());
+    io::stdout().flush()?;
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    Ok(())
+}
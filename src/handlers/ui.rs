@@ -0,0 +1,402 @@
+//! Handler for the `ui` command: an interactive terminal interface.
+//!
+//! The UI only reads the database directly. Actions run this same binary as
+//! a subprocess (`do`, `check`, `why`), so they behave exactly like the
+//! commands, and the view reloads once they finish.
+
+use anyhow::{Context, Result};
+use crate::theme;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use roadmap::engine::db::Db;
+use roadmap::engine::graph::TaskGraph;
+use roadmap::engine::repo::TaskRepo;
+use roadmap::engine::types::{DerivedStatus, Proof};
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+/// How often the view reloads on its own, to pick up commits and other terminals.
+const REFRESH: Duration = Duration::from_secs(5);
+
+/// Runs the UI until the user quits.
+///
+/// # Errors
+/// Returns error if the roadmap cannot be loaded or the terminal fails.
+pub fn handle() -> Result<()> {
+    let exe = std::env::current_exe().context("Failed to locate the roadmap binary")?;
+    // Fail on a missing or broken roadmap before taking over the terminal.
+    let mut app = App::new(exe)?;
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+/// A task as the UI shows it.
+struct TaskView {
+    slug: String,
+    title: String,
+    status: DerivedStatus,
+    test_cmd: Option<String>,
+    scopes: Vec<String>,
+    blockers: Vec<String>,
+    blocks: Vec<String>,
+    proof: Option<Proof>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Frontier,
+    All,
+}
+
+struct App {
+    exe: PathBuf,
+    tasks: Vec<TaskView>,
+    /// Indices into `tasks`.
+    frontier: Vec<usize>,
+    active: Option<String>,
+    focus: Pane,
+    frontier_state: ListState,
+    all_state: ListState,
+    message: String,
+}
+
+impl App {
+    fn new(exe: PathBuf) -> Result<Self> {
+        let mut app = Self {
+            exe,
+            tasks: Vec::new(),
+            frontier: Vec::new(),
+            active: None,
+            focus: Pane::Frontier,
+            frontier_state: ListState::default(),
+            all_state: ListState::default(),
+            message: String::new(),
+        };
+        app.reload()?;
+        Ok(app)
+    }
+
+    /// Rebuilds the view from the database, keeping the selection by slug.
+    fn reload(&mut self) -> Result<()> {
+        let selected = self.selected().map(|t| t.slug.clone());
+
+        let conn = Db::connect()?;
+        let graph = TaskGraph::build(&conn)?;
+        let mut tasks: Vec<_> = graph.tasks().collect();
+        tasks.sort_by_key(|t| t.id);
+        let slugs = |list: Vec<&roadmap::engine::types::Task>| -> Vec<String> {
+            let mut slugs: Vec<String> = list.into_iter().map(|t| t.slug.clone()).collect();
+            slugs.sort();
+            slugs
+        };
+        self.tasks = tasks
+            .iter()
+            .map(|t| TaskView {
+                slug: t.slug.clone(),
+                title: t.title.clone(),
+                status: t.derive_status(graph.context()),
+                test_cmd: t.test_cmd.clone(),
+                scopes: t.scopes.clone(),
+                blockers: slugs(graph.get_blockers(t.id)),
+                blocks: slugs(graph.get_blocked_by(t.id)),
+                proof: t.proof.clone(),
+            })
+            .collect();
+        self.frontier = graph
+            .get_frontier()
+            .iter()
+            .filter_map(|f| tasks.iter().position(|t| t.id == f.id))
+            .collect();
+        let repo = TaskRepo::new(&conn);
+        self.active = match repo.get_active_task_id()? {
+            Some(id) => repo.find_by_id(id)?.map(|t| t.slug),
+            None => None,
+        };
+
+        let reselect = |rows: &[usize], state: &mut ListState, tasks: &[TaskView]| {
+            let index = selected
+                .as_ref()
+                .and_then(|s| rows.iter().position(|&i| &tasks[i].slug == s))
+                .or_else(|| state.selected().map(|i| i.min(rows.len().saturating_sub(1))))
+                .or(Some(0));
+            state.select(if rows.is_empty() { None } else { index });
+        };
+        let all: Vec<usize> = (0..self.tasks.len()).collect();
+        reselect(&self.frontier, &mut self.frontier_state, &self.tasks);
+        reselect(&all, &mut self.all_state, &self.tasks);
+        Ok(())
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if !event::poll(REFRESH)? {
+                self.reload()?;
+                continue;
+            }
+            let Event::Key(key) = event::read()? else { continue };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Tab | KeyCode::Left | KeyCode::Right => {
+                    self.focus = match self.focus {
+                        Pane::Frontier => Pane::All,
+                        Pane::All => Pane::Frontier,
+                    };
+                }
+                KeyCode::Down | KeyCode::Char('j') => self.step(1),
+                KeyCode::Up | KeyCode::Char('k') => self.step(-1),
+                KeyCode::Char('d') => self.do_selected()?,
+                KeyCode::Char('c') => {
+                    *terminal = self.suspended(terminal, &["check"])?;
+                }
+                KeyCode::Char('w') => {
+                    if let Some(slug) = self.selected().map(|t| t.slug.clone()) {
+                        *terminal = self.suspended(terminal, &["why", &slug])?;
+                    }
+                }
+                KeyCode::Char('r') => {
+                    self.reload()?;
+                    self.message = "Refreshed".to_string();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn rows(&self) -> Vec<usize> {
+        match self.focus {
+            Pane::Frontier => self.frontier.clone(),
+            Pane::All => (0..self.tasks.len()).collect(),
+        }
+    }
+
+    fn state(&mut self) -> &mut ListState {
+        match self.focus {
+            Pane::Frontier => &mut self.frontier_state,
+            Pane::All => &mut self.all_state,
+        }
+    }
+
+    fn selected(&self) -> Option<&TaskView> {
+        let state = match self.focus {
+            Pane::Frontier => &self.frontier_state,
+            Pane::All => &self.all_state,
+        };
+        let row = state.selected().and_then(|i| self.rows().get(i).copied())?;
+        self.tasks.get(row)
+    }
+
+    fn step(&mut self, delta: isize) {
+        let len = self.rows().len();
+        if len == 0 {
+            return;
+        }
+        let state = self.state();
+        let current = state.selected().unwrap_or(0);
+        state.select(Some(current.saturating_add_signed(delta).min(len - 1)));
+    }
+
+    /// Makes the selected task active, reporting the command's verdict inline.
+    fn do_selected(&mut self) -> Result<()> {
+        let Some(slug) = self.selected().map(|t| t.slug.clone()) else {
+            return Ok(());
+        };
+        let output = Command::new(&self.exe)
+            .args(["do", &slug])
+            .env("NO_COLOR", "1")
+            .output()
+            .context("Failed to run roadmap do")?;
+        let text = if output.status.success() { output.stdout } else { output.stderr };
+        self.message = first_line(&String::from_utf8_lossy(&text));
+        self.reload()
+    }
+
+    /// Leaves the UI to run a command with the real terminal, then comes back.
+    ///
+    /// Verification output streams live and stays on screen until Enter.
+    fn suspended(&mut self, terminal: &mut DefaultTerminal, args: &[&str]) -> Result<DefaultTerminal> {
+        terminal.clear()?;
+        ratatui::restore();
+        let status = run_inherited(&self.exe, args);
+        println!("\n[Press Enter to return]");
+        let _ = io::stdin().lock().read_line(&mut String::new());
+        let restored = ratatui::init();
+
+        self.message = match status {
+            Ok(0) => format!("roadmap {} finished", args.join(" ")),
+            Ok(code) => format!("roadmap {} exited with {code}", args.join(" ")),
+            Err(e) => format!("roadmap {} failed: {e}", args.join(" ")),
+        };
+        self.reload()?;
+        Ok(restored)
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [body, footer] =
+            Layout::vertical([Constraint::Min(3), Constraint::Length(2)]).areas(frame.area());
+        let [left, detail] =
+            Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(body);
+        let [frontier, all] =
+            Layout::vertical([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(left);
+
+        let frontier_rows = self.frontier.clone();
+        let all_rows: Vec<usize> = (0..self.tasks.len()).collect();
+        self.draw_list(frame, frontier, Pane::Frontier, " Frontier ", &frontier_rows);
+        self.draw_list(frame, all, Pane::All, " All tasks ", &all_rows);
+        self.draw_detail(frame, detail);
+
+        let active = self.active.as_deref().unwrap_or("none");
+        let footer_text = vec![
+            Line::from(vec![
+                Span::styled(" Active: ", Style::new().add_modifier(Modifier::BOLD)),
+                Span::raw(format!("[{active}]  ")),
+                Span::styled(self.message.clone(), Style::new().add_modifier(Modifier::DIM)),
+            ]),
+            Line::from(Span::styled(
+                " ↑↓/jk move · Tab switch pane · d do · c check active · w why · r refresh · q quit",
+                Style::new().add_modifier(Modifier::DIM),
+            )),
+        ];
+        frame.render_widget(Paragraph::new(footer_text), footer);
+    }
+
+    fn draw_list(&mut self, frame: &mut Frame, area: Rect, pane: Pane, title: &str, rows: &[usize]) {
+        let items: Vec<ListItem> = rows
+            .iter()
+            .map(|&i| {
+                let task = &self.tasks[i];
+                let marker = if self.active.as_deref() == Some(task.slug.as_str()) { "▶ " } else { "  " };
+                ListItem::new(Line::from(vec![
+                    Span::raw(marker),
+                    Span::styled(theme::status_glyph(task.status), status_style(task.status)),
+                    Span::raw(format!(" [{}] {}", task.slug, task.title)),
+                ]))
+            })
+            .collect();
+        let focused = self.focus == pane;
+        let border = if focused { Style::new().add_modifier(Modifier::BOLD) } else { Style::new().add_modifier(Modifier::DIM) };
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title).border_style(border))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        let state = match pane {
+            Pane::Frontier => &mut self.frontier_state,
+            Pane::All => &mut self.all_state,
+        };
+        if focused {
+            frame.render_stateful_widget(list, area, state);
+        } else {
+            frame.render_widget(list, area);
+        }
+    }
+
+    fn draw_detail(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL).title(" Task ");
+        let Some(task) = self.selected() else {
+            frame.render_widget(Paragraph::new(" (No task selected)").block(block), area);
+            return;
+        };
+
+        let bold = Style::new().add_modifier(Modifier::BOLD);
+        let field = |name: &str, value: String| {
+            Line::from(vec![Span::styled(format!("{name:<10}"), bold), Span::raw(value)])
+        };
+        let list = |items: &[String]| {
+            if items.is_empty() {
+                "(none)".to_string()
+            } else {
+                items.iter().map(|s| format!("[{s}]")).collect::<Vec<_>>().join(" ")
+            }
+        };
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(format!("[{}] ", task.slug), bold),
+                Span::raw(task.title.clone()),
+            ]),
+            Line::default(),
+            Line::from(vec![
+                Span::styled(format!("{:<10}", "Status"), bold),
+                Span::styled(task.status.to_string(), status_style(task.status)),
+            ]),
+            field("Test", task.test_cmd.clone().unwrap_or_else(|| "(none)".to_string())),
+            field("Scopes", if task.scopes.is_empty() { "(global)".to_string() } else { task.scopes.join(", ") }),
+            field("After", list(&task.blockers)),
+            field("Blocks", list(&task.blocks)),
+            Line::default(),
+        ];
+        match &task.proof {
+            Some(proof) => {
+                lines.push(Line::from(Span::styled("Latest proof", bold)));
+                lines.push(field("Commit", proof.git_sha.chars().take(12).collect()));
+                lines.push(field("When", proof.timestamp.clone()));
+                lines.push(field("Exit", format!("{} in {}ms", proof.exit_code, proof.duration_ms)));
+                if let Some(reason) = &proof.attested_reason {
+                    lines.push(field("Attested", reason.clone()));
+                }
+            }
+            None => lines.push(Line::from(Span::styled(
+                "No proof recorded yet",
+                Style::new().add_modifier(Modifier::DIM),
+            ))),
+        }
+        frame.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: false }), area);
+    }
+}
+
+fn status_style(status: DerivedStatus) -> Style {
+    match theme::status_color(status) {
+        Some(color) => Style::new().fg(to_tui_color(color)),
+        None if status == DerivedStatus::Unproven => Style::new().add_modifier(Modifier::DIM),
+        None if matches!(status, DerivedStatus::Broken | DerivedStatus::Stale) => {
+            Style::new().add_modifier(Modifier::BOLD)
+        }
+        None => Style::new(),
+    }
+}
+
+fn to_tui_color(color: colored::Color) -> Color {
+    use colored::Color as C;
+    match color {
+        C::Black => Color::Black,
+        C::Red => Color::Red,
+        C::Green => Color::Green,
+        C::Yellow => Color::Yellow,
+        C::Blue => Color::Blue,
+        C::Magenta => Color::Magenta,
+        C::Cyan => Color::Cyan,
+        C::White => Color::Gray,
+        C::BrightBlack => Color::DarkGray,
+        C::BrightRed => Color::LightRed,
+        C::BrightGreen => Color::LightGreen,
+        C::BrightYellow => Color::LightYellow,
+        C::BrightBlue => Color::LightBlue,
+        C::BrightMagenta => Color::LightMagenta,
+        C::BrightCyan => Color::LightCyan,
+        C::BrightWhite => Color::White,
+        C::TrueColor { r, g, b } => Color::Rgb(r, g, b),
+    }
+}
+
+fn run_inherited(exe: &Path, args: &[&str]) -> Result<i32> {
+    let status = Command::new(exe)
+        .args(args)
+        .status()
+        .with_context(|| format!("Failed to run roadmap {}", args.join(" ")))?;
+    Ok(status.code().unwrap_or(1))
+}
+
+fn first_line(text: &str) -> String {
+    text.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or_default().to_string()
+}
@@ -0,0 +1,66 @@
+//! Handler for the `undo` command.
+
+use anyhow::Result;
+use colored::Colorize;
+use crate::table::{Align, Cell, Table};
+use crate::theme::Paint;
+use roadmap::engine::db::Db;
+use roadmap::engine::oplog;
+
+/// How many operations `--list` shows.
+const LIST_LIMIT: usize = 20;
+
+/// Reverts the last recorded command, or lists recent ones with `list`.
+///
+/// # Errors
+/// Returns error if the database fails or a revert statement cannot be applied.
+pub fn handle(list: bool) -> Result<()> {
+    let mut conn = Db::connect()?;
+    if list {
+        return print_log(&conn);
+    }
+
+    let Some(op) = oplog::undo_last(&mut conn)? else {
+        println!("   Nothing to undo.");
+        return Ok(());
+    };
+    println!(
+        "{} Undid #{} {} {}",
+        "↶".success(),
+        op.id,
+        format!("roadmap {}", op.command).bold(),
+        format!("({} change(s), {})", op.changes, op.timestamp).dimmed()
+    );
+    Ok(())
+}
+
+fn print_log(conn: &rusqlite::Connection) -> Result<()> {
+    let ops = oplog::recent(conn, LIST_LIMIT)?;
+    println!("{} Recent operations", "📜".info());
+    if ops.is_empty() {
+        println!("   (No operations recorded yet)");
+        return Ok(());
+    }
+
+    let mut table = Table::new(&[Align::Right, Align::Left, Align::Left, Align::Right, Align::Left])
+        .flex(2);
+    for op in &ops {
+        let note = match (op.reverts, op.undone) {
+            (Some(target), _) => format!("undid #{target}"),
+            (None, true) => "undone".to_string(),
+            (None, false) => String::new(),
+        };
+        let undone = op.undone;
+        table.row(vec![
+            Cell::new(format!("#{}", op.id), |s| s.dimmed()),
+            Cell::new(op.timestamp.clone(), |s| s.dimmed()),
+            Cell::new(op.command.clone(), move |s| if undone { s.strikethrough() } else { s.into() }),
+            Cell::plain(format!("{}×", op.changes)),
+            Cell::new(note, |s| s.warning()),
+        ]);
+    }
+    for line in table.render() {
+        println!("{line}");
+    }
+    Ok(())
+}
@@ -0,0 +1,115 @@
+//! Handler for the `verify-signatures` command.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use crate::theme::Paint;
+use roadmap::engine::chain;
+use roadmap::engine::db::Db;
+use roadmap::engine::repo::{ProofRecord, ProofRepo};
+use roadmap::engine::resolver::TaskResolver;
+use roadmap::engine::signing::{SignatureCheck, TrustStore};
+use schemars::{JsonSchema, Schema};
+use serde::Serialize;
+use std::path::Path;
+
+/// Options for signature verification.
+pub struct VerifyOptions<'a> {
+    pub task: Option<&'a str>,
+    pub allowed_signers: Option<&'a Path>,
+    pub keyring: Option<&'a Path>,
+    pub json: bool,
+}
+
+/// Checks signatures on attestations (and any other signed proofs).
+///
+/// # Errors
+/// Returns error if the database fails or any attestation is unsigned or invalid.
+pub fn handle(opts: &VerifyOptions<'_>) -> Result<()> {
+    let conn = Db::connect()?;
+    let task_id = match opts.task {
+        Some(task_ref) => Some(TaskResolver::new(&conn).resolve(task_ref)?.task.id),
+        None => None,
+    };
+
+    let trust = TrustStore {
+        allowed_signers: opts.allowed_signers,
+        keyring: opts.keyring,
+    };
+
+    let rows: Vec<SignatureRow> = ProofRepo::new(&conn)
+        .get_audit_trail()?
+        .into_iter()
+        .filter(|r| task_id.is_none_or(|id| r.task_id == id))
+        .filter(|r| r.proof.attested_reason.is_some() || r.proof.signature.is_some())
+        .map(|r| SignatureRow::check(&trust, r))
+        .collect();
+
+    if opts.json {
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+    } else {
+        print_human(&rows);
+    }
+
+    let bad = rows.iter().filter(|r| r.status != "valid").count();
+    if bad > 0 {
+        bail!("{bad} proof(s) unsigned or with invalid signatures");
+    }
+    Ok(())
+}
+
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(Vec<SignatureRow>)
+}
+
+#[derive(Serialize, JsonSchema)]
+struct SignatureRow {
+    proof_id: i64,
+    slug: String,
+    attested: bool,
+    status: &'static str,
+    detail: Option<String>,
+}
+
+impl SignatureRow {
+    fn check(trust: &TrustStore<'_>, record: ProofRecord) -> Self {
+        let payload = chain::payload(record.task_id, &record.proof);
+        let (status, detail) = match trust.check(&payload, record.proof.signature.as_deref()) {
+            SignatureCheck::Valid { signer } => ("valid", Some(signer)),
+            SignatureCheck::Invalid { reason } => ("invalid", Some(reason)),
+            SignatureCheck::Unsigned => ("unsigned", None),
+        };
+        Self {
+            proof_id: record.proof_id,
+            slug: record.slug,
+            attested: record.proof.attested_reason.is_some(),
+            status,
+            detail,
+        }
+    }
+}
+
+fn print_human(rows: &[SignatureRow]) {
+    println!("{} Proof Signatures", "🔏".info());
+    if rows.is_empty() {
+        println!("   (No attestations or signed proofs)");
+        return;
+    }
+
+    for row in rows {
+        let status = match row.status {
+            "valid" => "VALID   ".success(),
+            "invalid" => "INVALID ".failure(),
+            _ => "UNSIGNED".warning(),
+        };
+        let kind = if row.attested { "attested" } else { "verified" };
+        println!(
+            "   {}  #{:<4} [{}] {} {}",
+            status,
+            row.proof_id,
+            row.slug.bold(),
+            kind.dimmed(),
+            row.detail.as_deref().unwrap_or_default().dimmed()
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! Handler for the `watch` command.
+
+use anyhow::Result;
+use colored::Colorize;
+use crate::theme::{status_icon, Paint};
+use roadmap::engine::context::RepoContext;
+use roadmap::engine::db::Db;
+use roadmap::engine::graph::TaskGraph;
+use roadmap::engine::shell;
+use roadmap::engine::types::DerivedStatus;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+
+/// Watches HEAD, the working tree, and the database, reporting status and frontier changes.
+///
+/// Every `interval` seconds a cheap fingerprint is taken (HEAD, dirtiness,
+/// database modification times); statuses are recomputed only when it moves.
+/// With `notify`, stale proofs and frontier changes also raise a desktop
+/// notification where `notify-send` or `osascript` is available.
+///
+/// # Errors
+/// Returns error if the roadmap cannot be loaded.
+pub fn handle(interval: u64, notify: bool) -> Result<()> {
+    let mut stamp = Stamp::take()?;
+    let mut view = View::load()?;
+    println!(
+        "{} Watching {} task(s) at {} (every {interval}s, Ctrl-C to stop)",
+        "👁".info(),
+        view.statuses.len(),
+        short(&stamp.head).accent()
+    );
+    println!("   Frontier: {}", slugs(&view.frontier));
+
+    loop {
+        std::thread::sleep(Duration::from_secs(interval.max(1)));
+        let next_stamp = Stamp::take()?;
+        if next_stamp == stamp {
+            continue;
+        }
+        let next_view = View::load()?;
+        let changes = diff(&view, &next_view);
+        if next_stamp.head != stamp.head {
+            println!(
+                "\n{} HEAD {} → {}",
+                clock().dimmed(),
+                short(&stamp.head),
+                short(&next_stamp.head).accent()
+            );
+        } else if !changes.lines.is_empty() {
+            println!("\n{}", clock().dimmed());
+        }
+        for line in &changes.lines {
+            println!("   {line}");
+        }
+        if notify && !changes.alerts.is_empty() {
+            send_notification(&changes.alerts.join("\n"));
+        }
+        stamp = next_stamp;
+        view = next_view;
+    }
+}
+
+/// What has to change before statuses are worth recomputing.
+#[derive(PartialEq, Eq)]
+struct Stamp {
+    head: String,
+    dirty: bool,
+    db_modified: Option<SystemTime>,
+    wal_modified: Option<SystemTime>,
+}
+
+impl Stamp {
+    fn take() -> Result<Self> {
+        let context = RepoContext::new()?;
+        let db = Db::path();
+        let modified = |p: &Path| p.metadata().and_then(|m| m.modified()).ok();
+        Ok(Self {
+            head: context.head_sha().to_string(),
+            dirty: context.is_dirty,
+            db_modified: modified(&db),
+            wal_modified: modified(&db.with_extension("db-wal")),
+        })
+    }
+}
+
+struct View {
+    statuses: BTreeMap<String, DerivedStatus>,
+    frontier: BTreeSet<String>,
+}
+
+impl View {
+    fn load() -> Result<Self> {
+        let conn = Db::connect()?;
+        let graph = TaskGraph::build(&conn)?;
+        Ok(Self {
+            statuses: graph
+                .tasks()
+                .map(|t| (t.slug.clone(), t.derive_status(graph.context())))
+                .collect(),
+            frontier: graph.get_frontier().iter().map(|t| t.slug.clone()).collect(),
+        })
+    }
+}
+
+#[derive(Default)]
+struct Changes {
+    lines: Vec<String>,
+    /// Plain-text summaries worth a notification.
+    alerts: Vec<String>,
+}
+
+fn diff(before: &View, after: &View) -> Changes {
+    let mut changes = Changes::default();
+    for (slug, &status) in &after.statuses {
+        match before.statuses.get(slug) {
+            None => changes.lines.push(format!("{} [{slug}] added ({status})", "+".success())),
+            Some(&old) if old != status => {
+                changes.lines.push(format!(
+                    "{} [{slug}] {old} → {}",
+                    status_icon(status),
+                    status.to_string().status(status)
+                ));
+                if status == DerivedStatus::Stale || status == DerivedStatus::Broken {
+                    changes.alerts.push(format!("[{slug}] is now {status}"));
+                }
+            }
+            Some(_) => {}
+        }
+    }
+    for slug in before.statuses.keys().filter(|s| !after.statuses.contains_key(*s)) {
+        changes.lines.push(format!("{} [{slug}] removed", "-".failure()));
+    }
+
+    let entered: BTreeSet<String> = after.frontier.difference(&before.frontier).cloned().collect();
+    let left: BTreeSet<String> = before.frontier.difference(&after.frontier).cloned().collect();
+    if !entered.is_empty() || !left.is_empty() {
+        changes.lines.push(format!("Frontier: {}", slugs(&after.frontier)));
+        if !entered.is_empty() {
+            changes.alerts.push(format!("Ready to work on: {}", slugs(&entered)));
+        }
+    }
+    changes
+}
+
+fn send_notification(body: &str) {
+    let command = if shell::which("notify-send").is_some() {
+        Some(("notify-send", vec!["roadmap".to_string(), body.to_string()]))
+    } else if shell::which("osascript").is_some() {
+        let script = format!(
+            "display notification {:?} with title \"roadmap\"",
+            body.replace('\n', " · ")
+        );
+        Some(("osascript", vec!["-e".to_string(), script]))
+    } else {
+        None
+    };
+    if let Some((program, args)) = command {
+        // Best effort: a missing notification daemon must not stop the watch.
+        let _ = Command::new(program)
+            .args(args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+}
+
+fn slugs(set: &BTreeSet<String>) -> String {
+    if set.is_empty() {
+        return "(empty)".to_string();
+    }
+    set.iter().map(|s| format!("[{s}]")).collect::<Vec<_>>().join(" ")
+}
+
+fn short(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+fn clock() -> String {
+    chrono::Local::now().format("%H:%M:%S").to_string()
+}
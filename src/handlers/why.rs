@@ -2,24 +2,46 @@
 
 use anyhow::Result;
 use colored::Colorize;
+use crate::table::{Align, Cell, Table};
+use crate::theme::{status_icon, Paint};
+use roadmap::engine::config::Config;
 use roadmap::engine::context::RepoContext;
 use roadmap::engine::db::Db;
-use roadmap::engine::repo::ProofRepo;
+use roadmap::engine::graph::{TaskGraph, Unlock};
+use roadmap::engine::repo::{ProofRepo, TaskRepo};
 use roadmap::engine::resolver::TaskResolver;
+use roadmap::engine::trailers::{self, TaskCommit};
 use roadmap::engine::types::{DerivedStatus, Proof, Task};
+use rusqlite::Connection;
+use schemars::{JsonSchema, Schema};
 use serde::Serialize;
 
-/// Explains the status of a task and shows its audit log.
+/// Flags controlling a `why` report.
+#[allow(clippy::struct_excessive_bools)] // one field per command-line flag
+#[derive(Default)]
+pub struct WhyOptions {
+    pub json: bool,
+    /// Require an exact ID, slug, or alias (no fuzzy matching).
+    pub strict: bool,
+    /// Also show the environment each proof was recorded in.
+    pub full: bool,
+    /// Show what proving the task would unblock instead of its history.
+    pub impact: bool,
+}
+
+/// Explains the status of a task and shows its audit log, or with `impact`,
+/// what proving it would unblock.
 ///
 /// # Errors
 /// Returns error if task resolution or DB query fails.
-pub fn handle(task_ref: &str, json: bool, strict: bool) -> Result<()> {
+pub fn handle(task_ref: &str, opts: &WhyOptions) -> Result<()> {
+    let WhyOptions { json, strict, full, impact } = *opts;
     let conn = Db::connect()?;
     let proof_repo = ProofRepo::new(&conn);
     let context = RepoContext::new()?;
     let head_sha = context.head_sha();
 
-    let resolver = if strict {
+    let resolver = if strict || Config::load()?.resolve.strict {
         TaskResolver::strict(&conn)
     } else {
         TaskResolver::new(&conn)
@@ -27,82 +49,364 @@ pub fn handle(task_ref: &str, json: bool, strict: bool) -> Result<()> {
 
     let result = resolver.resolve(task_ref)?;
     let task = result.task;
+    if impact {
+        return show_impact(&conn, &task, json);
+    }
 
     let derived = task.derive_status(&context);
     let history = proof_repo.get_history(task.id)?;
+    let commits = commits_since_proof(&conn, &task)?;
 
     if json {
-        return print_json(&task, derived, &history, head_sha);
+        return print_json(&task, derived, &history, &commits, head_sha);
     }
 
-    print_human(&task, derived, &history, head_sha);
+    print_human(&task, derived, &history, &commits, &context);
+    if full {
+        print_environment(&history);
+    }
+    Ok(())
+}
+
+/// JSON Schema of the `--json` output.
+pub(crate) fn json_schema() -> Schema {
+    schemars::schema_for!(WhyReport)
+}
+
+/// JSON Schema of the `--impact --json` output.
+pub(crate) fn impact_schema() -> Schema {
+    schemars::schema_for!(ImpactReport)
+}
+
+#[derive(Serialize, JsonSchema)]
+struct ImpactReport {
+    slug: String,
+    status: String,
+    /// Every unfinished task waiting on this one, directly or not, most-unlocking first.
+    unlocks: Vec<UnlockView>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct UnlockView {
+    id: i64,
+    slug: String,
+    title: String,
+    status: String,
+    /// How many unfinished tasks wait on this one in turn.
+    descendants: usize,
+    /// Becomes actionable as soon as the task is proven.
+    ready: bool,
+    /// Its other unmet blockers.
+    waits_on: Vec<String>,
+}
+
+/// What proving `task` would unblock, for `--impact`.
+fn show_impact(conn: &Connection, task: &Task, json: bool) -> Result<()> {
+    let graph = TaskGraph::build(conn)?;
+    let status = task.derive_status(graph.context());
+    let unlocks = graph.get_impact(task.id);
+    if json {
+        let report = ImpactReport {
+            slug: task.slug.clone(),
+            status: status.to_string(),
+            unlocks: unlocks
+                .iter()
+                .map(|u| UnlockView {
+                    id: u.task.id,
+                    slug: u.task.slug.clone(),
+                    title: u.task.title.clone(),
+                    status: u.task.derive_status(graph.context()).to_string(),
+                    descendants: u.descendants,
+                    ready: u.waits_on.is_empty(),
+                    waits_on: u.waits_on.iter().map(|t| t.slug.clone()).collect(),
+                })
+                .collect(),
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+    print_impact(task, status, &unlocks);
     Ok(())
 }
 
-#[derive(Serialize)]
-struct WhyReport {
+fn print_impact(task: &Task, status: DerivedStatus, unlocks: &[Unlock<'_>]) {
+    if status.satisfies_dependency() {
+        println!(
+            "{} [{}] is already {}: nothing waits on it",
+            status_icon(status),
+            task.slug.info().bold(),
+            status.to_string().status(status)
+        );
+        return;
+    }
+    if unlocks.is_empty() {
+        println!("{} Nothing waits on [{}]", "ℹ".info(), task.slug.info().bold());
+        return;
+    }
+    let ready = unlocks.iter().filter(|u| u.waits_on.is_empty()).count();
+    println!(
+        "🔓 Proving [{}] moves {} tasks toward unblocked ({} at once):\n",
+        task.slug.info().bold(),
+        unlocks.len(),
+        ready
+    );
+    let mut table = Table::new(&[Align::Left, Align::Left, Align::Right, Align::Left]).flex(1);
+    for unlock in unlocks {
+        let then = if unlock.waits_on.is_empty() {
+            Cell::new("ready", |s| s.success())
+        } else {
+            let names: Vec<String> = unlock.waits_on.iter().map(|t| format!("[{}]", t.slug)).collect();
+            Cell::new(format!("after {}", names.join(", ")), |s| s.dimmed())
+        };
+        let descendants = match unlock.descendants {
+            0 => String::new(),
+            n => format!("unlocks {n}"),
+        };
+        table.row(vec![
+            Cell::new(format!("[{}]", unlock.task.slug), |s| s.warning()),
+            Cell::plain(&unlock.task.title),
+            Cell::new(descendants, |s| s.accent()),
+            then,
+        ]);
+    }
+    for line in table.render() {
+        println!("{line}");
+    }
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct WhyReport {
     task_id: i64,
     slug: String,
     title: String,
+    description: Option<String>,
     status: String,
     head_sha: String,
     proof: Option<Proof>,
     history: Vec<Proof>,
+    /// Commits naming the task (trailer or `[slug]` prefix) since its latest proof.
+    commits: Vec<TaskCommit>,
+}
+
+/// The `--json` report for a task, resolved fuzzily.
+///
+/// # Errors
+/// Returns error if the task cannot be resolved or the database fails.
+pub(crate) fn report(conn: &Connection, task_ref: &str) -> Result<WhyReport> {
+    let context = RepoContext::new()?;
+    let task = TaskResolver::new(conn).resolve(task_ref)?.task;
+    let history = ProofRepo::new(conn).get_history(task.id)?;
+    let commits = commits_since_proof(conn, &task)?;
+    let status = task.derive_status(&context);
+    Ok(build_report(&task, status, &history, &commits, context.head_sha()))
+}
+
+/// Commits that name the task since its latest proof, or ever if it has none.
+///
+/// A proof taken at a commit this clone lacks (e.g. pulled from a teammate)
+/// links nothing rather than failing the report.
+fn commits_since_proof(conn: &Connection, task: &Task) -> Result<Vec<TaskCommit>> {
+    let mut names = TaskRepo::new(conn).get_aliases(task.id)?;
+    names.push(task.slug.clone());
+    let range = task
+        .proof
+        .as_ref()
+        .map_or_else(|| "HEAD".to_string(), |p| format!("{}..HEAD", p.git_sha));
+    Ok(trailers::commits_for(&names, &range).unwrap_or_default())
 }
 
-fn print_json(task: &Task, status: DerivedStatus, history: &[Proof], head_sha: &str) -> Result<()> {
-    let report = WhyReport {
+fn build_report(
+    task: &Task,
+    status: DerivedStatus,
+    history: &[Proof],
+    commits: &[TaskCommit],
+    head_sha: &str,
+) -> WhyReport {
+    WhyReport {
         task_id: task.id,
         slug: task.slug.clone(),
         title: task.title.clone(),
+        description: task.description.clone(),
         status: format!("{status:?}"),
         head_sha: head_sha.to_string(),
         proof: task.proof.clone(),
         history: history.to_vec(),
-    };
+        commits: commits.to_vec(),
+    }
+}
+
+fn print_json(
+    task: &Task,
+    status: DerivedStatus,
+    history: &[Proof],
+    commits: &[TaskCommit],
+    head_sha: &str,
+) -> Result<()> {
+    let report = build_report(task, status, history, commits, head_sha);
     println!("{}", serde_json::to_string_pretty(&report)?);
     Ok(())
 }
 
-fn print_human(task: &Task, derived: DerivedStatus, history: &[Proof], head_sha: &str) {
+fn print_human(
+    task: &Task,
+    derived: DerivedStatus,
+    history: &[Proof],
+    commits: &[TaskCommit],
+    context: &RepoContext,
+) {
+    let head_sha = context.head_sha();
     println!(
         "{} [{}] {}",
         status_icon(derived),
-        task.slug.cyan().bold(),
+        task.slug.info().bold(),
         task.title
     );
-    println!("   Status:  {} ({})", derived, derived.color_hint().dimmed());
+    println!("   Status:  {}", derived.to_string().status(derived));
     println!("   Repo:    {}", head_sha.dimmed());
+    if let Some(parent) = &task.parent {
+        println!("   Parent:  [{}]", parent.accent());
+    }
+    if let Some(assignee) = &task.assignee {
+        println!("   Owner:   {}", assignee.info());
+    }
+    if let Some(cwd) = &task.cwd {
+        println!("   Runs in: {}", cwd.dimmed());
+    }
+    print_coverage_trend(task, history);
     println!();
 
-    print_explanation(derived, task.proof.as_ref(), head_sha);
+    if let Some(description) = &task.description {
+        for line in description.lines() {
+            if line.is_empty() {
+                println!();
+            } else {
+                println!("   {line}");
+            }
+        }
+        println!();
+    }
+    if !explain_subtasks(derived, task, context) {
+        print_explanation(derived, task, head_sha);
+    }
     println!();
+    print_commits(task, commits);
     print_history(history);
 }
 
-fn status_icon(status: DerivedStatus) -> colored::ColoredString {
-    match status {
-        DerivedStatus::Proven => "✓".green(),
-        DerivedStatus::Stale => "⚡".yellow(),
-        DerivedStatus::Broken => "✗".red(),
-        DerivedStatus::Unproven => "○".dimmed(),
-        DerivedStatus::Attested => "!".blue(),
+/// Where each proof was recorded, newest first, for `--full`.
+fn print_environment(history: &[Proof]) {
+    println!();
+    println!("{}", "Environment:".dimmed().underline());
+    let mut shown = false;
+    for proof in history {
+        let Some(env) = &proof.environment else {
+            continue;
+        };
+        shown = true;
+        let sha = &proof.git_sha[..7.min(proof.git_sha.len())];
+        println!("   {}  {}  {}", proof.timestamp.dimmed(), sha.warning(), env.os);
+        if let Some(image) = &env.container {
+            println!("      {:<8} {}", "image".info(), image);
+        }
+        for (tool, version) in &env.tools {
+            println!("      {:<8} {}", tool.info(), version);
+        }
+        for (name, value) in &env.vars {
+            println!("      {}={}", name.dimmed(), value);
+        }
+    }
+    if !shown {
+        println!("   (No proof recorded its environment)");
     }
 }
 
-fn print_explanation(status: DerivedStatus, proof: Option<&Proof>, head: &str) {
+fn print_commits(task: &Task, commits: &[TaskCommit]) {
+    if commits.is_empty() {
+        return;
+    }
+    let since = if task.proof.is_some() { "Commits since last proof:" } else { "Commits:" };
+    println!("{}", since.dimmed().underline());
+    for commit in commits {
+        println!(
+            "   {}  {}  {}",
+            &commit.sha[..7.min(commit.sha.len())].warning(),
+            commit.summary,
+            commit.author.dimmed()
+        );
+    }
+    println!();
+}
+
+/// Shows the latest coverage and its change since the previous measurement.
+fn print_coverage_trend(task: &Task, history: &[Proof]) {
+    let mut measured = history.iter().filter_map(|p| p.coverage);
+    let Some(latest) = measured.next() else {
+        return;
+    };
+
+    let trend = match measured.next() {
+        Some(prev) if latest > prev => format!("▲ +{:.1}", latest - prev).success(),
+        Some(prev) if latest < prev => format!("▼ -{:.1}", prev - latest).failure(),
+        Some(_) => "= no change".dimmed(),
+        None => "first measurement".dimmed(),
+    };
+    let min = task
+        .coverage_min
+        .map(|m| format!(" (min {m:.1}%)"))
+        .unwrap_or_default();
+    println!("   Coverage: {latest:.1}% {trend}{}", min.dimmed());
+}
+
+fn print_explanation(status: DerivedStatus, task: &Task, head: &str) {
+    let proof = task.proof.as_ref();
     match status {
         DerivedStatus::Stale => explain_stale(proof, head),
         DerivedStatus::Attested => explain_attested(proof),
         DerivedStatus::Proven => explain_proven(proof),
         DerivedStatus::Unproven => explain_unproven(),
-        DerivedStatus::Broken => explain_broken(proof),
+        DerivedStatus::Broken => explain_broken(task),
+        DerivedStatus::Quarantined => explain_quarantined(task),
+    }
+}
+
+/// Lists unfinished subtasks. Returns whether they, not the task's own
+/// proof, account for its status.
+fn explain_subtasks(status: DerivedStatus, task: &Task, context: &RepoContext) -> bool {
+    if task.subtasks.is_empty() {
+        return false;
+    }
+    let from_subtasks = status != task.own_status(context);
+    let open: Vec<(&Task, DerivedStatus)> = task
+        .subtasks
+        .iter()
+        .map(|t| (t, t.derive_status(context)))
+        .filter(|(_, s)| !s.satisfies_dependency())
+        .collect();
+    if open.is_empty() {
+        if from_subtasks {
+            println!("{} Every subtask is done.", "reason:".success());
+        }
+        return from_subtasks;
+    }
+
+    println!(
+        "{} Waiting on {} of {} subtasks:",
+        "reason:".warning(),
+        open.len(),
+        task.subtasks.len()
+    );
+    for (subtask, status) in open {
+        println!("         {} [{}] {}", status_icon(status), subtask.slug, subtask.title);
+    }
+    if !from_subtasks {
+        println!();
     }
+    from_subtasks
 }
 
 fn explain_stale(proof: Option<&Proof>, head: &str) {
     if let Some(p) = proof {
-        println!("{} Proof exists, but repo has moved.", "reason:".yellow());
+        println!("{} Proof exists, but repo has moved.", "reason:".warning());
         println!("         Proof SHA:   {}", &p.git_sha[..7.min(p.git_sha.len())]);
         println!("         Current SHA: {}", &head[..7.min(head.len())]);
     }
@@ -111,7 +415,7 @@ fn explain_stale(proof: Option<&Proof>, head: &str) {
 fn explain_attested(proof: Option<&Proof>) {
     if let Some(p) = proof {
         let reason = p.attested_reason.as_deref().unwrap_or("Unknown");
-        println!("{} Manually attested by human.", "reason:".blue());
+        println!("{} Manually attested by human.", "reason:".accent());
         println!("         Note: \"{reason}\"");
     }
 }
@@ -120,21 +424,50 @@ fn explain_proven(proof: Option<&Proof>) {
     if let Some(p) = proof {
         println!(
             "{} Valid proof exists for SHA {}.",
-            "reason:".green(),
+            "reason:".success(),
             &p.git_sha[..7.min(p.git_sha.len())]
         );
+        if let Some(origin) = &p.origin {
+            println!("         Imported from {}", origin.trim_start_matches("import:"));
+        }
     }
 }
 
+fn explain_quarantined(task: &Task) {
+    let reason = task.quarantine_reason.as_deref().unwrap_or_default();
+    println!("{} Quarantined as flaky; failures don't block dependents.", "reason:".special());
+    println!("         Note: \"{reason}\"");
+    println!("         Rehabilitate with `roadmap quarantine {} --release`.", task.slug);
+}
+
 fn explain_unproven() {
-    println!("{} No proof has ever been recorded for this task.", "reason:".yellow());
+    println!("{} No proof has ever been recorded for this task.", "reason:".warning());
 }
 
-fn explain_broken(proof: Option<&Proof>) {
-    println!("{} The last verification attempt failed.", "reason:".red());
-    if let Some(p) = proof {
+fn explain_broken(task: &Task) {
+    if let Some(p) = task.proof.as_ref().filter(|p| p.failure.is_some()) {
+        println!("{} The command passed, but the result was rejected.", "reason:".failure());
+        println!("         {}", p.failure.as_deref().unwrap_or_default());
+        for b in p.budgets.iter().filter(|b| !b.passed) {
+            println!("         budget {} — actual {}", b.budget, b.actual);
+        }
+        return;
+    }
+
+    if let Some(p) = task.proof.as_ref().filter(|p| p.exit_code == 0) {
+        let measured = p.coverage.map_or_else(|| "no data".to_string(), |c| format!("{c:.1}%"));
+        println!("{} Tests passed, but coverage is below the threshold.", "reason:".failure());
+        println!(
+            "         Coverage: {measured}  Required: {:.1}%",
+            task.coverage_min.unwrap_or_default()
+        );
+        return;
+    }
+
+    println!("{} The last verification attempt failed.", "reason:".failure());
+    if let Some(p) = &task.proof {
         if !p.stderr.is_empty() {
-            println!("\n{}:", "stderr".red());
+            println!("\n{}:", "stderr".failure());
             for line in p.stderr.lines().take(5) {
                 println!("  {}", line.dimmed());
             }
@@ -152,19 +485,43 @@ fn print_history(history: &[Proof]) {
     for proof in history {
         let sha = &proof.git_sha[..7.min(proof.git_sha.len())];
         let status = if proof.attested_reason.is_some() {
-            "ATTESTED".blue()
+            "ATTESTED".accent()
         } else if proof.exit_code == 0 {
-            "PASS    ".green()
+            "PASS    ".success()
         } else {
-            "FAIL    ".red()
+            "FAIL    ".failure()
         };
 
+        let coverage = proof
+            .coverage
+            .map(|c| format!("  {c:.1}%"))
+            .unwrap_or_default();
+        let origin = if proof.origin.is_some() { "  (imported)" } else { "" };
+        let truncated = proof
+            .truncated
+            .map(|bytes| format!("  output truncated ({bytes} bytes omitted)"))
+            .unwrap_or_default();
+        let usage = proof
+            .usage
+            .as_ref()
+            .map(|u| format!("  {}", u.summary()))
+            .unwrap_or_default();
+        // A retried run is flaky evidence, even when it passed in the end.
+        let attempts = proof
+            .attempts
+            .map(|n| format!("  {n} attempts"))
+            .unwrap_or_default();
         println!(
-            "   {}  {}  {}  {}",
+            "   {}  {}  {}  {}{}{}{}{}{}",
             proof.timestamp.dimmed(),
-            sha.yellow(),
+            sha.warning(),
             status,
-            format!("{}ms", proof.duration_ms).dimmed()
+            format!("{}ms", proof.duration_ms).dimmed(),
+            usage.dimmed(),
+            coverage.info(),
+            attempts.warning(),
+            origin.dimmed(),
+            truncated.dimmed()
         );
     }
 }
\ No newline at end of file
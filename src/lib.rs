@@ -1 +1,5 @@
 pub mod engine;
+pub mod error;
+
+pub use engine::facade::RoadmapEngine;
+pub use error::Error;
@@ -0,0 +1,179 @@
+//! Table: Aligned column output that adapts to the terminal width.
+//!
+//! Widths are measured on the plain text, then each cell is padded and
+//! styled, so escape codes never throw off alignment. One column may be
+//! marked flexible: it absorbs whatever width is left and is truncated with
+//! an ellipsis. When even that doesn't fit, rows switch to a compact
+//! unpadded two-line layout with the flexible cell on its own line.
+//!
+//! Output that isn't going to a terminal is never truncated, unless
+//! `COLUMNS` is set explicitly.
+
+use colored::ColoredString;
+use std::fmt::Write;
+use terminal_size::{terminal_size, Width};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+const INDENT: &str = "   ";
+const GAP: &str = "  ";
+/// Narrowest the flexible column may get before switching to compact rows.
+const MIN_FLEX: usize = 16;
+
+type Style = Box<dyn Fn(&str) -> ColoredString>;
+
+/// A cell's text and how to style it once laid out.
+pub struct Cell {
+    text: String,
+    style: Style,
+}
+
+impl Cell {
+    pub fn new(text: impl Into<String>, style: impl Fn(&str) -> ColoredString + 'static) -> Self {
+        Self {
+            text: text.into(),
+            style: Box::new(style),
+        }
+    }
+
+    /// A cell printed without styling.
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self::new(text, |s| ColoredString::from(s))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// A table with one column optionally marked flexible.
+pub struct Table {
+    aligns: Vec<Align>,
+    flex: Option<usize>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    /// Creates a table with the given column alignments.
+    pub fn new(aligns: &[Align]) -> Self {
+        Self {
+            aligns: aligns.to_vec(),
+            flex: None,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Marks the column that shrinks (and truncates) to fit the terminal.
+    #[must_use]
+    pub fn flex(mut self, column: usize) -> Self {
+        self.flex = Some(column);
+        self
+    }
+
+    pub fn row(&mut self, cells: Vec<Cell>) {
+        debug_assert_eq!(cells.len(), self.aligns.len());
+        self.rows.push(cells);
+    }
+
+    /// Renders every row; compact rows span two lines.
+    #[must_use]
+    pub fn render(&self) -> Vec<String> {
+        self.render_for(available_width())
+    }
+
+    fn render_for(&self, width: Option<usize>) -> Vec<String> {
+        let mut widths: Vec<usize> = (0..self.aligns.len())
+            .map(|c| self.rows.iter().map(|r| r[c].text.width()).max().unwrap_or(0))
+            .collect();
+
+        let mut compact = false;
+        if let (Some(total), Some(flex)) = (width, self.flex) {
+            let fixed: usize = widths
+                .iter()
+                .enumerate()
+                .filter(|(c, _)| *c != flex)
+                .map(|(_, w)| w + GAP.len())
+                .sum();
+            let room = total.saturating_sub(INDENT.len() + fixed);
+            if room < MIN_FLEX.min(widths[flex]) {
+                compact = true;
+                widths[flex] = total.saturating_sub(INDENT.len() * 2).max(1);
+            } else {
+                widths[flex] = widths[flex].min(room);
+            }
+        }
+
+        self.rows
+            .iter()
+            .map(|row| self.render_row(row, &widths, compact))
+            .collect()
+    }
+
+    fn render_row(&self, row: &[Cell], widths: &[usize], compact: bool) -> String {
+        let mut line = String::from(INDENT);
+        let mut detail = None;
+        let last = row.len() - 1;
+
+        for (c, cell) in row.iter().enumerate() {
+            let text = truncate(&cell.text, widths[c]);
+            if compact && Some(c) == self.flex {
+                detail = Some((cell.style)(&text).to_string());
+                continue;
+            }
+            // Compact rows trade alignment for width: no padding at all.
+            let pad = if compact {
+                String::new()
+            } else {
+                " ".repeat(widths[c] - text.width())
+            };
+            let styled = (cell.style)(&text);
+            match self.aligns[c] {
+                Align::Left => {
+                    let _ = write!(line, "{styled}{pad}");
+                }
+                Align::Right => {
+                    let _ = write!(line, "{pad}{styled}");
+                }
+            }
+            if c != last {
+                line.push_str(GAP);
+            }
+        }
+
+        let mut line = line.trim_end().to_string();
+        if let Some(detail) = detail {
+            let _ = write!(line, "\n{INDENT}{INDENT}{detail}");
+        }
+        line
+    }
+}
+
+/// Display width available for a row, or `None` when output is unbounded.
+pub(crate) fn available_width() -> Option<usize> {
+    if let Ok(cols) = std::env::var("COLUMNS") {
+        if let Ok(n) = cols.parse() {
+            return Some(n);
+        }
+    }
+    terminal_size().map(|(Width(w), _)| usize::from(w))
+}
+
+/// Cuts `text` to at most `max` display columns, ending in `…` if shortened.
+fn truncate(text: &str, max: usize) -> String {
+    if text.width() <= max {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for ch in text.chars() {
+        let w = ch.width().unwrap_or(0);
+        if used + w + 1 > max {
+            break;
+        }
+        used += w;
+        out.push(ch);
+    }
+    out.push('…');
+    out
+}
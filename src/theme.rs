@@ -0,0 +1,158 @@
+//! Theme: Maps semantic output roles to the configured palette.
+//!
+//! Handlers never pick hues directly. They say what a piece of text *means*
+//! (success, failure, warning, ...) and the palette from `[theme]` in
+//! `.roadmap/config.toml` decides how that looks. Bold and dim are emphasis,
+//! not color, and stay available through `colored` as usual.
+
+use colored::{Color, ColoredString, Colorize};
+use roadmap::engine::config::{ColorMode, Config, Palette};
+use roadmap::engine::types::DerivedStatus;
+use std::sync::OnceLock;
+
+static THEME: OnceLock<(Palette, ColorMode)> = OnceLock::new();
+
+/// The `[theme]` settings, read from config on first use.
+///
+/// A broken config falls back to the default theme with a warning, so
+/// output never fails just because it can't be colored.
+fn theme() -> (Palette, ColorMode) {
+    *THEME.get_or_init(|| match Config::load() {
+        Ok(config) => (config.theme.palette, config.theme.color),
+        Err(e) => {
+            eprintln!("warning: using the default theme. {e:#}");
+            (Palette::default(), ColorMode::default())
+        }
+    })
+}
+
+fn palette() -> Palette {
+    theme().0
+}
+
+/// Applies `theme.color` before anything is printed.
+///
+/// `NO_COLOR` and `CLICOLOR_FORCE` in the environment take precedence.
+pub fn init() {
+    if std::env::var_os("NO_COLOR").is_some() || std::env::var_os("CLICOLOR_FORCE").is_some() {
+        return;
+    }
+    match theme().1 {
+        ColorMode::Auto => {}
+        ColorMode::Always => colored::control::set_override(true),
+        ColorMode::Never => colored::control::set_override(false),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Role {
+    Success,
+    Failure,
+    Warning,
+    Info,
+    Accent,
+    Special,
+}
+
+fn color(palette: Palette, role: Role) -> Option<Color> {
+    match palette {
+        Palette::Default => Some(match role {
+            Role::Success => Color::Green,
+            Role::Failure => Color::Red,
+            Role::Warning => Color::Yellow,
+            Role::Info => Color::Cyan,
+            Role::Accent => Color::Blue,
+            Role::Special => Color::Magenta,
+        }),
+        Palette::Colorblind => Some(match role {
+            Role::Success => okabe_ito(0, 114, 178),
+            Role::Failure => okabe_ito(213, 94, 0),
+            Role::Warning => okabe_ito(240, 228, 66),
+            Role::Info => okabe_ito(86, 180, 233),
+            Role::Accent => okabe_ito(0, 158, 115),
+            Role::Special => okabe_ito(204, 121, 167),
+        }),
+        Palette::Mono => None,
+    }
+}
+
+const fn okabe_ito(r: u8, g: u8, b: u8) -> Color {
+    Color::TrueColor { r, g, b }
+}
+
+fn paint(text: ColoredString, role: Role) -> ColoredString {
+    match color(palette(), role) {
+        Some(c) => text.color(c),
+        // Without hue, failures and warnings must still stand out.
+        None if matches!(role, Role::Failure | Role::Warning) => text.bold(),
+        None => text,
+    }
+}
+
+/// The role a status is shown in; `None` means dimmed rather than colored.
+fn status_role(status: DerivedStatus) -> Option<Role> {
+    match status {
+        DerivedStatus::Proven => Some(Role::Success),
+        DerivedStatus::Stale => Some(Role::Warning),
+        DerivedStatus::Broken => Some(Role::Failure),
+        DerivedStatus::Unproven => None,
+        DerivedStatus::Attested => Some(Role::Accent),
+        DerivedStatus::Quarantined => Some(Role::Special),
+    }
+}
+
+/// The palette color of a status, for renderers that don't print through `colored`.
+///
+/// `None` means the status is shown dimmed, or the palette has no hues.
+pub fn status_color(status: DerivedStatus) -> Option<Color> {
+    status_role(status).and_then(|role| color(palette(), role))
+}
+
+/// Semantic coloring for plain and already-styled strings.
+pub trait Paint: Into<ColoredString> {
+    fn success(self) -> ColoredString {
+        paint(self.into(), Role::Success)
+    }
+    fn failure(self) -> ColoredString {
+        paint(self.into(), Role::Failure)
+    }
+    fn warning(self) -> ColoredString {
+        paint(self.into(), Role::Warning)
+    }
+    fn info(self) -> ColoredString {
+        paint(self.into(), Role::Info)
+    }
+    fn accent(self) -> ColoredString {
+        paint(self.into(), Role::Accent)
+    }
+    fn special(self) -> ColoredString {
+        paint(self.into(), Role::Special)
+    }
+    /// Colors text by the role associated with a task status.
+    fn status(self, status: DerivedStatus) -> ColoredString {
+        match status_role(status) {
+            Some(role) => paint(self.into(), role),
+            None => self.into().dimmed(),
+        }
+    }
+}
+
+impl Paint for &str {}
+impl Paint for ColoredString {}
+
+/// The glyph shown next to a task with this status.
+pub fn status_glyph(status: DerivedStatus) -> &'static str {
+    match status {
+        DerivedStatus::Proven => "✓",
+        DerivedStatus::Stale => "⚡",
+        DerivedStatus::Broken => "✗",
+        DerivedStatus::Unproven => "○",
+        DerivedStatus::Attested => "!",
+        DerivedStatus::Quarantined => "☣",
+    }
+}
+
+/// The status glyph, colored by its status.
+pub fn status_icon(status: DerivedStatus) -> ColoredString {
+    status_glyph(status).status(status)
+}
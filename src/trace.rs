@@ -0,0 +1,233 @@
+//! Trace: Phase timings for `--trace` / `ROADMAP_TRACE=1`.
+//!
+//! The engine opens `tracing` spans around its expensive phases (database
+//! open, graph build, git calls, hooks, verification). When tracing is on, a
+//! timing layer totals those spans per phase, prints a breakdown to stderr
+//! when the command ends, and appends it to `.roadmap/timings.jsonl` for
+//! `roadmap debug timings`. Nested phases overlap: `graph.build` includes
+//! the git calls it makes.
+
+use anyhow::{Context as _, Result};
+use colored::Colorize;
+use crate::table::{Align, Cell, Table};
+use roadmap::engine::db::DB_DIR;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+
+const LOG_FILE: &str = "timings.jsonl";
+/// Only the most recent invocations are kept.
+const MAX_LOG_ENTRIES: usize = 200;
+
+/// Timing of one phase label within an invocation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PhaseTiming {
+    pub name: String,
+    pub count: u32,
+    pub total_ms: u64,
+}
+
+/// One traced command run, as logged.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Invocation {
+    pub timestamp: String,
+    pub command: String,
+    pub ok: bool,
+    pub total_ms: u64,
+    pub phases: Vec<PhaseTiming>,
+}
+
+type Phases = Arc<Mutex<BTreeMap<String, (u32, Duration)>>>;
+
+/// Collects phase timings for the current process.
+pub struct Tracer {
+    command: String,
+    start: Instant,
+    phases: Phases,
+}
+
+impl Tracer {
+    /// Installs the timing layer if `--trace` was given or `ROADMAP_TRACE=1`.
+    pub fn start(flag: bool, command: &str) -> Option<Self> {
+        let from_env = std::env::var("ROADMAP_TRACE").is_ok_and(|v| v == "1");
+        if !flag && !from_env {
+            return None;
+        }
+
+        let phases = Phases::default();
+        let subscriber = tracing_subscriber::registry().with(TimingLayer {
+            phases: Arc::clone(&phases),
+        });
+        // Only fails if a subscriber is already set, in which case it keeps the spans.
+        tracing::subscriber::set_global_default(subscriber).ok()?;
+
+        Some(Self {
+            command: command.to_string(),
+            start: Instant::now(),
+            phases,
+        })
+    }
+
+    /// Prints the breakdown and logs it for `debug timings`.
+    pub fn finish(self, ok: bool) {
+        let phases = self
+            .phases
+            .lock()
+            .map(|p| {
+                p.iter()
+                    .map(|(name, (count, total))| PhaseTiming {
+                        name: name.clone(),
+                        count: *count,
+                        total_ms: millis(*total),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let invocation = Invocation {
+            timestamp: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            command: self.command,
+            ok,
+            total_ms: millis(self.start.elapsed()),
+            phases,
+        };
+
+        print_breakdown(&invocation);
+        if Path::new(DB_DIR).is_dir() {
+            if let Err(e) = append_log(invocation) {
+                eprintln!("warning: could not record timings: {e:#}");
+            }
+        }
+    }
+}
+
+fn print_breakdown(inv: &Invocation) {
+    eprintln!(
+        "{} trace: {} {}",
+        "⏱".dimmed(),
+        inv.command.bold(),
+        format!("{}ms total", inv.total_ms).dimmed()
+    );
+    let mut table = Table::new(&[Align::Left, Align::Right, Align::Right]);
+    let mut phases: Vec<_> = inv.phases.iter().collect();
+    phases.sort_by_key(|p| std::cmp::Reverse(p.total_ms));
+    for phase in phases {
+        table.row(vec![
+            Cell::plain(&phase.name),
+            Cell::new(format!("{}×", phase.count), |s| s.dimmed()),
+            Cell::plain(format!("{}ms", phase.total_ms)),
+        ]);
+    }
+    for line in table.render() {
+        eprintln!("{line}");
+    }
+}
+
+fn log_path() -> PathBuf {
+    Path::new(DB_DIR).join(LOG_FILE)
+}
+
+/// Reads logged invocations, oldest first. A missing log is empty.
+///
+/// # Errors
+/// Returns error if the log exists but cannot be read.
+pub fn read_log() -> Result<Vec<Invocation>> {
+    let path = log_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    // A torn or hand-edited line shouldn't hide every other entry.
+    Ok(raw
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+fn append_log(invocation: Invocation) -> Result<()> {
+    let mut entries = read_log()?;
+    entries.push(invocation);
+    let skip = entries.len().saturating_sub(MAX_LOG_ENTRIES);
+
+    let mut out = String::new();
+    for entry in &entries[skip..] {
+        out.push_str(&serde_json::to_string(entry)?);
+        out.push('\n');
+    }
+    fs::write(log_path(), out).context("Failed to write timings log")
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn millis(d: Duration) -> u64 {
+    d.as_millis() as u64
+}
+
+/// When a span was opened and the label it is totalled under.
+struct Started {
+    label: String,
+    at: Instant,
+}
+
+struct TimingLayer {
+    phases: Phases,
+}
+
+impl<S> Layer<S> for TimingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut op = OpField(None);
+        attrs.record(&mut op);
+        let name = attrs.metadata().name();
+        let label = match op.0 {
+            Some(op) => format!("{name} {op}"),
+            None => name.to_string(),
+        };
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Started {
+                label,
+                at: Instant::now(),
+            });
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let Some(started) = span.extensions_mut().remove::<Started>() else {
+            return;
+        };
+        if let Ok(mut phases) = self.phases.lock() {
+            let entry = phases.entry(started.label).or_default();
+            entry.0 += 1;
+            entry.1 += started.at.elapsed();
+        }
+    }
+}
+
+/// Extracts the `op` field that qualifies a span label (e.g. `git diff`).
+struct OpField(Option<String>);
+
+impl Visit for OpField {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "op" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "op" {
+            self.0 = Some(format!("{value:?}"));
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Byte-for-byte determinism of the exports that live in git.
+//!
+//! Each fixture restores the same roadmap into a fresh database, once in the
+//! listed order and once with every list reversed, so row IDs and query order
+//! differ while the state does not.
+
+use roadmap::engine::audit;
+use roadmap::engine::canonical;
+use roadmap::engine::db::Db;
+use roadmap::engine::snapshot::Snapshot;
+use rusqlite::Connection;
+
+const STATE: &str = r#"{
+  "version": 1,
+  "milestones": [
+    {"name": "beta", "title": "Public beta"},
+    {"name": "alpha"}
+  ],
+  "tasks": [
+    {"slug": "parse", "title": "Parse input", "status": "attested",
+     "created_at": "2024-01-01 10:00:00", "test_cmd": "cargo test parse",
+     "scopes": ["src/parse.rs", "src/lex.rs"], "tags": ["core", "io"],
+     "milestone": "alpha"},
+    {"slug": "eval", "title": "Evaluate", "status": "pending",
+     "created_at": "2024-01-01 10:00:00", "test_cmd": "cargo test eval",
+     "tags": ["core"], "parent": "parse", "priority": 2},
+    {"slug": "docs", "title": "Write docs", "status": "pending",
+     "created_at": "2024-01-02 09:30:00", "milestone": "beta"}
+  ],
+  "edges": [
+    {"blocker": "parse", "blocked": "eval"},
+    {"blocker": "parse", "blocked": "docs"},
+    {"blocker": "eval", "blocked": "docs"}
+  ],
+  "proofs": [
+    {"task": "parse", "proof": {"cmd": "cargo test parse", "exit_code": 0,
+     "git_sha": "1111111111111111111111111111111111111111",
+     "timestamp": "2024-01-03 12:00:00", "duration_ms": 40, "attested_reason": null}},
+    {"task": "eval", "proof": {"cmd": "cargo test eval", "exit_code": 1,
+     "git_sha": "1111111111111111111111111111111111111111",
+     "timestamp": "2024-01-03 12:00:00", "duration_ms": 55, "attested_reason": null}},
+    {"task": "parse", "proof": {"cmd": "cargo test parse", "exit_code": 0,
+     "git_sha": "2222222222222222222222222222222222222222",
+     "timestamp": "2024-01-04 08:15:00", "duration_ms": 38, "attested_reason": null}}
+  ]
+}"#;
+
+fn state(reversed: bool) -> Snapshot {
+    let mut snapshot = Snapshot::parse(STATE).expect("fixture parses");
+    if reversed {
+        snapshot.milestones.reverse();
+        snapshot.tasks.reverse();
+        snapshot.edges.reverse();
+        snapshot.proofs.reverse();
+        for task in &mut snapshot.tasks {
+            task.scopes.reverse();
+            task.tags.reverse();
+        }
+    }
+    snapshot
+}
+
+fn restore(reversed: bool) -> (tempfile::TempDir, Connection) {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let conn = Db::open(&dir.path().join("roadmap.db")).expect("open db");
+    state(reversed).apply(&conn).expect("apply fixture");
+    (dir, conn)
+}
+
+/// What `roadmap export --format json` writes.
+fn bundle(conn: &Connection) -> String {
+    let snapshot = Snapshot::capture(conn).expect("capture");
+    serde_json::to_string_pretty(&snapshot).expect("serialize bundle")
+}
+
+/// What `roadmap push-state` writes.
+fn snapshot(conn: &Connection) -> String {
+    canonical::to_pretty(&Snapshot::capture(conn).expect("capture")).expect("serialize snapshot")
+}
+
+/// What `roadmap export --audit-log` writes.
+fn audit_log(conn: &Connection) -> String {
+    audit::collect_events(conn)
+        .expect("collect events")
+        .iter()
+        .map(|e| canonical::to_line(e).expect("serialize event") + "\n")
+        .collect()
+}
+
+/// The audit log with row IDs dropped; they are local to each database.
+fn audit_log_without_ids(conn: &Connection) -> String {
+    audit::collect_events(conn)
+        .expect("collect events")
+        .iter()
+        .map(|e| {
+            let mut value = serde_json::to_value(e).expect("event to value");
+            if let Some(fields) = value.as_object_mut() {
+                fields.remove("task_id");
+                fields.remove("proof_id");
+            }
+            canonical::to_line(&value).expect("serialize event") + "\n"
+        })
+        .collect()
+}
+
+#[test]
+fn bundle_is_stable_across_runs_and_insertion_orders() {
+    let (_a, forward) = restore(false);
+    let (_b, backward) = restore(true);
+    let first = bundle(&forward);
+    assert_eq!(first, bundle(&forward));
+    assert_eq!(first, bundle(&backward));
+}
+
+#[test]
+fn snapshot_is_stable_across_runs_and_insertion_orders() {
+    let (_a, forward) = restore(false);
+    let (_b, backward) = restore(true);
+    let first = snapshot(&forward);
+    assert_eq!(first, snapshot(&forward));
+    assert_eq!(first, snapshot(&backward));
+}
+
+#[test]
+fn snapshot_round_trips_to_the_same_bytes() {
+    let (_a, original) = restore(false);
+    let first = snapshot(&original);
+
+    let dir = tempfile::tempdir().expect("temp dir");
+    let copy = Db::open(&dir.path().join("copy.db")).expect("open db");
+    Snapshot::parse(&first).expect("parse").apply(&copy).expect("apply");
+    assert_eq!(first, snapshot(&copy));
+}
+
+#[test]
+fn audit_log_is_stable_across_runs() {
+    let (_a, conn) = restore(false);
+    assert_eq!(audit_log(&conn), audit_log(&conn));
+
+    let (_b, again) = restore(false);
+    assert_eq!(audit_log(&conn), audit_log(&again));
+}
+
+#[test]
+fn audit_log_order_ignores_insertion_order() {
+    let (_a, forward) = restore(false);
+    let (_b, backward) = restore(true);
+    assert_eq!(audit_log_without_ids(&forward), audit_log_without_ids(&backward));
+}